@@ -0,0 +1,114 @@
+//! Conversion of tables to Apache Arrow, enabled by the `arrow` feature
+//!
+//! Columns are typed by [`Table::column_types`], dates become timestamps in
+//! milliseconds without time zone and empty values are null.
+//!
+//! ## Example
+//! ```
+//! use arrow_schema::{DataType, TimeUnit};
+//! use schedule_parser::XerFile;
+//!
+//! let data = "ERMHDR\t19.12\t2024-03-15\n%T\tTASK\n%F\ttask_id\ttarget_start_date\n%R\t1\t2024-03-18 08:00\n%R\t2\t\n%E\n";
+//! let xer = XerFile::parse(data.as_bytes()).unwrap();
+//! let batch = xer.table("TASK").unwrap().to_record_batch().unwrap();
+//! assert_eq!(batch.num_rows(), 2);
+//! assert_eq!(batch.schema().field(0).data_type(), &DataType::Int64);
+//! assert_eq!(
+//!     batch.schema().field(1).data_type(),
+//!     &DataType::Timestamp(TimeUnit::Millisecond, None)
+//! );
+//! assert_eq!(batch.column(1).null_count(), 1);
+//! ```
+
+use std::sync::Arc;
+
+use arrow_array::{
+    ArrayRef, Float64Array, Int64Array, RecordBatch, RecordBatchOptions, StringArray,
+    TimestampMillisecondArray,
+};
+use arrow_schema::{ArrowError, DataType, Field, Schema, TimeUnit};
+use indexmap::IndexMap;
+
+use crate::model::parse_datetime;
+use crate::parser::Table;
+use crate::schema::ColumnType;
+use crate::xer::XerFile;
+
+impl ColumnType {
+    /// Arrow type of a column of this type
+    pub fn data_type(self) -> DataType {
+        match self {
+            Self::Integer => DataType::Int64,
+            Self::Float => DataType::Float64,
+            Self::DateTime => DataType::Timestamp(TimeUnit::Millisecond, None),
+            Self::Text => DataType::Utf8,
+        }
+    }
+}
+
+impl Table {
+    /// Arrow schema with a nullable field per column, named by [`Table::unique_columns`]
+    pub fn arrow_schema(&self) -> Schema {
+        self.schema(&self.column_types())
+    }
+
+    /// All rows as one record batch
+    pub fn to_record_batch(&self) -> Result<RecordBatch, ArrowError> {
+        let types = self.column_types();
+        let columns = types
+            .iter()
+            .enumerate()
+            .map(|(i, kind)| self.array(i, *kind))
+            .collect();
+        // a table without columns still has a row count
+        let options = RecordBatchOptions::new().with_row_count(Some(self.rows.len()));
+        RecordBatch::try_new_with_options(Arc::new(self.schema(&types)), columns, &options)
+    }
+
+    fn schema(&self, types: &[ColumnType]) -> Schema {
+        let fields: Vec<Field> = self
+            .unique_columns()
+            .into_iter()
+            .zip(types)
+            .map(|(column, kind)| Field::new(column, kind.data_type(), true))
+            .collect();
+        Schema::new(fields)
+    }
+
+    /// Values of column `i` as array of `kind`, values not fitting `kind` are null
+    fn array(&self, i: usize, kind: ColumnType) -> ArrayRef {
+        let values = self.rows.iter().map(|row| {
+            row.get(i)
+                .map(String::as_str)
+                .filter(|value| !value.is_empty())
+        });
+        match kind {
+            ColumnType::Integer => Arc::new(
+                values
+                    .map(|value| value?.parse().ok())
+                    .collect::<Int64Array>(),
+            ),
+            ColumnType::Float => Arc::new(
+                values
+                    .map(|value| value?.parse().ok())
+                    .collect::<Float64Array>(),
+            ),
+            ColumnType::DateTime => Arc::new(
+                values
+                    .map(|value| Some(parse_datetime(value?)?.and_utc().timestamp_millis()))
+                    .collect::<TimestampMillisecondArray>(),
+            ),
+            ColumnType::Text => Arc::new(values.collect::<StringArray>()),
+        }
+    }
+}
+
+impl XerFile {
+    /// Every table as record batch by name, in file order
+    pub fn to_arrow(&self) -> Result<IndexMap<String, RecordBatch>, ArrowError> {
+        self.tables
+            .iter()
+            .map(|(name, table)| Ok((name.clone(), table.to_record_batch()?)))
+            .collect()
+    }
+}
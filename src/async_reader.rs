@@ -0,0 +1,318 @@
+//! Asynchronous parsing over tokio readers, enabled by the `tokio` feature
+//!
+//! Tables are parsed while the input streams in, e.g. from an upload body
+//! wrapped in `tokio_util::io::StreamReader`, without blocking threads or
+//! buffering the whole file.
+//!
+//! ## Example
+//! ```
+//! use schedule_parser::AsyncXerReader;
+//!
+//! # #[tokio::main(flavor = "current_thread")]
+//! # async fn main() {
+//! let data: &[u8] = b"ERMHDR\t19.12\t2024-03-15\n%T\tCURRTYPE\n%F\tcurr_id\n%R\t1\n%E\n";
+//! let mut reader = AsyncXerReader::from_reader(data).await.unwrap();
+//! while let Some(table) = reader.next_table().await.unwrap() {
+//!     assert_eq!(table.name, "CURRTYPE");
+//! }
+//! # }
+//! ```
+
+use std::path::Path;
+
+use indexmap::IndexMap;
+use tokio::fs::File;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, BufReader};
+
+use crate::error::ParseError;
+use crate::header::Header;
+use crate::parser::{
+    decode, drop_nul, parse_columns, parse_header, split_fields, table_name, take_line, Table, BOM,
+};
+use crate::xer::XerFile;
+
+/// Asynchronous counterpart of [`XerReader`](crate::XerReader)
+pub struct AsyncXerReader<R> {
+    header: Header,
+    lines: AsyncLineReader<R>,
+    only: Option<Vec<String>>,
+    started: bool,
+    done: bool,
+}
+
+impl AsyncXerReader<BufReader<File>> {
+    /// Open file at `path`
+    pub async fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, ParseError> {
+        let file = File::open(path)
+            .await
+            .map_err(|source| ParseError::Io { line: 0, source })?;
+        Self::from_reader(BufReader::new(file)).await
+    }
+}
+
+impl<R: AsyncBufRead + Unpin> AsyncXerReader<R> {
+    /// Wrap any buffered async reader, header line is read right away
+    pub async fn from_reader(reader: R) -> Result<Self, ParseError> {
+        let mut lines = AsyncLineReader::new(reader);
+        let header = parse_header(lines.next().await?)?;
+        Ok(Self {
+            header,
+            lines,
+            only: None,
+            started: false,
+            done: false,
+        })
+    }
+
+    /// Export info from the `ERMHDR` line
+    pub fn header(&self) -> &Header {
+        &self.header
+    }
+
+    /// Yield only tables named in `names`, e.g. `&["TASK", "TASKPRED"]`
+    pub fn only_tables(mut self, names: &[&str]) -> Self {
+        self.only = Some(names.iter().map(|name| name.to_string()).collect());
+        self
+    }
+
+    /// Next table, `Ok(None)` at the end of file and after the first error
+    pub async fn next_table(&mut self) -> Result<Option<Table>, ParseError> {
+        if self.done {
+            return Ok(None);
+        }
+        let table = self.read_table().await;
+        self.done = !matches!(table, Ok(Some(_)));
+        table
+    }
+
+    /// Input ended without `%E`, see [`TableIterator::is_truncated`](crate::TableIterator::is_truncated)
+    pub fn is_truncated(&self) -> bool {
+        self.lines.eof
+    }
+
+    /// Read all remaining tables, see [`XerFile::parse`]
+    pub async fn into_xer_file(mut self) -> Result<XerFile, ParseError> {
+        let mut tables = IndexMap::new();
+        while let Some(table) = self.next_table().await? {
+            tables.insert(table.name.clone(), table);
+        }
+        Ok(XerFile {
+            header: self.header,
+            tables,
+        })
+    }
+
+    async fn read_table(&mut self) -> Result<Option<Table>, ParseError> {
+        let name = loop {
+            let Some(name) = self.read_table_name().await? else {
+                return Ok(None);
+            };
+            if self.is_wanted(&name) {
+                break name;
+            }
+            self.lines.skip_while("%F").await?;
+            self.lines.skip_while("%R").await?;
+        };
+        let header = parse_columns(self.lines.next().await?)?;
+
+        let mut rows: Vec<Vec<String>> = Vec::new();
+        while let Some(line) = self.lines.next_record("%R").await? {
+            rows.push(split_fields(line).skip(1).map(String::from).collect());
+        }
+        let mut table = Table::new(name, header, rows);
+        table.truncated = self.lines.eof;
+        Ok(Some(table))
+    }
+
+    fn is_wanted(&self, name: &str) -> bool {
+        self.only
+            .as_ref()
+            .is_none_or(|only| only.iter().any(|n| n == name))
+    }
+
+    /// Name from the next `%T` line, `None` at the end of file
+    async fn read_table_name(&mut self) -> Result<Option<String>, ParseError> {
+        // skip lines until first table starts, afterwards only `%T` and `%E` may come
+        let (number, line) = loop {
+            let Some((number, line)) = self.lines.next().await? else {
+                return Ok(None);
+            };
+            if line.starts_with("%T") {
+                break (number, line);
+            }
+            if line.starts_with("%E") {
+                return Ok(None);
+            }
+            if self.started {
+                return Err(ParseError::UnexpectedRecord {
+                    line: number,
+                    content: line,
+                });
+            }
+        };
+        self.started = true;
+        table_name(number, line).map(Some)
+    }
+}
+
+impl XerFile {
+    /// Read all tables from async `reader`
+    pub async fn parse_async<R: AsyncBufRead + Unpin>(reader: R) -> Result<Self, ParseError> {
+        AsyncXerReader::from_reader(reader)
+            .await?
+            .into_xer_file()
+            .await
+    }
+}
+
+/// Line by line async reader with one line lookahead
+struct AsyncLineReader<R> {
+    reader: R,
+    buf: Vec<u8>,
+    /// Number of the last read line
+    number: usize,
+    /// Number of the first line of the record in `buf`
+    line: usize,
+    /// Last line ended with `\r`
+    after_cr: bool,
+    /// End of input was reached
+    eof: bool,
+    peeked: Option<(usize, String)>,
+}
+
+impl<R: AsyncBufRead + Unpin> AsyncLineReader<R> {
+    fn new(reader: R) -> Self {
+        Self {
+            reader,
+            buf: Vec::new(),
+            number: 0,
+            line: 0,
+            after_cr: false,
+            eof: false,
+            peeked: None,
+        }
+    }
+
+    /// Next line without line terminator, `Ok(None)` at the end of file
+    async fn next(&mut self) -> Result<Option<(usize, String)>, ParseError> {
+        if let Some(peeked) = self.peeked.take() {
+            return Ok(Some(peeked));
+        }
+        if !self.read_raw().await? {
+            return Ok(None);
+        }
+        let line = decode(self.line, &self.buf)?;
+        Ok(Some((self.line, line.into())))
+    }
+
+    /// Next line borrowed from `buf` if it starts with `prefix`, others are pushed back
+    async fn next_record(&mut self, prefix: &str) -> Result<Option<&str>, ParseError> {
+        match self.peeked.take() {
+            Some(peeked) if !peeked.1.starts_with(prefix) => {
+                self.peeked = Some(peeked);
+                return Ok(None);
+            }
+            Some((_, line)) => {
+                self.buf.clear();
+                self.buf.extend_from_slice(line.as_bytes());
+            }
+            None => {
+                if !self.read_raw().await? {
+                    return Ok(None);
+                }
+                if !self.buf.starts_with(prefix.as_bytes()) {
+                    let line = decode(self.line, &self.buf)?.into();
+                    self.peeked = Some((self.line, line));
+                    return Ok(None);
+                }
+            }
+        }
+        decode(self.line, &self.buf).map(Some)
+    }
+
+    /// Skip lines starting with `prefix` without decoding them
+    async fn skip_while(&mut self, prefix: &str) -> Result<(), ParseError> {
+        if let Some((number, line)) = self.peeked.take() {
+            if !line.starts_with(prefix) {
+                self.peeked = Some((number, line));
+                return Ok(());
+            }
+        }
+        while self.read_raw().await? {
+            if !self.buf.starts_with(prefix.as_bytes()) {
+                let line = decode(self.line, &self.buf)?.into();
+                self.peeked = Some((self.line, line));
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Read next record into `buf` without line terminator, `false` at the end of file
+    ///
+    /// Same rules as for the blocking reader: `\n`, `\r\n` and `\r` end a
+    /// line, NUL bytes and a leading BOM are dropped, lines not starting with
+    /// `%` continue the `%R` line before.
+    async fn read_raw(&mut self) -> Result<bool, ParseError> {
+        self.buf.clear();
+        let io = |line: usize| move |source| ParseError::Io { line, source };
+        let lines = self.read_line().await.map_err(io(self.number + 1))?;
+        if lines == 0 {
+            self.eof = true;
+            return Ok(false);
+        }
+        if self.number == 0 && self.buf.starts_with(BOM) {
+            self.buf.drain(..BOM.len());
+        }
+        self.number += lines;
+        self.line = self.number;
+
+        if self.buf.starts_with(b"%R") {
+            while let Some(newline) = self.continuation().await.map_err(io(self.number + 1))? {
+                self.buf.extend_from_slice(newline);
+                self.number += self.read_line().await.map_err(io(self.number + 1))?;
+            }
+        }
+        Ok(true)
+    }
+
+    /// Append next line to `buf`, returns the number of lines read
+    async fn read_line(&mut self) -> std::io::Result<usize> {
+        let mut lines = 0;
+        loop {
+            let chunk = self.reader.fill_buf().await?;
+            if chunk.is_empty() {
+                // last line without line break
+                return Ok(if drop_nul(&mut self.buf) && !self.buf.is_empty() {
+                    lines + 1
+                } else {
+                    0
+                });
+            }
+            let (used, complete) = take_line(chunk, &mut self.buf, &mut self.after_cr);
+            self.reader.consume(used);
+            if complete {
+                lines += 1;
+                if drop_nul(&mut self.buf) {
+                    return Ok(lines);
+                }
+            }
+        }
+    }
+
+    /// Line break ending the record in `buf` if the next line continues it
+    async fn continuation(&mut self) -> std::io::Result<Option<&'static [u8]>> {
+        let mut newline: &'static [u8] = if self.after_cr { b"\r" } else { b"\n" };
+        loop {
+            match self.reader.fill_buf().await?.first() {
+                Some(b'\n') if self.after_cr => {
+                    self.reader.consume(1);
+                    self.after_cr = false;
+                    newline = b"\r\n";
+                }
+                None | Some(b'%' | 0) => return Ok(None),
+                Some(_) => return Ok(Some(newline)),
+            }
+        }
+    }
+}
@@ -0,0 +1,476 @@
+//! Whole file conversion to other formats
+
+use std::io::{self, BufRead, Write};
+
+use schedule_parser::filter::BoundFilter;
+use schedule_parser::projection::BoundProjection;
+use schedule_parser::schema::ColumnType;
+use schedule_parser::{Header, Table, TableReader, XerFile};
+use serde::ser::{SerializeMap, Serializer};
+use serde::Serialize;
+
+/// Write `xer` as `{"header": {..}, "tables": {"TASK": {"columns": [..], "rows": [[..]]}}}`
+///
+/// Tables keep file order, rows are arrays of values in column order.
+pub fn write_json<W: Write>(xer: &XerFile, out: W, pretty: bool) -> serde_json::Result<()> {
+    let file = JsonFile {
+        header: &xer.header,
+        tables: JsonTables(xer),
+    };
+    if pretty {
+        serde_json::to_writer_pretty(out, &file)
+    } else {
+        serde_json::to_writer(out, &file)
+    }
+}
+
+#[derive(Serialize)]
+struct JsonFile<'a> {
+    header: &'a Header,
+    tables: JsonTables<'a>,
+}
+
+/// Map of table name to [`JsonTable`]
+struct JsonTables<'a>(&'a XerFile);
+
+impl Serialize for JsonTables<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.0.tables.len()))?;
+        for (name, table) in &self.0.tables {
+            map.serialize_entry(name, &JsonTable::from(table))?;
+        }
+        map.end()
+    }
+}
+
+#[derive(Serialize)]
+struct JsonTable<'a> {
+    columns: &'a [String],
+    rows: &'a [Vec<String>],
+    /// Only present if the file ends inside the table
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    truncated: bool,
+}
+
+impl<'a> From<&'a Table> for JsonTable<'a> {
+    fn from(table: &'a Table) -> Self {
+        Self {
+            columns: &table.header,
+            rows: &table.rows,
+            truncated: table.truncated,
+        }
+    }
+}
+
+/// Write each row of `rows` matching `filter` as a JSON object on its own
+/// line as it is read
+///
+/// Keys are the column names in file order or those of `projection`, values
+/// are strings as in the file, empty and cut off values are `null`.
+pub fn write_ndjson<R: BufRead, W: Write>(
+    rows: &mut TableReader<'_, R>,
+    filter: Option<&BoundFilter>,
+    projection: Option<&BoundProjection>,
+    mut out: W,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let columns = projection
+        .map_or(rows.columns(), BoundProjection::header)
+        .to_vec();
+    while let Some(row) = rows.next_row()? {
+        let values = row.values();
+        if filter.is_some_and(|filter| !filter.matches(values)) {
+            continue;
+        }
+        write_json_line(&mut out, &columns, values, projection)?;
+    }
+    Ok(())
+}
+
+/// Write the rows of `table` like [`write_ndjson`], e.g. after sorting
+pub fn write_ndjson_rows<W: Write>(
+    table: &Table,
+    projection: Option<&BoundProjection>,
+    mut out: W,
+) -> io::Result<()> {
+    let columns = projection.map_or(&table.header[..], BoundProjection::header);
+    for values in &table.rows {
+        write_json_line(&mut out, columns, values, projection)?;
+    }
+    Ok(())
+}
+
+fn write_json_line(
+    out: &mut impl Write,
+    columns: &[String],
+    values: &[String],
+    projection: Option<&BoundProjection>,
+) -> io::Result<()> {
+    match projection {
+        Some(projection) => serde_json::to_writer(
+            &mut *out,
+            &JsonRow {
+                columns,
+                values: &projection.select(values),
+            },
+        )?,
+        None => serde_json::to_writer(&mut *out, &JsonRow { columns, values })?,
+    }
+    out.write_all(b"\n")
+}
+
+/// Map of column name to value, see [`write_ndjson`]
+struct JsonRow<'a, V> {
+    columns: &'a [String],
+    values: &'a [V],
+}
+
+impl<V: AsRef<str>> Serialize for JsonRow<'_, V> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.columns.len()))?;
+        for (i, column) in self.columns.iter().enumerate() {
+            let value = self
+                .values
+                .get(i)
+                .map(AsRef::as_ref)
+                .filter(|value| !value.is_empty());
+            map.serialize_entry(column, &value)?;
+        }
+        map.end()
+    }
+}
+
+/// Write every table of `xer` to `TABLE.csv` in `dir`, quoted where needed
+///
+/// Rows with trailing values cut off are padded to the header. With
+/// `manifest` a `manifest.json` next to them lists the source, and per file
+/// the table, columns, row count and SHA-256 checksum.
+pub fn write_csv_dir(
+    xer: &XerFile,
+    source: &std::path::Path,
+    dir: &std::path::Path,
+    manifest: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use sha2::{Digest, Sha256};
+
+    #[derive(Serialize)]
+    struct Manifest<'a> {
+        source: String,
+        header: &'a Header,
+        files: Vec<ManifestFile<'a>>,
+    }
+
+    #[derive(Serialize)]
+    struct ManifestFile<'a> {
+        file: String,
+        table: &'a str,
+        columns: &'a [String],
+        rows: usize,
+        sha256: String,
+        #[serde(skip_serializing_if = "std::ops::Not::not")]
+        truncated: bool,
+    }
+
+    std::fs::create_dir_all(dir)?;
+    let mut files = Vec::new();
+    for table in xer.tables.values() {
+        let mut writer = csv::WriterBuilder::new()
+            .flexible(true)
+            .from_writer(Vec::new());
+        writer.write_record(&table.header)?;
+        let columns = table.header.len();
+        for row in &table.rows {
+            let padding = columns.saturating_sub(row.len());
+            writer.write_record(
+                row.iter()
+                    .map(String::as_str)
+                    .chain(std::iter::repeat_n("", padding)),
+            )?;
+        }
+        let data = writer.into_inner().map_err(|err| err.into_error())?;
+        let file = format!("{}.csv", table.name);
+        std::fs::write(dir.join(&file), &data)?;
+        let sha256 = Sha256::digest(&data)
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect();
+        files.push(ManifestFile {
+            file,
+            table: &table.name,
+            columns: &table.header,
+            rows: table.rows.len(),
+            sha256,
+            truncated: table.truncated,
+        });
+    }
+    if manifest {
+        let manifest = Manifest {
+            source: source
+                .file_name()
+                .map_or_else(String::new, |name| name.to_string_lossy().into_owned()),
+            header: &xer.header,
+            files,
+        };
+        let mut json = serde_json::to_vec_pretty(&manifest)?;
+        json.push(b'\n');
+        std::fs::write(dir.join("manifest.json"), json)?;
+    }
+    Ok(())
+}
+
+/// SQL database [`write_sql_script`] writes the load script for
+#[derive(Debug, Clone, Copy)]
+pub enum SqlDialect {
+    Duckdb,
+}
+
+/// Write `load.sql` to `dir` creating a typed table per CSV file of
+/// [`write_csv_dir`] and loading it, tables of the same name are replaced
+///
+/// Types are inferred by [`Table::column_types`], empty values load as
+/// `NULL`. File paths in the script are relative, run it from `dir`, e.g.
+/// `cd out && duckdb schedule.duckdb < load.sql`.
+pub fn write_sql_script(
+    xer: &XerFile,
+    dir: &std::path::Path,
+    dialect: SqlDialect,
+) -> std::io::Result<()> {
+    let mut sql = String::from("BEGIN TRANSACTION;\n");
+    for table in xer.tables.values().filter(|table| !table.header.is_empty()) {
+        let name = sql_identifier(&table.name);
+        let definitions: Vec<String> = table
+            .unique_columns()
+            .iter()
+            .zip(table.column_types())
+            .map(|(column, kind)| {
+                format!("  {} {}", sql_identifier(column), sql_type(dialect, kind))
+            })
+            .collect();
+        sql.push_str(&format!(
+            "CREATE OR REPLACE TABLE {name} (\n{}\n);\n",
+            definitions.join(",\n")
+        ));
+        let file = format!("{}.csv", table.name).replace('\'', "''");
+        match dialect {
+            SqlDialect::Duckdb => sql.push_str(&format!(
+                "COPY {name} FROM '{file}' (HEADER, TIMESTAMPFORMAT '%Y-%m-%d %H:%M');\n"
+            )),
+        }
+    }
+    sql.push_str("COMMIT;\n");
+    std::fs::write(dir.join("load.sql"), sql)
+}
+
+/// Column type of `dialect` for values of `kind`
+fn sql_type(dialect: SqlDialect, kind: ColumnType) -> &'static str {
+    match (dialect, kind) {
+        (SqlDialect::Duckdb, ColumnType::Integer) => "BIGINT",
+        (SqlDialect::Duckdb, ColumnType::Float) => "DOUBLE",
+        (SqlDialect::Duckdb, ColumnType::DateTime) => "TIMESTAMP",
+        (SqlDialect::Duckdb, ColumnType::Text) => "VARCHAR",
+    }
+}
+
+/// Write every table of `xer` to the DuckDB database at `path` with the
+/// column types of [`write_sql_script`]
+///
+/// Tables already in the database under the same name are replaced, all in
+/// one transaction. Empty values are `NULL`.
+#[cfg(feature = "duckdb")]
+pub fn write_duckdb(xer: &XerFile, path: &std::path::Path) -> duckdb::Result<()> {
+    use duckdb::types::{TimeUnit, Value};
+    use schedule_parser::model::parse_datetime;
+
+    let mut db = duckdb::Connection::open(path)?;
+    let tx = db.transaction()?;
+    for table in xer.tables.values().filter(|table| !table.header.is_empty()) {
+        let types = table.column_types();
+        let definitions: Vec<String> = table
+            .unique_columns()
+            .iter()
+            .zip(&types)
+            .map(|(column, &kind)| {
+                let sql_type = sql_type(SqlDialect::Duckdb, kind);
+                format!("{} {sql_type}", sql_identifier(column))
+            })
+            .collect();
+        tx.execute_batch(&format!(
+            "CREATE OR REPLACE TABLE {} ({});",
+            sql_identifier(&table.name),
+            definitions.join(", ")
+        ))?;
+
+        let mut appender = tx.appender(&table.name)?;
+        for row in &table.rows {
+            let values = types.iter().enumerate().map(|(i, kind)| {
+                let value = row.get(i).map_or("", String::as_str);
+                match kind {
+                    _ if value.is_empty() => Value::Null,
+                    ColumnType::Integer => value.parse().map_or(Value::Null, Value::BigInt),
+                    ColumnType::Float => value.parse().map_or(Value::Null, Value::Double),
+                    ColumnType::DateTime => parse_datetime(value).map_or(Value::Null, |date| {
+                        Value::Timestamp(TimeUnit::Microsecond, date.and_utc().timestamp_micros())
+                    }),
+                    ColumnType::Text => Value::Text(value.into()),
+                }
+            });
+            appender.append_row(duckdb::appender_params_from_iter(values))?;
+        }
+        appender.flush()?;
+    }
+    tx.commit()
+}
+
+/// SQL identifier in double quotes
+pub fn sql_identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+/// Write every table of `xer` to the SQLite database at `path` with inferred column types
+///
+/// Tables already in the database under the same name are replaced. Empty
+/// values are `NULL`, dates are kept as `2024-03-15 08:00` text which SQLite
+/// date functions accept. Columns ending in `_id` are indexed.
+#[cfg(feature = "sqlite")]
+pub fn write_sqlite(xer: &XerFile, path: &std::path::Path) -> rusqlite::Result<()> {
+    let mut db = rusqlite::Connection::open(path)?;
+    fill_sqlite(xer, &mut db)
+}
+
+/// Create and fill the tables of [`write_sqlite`] in `db`
+#[cfg(feature = "sqlite")]
+pub fn fill_sqlite(xer: &XerFile, db: &mut rusqlite::Connection) -> rusqlite::Result<()> {
+    use rusqlite::types::Value;
+
+    let tx = db.transaction()?;
+    for table in xer.tables.values().filter(|table| !table.header.is_empty()) {
+        let types = table.column_types();
+        let columns = table.unique_columns();
+        let name = sql_identifier(&table.name);
+        let definitions: Vec<String> = columns
+            .iter()
+            .zip(&types)
+            .map(|(column, kind)| {
+                let sql_type = match kind {
+                    ColumnType::Integer => "INTEGER",
+                    ColumnType::Float => "REAL",
+                    ColumnType::DateTime => "DATETIME",
+                    ColumnType::Text => "TEXT",
+                };
+                format!("{} {sql_type}", sql_identifier(column))
+            })
+            .collect();
+        tx.execute_batch(&format!(
+            "DROP TABLE IF EXISTS {name}; CREATE TABLE {name} ({});",
+            definitions.join(", ")
+        ))?;
+
+        let placeholders = vec!["?"; columns.len()].join(", ");
+        let mut insert = tx.prepare(&format!("INSERT INTO {name} VALUES ({placeholders})"))?;
+        for row in &table.rows {
+            let values = types.iter().enumerate().map(|(i, kind)| {
+                let value = row.get(i).map_or("", String::as_str);
+                match kind {
+                    _ if value.is_empty() => Value::Null,
+                    ColumnType::Integer => value.parse().map_or(Value::Null, Value::Integer),
+                    ColumnType::Float => value.parse().map_or(Value::Null, Value::Real),
+                    ColumnType::DateTime | ColumnType::Text => Value::Text(value.into()),
+                }
+            });
+            insert.execute(rusqlite::params_from_iter(values))?;
+        }
+
+        for column in columns.iter().filter(|column| column.ends_with("_id")) {
+            let index = sql_identifier(&format!("{}_{column}", table.name));
+            let column = sql_identifier(column);
+            tx.execute_batch(&format!("CREATE INDEX {index} ON {name} ({column});"))?;
+        }
+    }
+    tx.commit()
+}
+
+/// Rows per Parquet row group
+#[cfg(feature = "parquet")]
+const ROW_GROUP_ROWS: usize = 64 * 1024;
+
+/// Write every table of `xer` to `TABLE.parquet` in `dir`, see [`schedule_parser::arrow`]
+///
+/// Files are Snappy compressed.
+#[cfg(feature = "parquet")]
+pub fn write_parquet(
+    xer: &XerFile,
+    dir: &std::path::Path,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use parquet::arrow::ArrowWriter;
+    use parquet::basic::Compression;
+    use parquet::file::properties::WriterProperties;
+
+    std::fs::create_dir_all(dir)?;
+    for table in xer.tables.values().filter(|table| !table.header.is_empty()) {
+        let batch = table.to_record_batch()?;
+        let file = std::fs::File::create(dir.join(format!("{}.parquet", table.name)))?;
+        let properties = WriterProperties::builder()
+            .set_compression(Compression::SNAPPY)
+            .set_max_row_group_row_count(Some(ROW_GROUP_ROWS))
+            .build();
+        let mut writer = ArrowWriter::try_new(file, batch.schema(), Some(properties))?;
+        writer.write(&batch)?;
+        writer.close()?;
+    }
+    Ok(())
+}
+
+/// Write every table of `xer` to a worksheet of the workbook at `path`
+///
+/// The header row is bold and frozen. Numbers and dates are written as typed
+/// cells by [`Table::column_types`], empty values as blank cells. With
+/// `deterministic` the workbook is created at the export date of the file
+/// instead of now, so the same data gives the same bytes.
+#[cfg(feature = "xlsx")]
+pub fn write_xlsx(
+    xer: &XerFile,
+    path: &std::path::Path,
+    deterministic: bool,
+) -> Result<(), rust_xlsxwriter::XlsxError> {
+    use rust_xlsxwriter::{DocProperties, Format, Workbook};
+    use schedule_parser::model::parse_datetime;
+
+    let bold = Format::new().set_bold();
+    let date = Format::new().set_num_format("yyyy-mm-dd hh:mm");
+    let mut workbook = Workbook::new();
+    if deterministic {
+        workbook
+            .set_properties(&DocProperties::new().set_creation_datetime(&xer.header.export_date));
+    }
+    for table in xer.tables.values() {
+        let sheet = workbook.add_worksheet();
+        sheet.set_name(&table.name)?;
+        for (col, column) in table.header.iter().enumerate() {
+            sheet.write_string_with_format(0, col as u16, column, &bold)?;
+        }
+        sheet.set_freeze_panes(1, 0)?;
+
+        let types = table.column_types();
+        for (i, row) in table.rows.iter().enumerate() {
+            let row_number = i as u32 + 1;
+            for (col, (value, kind)) in row.iter().zip(&types).enumerate() {
+                let col = col as u16;
+                match kind {
+                    _ if value.is_empty() => continue,
+                    ColumnType::Integer | ColumnType::Float => match value.parse::<f64>() {
+                        Ok(number) => sheet.write_number(row_number, col, number)?,
+                        Err(_) => sheet.write_string(row_number, col, value)?,
+                    },
+                    ColumnType::DateTime => match parse_datetime(value) {
+                        Some(datetime) => {
+                            sheet.write_datetime_with_format(row_number, col, datetime, &date)?
+                        }
+                        None => sheet.write_string(row_number, col, value)?,
+                    },
+                    ColumnType::Text => sheet.write_string(row_number, col, value)?,
+                };
+            }
+        }
+        sheet.autofit();
+    }
+    workbook.save(path)
+}
@@ -0,0 +1,136 @@
+//! Load all tables into a PostgreSQL database through `psql`
+
+use std::io::{self, Write};
+use std::path::Path;
+use std::process::{Command, ExitCode, Stdio};
+
+use schedule_parser::projection::Projection;
+use schedule_parser::schema::ColumnType;
+use schedule_parser::Table;
+
+use crate::convert::sql_identifier;
+use crate::{open, warn_truncated, with_path, Result};
+
+/// What happens to tables already in the schema
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoadMode {
+    /// Create the tables, fail if one exists
+    Create,
+    /// Empty existing tables before loading, create missing ones
+    Truncate,
+    /// Add the rows to existing tables, create missing ones
+    Append,
+}
+
+/// Create a typed table in `schema` for every table of the file at `path`
+/// and `COPY` its rows, all in one transaction
+///
+/// The statements and rows are piped into `psql`, which must be on the
+/// `PATH`, connecting with `connection`, a connection string or URI. Types
+/// are inferred by [`Table::column_types`], empty values load as `NULL`.
+/// With `projection` the tables with all its columns keep only those.
+pub fn load_postgres(
+    path: &Path,
+    connection: &str,
+    schema: &str,
+    mode: LoadMode,
+    projection: Option<&Projection>,
+) -> Result<ExitCode> {
+    let mut xer = open(path)?;
+    warn_truncated(path, &xer);
+    if let Some(projection) = projection {
+        xer.project_columns(projection)
+            .map_err(|err| with_path(path, err))?;
+    }
+
+    let mut child = Command::new("psql")
+        .args([
+            "--no-psqlrc",
+            "--quiet",
+            "--single-transaction",
+            "--set=ON_ERROR_STOP=1",
+            "--file=-",
+            "--dbname",
+            connection,
+        ])
+        .stdin(Stdio::piped())
+        .spawn()
+        .map_err(|err| format!("cannot run psql: {err}"))?;
+    let stdin = child.stdin.take().expect("stdin is piped");
+    let mut sql = io::BufWriter::new(stdin);
+    let written = (|| -> io::Result<()> {
+        let schema = sql_identifier(schema);
+        // no notices for tables kept by `IF NOT EXISTS`
+        writeln!(sql, "SET client_min_messages = warning;")?;
+        writeln!(sql, "CREATE SCHEMA IF NOT EXISTS {schema};")?;
+        for table in xer.tables.values().filter(|table| !table.header.is_empty()) {
+            write_table(&mut sql, &schema, table, mode)?;
+        }
+        sql.flush()
+    })();
+    // closing stdin ends the script
+    drop(sql);
+    let status = child.wait()?;
+    if !status.success() {
+        return Err(format!("{}: psql failed, nothing was loaded", path.display()).into());
+    }
+    written.map_err(|err| with_path(path, err))?;
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Statements and CSV rows loading `table` into `schema`
+fn write_table(
+    out: &mut impl Write,
+    schema: &str,
+    table: &Table,
+    mode: LoadMode,
+) -> io::Result<()> {
+    let name = format!("{schema}.{}", sql_identifier(&table.name));
+    let columns: Vec<String> = table
+        .unique_columns()
+        .iter()
+        .map(|column| sql_identifier(column))
+        .collect();
+    let definitions: Vec<String> = columns
+        .iter()
+        .zip(table.column_types())
+        .map(|(column, kind)| {
+            let sql_type = match kind {
+                ColumnType::Integer => "BIGINT",
+                ColumnType::Float => "DOUBLE PRECISION",
+                ColumnType::DateTime => "TIMESTAMP",
+                ColumnType::Text => "TEXT",
+            };
+            format!("  {column} {sql_type}")
+        })
+        .collect();
+    let create = match mode {
+        LoadMode::Create => "CREATE TABLE",
+        LoadMode::Truncate | LoadMode::Append => "CREATE TABLE IF NOT EXISTS",
+    };
+    writeln!(out, "{create} {name} (\n{}\n);", definitions.join(",\n"))?;
+    if mode == LoadMode::Truncate {
+        writeln!(out, "TRUNCATE {name};")?;
+    }
+    writeln!(
+        out,
+        "COPY {name} ({}) FROM STDIN WITH (FORMAT csv);",
+        columns.join(", ")
+    )?;
+    let width = table.header.len();
+    for row in &table.rows {
+        for i in 0..width {
+            if i > 0 {
+                out.write_all(b",")?;
+            }
+            let value = row.get(i).map_or("", String::as_str);
+            // unquoted empty values are NULL, everything else is quoted so
+            // no value reads as the end of data marker `\.`
+            if !value.is_empty() {
+                write!(out, "\"{}\"", value.replace('"', "\"\""))?;
+            }
+        }
+        out.write_all(b"\n")?;
+    }
+    writeln!(out, "\\.")
+}
@@ -0,0 +1,2594 @@
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufWriter, Write};
+use std::iter;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use chrono::{NaiveDate, NaiveDateTime};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use schedule_parser::check::{CheckOptions, Exceedance};
+use schedule_parser::dot::DotOptions;
+use schedule_parser::evm::PercentComplete;
+use schedule_parser::filter::{BoundFilter, Filter};
+use schedule_parser::health::{HealthOptions, HealthScore};
+use schedule_parser::histogram::Period;
+use schedule_parser::ics::EventFilter;
+use schedule_parser::mermaid::GanttOptions;
+use schedule_parser::model::{Relationship, Task, WbsRollup, WbsTree};
+use schedule_parser::projection::{BoundProjection, Projection};
+use schedule_parser::scrub::ScrubOptions;
+use schedule_parser::slippage::summarize_by_wbs;
+use schedule_parser::sort::SortError;
+use schedule_parser::{
+    DecodeReader, Header, ParseError, ParseOptions, Table, TableIterator, TableReader,
+    UnknownRecords, XerFile, XerReader,
+};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+mod convert;
+mod load;
+#[cfg(feature = "sqlite")]
+mod query;
+mod roundtrip;
+
+use convert::SqlDialect;
+use load::LoadMode;
+
+/// Inspect, extract and convert Primavera P6 `.xer` files
+#[derive(Parser)]
+#[command(
+    version,
+    about,
+    after_help = "Files ending in `.xml` are read as P6 XML (PMXML) exports by all commands \
+                  but `roundtrip`.\n\n\
+                  Exit status is 0 on success, 1 if `validate` or `check` find problems, \
+                  `diff` finds differences, `assess` finds failed checks, `check-float` \
+                  finds float discrepancies or `roundtrip` does not reproduce the file, \
+                  and 2 on errors."
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Print export info, projects and a summary of all tables
+    Info {
+        file: PathBuf,
+        /// Print as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// List table names with their row counts
+    ListTables { file: PathBuf },
+    /// Write one table as CSV or TSV
+    Extract {
+        /// Table name, e.g. `TASK`
+        table: String,
+        file: PathBuf,
+        /// Output file, standard output if omitted
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Output format, by default taken from the output file extension or CSV
+        #[arg(long, value_enum)]
+        format: Option<ExtractFormat>,
+        #[command(flatten)]
+        rows: RowOptions,
+    },
+    /// Convert the whole file to another format
+    Convert {
+        file: PathBuf,
+        /// Output format
+        #[arg(long, value_enum)]
+        to: Format,
+        /// Output file, standard output if omitted
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Output directory for formats with one file per table
+        #[arg(long)]
+        out_dir: Option<PathBuf>,
+        #[command(flatten)]
+        options: ConvertOptions,
+    },
+    /// Report malformed records, fails if any are found
+    Validate { file: PathBuf },
+    /// Compare activities, logic and assignments of two versions by activity ID
+    Diff {
+        old: PathBuf,
+        new: PathBuf,
+        /// Output format, text has one line per change, e.g. `~ A1000 planned_duration: 40 -> 56`
+        #[arg(long, value_enum, default_value_t = ReportFormat::Text)]
+        format: ReportFormat,
+        /// Output file, standard output if omitted
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Only compare tables and their row counts
+        #[arg(long, conflicts_with_all = ["format", "output"])]
+        tables: bool,
+        /// Only list tables and columns one of the files lacks
+        #[arg(long, conflicts_with_all = ["format", "output", "tables"])]
+        schema: bool,
+    },
+    /// Parse and rewrite the file, report byte and content differences
+    Roundtrip { file: PathBuf },
+    /// Replace names, users, user fields and notebooks with placeholders
+    Scrub {
+        file: PathBuf,
+        /// Output file, standard output if omitted
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Also set costs and rates to 0
+        #[arg(long)]
+        costs: bool,
+    },
+    /// Write one file per project named by its short name
+    Split {
+        file: PathBuf,
+        /// Partition by project, the only mode so far
+        #[arg(long, required = true)]
+        by_project: bool,
+        /// Output directory, the current directory if omitted
+        #[arg(long, default_value = ".")]
+        out_dir: PathBuf,
+        /// Keep relationships to activities of other projects
+        #[arg(long)]
+        keep_cross_project: bool,
+    },
+    /// Write the file without some tables and columns
+    Trim {
+        file: PathBuf,
+        /// Output file, standard output if omitted
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Tables to remove, e.g. `TASKMEMO,POBS`
+        #[arg(long, value_delimiter = ',')]
+        drop_tables: Vec<String>,
+        /// Columns to remove as `TABLE.column`, e.g. `TASK.task_memo`
+        #[arg(long, value_delimiter = ',')]
+        drop_columns: Vec<String>,
+    },
+    /// Renumber internal ids from 1 and rewrite all references to them
+    Renumber {
+        file: PathBuf,
+        /// Output file, standard output if omitted
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Move all dates by a number of days
+    #[command(group = clap::ArgGroup::new("offset").required(true))]
+    Shift {
+        file: PathBuf,
+        /// Output file, standard output if omitted
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Calendar days to move by, negative moves back
+        #[arg(long, group = "offset", allow_hyphen_values = true)]
+        days: Option<i64>,
+        /// Move so the data date of the first project becomes this date
+        #[arg(long, group = "offset", value_name = "YYYY-MM-DD")]
+        to_data_date: Option<NaiveDate>,
+    },
+    /// Convert all costs and rates to another base currency
+    Currency {
+        file: PathBuf,
+        /// Output file, standard output if omitted
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Units of the new currency per unit of the current one
+        #[arg(long)]
+        rate: f64,
+        /// Currency ID of the new currency, e.g. `USD`
+        #[arg(long)]
+        to: String,
+    },
+    /// Remove actuals and reset activities to not started
+    Deprogress {
+        file: PathBuf,
+        /// Output file, standard output if omitted
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Write activities finishing later in the update than in the baseline as CSV
+    Slippage {
+        baseline: PathBuf,
+        update: PathBuf,
+        /// Only activities slipping more than this many calendar days
+        #[arg(long, default_value_t = 0.0)]
+        threshold: f64,
+        /// Output file, standard output if omitted
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Write slipped activities and worst slip per WBS element instead
+        #[arg(long)]
+        by_wbs: bool,
+    },
+    /// Write earned value per WBS element of the update against the baseline
+    /// as CSV
+    EarnedValue {
+        baseline: PathBuf,
+        update: PathBuf,
+        /// Percent complete earning the budget, `activity` uses the percent
+        /// complete type of each activity
+        #[arg(long, value_enum, default_value_t = PercentCompleteType::Activity)]
+        percent_complete: PercentCompleteType,
+        /// Output file, standard output if omitted
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Write the activities on the longest path to each project finish as CSV
+    LongestPath {
+        file: PathBuf,
+        /// Output file, standard output if omitted
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Recompute early and late dates and floats and write them next to the
+    /// dates P6 exported as CSV
+    Schedule {
+        file: PathBuf,
+        /// Output file, standard output if omitted
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Only activities whose dates or total float differ from P6
+        #[arg(long)]
+        differences: bool,
+    },
+    /// Report open ends, negative float, logic loops, out of sequence
+    /// progress, long durations and high float
+    Check {
+        file: PathBuf,
+        /// JSON file with limits, e.g. `{"max_remaining_days": 20}`
+        #[arg(long)]
+        config: Option<PathBuf>,
+        /// Flag remaining durations over this many work days [default: 44]
+        #[arg(long)]
+        max_remaining_days: Option<f64>,
+        /// Flag total float over this many work days [default: 44]
+        #[arg(long)]
+        max_float_days: Option<f64>,
+    },
+    /// Score the checks into one weighted health score per file, successive
+    /// updates show the trend
+    Health {
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+        /// JSON file with limits and weights, e.g.
+        /// `{"weights": {"open_ends": 2}, "checks": {"max_float_days": 20}}`
+        #[arg(long)]
+        config: Option<PathBuf>,
+        /// Output format
+        #[arg(long, value_enum, default_value_t = ReportFormat::Text)]
+        format: ReportFormat,
+        /// Output file, standard output if omitted
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Roll dates, durations, costs and percent complete up the WBS
+    Rollup {
+        file: PathBuf,
+        /// Only elements down to this depth, `0` for project nodes
+        #[arg(long)]
+        depth: Option<usize>,
+        /// Output format
+        #[arg(long, value_enum, default_value_t = ReportFormat::Text)]
+        format: ReportFormat,
+        /// Output file, standard output if omitted
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Summarize lags and leads per link type and list relationships with lag
+    Lags {
+        file: PathBuf,
+        /// Flag lags over this many hours
+        #[arg(long)]
+        max_lag: Option<f64>,
+        /// Output format, CSV lists only the relationships
+        #[arg(long, value_enum, default_value_t = ReportFormat::Text)]
+        format: ReportFormat,
+        /// Output file, standard output if omitted
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// List constrained activities grouped by constraint type
+    Constraints {
+        file: PathBuf,
+        /// Output format
+        #[arg(long, value_enum, default_value_t = ReportFormat::Text)]
+        format: ReportFormat,
+        /// Output file, standard output if omitted
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Export resource units per period for histograms
+    Histogram {
+        file: PathBuf,
+        /// Length of a period, weeks start on Monday
+        #[arg(long, value_enum, default_value_t = HistogramPeriod::Week)]
+        period: HistogramPeriod,
+        /// Output format
+        #[arg(long, value_enum, default_value_t = DataFormat::Csv)]
+        format: DataFormat,
+        /// Output file, standard output if omitted
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Assess the schedule quality
+    Assess {
+        file: PathBuf,
+        /// Run the DCMA 14-point assessment
+        #[arg(long, required = true)]
+        dcma: bool,
+        /// Output format
+        #[arg(long, value_enum, default_value_t = ReportFormat::Text)]
+        format: ReportFormat,
+        /// Output file, standard output if omitted
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// List activities whose total float differs from the recomputed one
+    CheckFloat {
+        file: PathBuf,
+        /// Ignore differences up to this many hours
+        #[arg(long, default_value_t = 1.0)]
+        tolerance: f64,
+    },
+    /// Run an SQL query over the tables in an in-memory SQLite database, e.g.
+    /// `SELECT task_code FROM TASK WHERE total_float_hr_cnt < 0`
+    #[cfg(feature = "sqlite")]
+    Query {
+        sql: String,
+        file: PathBuf,
+        /// Output format
+        #[arg(long, value_enum, default_value_t = ReportFormat::Text)]
+        format: ReportFormat,
+        /// Output file, standard output if omitted
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Load all tables into typed tables of a PostgreSQL schema with `psql`
+    Load {
+        file: PathBuf,
+        /// Connection string or URI passed to `psql`, e.g.
+        /// `postgresql://user@host/db`
+        #[arg(long, required = true)]
+        postgres: String,
+        /// Schema to create the tables in, created if missing
+        #[arg(long, default_value = "xer")]
+        schema: String,
+        /// Empty existing tables before loading instead of failing
+        #[arg(long, conflicts_with = "append")]
+        truncate: bool,
+        /// Add the rows to existing tables instead of failing
+        #[arg(long)]
+        append: bool,
+        /// Only these columns in the tables with all of them, each
+        /// optionally renamed with `:`, e.g. `task_code,target_end_date:finish`
+        #[arg(long, value_name = "COLUMNS")]
+        columns: Option<Projection>,
+    },
+    /// Write an HTML report for viewing in a browser
+    Report {
+        file: PathBuf,
+        /// Gantt chart grouped by WBS with critical activities highlighted
+        #[arg(long, required = true)]
+        gantt: bool,
+        /// Output file, standard output if omitted
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Write the activity network as Graphviz DOT, boxes filled by total float
+    Dot {
+        file: PathBuf,
+        /// Only critical activities
+        #[arg(long)]
+        critical: bool,
+        /// Only activities of this WBS element and below, by code from the
+        /// project node, e.g. `PRJ-A.1.2`
+        #[arg(long)]
+        wbs: Option<String>,
+        /// Only activities near this activity ID
+        #[arg(long)]
+        around: Option<String>,
+        /// Relationships to follow from `--around` in either direction
+        #[arg(long, default_value_t = 2, requires = "around")]
+        hops: usize,
+        /// Output file, standard output if omitted
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Write a Mermaid Gantt diagram of the activities for Markdown
+    Mermaid {
+        file: PathBuf,
+        /// Only activities of this WBS element and below, by code from the
+        /// project node, e.g. `PRJ-A.1.2`
+        #[arg(long)]
+        wbs: Option<String>,
+        /// Only critical activities
+        #[arg(long)]
+        critical: bool,
+        /// Only milestones
+        #[arg(long)]
+        milestones: bool,
+        /// Draw only this many activities, those starting first
+        #[arg(long, default_value_t = 100)]
+        max_activities: usize,
+        /// Output file, standard output if omitted
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Combine files into one, renumbering colliding ids and sharing
+    /// calendars, resources, UDF types and other dictionaries
+    Merge {
+        #[arg(required = true, num_args = 2..)]
+        files: Vec<PathBuf>,
+        /// Output file, standard output if omitted
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
+/// Options of `convert` for some formats only
+#[derive(Args)]
+struct ConvertOptions {
+    /// Indent JSON output
+    #[arg(long)]
+    pretty: bool,
+    /// Activities to write as events, only for `--to ics`, all by default
+    #[arg(long, value_enum)]
+    filter: Option<IcsFilter>,
+    /// Also write `manifest.json` with row counts and SHA-256 checksums,
+    /// only for `--to csv`
+    #[arg(long)]
+    manifest: bool,
+    /// Also write `load.sql` creating typed tables and loading the files
+    /// into this database, only for `--to csv`
+    #[arg(long, value_enum)]
+    sql: Option<SqlScript>,
+    /// Table to write, only and required for `--to ndjson`
+    #[arg(long)]
+    table: Option<String>,
+    #[command(flatten)]
+    rows: RowOptions,
+}
+
+/// Row selection and order of `extract` and `convert`, applied to the
+/// tables with the named columns
+#[derive(Args)]
+struct RowOptions {
+    /// Only rows matching this expression, e.g.
+    /// `status_code = 'TK_Active' && target_end_date < 2025-01-01`
+    #[arg(long = "where", value_name = "EXPR")]
+    condition: Option<Filter>,
+    /// Only these columns in this order, each optionally renamed with `:`,
+    /// e.g. `task_code,task_name,target_end_date:finish`, not for `--to
+    /// mspdi`, `pmxml` or `ics`
+    #[arg(long, value_name = "COLUMNS")]
+    columns: Option<Projection>,
+    /// Sort rows by this column, as numbers or dates if all its values are,
+    /// empty values last
+    #[arg(long, value_name = "COLUMN")]
+    sort_by: Option<String>,
+    /// Sort in descending order
+    #[arg(long, requires = "sort_by")]
+    desc: bool,
+    /// Sort tables by name and rows by all columns first, so the same data
+    /// always gives the same output, `--sort-by` then orders by its column
+    #[arg(long)]
+    deterministic: bool,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    Json,
+    /// Microsoft Project XML
+    Mspdi,
+    /// Primavera P6 XML
+    Pmxml,
+    /// iCalendar with one event per activity, see `--filter`
+    Ics,
+    /// One CSV file per table, needs `--out-dir`, see `--manifest`
+    Csv,
+    /// One JSON object per row of the `--table`, streamed while reading
+    Ndjson,
+    /// DuckDB database with typed tables, needs `-o`
+    #[cfg(feature = "duckdb")]
+    Duckdb,
+    /// One Parquet file per table, needs `--out-dir`
+    #[cfg(feature = "parquet")]
+    Parquet,
+    /// SQLite database, needs `-o`
+    #[cfg(feature = "sqlite")]
+    Sqlite,
+    /// Excel workbook with one worksheet per table, needs `-o`
+    #[cfg(feature = "xlsx")]
+    Xlsx,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum SqlScript {
+    /// Run with `duckdb FILE < load.sql` from the output directory
+    Duckdb,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ReportFormat {
+    /// Plain text lines for reading in a terminal
+    Text,
+    Csv,
+    Json,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum DataFormat {
+    Csv,
+    Json,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum HistogramPeriod {
+    Day,
+    Week,
+    Month,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum IcsFilter {
+    All,
+    Milestones,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum PercentCompleteType {
+    Activity,
+    Duration,
+    Physical,
+    Units,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ExtractFormat {
+    Csv,
+    Tsv,
+    /// GitHub-flavored Markdown table
+    Md,
+}
+
+impl ExtractFormat {
+    /// Format for `path` by its extension, CSV if unknown
+    fn for_path(path: Option<&Path>) -> Self {
+        match path.and_then(Path::extension) {
+            Some(ext) if ext.eq_ignore_ascii_case("tsv") => Self::Tsv,
+            Some(ext) if ext.eq_ignore_ascii_case("md") => Self::Md,
+            _ => Self::Csv,
+        }
+    }
+}
+
+type Result<T> = std::result::Result<T, Box<dyn Error>>;
+
+fn main() -> ExitCode {
+    match run(Cli::parse().command) {
+        Ok(code) => code,
+        // output piped into e.g. `head` was closed
+        Err(err) if is_broken_pipe(&*err) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::from(2)
+        }
+    }
+}
+
+fn run(command: Command) -> Result<ExitCode> {
+    match command {
+        Command::Info { file, json } => info(&file, json),
+        Command::ListTables { file } => list_tables(&file),
+        Command::Extract {
+            table,
+            file,
+            output,
+            format,
+            rows,
+        } => {
+            let format = format.unwrap_or_else(|| ExtractFormat::for_path(output.as_deref()));
+            extract(&table, &file, output.as_deref(), format, &rows)
+        }
+        Command::Convert {
+            file,
+            to,
+            output,
+            out_dir,
+            options,
+        } => convert(&file, to, output.as_deref(), out_dir.as_deref(), &options),
+        Command::Validate { file } => validate(&file),
+        Command::Diff {
+            old,
+            new,
+            format,
+            output,
+            tables,
+            schema,
+        } => {
+            if tables {
+                diff_tables(&old, &new)
+            } else if schema {
+                diff_schema(&old, &new)
+            } else {
+                diff(&old, &new, format, output.as_deref())
+            }
+        }
+        Command::Roundtrip { file } => roundtrip::roundtrip(&file),
+        Command::Scrub {
+            file,
+            output,
+            costs,
+        } => scrub(&file, output.as_deref(), costs),
+        Command::Split {
+            file,
+            by_project: _,
+            out_dir,
+            keep_cross_project,
+        } => split(&file, &out_dir, keep_cross_project),
+        Command::Trim {
+            file,
+            output,
+            drop_tables,
+            drop_columns,
+        } => trim(&file, output.as_deref(), &drop_tables, &drop_columns),
+        Command::Renumber { file, output } => renumber(&file, output.as_deref()),
+        Command::Shift {
+            file,
+            output,
+            days,
+            to_data_date,
+        } => shift(&file, output.as_deref(), days, to_data_date),
+        Command::Currency {
+            file,
+            output,
+            rate,
+            to,
+        } => currency(&file, output.as_deref(), rate, &to),
+        Command::Deprogress { file, output } => deprogress(&file, output.as_deref()),
+        Command::Slippage {
+            baseline,
+            update,
+            threshold,
+            output,
+            by_wbs,
+        } => slippage(&baseline, &update, threshold, output.as_deref(), by_wbs),
+        Command::EarnedValue {
+            baseline,
+            update,
+            percent_complete,
+            output,
+        } => earned_value(&baseline, &update, percent_complete, output.as_deref()),
+        Command::LongestPath { file, output } => longest_path(&file, output.as_deref()),
+        Command::Schedule {
+            file,
+            output,
+            differences,
+        } => schedule(&file, output.as_deref(), differences),
+        Command::Check {
+            file,
+            config,
+            max_remaining_days,
+            max_float_days,
+        } => {
+            let mut options = match config {
+                Some(config) => read_options(&config)?,
+                None => CheckOptions::default(),
+            };
+            if let Some(days) = max_remaining_days {
+                options.max_remaining_days = days;
+            }
+            if let Some(days) = max_float_days {
+                options.max_float_days = days;
+            }
+            check(&file, &options)
+        }
+        Command::Health {
+            files,
+            config,
+            format,
+            output,
+        } => {
+            let options = match config {
+                Some(config) => read_options(&config)?,
+                None => HealthOptions::default(),
+            };
+            health(&files, &options, format, output.as_deref())
+        }
+        Command::Rollup {
+            file,
+            depth,
+            format,
+            output,
+        } => rollup(&file, depth, format, output.as_deref()),
+        Command::Lags {
+            file,
+            max_lag,
+            format,
+            output,
+        } => lags(&file, max_lag, format, output.as_deref()),
+        Command::Constraints {
+            file,
+            format,
+            output,
+        } => constraints(&file, format, output.as_deref()),
+        Command::Histogram {
+            file,
+            period,
+            format,
+            output,
+        } => histogram(&file, period, format, output.as_deref()),
+        Command::Assess {
+            file,
+            dcma: _,
+            format,
+            output,
+        } => assess(&file, format, output.as_deref()),
+        Command::CheckFloat { file, tolerance } => check_float(&file, tolerance),
+        #[cfg(feature = "sqlite")]
+        Command::Query {
+            sql,
+            file,
+            format,
+            output,
+        } => query::query(&sql, &file, format, output.as_deref()),
+        Command::Load {
+            file,
+            postgres,
+            schema,
+            truncate,
+            append,
+            columns,
+        } => {
+            let mode = if truncate {
+                LoadMode::Truncate
+            } else if append {
+                LoadMode::Append
+            } else {
+                LoadMode::Create
+            };
+            load::load_postgres(&file, &postgres, &schema, mode, columns.as_ref())
+        }
+        Command::Report {
+            file,
+            gantt: _,
+            output,
+        } => report(&file, output.as_deref()),
+        Command::Dot {
+            file,
+            critical,
+            wbs,
+            around,
+            hops,
+            output,
+        } => dot(
+            &file,
+            critical,
+            wbs.as_deref(),
+            around.as_deref().map(|code| (code, hops)),
+            output.as_deref(),
+        ),
+        Command::Mermaid {
+            file,
+            wbs,
+            critical,
+            milestones,
+            max_activities,
+            output,
+        } => mermaid(
+            &file,
+            wbs.as_deref(),
+            critical,
+            milestones,
+            max_activities,
+            output.as_deref(),
+        ),
+        Command::Merge { files, output } => merge(&files, output.as_deref()),
+    }
+}
+
+/// Summary printed by `info`
+#[derive(Serialize)]
+struct Info<'a> {
+    header: &'a Header,
+    projects: Vec<ProjectInfo>,
+    tables: Vec<TableInfo<'a>>,
+}
+
+#[derive(Serialize)]
+struct ProjectInfo {
+    proj_id: u64,
+    proj_short_name: String,
+    data_date: Option<NaiveDateTime>,
+}
+
+#[derive(Serialize)]
+struct TableInfo<'a> {
+    name: &'a str,
+    columns: usize,
+    rows: usize,
+    truncated: bool,
+}
+
+fn info(path: &Path, json: bool) -> Result<ExitCode> {
+    let xer = open(path)?;
+    let projects = xer
+        .projects()
+        .map_err(|err| with_path(path, err))?
+        .into_iter()
+        .map(|project| ProjectInfo {
+            proj_id: project.proj_id,
+            proj_short_name: project.proj_short_name,
+            data_date: project.data_date,
+        })
+        .collect();
+    let tables = xer
+        .tables
+        .values()
+        .map(|table| TableInfo {
+            name: &table.name,
+            columns: table.header.len(),
+            rows: table.row_count(),
+            truncated: table.truncated,
+        })
+        .collect();
+    let info = Info {
+        header: &xer.header,
+        projects,
+        tables,
+    };
+    if json {
+        let mut out = io::stdout().lock();
+        serde_json::to_writer_pretty(&mut out, &info)?;
+        writeln!(out)?;
+    } else {
+        print_info(&info);
+    }
+    warn_truncated(path, &xer);
+    Ok(ExitCode::SUCCESS)
+}
+
+fn print_info(info: &Info) {
+    let header = info.header;
+    println!(
+        "P6 {} {} export by {} ({}) at {}",
+        header.version, header.export_type, header.user, header.user_name, header.export_date
+    );
+    print!(
+        "database {}, {}, currency {}",
+        header.database, header.module, header.currency
+    );
+    match &header.codepage {
+        Some(codepage) => println!(", code page {codepage}"),
+        None => println!(),
+    }
+
+    println!("\n{} projects", info.projects.len());
+    for project in &info.projects {
+        let data_date = project
+            .data_date
+            .map_or("-".into(), |date| date.format("%Y-%m-%d %H:%M").to_string());
+        println!(
+            "{:>15} {:>8} data date {data_date}",
+            project.proj_short_name, project.proj_id
+        );
+    }
+
+    println!("\n{} tables", info.tables.len());
+    for table in &info.tables {
+        println!(
+            "{:>15} {:>3} columns {:>6} rows{}",
+            table.name,
+            table.columns,
+            table.rows,
+            if table.truncated { ", truncated" } else { "" }
+        );
+    }
+}
+
+/// Row counts from a quick prescan, tables are not parsed
+fn list_tables(path: &Path) -> Result<ExitCode> {
+    if is_pmxml(path) {
+        let xer = open(path)?;
+        let mut out = io::stdout().lock();
+        for (name, table) in &xer.tables {
+            writeln!(out, "{name}\t{}", table.rows.len())?;
+        }
+        return Ok(ExitCode::SUCCESS);
+    }
+    let reader = XerReader::from_path(path)
+        .and_then(XerReader::prescan)
+        .map_err(|err| with_path(path, err))?;
+    let mut out = io::stdout().lock();
+    for (name, rows) in reader.row_counts().unwrap_or_default() {
+        writeln!(out, "{name}\t{rows}")?;
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Rows are streamed unless sorted, fields with delimiters, quotes or line
+/// breaks are quoted
+fn extract(
+    table: &str,
+    path: &Path,
+    output: Option<&Path>,
+    format: ExtractFormat,
+    options: &RowOptions,
+) -> Result<ExitCode> {
+    let mut tables = stream_tables(path, table)?;
+    let Some(mut rows) = table_rows(path, table, tables.as_mut())? else {
+        return Err(format!("{}: no table {table}", path.display()).into());
+    };
+    let condition = bind(path, table, options.condition.as_ref(), rows.columns())?;
+    let projection = project(path, table, options.columns.as_ref(), rows.columns())?;
+    let mut sorted = sort_buffer(path, table, options, rows.columns())?;
+    let header = projection
+        .as_ref()
+        .map_or(rows.columns(), BoundProjection::header);
+    let mut out = RowWriter::new(format, output, header)?;
+    let mut write = |values: &[String]| match &projection {
+        Some(projection) => out.write(&projection.select(values)),
+        None => out.write(values),
+    };
+    while let Some(values) = rows.next_row().map_err(|err| with_path(path, err))? {
+        if condition
+            .as_ref()
+            .is_some_and(|filter| !filter.matches(values))
+        {
+            continue;
+        }
+        match &mut sorted {
+            Some(sorted) => sorted.rows.push(values.to_vec()),
+            None => write(values)?,
+        }
+    }
+    if let Some(mut sorted) = sorted {
+        sort_table(path, &mut sorted, options)?;
+        for values in &sorted.rows {
+            write(values)?;
+        }
+    }
+    out.finish()?;
+    warn_truncated_table(path, table, rows.is_truncated());
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Rows of `extract` in one of its formats, under a header
+enum RowWriter {
+    Delimited {
+        out: Box<csv::Writer<Box<dyn Write>>>,
+        columns: usize,
+    },
+    Markdown {
+        out: Box<dyn Write>,
+        columns: usize,
+    },
+}
+
+impl RowWriter {
+    fn new(format: ExtractFormat, output: Option<&Path>, header: &[String]) -> Result<Self> {
+        let delimiter = match format {
+            ExtractFormat::Csv => b',',
+            ExtractFormat::Tsv => b'\t',
+            ExtractFormat::Md => {
+                let mut out = create(output)?;
+                markdown_row(&mut out, header)?;
+                writeln!(out, "|{}", " --- |".repeat(header.len()))?;
+                return Ok(Self::Markdown {
+                    out,
+                    columns: header.len(),
+                });
+            }
+        };
+        let mut out = csv::WriterBuilder::new()
+            .delimiter(delimiter)
+            .flexible(true)
+            .from_writer(create(output)?);
+        out.write_record(header)?;
+        Ok(Self::Delimited {
+            out: Box::new(out),
+            columns: header.len(),
+        })
+    }
+
+    /// Rows with trailing values cut off are padded to the header
+    fn write(&mut self, values: &[impl AsRef<str>]) -> Result<()> {
+        match self {
+            // extra values are kept
+            Self::Delimited { out, columns } => {
+                let padding = columns.saturating_sub(values.len());
+                out.write_record(
+                    values
+                        .iter()
+                        .map(AsRef::as_ref)
+                        .chain(iter::repeat_n("", padding)),
+                )?;
+            }
+            // values past the header have no column to go to
+            Self::Markdown { out, columns } => {
+                let padding = columns.saturating_sub(values.len());
+                let cells: Vec<&str> = values
+                    .iter()
+                    .take(*columns)
+                    .map(AsRef::as_ref)
+                    .chain(iter::repeat_n("", padding))
+                    .collect();
+                markdown_row(out, &cells)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn finish(self) -> Result<()> {
+        match self {
+            Self::Delimited { mut out, .. } => out.flush()?,
+            Self::Markdown { mut out, .. } => out.flush()?,
+        }
+        Ok(())
+    }
+}
+
+/// `condition` bound to the `columns` of `table`, if given
+fn bind(
+    path: &Path,
+    table: &str,
+    condition: Option<&Filter>,
+    columns: &[String],
+) -> Result<Option<BoundFilter>> {
+    condition
+        .map(|condition| condition.bind(columns))
+        .transpose()
+        .map_err(|err| format!("{}: table {table}: {err}", path.display()).into())
+}
+
+/// Tables of the `.xer` file at `path` read one by one, only `table`,
+/// `None` for PMXML files which are [loaded whole](open)
+fn stream_tables(path: &Path, table: &str) -> Result<Option<TableIterator<DecodeReader<File>>>> {
+    if is_pmxml(path) {
+        return Ok(None);
+    }
+    let reader = XerReader::from_path(path).map_err(|err| with_path(path, err))?;
+    Ok(Some(reader.only_tables(&[table]).tables()))
+}
+
+/// Rows of `table` from `tables`, or from the PMXML file at `path` without them
+fn table_rows<'a, R: BufRead>(
+    path: &Path,
+    table: &str,
+    tables: Option<&'a mut TableIterator<R>>,
+) -> Result<Option<TableRows<'a, R>>> {
+    let Some(tables) = tables else {
+        let mut xer = open(path)?;
+        return Ok(xer
+            .tables
+            .shift_remove(table)
+            .map(|table| TableRows::Loaded { table, next: 0 }));
+    };
+    let rows = tables.next_table().map_err(|err| with_path(path, err))?;
+    Ok(rows.map(TableRows::Streamed))
+}
+
+/// Rows of one table, read as they come or of a file loaded whole
+enum TableRows<'a, R> {
+    Streamed(TableReader<'a, R>),
+    Loaded { table: Table, next: usize },
+}
+
+impl<R: BufRead> TableRows<'_, R> {
+    fn columns(&self) -> &[String] {
+        match self {
+            Self::Streamed(rows) => rows.columns(),
+            Self::Loaded { table, .. } => &table.header,
+        }
+    }
+
+    fn next_row(&mut self) -> std::result::Result<Option<&[String]>, ParseError> {
+        match self {
+            Self::Streamed(rows) => Ok(rows.next_row()?.map(|row| row.values())),
+            Self::Loaded { table, next } => {
+                let row = table.rows.get(*next);
+                *next += 1;
+                Ok(row.map(Vec::as_slice))
+            }
+        }
+    }
+
+    fn is_truncated(&self) -> bool {
+        match self {
+            Self::Streamed(rows) => rows.is_truncated(),
+            Self::Loaded { table, .. } => table.truncated,
+        }
+    }
+}
+
+/// Empty table to collect the rows of `table` in if `options` sort them,
+/// checking the sort column is among `columns` before anything is written
+fn sort_buffer(
+    path: &Path,
+    table: &str,
+    options: &RowOptions,
+    columns: &[String],
+) -> Result<Option<Table>> {
+    if let Some(column) = &options.sort_by {
+        if !columns.contains(column) {
+            let err = SortError::MissingColumn {
+                column: column.clone(),
+            };
+            return Err(format!("{}: table {table}: {err}", path.display()).into());
+        }
+    }
+    Ok((options.sort_by.is_some() || options.deterministic)
+        .then(|| Table::new(table.to_string(), columns.to_vec(), Vec::new())))
+}
+
+/// `table` sorted by [`RowOptions::deterministic`] then [`RowOptions::sort_by`]
+fn sort_table(path: &Path, table: &mut Table, options: &RowOptions) -> Result<()> {
+    if options.deterministic {
+        table.sort_canonical();
+    }
+    if let Some(column) = &options.sort_by {
+        table
+            .sort_by_column(column, options.desc)
+            .map_err(|err| format!("{}: table {}: {err}", path.display(), table.name))?;
+    }
+    Ok(())
+}
+
+/// `projection` bound to the `columns` of `table`, if given
+fn project(
+    path: &Path,
+    table: &str,
+    projection: Option<&Projection>,
+    columns: &[String],
+) -> Result<Option<BoundProjection>> {
+    projection
+        .map(|projection| projection.bind(columns))
+        .transpose()
+        .map_err(|err| format!("{}: table {table}: {err}", path.display()).into())
+}
+
+/// Table row with pipes escaped and line breaks as `<br>`
+fn markdown_row(out: &mut dyn Write, cells: &[impl AsRef<str>]) -> io::Result<()> {
+    write!(out, "|")?;
+    for cell in cells {
+        let cell = cell
+            .as_ref()
+            .replace('|', "\\|")
+            .replace("\r\n", "<br>")
+            .replace('\n', "<br>");
+        write!(out, " {cell} |")?;
+    }
+    writeln!(out)
+}
+
+fn warn_truncated_table(path: &Path, table: &str, truncated: bool) {
+    if truncated {
+        eprintln!(
+            "{}: file ends inside table {table} without %E",
+            path.display()
+        );
+    }
+}
+
+fn convert(
+    path: &Path,
+    to: Format,
+    output: Option<&Path>,
+    out_dir: Option<&Path>,
+    options: &ConvertOptions,
+) -> Result<ExitCode> {
+    if options.filter.is_some() && !matches!(to, Format::Ics) {
+        return Err("--filter only applies to --to ics".into());
+    }
+    if options.manifest && !matches!(to, Format::Csv) {
+        return Err("--manifest only applies to --to csv".into());
+    }
+    if options.sql.is_some() && !matches!(to, Format::Csv) {
+        return Err("--sql only applies to --to csv".into());
+    }
+    if options.table.is_some() && !matches!(to, Format::Ndjson) {
+        return Err("--table only applies to --to ndjson".into());
+    }
+    if options.rows.columns.is_some() && matches!(to, Format::Mspdi | Format::Pmxml | Format::Ics) {
+        return Err("--columns does not apply to --to mspdi, pmxml or ics".into());
+    }
+    if let Format::Ndjson = to {
+        if out_dir.is_some() {
+            return Err("--to ndjson writes a single file, use -o instead of --out-dir".into());
+        }
+        let table = options
+            .table
+            .as_deref()
+            .ok_or("--to ndjson needs a table, --table")?;
+        return ndjson(path, table, &options.rows, output);
+    }
+    let mut xer = open(path)?;
+    if options.rows.deterministic {
+        xer.sort_canonical();
+    }
+    if let Some(condition) = &options.rows.condition {
+        xer.filter_rows(condition)
+            .map_err(|err| with_path(path, err))?;
+    }
+    if let Some(column) = &options.rows.sort_by {
+        xer.sort_rows(column, options.rows.desc)
+            .map_err(|err| with_path(path, err))?;
+    }
+    if let Some(projection) = &options.rows.columns {
+        xer.project_columns(projection)
+            .map_err(|err| with_path(path, err))?;
+    }
+    match to {
+        Format::Json => {
+            if out_dir.is_some() {
+                return Err("--to json writes a single file, use -o instead of --out-dir".into());
+            }
+            let mut out = create(output)?;
+            convert::write_json(&xer, &mut out, options.pretty)?;
+            writeln!(out)?;
+            out.flush()?;
+        }
+        Format::Mspdi => {
+            if out_dir.is_some() {
+                return Err("--to mspdi writes a single file, use -o instead of --out-dir".into());
+            }
+            let xml = xer.to_mspdi().map_err(|err| with_path(path, err))?;
+            let mut out = create(output)?;
+            out.write_all(xml.as_bytes())?;
+            out.flush()?;
+        }
+        Format::Ics => {
+            if out_dir.is_some() {
+                return Err("--to ics writes a single file, use -o instead of --out-dir".into());
+            }
+            let filter = match options.filter.unwrap_or(IcsFilter::All) {
+                IcsFilter::All => EventFilter::All,
+                IcsFilter::Milestones => EventFilter::Milestones,
+            };
+            let ics = xer.to_ics(filter).map_err(|err| with_path(path, err))?;
+            let mut out = create(output)?;
+            out.write_all(ics.as_bytes())?;
+            out.flush()?;
+        }
+        Format::Csv => {
+            let dir = out_dir.ok_or("--to csv needs an output directory, --out-dir")?;
+            convert::write_csv_dir(&xer, path, dir, options.manifest)
+                .map_err(|err| with_path(dir, &*err))?;
+            if let Some(script) = options.sql {
+                let dialect = match script {
+                    SqlScript::Duckdb => SqlDialect::Duckdb,
+                };
+                convert::write_sql_script(&xer, dir, dialect).map_err(|err| with_path(dir, err))?;
+            }
+        }
+        Format::Pmxml => {
+            if out_dir.is_some() {
+                return Err("--to pmxml writes a single file, use -o instead of --out-dir".into());
+            }
+            let xml = xer.to_pmxml().map_err(|err| with_path(path, err))?;
+            let mut out = create(output)?;
+            out.write_all(xml.as_bytes())?;
+            out.flush()?;
+        }
+        Format::Ndjson => unreachable!("streamed above"),
+        #[cfg(feature = "parquet")]
+        Format::Parquet => {
+            let dir = out_dir.ok_or("--to parquet needs an output directory, --out-dir")?;
+            convert::write_parquet(&xer, dir).map_err(|err| with_path(dir, &*err))?;
+        }
+        #[cfg(feature = "duckdb")]
+        Format::Duckdb => {
+            let output = output.ok_or("--to duckdb needs an output file, -o")?;
+            convert::write_duckdb(&xer, output).map_err(|err| with_path(output, err))?;
+        }
+        #[cfg(feature = "sqlite")]
+        Format::Sqlite => {
+            let output = output.ok_or("--to sqlite needs an output file, -o")?;
+            convert::write_sqlite(&xer, output).map_err(|err| with_path(output, err))?;
+        }
+        #[cfg(feature = "xlsx")]
+        Format::Xlsx => {
+            let output = output.ok_or("--to xlsx needs an output file, -o")?;
+            convert::write_xlsx(&xer, output, options.rows.deterministic)
+                .map_err(|err| with_path(output, err))?;
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Rows are written as they are read, `.xer` files are never loaded whole
+fn ndjson(
+    path: &Path,
+    table: &str,
+    options: &RowOptions,
+    output: Option<&Path>,
+) -> Result<ExitCode> {
+    let mut tables = stream_tables(path, table)?;
+    let Some(mut rows) = table_rows(path, table, tables.as_mut())? else {
+        return Err(format!("{}: no table {table}", path.display()).into());
+    };
+    let condition = bind(path, table, options.condition.as_ref(), rows.columns())?;
+    let projection = project(path, table, options.columns.as_ref(), rows.columns())?;
+    let sorted = sort_buffer(path, table, options, rows.columns())?;
+    let mut out = create(output)?;
+    match (&mut rows, sorted) {
+        (TableRows::Streamed(reader), None) => {
+            convert::write_ndjson(reader, condition.as_ref(), projection.as_ref(), &mut out)
+                .map_err(|err| with_path(path, &*err))?;
+        }
+        (rows, sorted) => {
+            let mut sorted = sorted.unwrap_or_else(|| {
+                Table::new(table.to_string(), rows.columns().to_vec(), Vec::new())
+            });
+            while let Some(values) = rows.next_row().map_err(|err| with_path(path, err))? {
+                if condition
+                    .as_ref()
+                    .is_none_or(|filter| filter.matches(values))
+                {
+                    sorted.rows.push(values.to_vec());
+                }
+            }
+            sort_table(path, &mut sorted, options)?;
+            convert::write_ndjson_rows(&sorted, projection.as_ref(), &mut out)?;
+        }
+    }
+    out.flush()?;
+    warn_truncated_table(path, table, rows.is_truncated());
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Parse leniently to collect every anomaly instead of stopping at the first
+/// PMXML files have no records to check, they are valid if they load
+fn validate(path: &Path) -> Result<ExitCode> {
+    if is_pmxml(path) {
+        open(path)?;
+        return Ok(ExitCode::SUCCESS);
+    }
+    let options = ParseOptions {
+        lenient: true,
+        unknown_records: UnknownRecords::Warn,
+        ..Default::default()
+    };
+    let (xer, warnings) =
+        XerFile::from_path_with_options(path, options).map_err(|err| with_path(path, err))?;
+    for warning in &warnings {
+        println!("{}: {warning}", path.display());
+    }
+    warn_truncated(path, &xer);
+    if warnings.is_empty() && !xer.is_truncated() {
+        Ok(ExitCode::SUCCESS)
+    } else {
+        Ok(ExitCode::FAILURE)
+    }
+}
+
+/// Durations and floats are in hours
+fn diff(
+    old_path: &Path,
+    new_path: &Path,
+    format: ReportFormat,
+    output: Option<&Path>,
+) -> Result<ExitCode> {
+    let old = open(old_path)?;
+    let new = open(new_path)?;
+    let changes = old.compare(&new).map_err(|err| with_path(old_path, err))?;
+    let mut out = create(output)?;
+    match format {
+        ReportFormat::Text => {
+            for change in &changes {
+                writeln!(out, "{change}")?;
+            }
+        }
+        ReportFormat::Csv => {
+            let mut writer = csv::Writer::from_writer(&mut out);
+            writer.write_record(["task_code", "category", "kind", "field", "old", "new"])?;
+            for change in &changes {
+                writer.write_record([
+                    change.task_code.as_str(),
+                    change.category.name(),
+                    change.kind.name(),
+                    &change.field,
+                    change.old.as_deref().unwrap_or(""),
+                    change.new.as_deref().unwrap_or(""),
+                ])?;
+            }
+            writer.flush()?;
+        }
+        ReportFormat::Json => {
+            serde_json::to_writer_pretty(&mut out, &changes)?;
+            writeln!(out)?;
+        }
+    }
+    out.flush()?;
+    Ok(if changes.is_empty() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    })
+}
+
+fn diff_schema(old_path: &Path, new_path: &Path) -> Result<ExitCode> {
+    let old = open(old_path)?;
+    let new = open(new_path)?;
+    if old.header.version != new.header.version {
+        println!("version {} -> {}", old.header.version, new.header.version);
+    }
+    let changes = old.schema_diff(&new);
+    for change in &changes {
+        println!("{change}");
+    }
+    Ok(if changes.is_empty() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    })
+}
+
+fn diff_tables(old_path: &Path, new_path: &Path) -> Result<ExitCode> {
+    let old = open(old_path)?;
+    let new = open(new_path)?;
+    let mut same = true;
+    for (name, table) in &old.tables {
+        match new.table(name) {
+            None => println!("- {name}"),
+            Some(other) if other.row_count() != table.row_count() => {
+                println!(
+                    "~ {name} {} -> {} rows",
+                    table.row_count(),
+                    other.row_count()
+                )
+            }
+            Some(other) if other.header != table.header || other.rows != table.rows => {
+                println!("~ {name} values differ")
+            }
+            Some(_) => continue,
+        }
+        same = false;
+    }
+    let known: HashSet<&String> = old.tables.keys().collect();
+    for name in new.tables.keys().filter(|name| !known.contains(name)) {
+        println!("+ {name}");
+        same = false;
+    }
+    Ok(if same {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    })
+}
+
+fn scrub(path: &Path, output: Option<&Path>, costs: bool) -> Result<ExitCode> {
+    let mut xer = open(path)?;
+    xer.scrub(&ScrubOptions { costs });
+    let mut out = create(output)?;
+    xer.write(&mut out)?;
+    out.flush()?;
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Files are named by project short name, characters unsafe in file names are replaced
+fn split(path: &Path, out_dir: &Path, cross_project: bool) -> Result<ExitCode> {
+    let xer = open(path)?;
+    let projects = xer.split_by_project(cross_project);
+    if projects.is_empty() {
+        return Err(format!("{}: no projects", path.display()).into());
+    }
+    fs::create_dir_all(out_dir).map_err(|err| with_path(out_dir, err))?;
+    for (name, project) in &projects {
+        let name: String = name
+            .chars()
+            .map(|c| match c {
+                '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+                c if c.is_control() => '_',
+                c => c,
+            })
+            .collect();
+        let output = out_dir.join(format!("{name}.xer"));
+        project
+            .save(&output)
+            .map_err(|err| with_path(&output, err))?;
+        println!("{}", output.display());
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Tables and columns not in the file are reported but not an error
+fn trim(
+    path: &Path,
+    output: Option<&Path>,
+    tables: &[String],
+    columns: &[String],
+) -> Result<ExitCode> {
+    let columns = columns
+        .iter()
+        .map(|column| {
+            column
+                .split_once('.')
+                .ok_or_else(|| format!("column must be given as TABLE.column: {column}"))
+        })
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    let mut xer = open(path)?;
+    for name in tables {
+        if xer.tables.shift_remove(name).is_none() {
+            eprintln!("warning: {}: no table {name}", path.display());
+        }
+    }
+    for (name, column) in columns {
+        let dropped = xer
+            .tables
+            .get_mut(name)
+            .is_some_and(|table| table.drop_column(column));
+        if !dropped {
+            eprintln!("warning: {}: no column {name}.{column}", path.display());
+        }
+    }
+    let mut out = create(output)?;
+    xer.write(&mut out)?;
+    out.flush()?;
+    Ok(ExitCode::SUCCESS)
+}
+
+fn renumber(path: &Path, output: Option<&Path>) -> Result<ExitCode> {
+    let mut xer = open(path)?;
+    xer.renumber();
+    let mut out = create(output)?;
+    xer.write(&mut out)?;
+    out.flush()?;
+    Ok(ExitCode::SUCCESS)
+}
+
+fn shift(
+    path: &Path,
+    output: Option<&Path>,
+    days: Option<i64>,
+    to_data_date: Option<NaiveDate>,
+) -> Result<ExitCode> {
+    let mut xer = open(path)?;
+    let days = match (days, to_data_date) {
+        (Some(days), _) => days,
+        (None, Some(date)) => {
+            let projects = xer.projects().map_err(|err| with_path(path, err))?;
+            let data_date = projects
+                .first()
+                .and_then(|project| project.data_date)
+                .ok_or_else(|| format!("{}: no project with a data date", path.display()))?;
+            (date - data_date.date()).num_days()
+        }
+        (None, None) => unreachable!("clap requires one of them"),
+    };
+    xer.shift_dates(days);
+    let mut out = create(output)?;
+    xer.write(&mut out)?;
+    out.flush()?;
+    Ok(ExitCode::SUCCESS)
+}
+
+fn currency(path: &Path, output: Option<&Path>, rate: f64, to: &str) -> Result<ExitCode> {
+    if !(rate.is_finite() && rate > 0.0) {
+        return Err(format!("rate must be a positive number: {rate}").into());
+    }
+    let mut xer = open(path)?;
+    xer.convert_currency(rate, to);
+    let mut out = create(output)?;
+    xer.write(&mut out)?;
+    out.flush()?;
+    Ok(ExitCode::SUCCESS)
+}
+
+fn deprogress(path: &Path, output: Option<&Path>) -> Result<ExitCode> {
+    let mut xer = open(path)?;
+    xer.remove_progress();
+    let mut out = create(output)?;
+    xer.write(&mut out)?;
+    out.flush()?;
+    Ok(ExitCode::SUCCESS)
+}
+
+/// One row per WBS element in WBS order, amounts with 2 decimals
+fn earned_value(
+    baseline_path: &Path,
+    update_path: &Path,
+    percent_complete: PercentCompleteType,
+    output: Option<&Path>,
+) -> Result<ExitCode> {
+    let baseline = open(baseline_path)?;
+    let update = open(update_path)?;
+    let percent_complete = match percent_complete {
+        PercentCompleteType::Activity => PercentComplete::Activity,
+        PercentCompleteType::Duration => PercentComplete::Duration,
+        PercentCompleteType::Physical => PercentComplete::Physical,
+        PercentCompleteType::Units => PercentComplete::Units,
+    };
+    let values = baseline
+        .earned_value(&update, percent_complete)
+        .map_err(|err| with_path(update_path, err))?;
+    let mut writer = csv::Writer::from_writer(create(output)?);
+    writer.write_record([
+        "data_date",
+        "wbs_code",
+        "wbs_name",
+        "depth",
+        "bac",
+        "pv",
+        "ev",
+        "ac",
+        "sv",
+        "cv",
+        "spi",
+        "cpi",
+    ])?;
+    let money = |value: f64| format!("{value:.2}");
+    let index = |value: Option<f64>| value.map_or(String::new(), |value| format!("{value:.3}"));
+    for value in values {
+        writer.write_record([
+            value.data_date.format("%Y-%m-%d %H:%M").to_string(),
+            value.wbs_code,
+            value.wbs_name,
+            value.depth.to_string(),
+            money(value.budget_at_completion),
+            money(value.planned_value),
+            money(value.earned_value),
+            money(value.actual_cost),
+            money(value.schedule_variance),
+            money(value.cost_variance),
+            index(value.schedule_performance_index),
+            index(value.cost_performance_index),
+        ])?;
+    }
+    writer.flush()?;
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Sorted by slip, floats are in hours
+fn slippage(
+    baseline_path: &Path,
+    update_path: &Path,
+    threshold: f64,
+    output: Option<&Path>,
+    by_wbs: bool,
+) -> Result<ExitCode> {
+    let baseline = open(baseline_path)?;
+    let update = open(update_path)?;
+    let slipped = baseline
+        .slippage(&update, threshold)
+        .map_err(|err| with_path(update_path, err))?;
+    let mut writer = csv::Writer::from_writer(create(output)?);
+    if by_wbs {
+        let wbs = update
+            .wbs_tree()
+            .map_err(|err| with_path(update_path, err))?;
+        writer.write_record([
+            "wbs_code",
+            "wbs_name",
+            "depth",
+            "activities",
+            "max_slip_days",
+        ])?;
+        for element in summarize_by_wbs(&slipped, &wbs) {
+            writer.write_record([
+                element.wbs_code,
+                element.wbs_name,
+                element.depth.to_string(),
+                element.activities.to_string(),
+                format!("{:.2}", element.max_slip_days),
+            ])?;
+        }
+    } else {
+        writer.write_record([
+            "task_code",
+            "task_name",
+            "baseline_finish",
+            "update_finish",
+            "slip_days",
+            "baseline_total_float_hr",
+            "update_total_float_hr",
+            "float_erosion_hr",
+            "driving_path",
+        ])?;
+        let number = |value: Option<f64>| value.map_or(String::new(), |value| value.to_string());
+        for slippage in slipped {
+            writer.write_record([
+                slippage.task_code,
+                slippage.task_name,
+                slippage
+                    .baseline_finish
+                    .format("%Y-%m-%d %H:%M")
+                    .to_string(),
+                slippage.update_finish.format("%Y-%m-%d %H:%M").to_string(),
+                format!("{:.2}", slippage.slip_days),
+                number(slippage.baseline_total_float),
+                number(slippage.update_total_float),
+                number(slippage.float_erosion),
+                slippage.driving_path.join(" "),
+            ])?;
+        }
+    }
+    writer.flush()?;
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Dates match to the minute and floats to a hundredth of an hour, dates
+/// and floats P6 did not export are not compared
+fn schedule(path: &Path, output: Option<&Path>, differences: bool) -> Result<ExitCode> {
+    let xer = open(path)?;
+    warn_truncated(path, &xer);
+    let scheduled = xer.schedule().map_err(|err| with_path(path, err))?;
+    let tasks = xer.tasks().map_err(|err| with_path(path, err))?;
+    let tasks: HashMap<u64, Task> = tasks.into_iter().map(|task| (task.task_id, task)).collect();
+    let relationships: HashMap<u64, Relationship> = xer
+        .relationships()
+        .map_err(|err| with_path(path, err))?
+        .into_iter()
+        .map(|link| (link.task_pred_id, link))
+        .collect();
+
+    let mut writer = csv::Writer::from_writer(create(output)?);
+    writer.write_record([
+        "task_code",
+        "early_start",
+        "early_finish",
+        "late_start",
+        "late_finish",
+        "total_float_hr",
+        "free_float_hr",
+        "driving_predecessors",
+        "p6_early_start",
+        "p6_early_finish",
+        "p6_late_start",
+        "p6_late_finish",
+        "p6_total_float_hr",
+        "p6_free_float_hr",
+        "matches",
+    ])?;
+    let date = |date: Option<NaiveDateTime>| {
+        date.map_or(String::new(), |date| {
+            date.format("%Y-%m-%d %H:%M").to_string()
+        })
+    };
+    let number = |value: Option<f64>| value.map_or(String::new(), |value| format!("{value:.2}"));
+    for computed in scheduled {
+        let task = &tasks[&computed.task_id];
+        let same_date =
+            |p6: Option<NaiveDateTime>, ours: NaiveDateTime| p6.is_none_or(|p6| p6 == ours);
+        let matches = same_date(task.early_start, computed.early_start)
+            && same_date(task.early_finish, computed.early_finish)
+            && same_date(task.late_start, computed.late_start)
+            && same_date(task.late_finish, computed.late_finish)
+            && match (task.total_float, computed.total_float) {
+                (Some(p6), Some(ours)) => (p6 - ours).abs() < 0.01,
+                _ => true,
+            };
+        if differences && matches {
+            continue;
+        }
+        // e.g. `A1020 FS, A1030 SS`
+        let driving: Vec<String> = computed
+            .driving
+            .iter()
+            .map(|id| {
+                let link = &relationships[id];
+                format!(
+                    "{} {}",
+                    tasks[&link.pred_task_id].task_code,
+                    link.link_type.abbr()
+                )
+            })
+            .collect();
+        writer.write_record([
+            task.task_code.clone(),
+            date(Some(computed.early_start)),
+            date(Some(computed.early_finish)),
+            date(Some(computed.late_start)),
+            date(Some(computed.late_finish)),
+            number(computed.total_float),
+            number(computed.free_float),
+            driving.join(", "),
+            date(task.early_start),
+            date(task.early_finish),
+            date(task.late_start),
+            date(task.late_finish),
+            number(task.total_float),
+            number(task.free_float),
+            if matches { "Y" } else { "N" }.into(),
+        ])?;
+    }
+    writer.flush()?;
+    Ok(ExitCode::SUCCESS)
+}
+
+fn longest_path(path: &Path, output: Option<&Path>) -> Result<ExitCode> {
+    let xer = open(path)?;
+    warn_truncated(path, &xer);
+    let longest = xer.longest_path().map_err(|err| with_path(path, err))?;
+    let tasks = xer.tasks().map_err(|err| with_path(path, err))?;
+    let tasks: HashMap<u64, Task> = tasks.into_iter().map(|task| (task.task_id, task)).collect();
+    let projects: HashMap<u64, String> = xer
+        .projects()
+        .map_err(|err| with_path(path, err))?
+        .into_iter()
+        .map(|project| (project.proj_id, project.proj_short_name))
+        .collect();
+
+    let mut writer = csv::Writer::from_writer(create(output)?);
+    writer.write_record([
+        "proj_short_name",
+        "task_code",
+        "task_name",
+        "early_start",
+        "early_finish",
+        "total_float_hr",
+        "p6_longest_path",
+    ])?;
+    for computed in longest {
+        let task = &tasks[&computed.task_id];
+        writer.write_record([
+            projects.get(&task.proj_id).cloned().unwrap_or_default(),
+            task.task_code.clone(),
+            task.task_name.clone(),
+            computed.early_start.format("%Y-%m-%d %H:%M").to_string(),
+            computed.early_finish.format("%Y-%m-%d %H:%M").to_string(),
+            computed
+                .total_float
+                .map_or(String::new(), |value| format!("{value:.2}")),
+            if task.driving_path { "Y" } else { "N" }.into(),
+        ])?;
+    }
+    writer.flush()?;
+    Ok(ExitCode::SUCCESS)
+}
+
+fn read_options<T: DeserializeOwned>(path: &Path) -> Result<T> {
+    let file = File::open(path).map_err(|err| with_path(path, err))?;
+    serde_json::from_reader(io::BufReader::new(file)).map_err(|err| with_path(path, err))
+}
+
+fn health(
+    paths: &[PathBuf],
+    options: &HealthOptions,
+    format: ReportFormat,
+    output: Option<&Path>,
+) -> Result<ExitCode> {
+    #[derive(Serialize)]
+    struct Row<'a> {
+        file: &'a Path,
+        /// Score change since the previous file
+        change: Option<f64>,
+        #[serde(flatten)]
+        health: HealthScore,
+    }
+
+    let mut rows: Vec<Row> = Vec::new();
+    for path in paths {
+        let xer = open(path)?;
+        warn_truncated(path, &xer);
+        let health = xer
+            .health_score(options)
+            .map_err(|err| with_path(path, err))?;
+        let change = rows
+            .last()
+            .map(|previous| health.score - previous.health.score);
+        rows.push(Row {
+            file: path,
+            change,
+            health,
+        });
+    }
+    let date = |date: Option<NaiveDateTime>| {
+        date.map_or(String::new(), |date| {
+            date.format("%Y-%m-%d %H:%M").to_string()
+        })
+    };
+    let mut out = create(output)?;
+    match format {
+        ReportFormat::Text => {
+            for row in &rows {
+                write!(out, "{}", row.file.display())?;
+                if let Some(data_date) = row.health.data_date {
+                    write!(out, " ({})", data_date.format("%Y-%m-%d"))?;
+                }
+                write!(out, ": {:.1}", row.health.score)?;
+                if let Some(change) = row.change {
+                    write!(out, " ({change:+.1})")?;
+                }
+                writeln!(out)?;
+                for metric in &row.health.metrics {
+                    writeln!(
+                        out,
+                        "  {}: {:.1} ({} of {}, weight {})",
+                        metric.name, metric.score, metric.count, metric.total, metric.weight
+                    )?;
+                }
+            }
+        }
+        ReportFormat::Csv => {
+            let mut writer = csv::Writer::from_writer(&mut out);
+            writer.write_record([
+                "file",
+                "data_date",
+                "metric",
+                "count",
+                "total",
+                "weight",
+                "score",
+            ])?;
+            for row in &rows {
+                let file = row.file.display().to_string();
+                let data_date = date(row.health.data_date);
+                for metric in &row.health.metrics {
+                    writer.write_record([
+                        file.clone(),
+                        data_date.clone(),
+                        metric.name.to_string(),
+                        metric.count.to_string(),
+                        metric.total.to_string(),
+                        metric.weight.to_string(),
+                        format!("{:.2}", metric.score),
+                    ])?;
+                }
+                writer.write_record([
+                    file,
+                    data_date,
+                    "health".to_string(),
+                    String::new(),
+                    String::new(),
+                    String::new(),
+                    format!("{:.2}", row.health.score),
+                ])?;
+            }
+            writer.flush()?;
+        }
+        ReportFormat::Json => {
+            serde_json::to_writer_pretty(&mut out, &rows)?;
+            writeln!(out)?;
+        }
+    }
+    out.flush()?;
+    Ok(ExitCode::SUCCESS)
+}
+
+fn check(path: &Path, options: &CheckOptions) -> Result<ExitCode> {
+    let xer = open(path)?;
+    warn_truncated(path, &xer);
+    let wbs = xer.wbs_tree().map_err(|err| with_path(path, err))?;
+    let open_ends = xer.open_ends().map_err(|err| with_path(path, err))?;
+    if !open_ends.is_empty() {
+        println!("open ends:");
+        let mut element = None;
+        for open_end in &open_ends {
+            if element != Some(open_end.wbs_id) {
+                element = Some(open_end.wbs_id);
+                println!("  {}", wbs_label(&wbs, open_end.wbs_id));
+            }
+            println!(
+                "    {} {}: {}",
+                open_end.task_code,
+                open_end.task_name,
+                open_end.missing.name()
+            );
+        }
+    }
+    let negative = xer.negative_float().map_err(|err| with_path(path, err))?;
+    if !negative.is_empty() {
+        println!("negative float:");
+        for task in &negative {
+            let cause = task
+                .cause
+                .as_ref()
+                .map_or("no constraint found".into(), |cause| cause.to_string());
+            println!(
+                "  {} {}: {} h, {cause}",
+                task.task_code, task.task_name, task.total_float
+            );
+        }
+    }
+    let loops = xer.logic_loops().map_err(|err| with_path(path, err))?;
+    if !loops.is_empty() {
+        println!("logic loops:");
+        for tasks in &loops {
+            println!("  {} -> {}", tasks.join(" -> "), tasks[0]);
+        }
+    }
+    let out_of_sequence = xer.out_of_sequence().map_err(|err| with_path(path, err))?;
+    if !out_of_sequence.is_empty() {
+        println!("out of sequence progress:");
+        for progress in &out_of_sequence {
+            println!("  {progress}");
+        }
+    }
+    let long = xer
+        .long_durations(options.max_remaining_days)
+        .map_err(|err| with_path(path, err))?;
+    print_exceedances(
+        &format!(
+            "remaining duration over {} days",
+            options.max_remaining_days
+        ),
+        &long,
+    );
+    let high = xer
+        .high_float(options.max_float_days)
+        .map_err(|err| with_path(path, err))?;
+    print_exceedances(
+        &format!("total float over {} days", options.max_float_days),
+        &high,
+    );
+    let found = !open_ends.is_empty()
+        || !negative.is_empty()
+        || !loops.is_empty()
+        || !out_of_sequence.is_empty()
+        || !long.is_empty()
+        || !high.is_empty();
+    Ok(if found {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    })
+}
+
+fn print_exceedances(title: &str, exceedances: &[Exceedance]) {
+    if !exceedances.is_empty() {
+        println!("{title}:");
+        for exceedance in exceedances {
+            println!(
+                "  {} {}: {:.1} days",
+                exceedance.task_code, exceedance.task_name, exceedance.days
+            );
+        }
+    }
+}
+
+/// WBS code from the project node down and name, e.g. `PRJ-A.1.2 Design`
+fn wbs_label(wbs: &WbsTree, wbs_id: u64) -> String {
+    let Some(element) = wbs.get(wbs_id) else {
+        return format!("WBS {wbs_id}");
+    };
+    let mut path: Vec<&str> = wbs
+        .path_to_root(wbs_id)
+        .map(|node| node.wbs_short_name.as_str())
+        .collect();
+    path.reverse();
+    format!("{} {}", path.join("."), element.wbs_name)
+}
+
+fn rollup(
+    path: &Path,
+    max_depth: Option<usize>,
+    format: ReportFormat,
+    output: Option<&Path>,
+) -> Result<ExitCode> {
+    #[derive(Serialize)]
+    struct Row {
+        wbs_code: String,
+        wbs_name: String,
+        #[serde(flatten)]
+        rollup: WbsRollup,
+    }
+
+    let xer = open(path)?;
+    warn_truncated(path, &xer);
+    let wbs = xer.wbs_tree().map_err(|err| with_path(path, err))?;
+    let tasks = xer.tasks().map_err(|err| with_path(path, err))?;
+    let assignments = xer.assignments().map_err(|err| with_path(path, err))?;
+    let expenses = xer.expenses().map_err(|err| with_path(path, err))?;
+    let currency = xer.base_currency().map_err(|err| with_path(path, err))?;
+    let rows: Vec<Row> = wbs
+        .rollup(&tasks, &assignments, &expenses, &currency)
+        .into_iter()
+        .filter(|rollup| max_depth.is_none_or(|max| rollup.depth <= max))
+        .map(|rollup| {
+            let mut path: Vec<&str> = wbs
+                .path_to_root(rollup.wbs_id)
+                .map(|node| node.wbs_short_name.as_str())
+                .collect();
+            path.reverse();
+            Row {
+                wbs_code: path.join("."),
+                wbs_name: wbs
+                    .get(rollup.wbs_id)
+                    .map(|element| element.wbs_name.clone())
+                    .unwrap_or_default(),
+                rollup,
+            }
+        })
+        .collect();
+    let date = |date: Option<NaiveDateTime>| {
+        date.map_or(String::new(), |date| {
+            date.format("%Y-%m-%d %H:%M").to_string()
+        })
+    };
+    let mut out = create(output)?;
+    match format {
+        ReportFormat::Text => {
+            for row in &rows {
+                let rollup = &row.rollup;
+                writeln!(
+                    out,
+                    "{:indent$}{} {}: {} - {}, {:.1}% complete, budgeted {}, actual {}",
+                    "",
+                    row.wbs_code,
+                    row.wbs_name,
+                    date(rollup.start),
+                    date(rollup.finish),
+                    rollup.percent_complete,
+                    currency.format(rollup.costs.budgeted.amount),
+                    currency.format(rollup.costs.actual.amount),
+                    indent = rollup.depth * 2
+                )?;
+            }
+        }
+        ReportFormat::Csv => {
+            let mut writer = csv::Writer::from_writer(&mut out);
+            writer.write_record([
+                "wbs_code",
+                "wbs_name",
+                "depth",
+                "activities",
+                "start",
+                "finish",
+                "planned_duration_hr",
+                "remaining_duration_hr",
+                "budgeted_cost",
+                "actual_cost",
+                "remaining_cost",
+                "percent_complete",
+            ])?;
+            for row in rows {
+                let rollup = row.rollup;
+                writer.write_record([
+                    row.wbs_code,
+                    row.wbs_name,
+                    rollup.depth.to_string(),
+                    rollup.activities.to_string(),
+                    date(rollup.start),
+                    date(rollup.finish),
+                    rollup.planned_duration.to_string(),
+                    rollup.remaining_duration.to_string(),
+                    format!("{:.2}", rollup.costs.budgeted.amount),
+                    format!("{:.2}", rollup.costs.actual.amount),
+                    format!("{:.2}", rollup.costs.remaining.amount),
+                    format!("{:.1}", rollup.percent_complete),
+                ])?;
+            }
+            writer.flush()?;
+        }
+        ReportFormat::Json => {
+            serde_json::to_writer_pretty(&mut out, &rows)?;
+            writeln!(out)?;
+        }
+    }
+    out.flush()?;
+    Ok(ExitCode::SUCCESS)
+}
+
+fn lags(
+    path: &Path,
+    max_lag: Option<f64>,
+    format: ReportFormat,
+    output: Option<&Path>,
+) -> Result<ExitCode> {
+    let xer = open(path)?;
+    warn_truncated(path, &xer);
+    let analysis = xer
+        .lag_analysis(max_lag)
+        .map_err(|err| with_path(path, err))?;
+    let mut out = create(output)?;
+    match format {
+        ReportFormat::Text => {
+            writeln!(out, "type  relationships  lags  leads  over limit")?;
+            for counts in &analysis.summary {
+                writeln!(
+                    out,
+                    "{:<4}  {:>13}  {:>4}  {:>5}  {:>10}",
+                    counts.link_type.abbr(),
+                    counts.relationships,
+                    counts.lags,
+                    counts.leads,
+                    counts.over_limit
+                )?;
+            }
+            if !analysis.lags.is_empty() {
+                writeln!(out)?;
+            }
+            for lag in &analysis.lags {
+                let flag = if lag.over_limit { " over limit" } else { "" };
+                writeln!(
+                    out,
+                    "{} {}: {} {} {}{flag}",
+                    lag.task_code,
+                    lag.task_name,
+                    lag.link_type.abbr(),
+                    lag.predecessor,
+                    lag.lag
+                )?;
+            }
+        }
+        ReportFormat::Csv => {
+            let mut writer = csv::Writer::from_writer(&mut out);
+            writer.write_record([
+                "task_code",
+                "task_name",
+                "predecessor",
+                "link_type",
+                "lag_hr",
+                "over_limit",
+            ])?;
+            for lag in &analysis.lags {
+                writer.write_record([
+                    lag.task_code.clone(),
+                    lag.task_name.clone(),
+                    lag.predecessor.clone(),
+                    lag.link_type.abbr().into(),
+                    lag.lag.to_string(),
+                    if lag.over_limit { "Y" } else { "N" }.into(),
+                ])?;
+            }
+            writer.flush()?;
+        }
+        ReportFormat::Json => {
+            serde_json::to_writer_pretty(&mut out, &analysis)?;
+            writeln!(out)?;
+        }
+    }
+    out.flush()?;
+    Ok(ExitCode::SUCCESS)
+}
+
+fn constraints(path: &Path, format: ReportFormat, output: Option<&Path>) -> Result<ExitCode> {
+    let xer = open(path)?;
+    warn_truncated(path, &xer);
+    let groups = xer.constraint_usage().map_err(|err| with_path(path, err))?;
+    let date = |date: Option<NaiveDateTime>| {
+        date.map_or(String::new(), |date| {
+            date.format("%Y-%m-%d %H:%M").to_string()
+        })
+    };
+    let mut out = create(output)?;
+    match format {
+        ReportFormat::Text => {
+            for group in &groups {
+                let hard = if group.hard { ", hard" } else { "" };
+                writeln!(
+                    out,
+                    "{} ({}{hard}): {}",
+                    group.name,
+                    group.constraint_type,
+                    group.activities.len()
+                )?;
+                for activity in &group.activities {
+                    let secondary = if activity.secondary { " secondary" } else { "" };
+                    writeln!(
+                        out,
+                        "  {} {}: {}{secondary}",
+                        activity.task_code,
+                        activity.task_name,
+                        date(activity.constraint_date)
+                    )?;
+                }
+            }
+        }
+        ReportFormat::Csv => {
+            let mut writer = csv::Writer::from_writer(&mut out);
+            writer.write_record([
+                "constraint_type",
+                "name",
+                "hard",
+                "task_code",
+                "task_name",
+                "constraint_date",
+                "secondary",
+            ])?;
+            let flag = |value: bool| if value { "Y" } else { "N" }.to_string();
+            for group in &groups {
+                for activity in &group.activities {
+                    writer.write_record([
+                        group.constraint_type.clone(),
+                        group.name.clone(),
+                        flag(group.hard),
+                        activity.task_code.clone(),
+                        activity.task_name.clone(),
+                        date(activity.constraint_date),
+                        flag(activity.secondary),
+                    ])?;
+                }
+            }
+            writer.flush()?;
+        }
+        ReportFormat::Json => {
+            serde_json::to_writer_pretty(&mut out, &groups)?;
+            writeln!(out)?;
+        }
+    }
+    out.flush()?;
+    Ok(ExitCode::SUCCESS)
+}
+
+fn histogram(
+    path: &Path,
+    period: HistogramPeriod,
+    format: DataFormat,
+    output: Option<&Path>,
+) -> Result<ExitCode> {
+    let xer = open(path)?;
+    warn_truncated(path, &xer);
+    let period = match period {
+        HistogramPeriod::Day => Period::Day,
+        HistogramPeriod::Week => Period::Week,
+        HistogramPeriod::Month => Period::Month,
+    };
+    let loading = xer
+        .resource_loading(period)
+        .map_err(|err| with_path(path, err))?;
+    let mut out = create(output)?;
+    match format {
+        DataFormat::Csv => {
+            let mut writer = csv::Writer::from_writer(&mut out);
+            writer.write_record([
+                "rsrc_id",
+                "rsrc_short_name",
+                "period_start",
+                "planned_units",
+                "actual_units",
+                "remaining_units",
+            ])?;
+            for row in &loading {
+                writer.write_record([
+                    row.rsrc_id.to_string(),
+                    row.rsrc_short_name.clone(),
+                    row.period_start.to_string(),
+                    row.planned_units.to_string(),
+                    row.actual_units.to_string(),
+                    row.remaining_units.to_string(),
+                ])?;
+            }
+            writer.flush()?;
+        }
+        DataFormat::Json => {
+            serde_json::to_writer_pretty(&mut out, &loading)?;
+            writeln!(out)?;
+        }
+    }
+    out.flush()?;
+    Ok(ExitCode::SUCCESS)
+}
+
+fn assess(path: &Path, format: ReportFormat, output: Option<&Path>) -> Result<ExitCode> {
+    let xer = open(path)?;
+    warn_truncated(path, &xer);
+    let checks = xer.assess_dcma().map_err(|err| with_path(path, err))?;
+    let mut out = create(output)?;
+    match format {
+        ReportFormat::Text => {
+            for check in &checks {
+                writeln!(out, "{check}")?;
+            }
+        }
+        ReportFormat::Csv => {
+            let mut writer = csv::Writer::from_writer(&mut out);
+            writer.write_record([
+                "number",
+                "name",
+                "count",
+                "total",
+                "value",
+                "threshold",
+                "passed",
+            ])?;
+            let number =
+                |value: Option<usize>| value.map_or(String::new(), |value| value.to_string());
+            for check in &checks {
+                writer.write_record([
+                    check.number.to_string(),
+                    check.name.into(),
+                    number(check.count),
+                    number(check.total),
+                    format!("{:.2}", check.value),
+                    check.threshold.to_string(),
+                    if check.passed { "Y" } else { "N" }.into(),
+                ])?;
+            }
+            writer.flush()?;
+        }
+        ReportFormat::Json => {
+            serde_json::to_writer_pretty(&mut out, &checks)?;
+            writeln!(out)?;
+        }
+    }
+    out.flush()?;
+    Ok(if checks.iter().all(|check| check.passed) {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    })
+}
+
+fn check_float(path: &Path, tolerance: f64) -> Result<ExitCode> {
+    let xer = open(path)?;
+    warn_truncated(path, &xer);
+    let discrepancies = xer
+        .reconcile_float(tolerance)
+        .map_err(|err| with_path(path, err))?;
+    for discrepancy in &discrepancies {
+        println!("{}: {discrepancy}", path.display());
+    }
+    if discrepancies.is_empty() {
+        Ok(ExitCode::SUCCESS)
+    } else {
+        Ok(ExitCode::FAILURE)
+    }
+}
+
+fn report(path: &Path, output: Option<&Path>) -> Result<ExitCode> {
+    let xer = open(path)?;
+    warn_truncated(path, &xer);
+    let html = xer.to_html_gantt().map_err(|err| with_path(path, err))?;
+    let mut out = create(output)?;
+    out.write_all(html.as_bytes())?;
+    out.flush()?;
+    Ok(ExitCode::SUCCESS)
+}
+
+/// WBS codes are matched from the project node down, activity IDs in all
+/// projects
+fn dot(
+    path: &Path,
+    critical: bool,
+    wbs_code: Option<&str>,
+    around: Option<(&str, usize)>,
+    output: Option<&Path>,
+) -> Result<ExitCode> {
+    let xer = open(path)?;
+    warn_truncated(path, &xer);
+    let wbs_id = wbs_code
+        .map(|code| find_wbs(path, &xer, code))
+        .transpose()?;
+    let around = match around {
+        Some((code, hops)) => {
+            let tasks = xer.tasks().map_err(|err| with_path(path, err))?;
+            let mut matches = tasks.iter().filter(|task| task.task_code == code);
+            match (matches.next(), matches.next()) {
+                (Some(task), None) => Some((task.task_id, hops)),
+                (None, _) => return Err(format!("{}: no activity {code}", path.display()).into()),
+                (Some(_), Some(_)) => {
+                    return Err(format!(
+                        "{}: activity {code} in more than one project",
+                        path.display()
+                    )
+                    .into())
+                }
+            }
+        }
+        None => None,
+    };
+    let options = DotOptions {
+        critical,
+        wbs_id,
+        around,
+    };
+    let dot = xer.to_dot(&options).map_err(|err| with_path(path, err))?;
+    let mut out = create(output)?;
+    out.write_all(dot.as_bytes())?;
+    out.flush()?;
+    Ok(ExitCode::SUCCESS)
+}
+
+/// `wbs_id` of the element with `code` from the project node down
+fn find_wbs(path: &Path, xer: &XerFile, code: &str) -> Result<u64> {
+    let wbs = xer.wbs_tree().map_err(|err| with_path(path, err))?;
+    let wbs_id = wbs
+        .iter()
+        .find(|element| {
+            let mut codes: Vec<&str> = wbs
+                .path_to_root(element.wbs_id)
+                .map(|node| node.wbs_short_name.as_str())
+                .collect();
+            codes.reverse();
+            codes.join(".") == code
+        })
+        .map(|element| element.wbs_id)
+        .ok_or_else(|| format!("{}: no WBS element {code}", path.display()))?;
+    Ok(wbs_id)
+}
+
+fn mermaid(
+    path: &Path,
+    wbs_code: Option<&str>,
+    critical: bool,
+    milestones: bool,
+    max_activities: usize,
+    output: Option<&Path>,
+) -> Result<ExitCode> {
+    let xer = open(path)?;
+    warn_truncated(path, &xer);
+    let options = GanttOptions {
+        wbs_id: wbs_code
+            .map(|code| find_wbs(path, &xer, code))
+            .transpose()?,
+        critical,
+        milestones,
+        max_activities,
+        ..GanttOptions::default()
+    };
+    let gantt = xer
+        .to_mermaid_gantt(&options)
+        .map_err(|err| with_path(path, err))?;
+    let mut out = create(output)?;
+    out.write_all(gantt.as_bytes())?;
+    out.flush()?;
+    Ok(ExitCode::SUCCESS)
+}
+
+/// The header is taken from the first file
+fn merge(paths: &[PathBuf], output: Option<&Path>) -> Result<ExitCode> {
+    let mut merged = open(&paths[0])?;
+    warn_truncated(&paths[0], &merged);
+    for path in &paths[1..] {
+        let xer = open(path)?;
+        warn_truncated(path, &xer);
+        merged.merge(&xer);
+    }
+    let mut out = create(output)?;
+    merged.write(&mut out)?;
+    out.flush()?;
+    Ok(ExitCode::SUCCESS)
+}
+
+/// `.xer` file at `path`, P6 XML for `.xml` files
+fn open(path: &Path) -> Result<XerFile> {
+    if is_pmxml(path) {
+        return XerFile::from_pmxml_path(path).map_err(|err| with_path(path, err));
+    }
+    XerFile::from_path(path).map_err(|err| with_path(path, err))
+}
+
+/// File is read as P6 XML by its `.xml` extension
+fn is_pmxml(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("xml"))
+}
+
+/// File at `path` or standard output, buffered
+fn create(path: Option<&Path>) -> Result<Box<dyn Write>> {
+    Ok(match path {
+        Some(path) => {
+            let file = File::create(path).map_err(|err| with_path(path, err))?;
+            Box::new(BufWriter::new(file))
+        }
+        None => Box::new(BufWriter::new(io::stdout().lock())),
+    })
+}
+
+fn is_broken_pipe(err: &(dyn Error + 'static)) -> bool {
+    let kind = if let Some(err) = err.downcast_ref::<io::Error>() {
+        Some(err.kind())
+    } else if let Some(err) = err.downcast_ref::<csv::Error>() {
+        match err.kind() {
+            csv::ErrorKind::Io(err) => Some(err.kind()),
+            _ => None,
+        }
+    } else if let Some(err) = err.downcast_ref::<serde_json::Error>() {
+        err.io_error_kind()
+    } else {
+        None
+    };
+    kind == Some(io::ErrorKind::BrokenPipe)
+}
+
+fn with_path(path: &Path, err: impl Error) -> Box<dyn Error> {
+    format!("{}: {err}", path.display()).into()
+}
+
+fn warn_truncated(path: &Path, xer: &XerFile) {
+    for table in xer.tables.values().filter(|table| table.truncated) {
+        eprintln!(
+            "{}: file ends inside table {} without %E",
+            path.display(),
+            table.name
+        );
+    }
+}
@@ -0,0 +1,129 @@
+//! SQL queries over the tables of a file in an in-memory SQLite database
+
+use std::io::Write;
+use std::path::Path;
+use std::process::ExitCode;
+
+use rusqlite::types::Value;
+use serde::ser::{Serialize, SerializeMap, Serializer};
+use serde_json::Value as Json;
+
+use crate::{convert, create, open, warn_truncated, with_path, ReportFormat, Result};
+
+/// Run the statement `sql` over the tables of the file at `path`, see
+/// [`convert::write_sqlite`] for tables and types
+///
+/// Text output aligns the columns under a header, `NULL` is left blank.
+pub fn query(
+    sql: &str,
+    path: &Path,
+    format: ReportFormat,
+    output: Option<&Path>,
+) -> Result<ExitCode> {
+    let xer = open(path)?;
+    warn_truncated(path, &xer);
+    let mut db = rusqlite::Connection::open_in_memory()?;
+    convert::fill_sqlite(&xer, &mut db).map_err(|err| with_path(path, err))?;
+
+    let mut statement = db.prepare(sql)?;
+    let columns: Vec<String> = statement
+        .column_names()
+        .into_iter()
+        .map(String::from)
+        .collect();
+    let mut rows = Vec::new();
+    let mut results = statement.query([])?;
+    while let Some(row) = results.next()? {
+        let values = (0..columns.len())
+            .map(|i| row.get::<_, Value>(i))
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        rows.push(values);
+    }
+
+    let mut out = create(output)?;
+    match format {
+        ReportFormat::Text => {
+            let cells: Vec<Vec<String>> = rows
+                .iter()
+                .map(|row| row.iter().map(text).collect())
+                .collect();
+            let mut widths: Vec<usize> = columns.iter().map(|name| name.chars().count()).collect();
+            for row in &cells {
+                for (width, cell) in widths.iter_mut().zip(row) {
+                    *width = (*width).max(cell.chars().count());
+                }
+            }
+            let line = |out: &mut dyn Write, cells: &[String]| {
+                let padded: Vec<String> = cells
+                    .iter()
+                    .zip(&widths)
+                    .map(|(cell, width)| format!("{cell:width$}"))
+                    .collect();
+                writeln!(out, "{}", padded.join("  ").trim_end())
+            };
+            line(&mut out, &columns)?;
+            let rule: Vec<String> = widths.iter().map(|&width| "-".repeat(width)).collect();
+            line(&mut out, &rule)?;
+            for row in &cells {
+                line(&mut out, row)?;
+            }
+        }
+        ReportFormat::Csv => {
+            let mut writer = csv::Writer::from_writer(&mut out);
+            writer.write_record(&columns)?;
+            for row in &rows {
+                writer.write_record(row.iter().map(text))?;
+            }
+            writer.flush()?;
+        }
+        ReportFormat::Json => {
+            let objects: Vec<Object> = rows
+                .into_iter()
+                .map(|row| Object {
+                    columns: &columns,
+                    values: row.into_iter().map(json).collect(),
+                })
+                .collect();
+            serde_json::to_writer_pretty(&mut out, &objects)?;
+            writeln!(out)?;
+        }
+    }
+    out.flush()?;
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Row as a JSON object with keys in column order
+struct Object<'a> {
+    columns: &'a [String],
+    values: Vec<Json>,
+}
+
+impl Serialize for Object<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.columns.len()))?;
+        for (column, value) in self.columns.iter().zip(&self.values) {
+            map.serialize_entry(column, value)?;
+        }
+        map.end()
+    }
+}
+
+fn text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::Integer(value) => value.to_string(),
+        Value::Real(value) => value.to_string(),
+        Value::Text(value) => value.clone(),
+        Value::Blob(value) => format!("<{} bytes>", value.len()),
+    }
+}
+
+fn json(value: Value) -> Json {
+    match value {
+        Value::Null => Json::Null,
+        Value::Integer(value) => value.into(),
+        Value::Real(value) => value.into(),
+        Value::Text(value) => value.into(),
+        Value::Blob(value) => value.into(),
+    }
+}
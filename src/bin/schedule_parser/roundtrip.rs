@@ -0,0 +1,144 @@
+//! Check that parsing and writing a file reproduces it
+
+use std::fs;
+use std::io::Cursor;
+use std::path::Path;
+use std::process::ExitCode;
+
+use schedule_parser::encoding::{DecodeReader, Encoding};
+use schedule_parser::{XerFile, XerWriter};
+
+use crate::{is_pmxml, warn_truncated, with_path, Result};
+
+/// Rewrite the file at `path` in memory in its own encoding and line breaks,
+/// then compare bytes and the parsed tables of both
+///
+/// Fails if the bytes differ, e.g. by a BOM, mixed line breaks or header
+/// fields unknown to the parser.
+pub fn roundtrip(path: &Path) -> Result<ExitCode> {
+    if is_pmxml(path) {
+        return Err(format!("{}: roundtrip only applies to .xer files", path.display()).into());
+    }
+    let data = fs::read(path).map_err(|err| with_path(path, err))?;
+    let reader = DecodeReader::detect(Cursor::new(&data)).map_err(|err| with_path(path, err))?;
+    let encoding = reader.encoding();
+    let xer = XerFile::parse(reader).map_err(|err| with_path(path, err))?;
+    warn_truncated(path, &xer);
+
+    // the writer encodes UTF-16 as UTF-8
+    let output_encoding = encoding.output_encoding();
+    let line_break = line_break(&encoding.decode(&data[..data.len().min(64 * 1024)]).0);
+    let mut rewritten = Vec::with_capacity(data.len());
+    let writer = XerWriter::new(&mut rewritten)
+        .with_encoding(output_encoding)
+        .with_line_break(line_break);
+    xer.write_to(writer).map_err(|err| with_path(path, err))?;
+
+    let display = path.display();
+    let same_bytes = match data.iter().zip(&rewritten).position(|(a, b)| a != b) {
+        _ if output_encoding != encoding => {
+            println!(
+                "{display}: {} is rewritten as {}, bytes not compared",
+                encoding.name(),
+                output_encoding.name()
+            );
+            false
+        }
+        None if data.len() == rewritten.len() => {
+            println!("{display}: rewritten file is byte-identical");
+            true
+        }
+        offset => {
+            let offset = offset.unwrap_or(data.len().min(rewritten.len()));
+            let line = line_breaks(&data[..offset]) + 1;
+            println!(
+                "{display}: bytes differ at line {line}, offset {offset} ({} vs {} bytes)",
+                data.len(),
+                rewritten.len()
+            );
+            println!("  original:  {:?}", line_at(&data, offset, encoding));
+            println!(
+                "  rewritten: {:?}",
+                line_at(&rewritten, offset, output_encoding)
+            );
+            false
+        }
+    };
+
+    let reparsed = XerFile::parse(DecodeReader::new(Cursor::new(&rewritten), output_encoding))
+        .map_err(|err| format!("{display}: rewritten file does not parse: {err}"))?;
+    let differences = differences(&xer, &reparsed);
+    for difference in &differences {
+        println!("{display}: {difference}");
+    }
+    if differences.is_empty() && !same_bytes {
+        println!("{display}: content is identical");
+    }
+    Ok(if same_bytes && differences.is_empty() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    })
+}
+
+/// Line break of the first line of `text`, `\r\n` if it has none
+fn line_break(text: &str) -> &'static str {
+    match text.find(['\r', '\n']).map(|i| &text[i..]) {
+        Some(rest) if rest.starts_with("\r\n") => "\r\n",
+        Some(rest) if rest.starts_with('\r') => "\r",
+        Some(_) => "\n",
+        None => "\r\n",
+    }
+}
+
+/// Number of `\n`, `\r\n` and lone `\r` line breaks in `data`
+fn line_breaks(data: &[u8]) -> usize {
+    memchr::memchr2_iter(b'\n', b'\r', data)
+        .filter(|&i| data[i] == b'\n' || data.get(i + 1) != Some(&b'\n'))
+        .count()
+}
+
+/// Line of `data` containing byte `offset` with its line break, decoded lossy
+fn line_at(data: &[u8], offset: usize, encoding: &'static Encoding) -> String {
+    let start = memchr::memrchr2(b'\n', b'\r', &data[..offset]).map_or(0, |i| i + 1);
+    let end = match memchr::memchr2(b'\n', b'\r', &data[offset..]) {
+        Some(i) if data[offset + i..].starts_with(b"\r\n") => offset + i + 2,
+        Some(i) => offset + i + 1,
+        None => data.len(),
+    };
+    encoding
+        .decode_without_bom_handling(&data[start..end])
+        .0
+        .into_owned()
+}
+
+/// Differences between the parsed original and the parsed rewritten file
+fn differences(original: &XerFile, rewritten: &XerFile) -> Vec<String> {
+    let mut differences = Vec::new();
+    if original.header != rewritten.header {
+        differences.push("header differs".into());
+    }
+    if !original.tables.keys().eq(rewritten.tables.keys()) {
+        differences.push("table order differs".into());
+    }
+    for (name, table) in &original.tables {
+        let Some(other) = rewritten.table(name) else {
+            differences.push(format!("table {name} is missing"));
+            continue;
+        };
+        if table.header != other.header {
+            differences.push(format!("{name}: columns differ"));
+        }
+        if table.rows.len() != other.rows.len() {
+            differences.push(format!(
+                "{name}: {} rows instead of {}",
+                other.rows.len(),
+                table.rows.len()
+            ));
+        }
+        if let Some(i) = table.rows.iter().zip(&other.rows).position(|(a, b)| a != b) {
+            differences.push(format!("{name}: row {} differs", i + 1));
+        }
+    }
+    differences
+}
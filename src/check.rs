@@ -0,0 +1,486 @@
+//! Logic checks schedule audits start with
+//!
+//! [`XerFile::open_ends`] lists activities without predecessor or successor.
+//! Start milestones need no predecessor and finish milestones no successor,
+//! level of effort and WBS summary activities are skipped.
+//!
+//! [`XerFile::negative_float`] lists activities with negative total float and
+//! the constraint likely causing it: of the activity itself and the
+//! activities after it with a constraint on their late dates, the one with
+//! the least float, the nearest of equal ones. Without such a constraint the
+//! project's must finish by date is assumed.
+//!
+//! [`XerFile::logic_loops`] lists circular logic P6 refuses to schedule.
+//!
+//! [`XerFile::out_of_sequence`] lists relationships progress ignored:
+//! activities started before a finish to start predecessor finished or a
+//! start to start predecessor started.
+//!
+//! [`XerFile::long_durations`] and [`XerFile::high_float`] list incomplete
+//! activities over the limits of [`CheckOptions`], counted in work days of
+//! the activity calendar.
+//!
+//! ## Example
+//! ```
+//! use schedule_parser::check::Missing;
+//! use schedule_parser::XerFile;
+//!
+//! let data = "ERMHDR\t19.12\t2024-03-15\n%T\tTASK\n\
+//!     %F\ttask_id\tproj_id\twbs_id\ttask_code\ttask_name\ttask_type\tstatus_code\n\
+//!     %R\t1\t1\t1\tM1000\tStart\tTT_Mile\tTK_NotStart\n\
+//!     %R\t2\t1\t1\tA1000\tDesign\tTT_Task\tTK_NotStart\n\
+//!     %T\tTASKPRED\n%F\ttask_pred_id\ttask_id\tpred_task_id\tpred_type\n%R\t1\t2\t1\tPR_FS\n%E\n";
+//! let xer = XerFile::parse(data.as_bytes()).unwrap();
+//!
+//! let open_ends = xer.open_ends().unwrap();
+//! assert_eq!(open_ends.len(), 1);
+//! assert_eq!(open_ends[0].task_code, "A1000");
+//! assert_eq!(open_ends[0].missing, Missing::Successor);
+//! ```
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+use crate::error::ParseError;
+use crate::graph::LogicGraph;
+use crate::model::{LinkType, Relationship, Task, TaskStatus, TaskType, WbsTree};
+use crate::xer::XerFile;
+
+/// Limits of the configurable checks, the DCMA ones by default
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CheckOptions {
+    /// Work days of remaining duration [`XerFile::long_durations`] allows
+    pub max_remaining_days: f64,
+    /// Work days of total float [`XerFile::high_float`] allows
+    pub max_float_days: f64,
+}
+
+impl Default for CheckOptions {
+    fn default() -> Self {
+        Self {
+            max_remaining_days: 44.0,
+            max_float_days: 44.0,
+        }
+    }
+}
+
+/// Activity with dangling logic
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OpenEnd {
+    pub task_id: u64,
+    pub task_code: String,
+    pub task_name: String,
+    pub wbs_id: u64,
+    pub missing: Missing,
+}
+
+/// Relationships an [`OpenEnd`] lacks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Missing {
+    Predecessor,
+    Successor,
+    Both,
+}
+
+impl Missing {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Predecessor => "no predecessor",
+            Self::Successor => "no successor",
+            Self::Both => "no predecessor and no successor",
+        }
+    }
+}
+
+/// Constraints limiting late dates, they cause negative float
+const LATE_CONSTRAINTS: &[&str] = &[
+    "CS_MSOB",
+    "CS_MEOB",
+    "CS_MSO",
+    "CS_MEO",
+    "CS_MANDSTART",
+    "CS_MANDFIN",
+];
+
+/// Incomplete activity with a remaining duration or total float over a limit
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Exceedance {
+    pub task_id: u64,
+    pub task_code: String,
+    pub task_name: String,
+    /// Work days of the activity calendar
+    pub days: f64,
+}
+
+/// Activity with negative total float
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct NegativeFloat {
+    pub task_id: u64,
+    pub task_code: String,
+    pub task_name: String,
+    /// Hours
+    pub total_float: f64,
+    /// Likely cause, none if neither a constraint nor a must finish by date
+    /// was found
+    pub cause: Option<FloatCause>,
+}
+
+/// Deadline an activity with [`NegativeFloat`] violates
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FloatCause {
+    /// Constraint of the activity itself or one after it
+    Constraint {
+        task_code: String,
+        constraint_type: String,
+        constraint_date: Option<NaiveDateTime>,
+    },
+    /// `PROJECT.plan_end_date`
+    MustFinishBy(NaiveDateTime),
+}
+
+/// `CS_MEOB 2024-03-06 17:00 of A1060` or `must finish by 2024-06-28 17:00`
+impl fmt::Display for FloatCause {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Constraint {
+                task_code,
+                constraint_type,
+                constraint_date,
+            } => {
+                write!(f, "{constraint_type} ")?;
+                if let Some(date) = constraint_date {
+                    write!(f, "{} ", date.format("%Y-%m-%d %H:%M"))?;
+                }
+                write!(f, "of {task_code}")
+            }
+            Self::MustFinishBy(date) => {
+                write!(f, "must finish by {}", date.format("%Y-%m-%d %H:%M"))
+            }
+        }
+    }
+}
+
+/// Activity started against the logic of one of its relationships
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OutOfSequenceProgress {
+    pub task_id: u64,
+    pub task_code: String,
+    pub task_name: String,
+    pub actual_start: NaiveDateTime,
+    /// Activity ID of the predecessor
+    pub predecessor: String,
+    /// Finish to start or start to start
+    pub link_type: LinkType,
+    /// Actual finish or start of the predecessor, none if it has not
+    /// happened yet
+    pub predecessor_date: Option<NaiveDateTime>,
+}
+
+/// `A1030 Foundations started 2024-01-20 08:00 before FS predecessor A1020
+/// finished 2024-01-24 17:00`
+impl fmt::Display for OutOfSequenceProgress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let event = match self.link_type {
+            LinkType::FinishToStart => "finished",
+            _ => "started",
+        };
+        write!(
+            f,
+            "{} {} started {} before {} predecessor {} {event}",
+            self.task_code,
+            self.task_name,
+            self.actual_start.format("%Y-%m-%d %H:%M"),
+            self.link_type.abbr(),
+            self.predecessor,
+        )?;
+        if let Some(date) = self.predecessor_date {
+            write!(f, " {}", date.format("%Y-%m-%d %H:%M"))?;
+        }
+        Ok(())
+    }
+}
+
+impl XerFile {
+    /// Activities without predecessor or successor in WBS order, see
+    /// [`check`](crate::check)
+    ///
+    /// Activities of WBS elements not in the file come last.
+    pub fn open_ends(&self) -> Result<Vec<OpenEnd>, ParseError> {
+        let graph = self.logic_graph()?;
+        let mut open_ends = Vec::new();
+        for task in self.tasks()? {
+            if matches!(
+                task.task_type,
+                TaskType::LevelOfEffort | TaskType::WbsSummary
+            ) {
+                continue;
+            }
+            let predecessor = task.task_type == TaskType::StartMilestone
+                || graph.predecessors(task.task_id).next().is_some();
+            let successor = task.task_type == TaskType::FinishMilestone
+                || graph.successors(task.task_id).next().is_some();
+            let missing = match (predecessor, successor) {
+                (true, true) => continue,
+                (false, true) => Missing::Predecessor,
+                (true, false) => Missing::Successor,
+                (false, false) => Missing::Both,
+            };
+            open_ends.push(OpenEnd {
+                task_id: task.task_id,
+                task_code: task.task_code,
+                task_name: task.task_name,
+                wbs_id: task.wbs_id,
+                missing,
+            });
+        }
+        sort_by_wbs(&mut open_ends, &self.wbs_tree()?, |open_end| {
+            open_end.wbs_id
+        });
+        Ok(open_ends)
+    }
+
+    /// Activity IDs of one loop per group of activities reachable from each
+    /// other, see [`LogicGraph::loops`](crate::graph::LogicGraph::loops)
+    ///
+    /// Activities missing from `TASK` are shown by `task_id`.
+    pub fn logic_loops(&self) -> Result<Vec<Vec<String>>, ParseError> {
+        let codes: HashMap<u64, String> = self
+            .tasks()?
+            .into_iter()
+            .map(|task| (task.task_id, task.task_code))
+            .collect();
+        let loops = self.logic_graph()?.loops();
+        Ok(loops
+            .into_iter()
+            .map(|tasks| {
+                tasks
+                    .into_iter()
+                    .map(|id| codes.get(&id).cloned().unwrap_or_else(|| id.to_string()))
+                    .collect()
+            })
+            .collect())
+    }
+
+    /// Relationships of started activities their progress ignored in `TASK`
+    /// order, see [`check`](crate::check)
+    pub fn out_of_sequence(&self) -> Result<Vec<OutOfSequenceProgress>, ParseError> {
+        let tasks: HashMap<u64, Task> = self
+            .tasks()?
+            .into_iter()
+            .map(|task| (task.task_id, task))
+            .collect();
+        let graph = self.logic_graph()?;
+        let mut progress = Vec::new();
+        for id in graph.tasks() {
+            let Some(task) = tasks.get(id) else {
+                continue;
+            };
+            for link in out_of_sequence_links(task, &graph, &tasks) {
+                let predecessor = &tasks[&link.pred_task_id];
+                progress.push(OutOfSequenceProgress {
+                    task_id: task.task_id,
+                    task_code: task.task_code.clone(),
+                    task_name: task.task_name.clone(),
+                    actual_start: task.actual_start.unwrap_or_default(),
+                    predecessor: predecessor.task_code.clone(),
+                    link_type: link.link_type,
+                    predecessor_date: match link.link_type {
+                        LinkType::FinishToStart => actual_finish(predecessor),
+                        _ => predecessor.actual_start,
+                    },
+                });
+            }
+        }
+        Ok(progress)
+    }
+
+    /// Activities with negative total float in `TASK` order with the
+    /// constraint likely causing it, see [`check`](crate::check)
+    pub fn negative_float(&self) -> Result<Vec<NegativeFloat>, ParseError> {
+        let tasks = self.tasks()?;
+        let by_id: HashMap<u64, &Task> = tasks.iter().map(|task| (task.task_id, task)).collect();
+        let must_finish_by: HashMap<u64, NaiveDateTime> = self
+            .projects()?
+            .into_iter()
+            .filter_map(|project| Some((project.proj_id, project.must_finish_by?)))
+            .collect();
+        let graph = self.logic_graph()?;
+
+        let mut negative = Vec::new();
+        for task in &tasks {
+            let Some(total_float) = task.total_float.filter(|float| *float < 0.0) else {
+                continue;
+            };
+            // breadth first, so the first of equal floats is the nearest
+            let mut constrained: Option<(&Task, f64)> = None;
+            let mut seen = HashSet::from([task.task_id]);
+            let mut queue = VecDeque::from([task]);
+            while let Some(current) = queue.pop_front() {
+                let limits_late = current
+                    .constraint_type
+                    .as_deref()
+                    .is_some_and(|constraint| LATE_CONSTRAINTS.contains(&constraint));
+                let float = current.total_float.unwrap_or(f64::INFINITY);
+                if limits_late
+                    && float <= total_float + 0.01
+                    && constrained.is_none_or(|(_, least)| float < least)
+                {
+                    constrained = Some((current, float));
+                }
+                for link in graph.successors(current.task_id) {
+                    if let Some(&successor) = by_id.get(&link.task_id) {
+                        if seen.insert(successor.task_id) {
+                            queue.push_back(successor);
+                        }
+                    }
+                }
+            }
+            let cause = match constrained {
+                Some((constrained, _)) => Some(FloatCause::Constraint {
+                    task_code: constrained.task_code.clone(),
+                    constraint_type: constrained.constraint_type.clone().unwrap_or_default(),
+                    constraint_date: constrained.constraint_date,
+                }),
+                None => must_finish_by
+                    .get(&task.proj_id)
+                    .map(|date| FloatCause::MustFinishBy(*date)),
+            };
+            negative.push(NegativeFloat {
+                task_id: task.task_id,
+                task_code: task.task_code.clone(),
+                task_name: task.task_name.clone(),
+                total_float,
+                cause,
+            });
+        }
+        Ok(negative)
+    }
+
+    /// Incomplete activities with more than `max_days` of remaining
+    /// duration in `TASK` order, see [`check`](crate::check)
+    pub fn long_durations(&self, max_days: f64) -> Result<Vec<Exceedance>, ParseError> {
+        self.exceeding(max_days, |task| Some(task.remaining_duration))
+    }
+
+    /// Incomplete activities with more than `max_days` of total float in
+    /// `TASK` order, see [`check`](crate::check)
+    pub fn high_float(&self, max_days: f64) -> Result<Vec<Exceedance>, ParseError> {
+        self.exceeding(max_days, |task| task.total_float)
+    }
+
+    fn exceeding(
+        &self,
+        max_days: f64,
+        hours: impl Fn(&Task) -> Option<f64>,
+    ) -> Result<Vec<Exceedance>, ParseError> {
+        let day_hours: HashMap<u64, f64> = self
+            .calendars()?
+            .into_iter()
+            .map(|calendar| (calendar.clndr_id, calendar.day_hours))
+            .collect();
+        let mut exceeding = Vec::new();
+        for task in self.tasks()? {
+            if task.status == TaskStatus::Completed
+                || matches!(
+                    task.task_type,
+                    TaskType::LevelOfEffort | TaskType::WbsSummary
+                )
+            {
+                continue;
+            }
+            let Some(hours) = hours(&task) else {
+                continue;
+            };
+            let per_day = task
+                .clndr_id
+                .and_then(|id| day_hours.get(&id))
+                .copied()
+                .filter(|hours| *hours > 0.0)
+                .unwrap_or(8.0);
+            let days = hours / per_day;
+            if days > max_days {
+                exceeding.push(Exceedance {
+                    task_id: task.task_id,
+                    task_code: task.task_code,
+                    task_name: task.task_name,
+                    days,
+                });
+            }
+        }
+        Ok(exceeding)
+    }
+}
+
+/// Finish to start relationships of `task` whose predecessor had not
+/// finished when `task` started, start to start ones whose predecessor had not
+/// started
+pub(crate) fn out_of_sequence_links<'a>(
+    task: &Task,
+    graph: &'a LogicGraph,
+    tasks: &HashMap<u64, Task>,
+) -> Vec<&'a Relationship> {
+    let Some(started) = task.actual_start else {
+        return Vec::new();
+    };
+    graph
+        .predecessors(task.task_id)
+        .filter(|link| {
+            let Some(predecessor) = tasks.get(&link.pred_task_id) else {
+                return false;
+            };
+            let date = match link.link_type {
+                LinkType::FinishToStart => actual_finish(predecessor),
+                LinkType::StartToStart => predecessor.actual_start,
+                LinkType::FinishToFinish | LinkType::StartToFinish => return false,
+            };
+            date.is_none_or(|date| date > started)
+        })
+        .collect()
+}
+
+/// Start milestones finish when they start
+fn actual_finish(task: &Task) -> Option<NaiveDateTime> {
+    match task.task_type {
+        TaskType::StartMilestone => task.actual_finish.or(task.actual_start),
+        _ => task.actual_finish,
+    }
+}
+
+/// Stable sort of `items` by the WBS preorder position of their element
+fn sort_by_wbs<T>(items: &mut [T], wbs: &WbsTree, wbs_id: impl Fn(&T) -> u64) {
+    let position: HashMap<u64, usize> = wbs
+        .preorder()
+        .enumerate()
+        .map(|(i, (_, element))| (element.wbs_id, i))
+        .collect();
+    items.sort_by_key(|item| position.get(&wbs_id(item)).copied().unwrap_or(usize::MAX));
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::parse;
+
+    #[test]
+    fn logic_loops_by_activity_id() {
+        let xer = parse(
+            "%T\tTASK\n\
+             %F\ttask_id\tproj_id\twbs_id\ttask_code\ttask_name\ttask_type\tstatus_code\n\
+             %R\t1\t1\t1\tA1000\tDesign\tTT_Task\tTK_NotStart\n\
+             %R\t2\t1\t1\tA1010\tBuild\tTT_Task\tTK_NotStart\n\
+             %R\t3\t1\t1\tA1020\tTest\tTT_Task\tTK_NotStart\n\
+             %T\tTASKPRED\n%F\ttask_pred_id\ttask_id\tpred_task_id\tpred_type\n\
+             %R\t1\t2\t1\tPR_FS\n%R\t2\t3\t2\tPR_FS\n%R\t3\t2\t3\tPR_FS\n\
+             %R\t4\t3\t3\tPR_FS\n%R\t5\t9\t1\tPR_FS\n%R\t6\t1\t9\tPR_FS\n",
+        );
+        assert_eq!(
+            xer.logic_loops().unwrap(),
+            [vec!["A1000", "9"], vec!["A1010", "A1020"]]
+        );
+    }
+}
@@ -0,0 +1,457 @@
+//! Comparison of two versions of a schedule
+//!
+//! [`XerFile::compare`] matches activities by their activity ID
+//! ([`Task::task_code`]) like Claim Digger, so internal ids may differ
+//! between the versions. It reports added and deleted activities, changed
+//! names, durations, dates and floats, and changes of relationships and
+//! resource assignments. If several projects have the same activity ID, the
+//! first activity is compared. Changed driving predecessors, see
+//! [`ScheduledTask::driving`](crate::cpm::ScheduledTask::driving), are
+//! reported if both versions can be [scheduled](XerFile::schedule).
+//!
+//! ## Example
+//! ```
+//! use schedule_parser::compare::{Category, ChangeKind};
+//! use schedule_parser::XerFile;
+//!
+//! let old = "ERMHDR\t19.12\t2024-03-15\n%T\tTASK\n\
+//!     %F\ttask_id\tproj_id\twbs_id\ttask_code\ttask_name\ttask_type\tstatus_code\ttarget_drtn_hr_cnt\n\
+//!     %R\t1\t1\t1\tA1000\tExcavate\tTT_Task\tTK_NotStart\t40\n%E\n";
+//! let new = old.replace("\t40\n", "\t56\n");
+//! let old = XerFile::parse(old.as_bytes()).unwrap();
+//! let new = XerFile::parse(new.as_bytes()).unwrap();
+//!
+//! let changes = old.compare(&new).unwrap();
+//! assert_eq!(changes.len(), 1);
+//! assert_eq!(changes[0].category, Category::Duration);
+//! assert_eq!(changes[0].kind, ChangeKind::Changed);
+//! assert_eq!(changes[0].to_string(), "~ A1000 planned_duration: 40 -> 56");
+//! ```
+
+use std::collections::HashMap;
+use std::fmt;
+
+use chrono::NaiveDateTime;
+use indexmap::IndexMap;
+use serde::Serialize;
+
+use crate::error::ParseError;
+use crate::model::{Assignment, Resource, Role, Task};
+use crate::xer::XerFile;
+
+/// What a [`Change`] is about
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Category {
+    /// Activity added or deleted, its name, type or status
+    Activity,
+    Duration,
+    /// Planned, early, late, actual and constraint dates
+    Date,
+    Float,
+    /// Predecessor of the activity
+    Logic,
+    /// Resource or role assigned to the activity
+    Resource,
+}
+
+impl Category {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Activity => "activity",
+            Self::Duration => "duration",
+            Self::Date => "date",
+            Self::Float => "float",
+            Self::Logic => "logic",
+            Self::Resource => "resource",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    Added,
+    Deleted,
+    Changed,
+}
+
+impl ChangeKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Added => "added",
+            Self::Deleted => "deleted",
+            Self::Changed => "changed",
+        }
+    }
+}
+
+/// Difference between two versions of an activity
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Change {
+    /// Activity ID, of the successor for relationships
+    pub task_code: String,
+    pub category: Category,
+    pub kind: ChangeKind,
+    /// [`Task`] field like `planned_duration`, `predecessor A1000` for
+    /// relationships, `driving predecessors`, `resource ENG` or
+    /// `resource ENG budgeted_units` for assignments and `activity` for added
+    /// and deleted activities
+    pub field: String,
+    /// Value in the old version, durations and floats in hours
+    pub old: Option<String>,
+    /// Value in the new version
+    pub new: Option<String>,
+}
+
+/// `~ A1000 planned_duration: 40 -> 56`, `+` and `-` for added and deleted
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = |value: &Option<String>| value.clone().unwrap_or_else(|| "none".into());
+        match self.kind {
+            ChangeKind::Added => write!(
+                f,
+                "+ {} {}: {}",
+                self.task_code,
+                self.field,
+                value(&self.new)
+            ),
+            ChangeKind::Deleted => write!(
+                f,
+                "- {} {}: {}",
+                self.task_code,
+                self.field,
+                value(&self.old)
+            ),
+            ChangeKind::Changed => write!(
+                f,
+                "~ {} {}: {} -> {}",
+                self.task_code,
+                self.field,
+                value(&self.old),
+                value(&self.new)
+            ),
+        }
+    }
+}
+
+impl XerFile {
+    /// Changes from `self` to the `new` version of the schedule, see
+    /// [`compare`](crate::compare)
+    ///
+    /// Activities come first in the order of `self` with added ones last,
+    /// then relationships and assignments.
+    pub fn compare(&self, new: &XerFile) -> Result<Vec<Change>, ParseError> {
+        let old = Version::new(self)?;
+        let new = Version::new(new)?;
+        let mut changes = Vec::new();
+
+        for (code, task) in &old.tasks {
+            let Some(other) = new.tasks.get(code) else {
+                let name = Some(task.task_name.clone());
+                changes.push(change(
+                    code,
+                    Category::Activity,
+                    ChangeKind::Deleted,
+                    "activity",
+                    name,
+                    None,
+                ));
+                continue;
+            };
+            for ((category, field, old), (_, _, new)) in fields(task).into_iter().zip(fields(other))
+            {
+                if old != new {
+                    changes.push(change(code, category, ChangeKind::Changed, field, old, new));
+                }
+            }
+        }
+        for (code, task) in &new.tasks {
+            if !old.tasks.contains_key(code) {
+                let name = Some(task.task_name.clone());
+                changes.push(change(
+                    code,
+                    Category::Activity,
+                    ChangeKind::Added,
+                    "activity",
+                    None,
+                    name,
+                ));
+            }
+        }
+
+        for ((code, predecessor), link) in &old.logic {
+            let field = format!("predecessor {predecessor}");
+            match new.logic.get(&(code.clone(), predecessor.clone())) {
+                Some(other) if other == link => {}
+                Some(other) => changes.push(change(
+                    code,
+                    Category::Logic,
+                    ChangeKind::Changed,
+                    &field,
+                    Some(link.clone()),
+                    Some(other.clone()),
+                )),
+                None => changes.push(change(
+                    code,
+                    Category::Logic,
+                    ChangeKind::Deleted,
+                    &field,
+                    Some(link.clone()),
+                    None,
+                )),
+            }
+        }
+        for ((code, predecessor), link) in &new.logic {
+            if !old.logic.contains_key(&(code.clone(), predecessor.clone())) {
+                let field = format!("predecessor {predecessor}");
+                changes.push(change(
+                    code,
+                    Category::Logic,
+                    ChangeKind::Added,
+                    &field,
+                    None,
+                    Some(link.clone()),
+                ));
+            }
+        }
+
+        if let (Some(old_driving), Some(new_driving)) = (&old.driving, &new.driving) {
+            for (code, driving) in old_driving {
+                let Some(other) = new_driving.get(code) else {
+                    continue;
+                };
+                if other != driving {
+                    let value = |codes: &String| (!codes.is_empty()).then(|| codes.clone());
+                    changes.push(change(
+                        code,
+                        Category::Logic,
+                        ChangeKind::Changed,
+                        "driving predecessors",
+                        value(driving),
+                        value(other),
+                    ));
+                }
+            }
+        }
+
+        for ((code, resource), assignment) in &old.resources {
+            let field = format!("resource {resource}");
+            let Some(other) = new.resources.get(&(code.clone(), resource.clone())) else {
+                let units = Some(number(assignment.budgeted_units));
+                changes.push(change(
+                    code,
+                    Category::Resource,
+                    ChangeKind::Deleted,
+                    &field,
+                    units,
+                    None,
+                ));
+                continue;
+            };
+            for ((name, old), (_, new)) in assignment_fields(assignment)
+                .into_iter()
+                .zip(assignment_fields(other))
+            {
+                if old != new {
+                    let field = format!("{field} {name}");
+                    changes.push(change(
+                        code,
+                        Category::Resource,
+                        ChangeKind::Changed,
+                        &field,
+                        Some(old),
+                        Some(new),
+                    ));
+                }
+            }
+        }
+        for ((code, resource), assignment) in &new.resources {
+            if !old
+                .resources
+                .contains_key(&(code.clone(), resource.clone()))
+            {
+                let field = format!("resource {resource}");
+                let units = Some(number(assignment.budgeted_units));
+                changes.push(change(
+                    code,
+                    Category::Resource,
+                    ChangeKind::Added,
+                    &field,
+                    None,
+                    units,
+                ));
+            }
+        }
+        Ok(changes)
+    }
+}
+
+/// Activities, relationships and assignments of one version by activity ID
+struct Version {
+    tasks: IndexMap<String, Task>,
+    /// Link type and lag by activity ID of successor and predecessor, e.g. `FS 8`
+    logic: IndexMap<(String, String), String>,
+    /// Assignments by activity ID and resource or role ID
+    resources: IndexMap<(String, String), Assignment>,
+    /// Activity IDs of the driving predecessors joined by `, ` by activity
+    /// ID, `None` if the version cannot be scheduled
+    driving: Option<IndexMap<String, String>>,
+}
+
+impl Version {
+    fn new(xer: &XerFile) -> Result<Self, ParseError> {
+        let mut codes = HashMap::new();
+        let mut tasks = IndexMap::new();
+        for task in xer.tasks()? {
+            codes.insert(task.task_id, task.task_code.clone());
+            tasks.entry(task.task_code.clone()).or_insert(task);
+        }
+
+        let mut logic = IndexMap::new();
+        for link in xer.relationships()? {
+            // predecessors in other projects not in the file are skipped
+            let (Some(code), Some(predecessor)) =
+                (codes.get(&link.task_id), codes.get(&link.pred_task_id))
+            else {
+                continue;
+            };
+            let value = format!("{} {}", link.link_type.abbr(), number(link.lag));
+            logic
+                .entry((code.clone(), predecessor.clone()))
+                .or_insert(value);
+        }
+
+        let resource_names: HashMap<u64, String> = xer
+            .records::<Resource>()?
+            .into_iter()
+            .map(|resource| (resource.rsrc_id, resource.rsrc_short_name))
+            .collect();
+        let role_names: HashMap<u64, String> = xer
+            .records::<Role>()?
+            .into_iter()
+            .map(|role| (role.role_id, role.role_short_name))
+            .collect();
+        let mut resources = IndexMap::new();
+        for assignment in xer.assignments()?.iter() {
+            let Some(code) = codes.get(&assignment.task_id) else {
+                continue;
+            };
+            let name = match (assignment.rsrc_id, assignment.role_id) {
+                (Some(id), _) => resource_names
+                    .get(&id)
+                    .cloned()
+                    .unwrap_or_else(|| id.to_string()),
+                (None, Some(id)) => format!(
+                    "role {}",
+                    role_names
+                        .get(&id)
+                        .cloned()
+                        .unwrap_or_else(|| id.to_string())
+                ),
+                (None, None) => "none".into(),
+            };
+            // the same resource assigned twice is told apart by a number
+            let mut key = (code.clone(), name.clone());
+            let mut n = 1;
+            while resources.contains_key(&key) {
+                n += 1;
+                key.1 = format!("{name} #{n}");
+            }
+            resources.insert(key, assignment.clone());
+        }
+        let predecessors: HashMap<u64, u64> = xer
+            .relationships()?
+            .into_iter()
+            .map(|link| (link.task_pred_id, link.pred_task_id))
+            .collect();
+        let driving = xer.schedule().ok().map(|scheduled| {
+            let mut driving = IndexMap::new();
+            for task in scheduled {
+                let code = &codes[&task.task_id];
+                let predecessors: Vec<&str> = task
+                    .driving
+                    .iter()
+                    .map(|id| codes[&predecessors[id]].as_str())
+                    .collect();
+                driving
+                    .entry(code.clone())
+                    .or_insert_with(|| predecessors.join(", "));
+            }
+            driving
+        });
+        Ok(Self {
+            tasks,
+            logic,
+            resources,
+            driving,
+        })
+    }
+}
+
+/// Compared fields of an activity
+fn fields(task: &Task) -> [(Category, &'static str, Option<String>); 16] {
+    use Category::*;
+    [
+        (Activity, "task_name", Some(task.task_name.clone())),
+        (Activity, "task_type", Some(task.task_type.code().into())),
+        (Activity, "status", Some(task.status.code().into())),
+        (
+            Duration,
+            "planned_duration",
+            Some(number(task.planned_duration)),
+        ),
+        (
+            Duration,
+            "remaining_duration",
+            Some(number(task.remaining_duration)),
+        ),
+        (Date, "planned_start", date(task.planned_start)),
+        (Date, "planned_finish", date(task.planned_finish)),
+        (Date, "early_start", date(task.early_start)),
+        (Date, "early_finish", date(task.early_finish)),
+        (Date, "late_start", date(task.late_start)),
+        (Date, "late_finish", date(task.late_finish)),
+        (Date, "actual_start", date(task.actual_start)),
+        (Date, "actual_finish", date(task.actual_finish)),
+        (Date, "constraint_type", task.constraint_type.clone()),
+        (Date, "constraint_date", date(task.constraint_date)),
+        (Float, "total_float", task.total_float.map(number)),
+    ]
+}
+
+/// Compared fields of an assignment
+fn assignment_fields(assignment: &Assignment) -> [(&'static str, String); 4] {
+    [
+        ("budgeted_units", number(assignment.budgeted_units)),
+        ("actual_units", number(assignment.actual_units)),
+        ("remaining_units", number(assignment.remaining_units)),
+        ("budgeted_cost", number(assignment.budgeted_cost)),
+    ]
+}
+
+fn change(
+    task_code: &str,
+    category: Category,
+    kind: ChangeKind,
+    field: &str,
+    old: Option<String>,
+    new: Option<String>,
+) -> Change {
+    Change {
+        task_code: task_code.into(),
+        category,
+        kind,
+        field: field.into(),
+        old,
+        new,
+    }
+}
+
+fn number(value: f64) -> String {
+    value.to_string()
+}
+
+fn date(value: Option<NaiveDateTime>) -> Option<String> {
+    value.map(|date| date.format("%Y-%m-%d %H:%M").to_string())
+}
@@ -0,0 +1,145 @@
+//! Constraint usage of the activities
+//!
+//! [`XerFile::constraint_usage`] groups activities by primary and secondary
+//! constraint. Hard constraints fix or cap dates regardless of logic:
+//!
+//! | Code           | Name                | Hard |
+//! |----------------|---------------------|------|
+//! | `CS_MANDSTART` | Mandatory Start     | yes  |
+//! | `CS_MANDFIN`   | Mandatory Finish    | yes  |
+//! | `CS_MSO`       | Start On            | yes  |
+//! | `CS_MEO`       | Finish On           | yes  |
+//! | `CS_MSOB`      | Start On or Before  | yes  |
+//! | `CS_MEOB`      | Finish On or Before | yes  |
+//! | `CS_MSOA`      | Start On or After   | no   |
+//! | `CS_MEOA`      | Finish On or After  | no   |
+//! | `CS_ALAP`      | As Late As Possible | no   |
+//!
+//! ## Example
+//! ```
+//! use schedule_parser::XerFile;
+//!
+//! let data = "ERMHDR\t19.12\t2024-03-15\n%T\tTASK\n\
+//!     %F\ttask_id\tproj_id\twbs_id\ttask_code\ttask_name\ttask_type\tstatus_code\tcstr_type\tcstr_date\n\
+//!     %R\t1\t1\t1\tA1000\tDesign\tTT_Task\tTK_NotStart\tCS_MSOA\t2024-03-18 08:00\n\
+//!     %R\t2\t1\t1\tA1010\tHandover\tTT_FinMile\tTK_NotStart\tCS_MANDFIN\t2024-06-28 17:00\n%E\n";
+//! let xer = XerFile::parse(data.as_bytes()).unwrap();
+//!
+//! let groups = xer.constraint_usage().unwrap();
+//! assert_eq!(groups[0].name, "Mandatory Finish");
+//! assert!(groups[0].hard);
+//! assert_eq!(groups[1].activities[0].task_code, "A1000");
+//! ```
+
+use chrono::NaiveDateTime;
+use serde::Serialize;
+
+use crate::error::ParseError;
+use crate::xer::XerFile;
+
+/// Code, name and whether it is hard, in report order
+const CONSTRAINTS: &[(&str, &str, bool)] = &[
+    ("CS_MANDSTART", "Mandatory Start", true),
+    ("CS_MANDFIN", "Mandatory Finish", true),
+    ("CS_MSO", "Start On", true),
+    ("CS_MEO", "Finish On", true),
+    ("CS_MSOB", "Start On or Before", true),
+    ("CS_MEOB", "Finish On or Before", true),
+    ("CS_MSOA", "Start On or After", false),
+    ("CS_MEOA", "Finish On or After", false),
+    ("CS_ALAP", "As Late As Possible", false),
+];
+
+/// Name shown in P6 of a `cstr_type` like `CS_MSOA`
+pub fn constraint_name(code: &str) -> Option<&'static str> {
+    CONSTRAINTS
+        .iter()
+        .find(|(c, _, _)| *c == code)
+        .map(|(_, name, _)| *name)
+}
+
+/// `cstr_type` of a constraint name shown in P6 like `Start On or After`
+pub fn constraint_code(name: &str) -> Option<&'static str> {
+    CONSTRAINTS
+        .iter()
+        .find(|(_, n, _)| *n == name)
+        .map(|(code, _, _)| *code)
+}
+
+/// Whether a `cstr_type` overrides logic, see [`constraints`](crate::constraints)
+pub fn is_hard_constraint(code: &str) -> bool {
+    CONSTRAINTS.iter().any(|(c, _, hard)| *c == code && *hard)
+}
+
+/// Activities with one constraint type
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ConstraintGroup {
+    /// `cstr_type`, e.g. `CS_MSOA`
+    pub constraint_type: String,
+    /// Name shown in P6, the code for unknown types
+    pub name: String,
+    pub hard: bool,
+    pub activities: Vec<ConstrainedTask>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ConstrainedTask {
+    pub task_id: u64,
+    pub task_code: String,
+    pub task_name: String,
+    pub constraint_date: Option<NaiveDateTime>,
+    /// Constraint is the secondary one, `cstr_type2`
+    pub secondary: bool,
+}
+
+impl XerFile {
+    /// Constrained activities grouped by constraint type, hard ones first,
+    /// see [`constraints`](crate::constraints)
+    ///
+    /// Activities keep `TASK` order within a group, unknown types come last.
+    pub fn constraint_usage(&self) -> Result<Vec<ConstraintGroup>, ParseError> {
+        let mut groups: Vec<ConstraintGroup> = Vec::new();
+        for task in self.tasks()? {
+            let constraints = [
+                (&task.constraint_type, task.constraint_date, false),
+                (
+                    &task.secondary_constraint_type,
+                    task.secondary_constraint_date,
+                    true,
+                ),
+            ];
+            for (constraint_type, constraint_date, secondary) in constraints {
+                let Some(constraint_type) = constraint_type else {
+                    continue;
+                };
+                let activity = ConstrainedTask {
+                    task_id: task.task_id,
+                    task_code: task.task_code.clone(),
+                    task_name: task.task_name.clone(),
+                    constraint_date,
+                    secondary,
+                };
+                match groups
+                    .iter_mut()
+                    .find(|group| group.constraint_type == *constraint_type)
+                {
+                    Some(group) => group.activities.push(activity),
+                    None => groups.push(ConstraintGroup {
+                        constraint_type: constraint_type.clone(),
+                        name: constraint_name(constraint_type)
+                            .map_or_else(|| constraint_type.clone(), String::from),
+                        hard: is_hard_constraint(constraint_type),
+                        activities: vec![activity],
+                    }),
+                }
+            }
+        }
+        groups.sort_by_key(|group| {
+            CONSTRAINTS
+                .iter()
+                .position(|(code, _, _)| *code == group.constraint_type)
+                .unwrap_or(CONSTRAINTS.len())
+        });
+        Ok(groups)
+    }
+}
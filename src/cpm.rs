@@ -0,0 +1,757 @@
+//! Critical path scheduling independent of P6
+//!
+//! [`XerFile::schedule`] recomputes early and late dates and floats from
+//! `TASK`, `TASKPRED`, `CALENDAR`, `PROJECT` and `SCHEDOPTIONS` to audit the
+//! dates P6 exported:
+//!
+//! - durations and lags are counted in work hours on the activity calendar,
+//!   lags on the calendar chosen in the scheduling options
+//! - completed activities keep their actual dates, activities in progress
+//!   schedule their remaining duration from the data date
+//! - start on, start and finish on or after, on or before and mandatory
+//!   constraints are honored, as late as possible is not
+//! - activities without successors finish by the project's must finish by
+//!   date or the latest early finish of their project
+//! - level of effort and WBS summary activities are not scheduled
+//! - activities of projects missing from `PROJECT` use the default calendar
+//!   and the latest data date, or the export date without any project
+//!
+//! [`XerFile::reconcile_float`] lists activities whose exported total float
+//! differs from the computed one.
+//!
+//! [`XerFile::driving_relationships`] lists the relationships determining
+//! early starts, [`ScheduledTask::driving`] those of one activity.
+//!
+//! [`XerFile::longest_path`] follows the relationships driving early starts
+//! back from the activities finishing last in each project, like the longest
+//! path of P6. Progress is respected as the chain ends at completed
+//! activities and those driven by the data date, and it ends at
+//! relationships from other projects too.
+//!
+//! ## Example
+//! ```
+//! use schedule_parser::XerFile;
+//!
+//! let data = "ERMHDR\t19.12\t2024-03-15\n\
+//!     %T\tPROJECT\n%F\tproj_id\tproj_short_name\tlast_recalc_date\n%R\t1\tA\t2024-03-04 08:00\n\
+//!     %T\tTASK\n%F\ttask_id\tproj_id\twbs_id\ttask_code\ttask_name\ttask_type\tstatus_code\tremain_drtn_hr_cnt\n\
+//!     %R\t1\t1\t1\tA1000\tDesign\tTT_Task\tTK_NotStart\t48\n\
+//!     %R\t2\t1\t1\tA1010\tPermit\tTT_Task\tTK_NotStart\t24\n\
+//!     %T\tTASKPRED\n%F\ttask_pred_id\ttask_id\tpred_task_id\tpred_type\tlag_hr_cnt\n%R\t1\t2\t1\tPR_FS\t0\n%E\n";
+//! let xer = XerFile::parse(data.as_bytes()).unwrap();
+//!
+//! // without calendars time is counted continuously
+//! let schedule = xer.schedule().unwrap();
+//! assert_eq!(schedule[1].early_start.to_string(), "2024-03-06 08:00:00");
+//! assert_eq!(schedule[1].early_finish.to_string(), "2024-03-07 08:00:00");
+//! assert_eq!(schedule[0].total_float, Some(0.0));
+//! assert_eq!(schedule[1].driving, [1]);
+//!
+//! let path: Vec<u64> = xer.longest_path().unwrap().iter().map(|task| task.task_id).collect();
+//! assert_eq!(path, [1, 2]);
+//! ```
+
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::fmt;
+
+use chrono::{NaiveDateTime, NaiveTime};
+use serde::Serialize;
+
+use crate::check::out_of_sequence_links;
+use crate::error::ParseError;
+use crate::graph::{LogicGraph, LogicLoop};
+use crate::model::{
+    Calendar, FloatType, LagCalendar, LinkType, OutOfSequence, Project, Relationship, SchedOptions,
+    Task, TaskStatus, TaskType,
+};
+use crate::xer::XerFile;
+
+/// Dates and floats of an activity computed by [`XerFile::schedule`]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ScheduledTask {
+    pub task_id: u64,
+    /// Remaining early start for activities in progress
+    pub early_start: NaiveDateTime,
+    pub early_finish: NaiveDateTime,
+    pub late_start: NaiveDateTime,
+    pub late_finish: NaiveDateTime,
+    /// Hours on the activity calendar, `None` for completed activities
+    pub total_float: Option<f64>,
+    pub free_float: Option<f64>,
+    /// `task_pred_id` of the relationships determining the early start, none
+    /// if the data date, a constraint or actual dates do
+    pub driving: Vec<u64>,
+}
+
+/// Activity whose total float in the file differs from the computed one,
+/// see [`XerFile::reconcile_float`]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FloatDiscrepancy {
+    pub task_id: u64,
+    pub task_code: String,
+    /// Total float exported by P6 in hours
+    pub p6_total_float: f64,
+    /// Total float computed by [`XerFile::schedule`] in hours
+    pub total_float: f64,
+    /// Likely reason P6 scheduled the activity differently
+    pub cause: Option<FloatAnomaly>,
+}
+
+/// `A1020: total float 0 in P6, 8 computed (out of sequence progress)`
+impl fmt::Display for FloatDiscrepancy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: total float {} in P6, {} computed",
+            self.task_code, self.p6_total_float, self.total_float
+        )?;
+        match self.cause {
+            Some(cause) => write!(f, " ({})", cause.name()),
+            None => Ok(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FloatAnomaly {
+    /// Activity started before a start or finish to start predecessor
+    /// allowed it
+    OutOfSequence,
+    /// Activity has a schedule constraint
+    Constraint,
+}
+
+impl FloatAnomaly {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::OutOfSequence => "out of sequence progress",
+            Self::Constraint => "constraint",
+        }
+    }
+}
+
+/// Failure of [`XerFile::schedule`]
+#[derive(Debug)]
+pub enum ScheduleError {
+    Parse(ParseError),
+    /// Relationships form a loop, the network has no order to schedule in
+    Loop(LogicLoop),
+    /// Project has neither data date nor planned start
+    MissingDataDate {
+        proj_id: u64,
+    },
+}
+
+impl fmt::Display for ScheduleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse(err) => err.fmt(f),
+            Self::Loop(err) => err.fmt(f),
+            Self::MissingDataDate { proj_id } => {
+                write!(f, "project {proj_id} has no data date")
+            }
+        }
+    }
+}
+
+impl Error for ScheduleError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Parse(err) => Some(err),
+            Self::Loop(err) => Some(err),
+            Self::MissingDataDate { .. } => None,
+        }
+    }
+}
+
+impl From<ParseError> for ScheduleError {
+    fn from(err: ParseError) -> Self {
+        Self::Parse(err)
+    }
+}
+
+impl From<LogicLoop> for ScheduleError {
+    fn from(err: LogicLoop) -> Self {
+        Self::Loop(err)
+    }
+}
+
+impl XerFile {
+    /// Early and late dates of all scheduled activities in `TASK` order, see
+    /// [`cpm`](crate::cpm)
+    pub fn schedule(&self) -> Result<Vec<ScheduledTask>, ScheduleError> {
+        self.schedule_tasks(self.tasks()?)
+    }
+
+    /// Schedule `tasks` instead of those in the file, e.g. with a changed
+    /// duration
+    pub(crate) fn schedule_tasks(
+        &self,
+        tasks: Vec<Task>,
+    ) -> Result<Vec<ScheduledTask>, ScheduleError> {
+        let tasks: Vec<Task> = tasks
+            .into_iter()
+            .filter(|task| {
+                !matches!(
+                    task.task_type,
+                    TaskType::LevelOfEffort | TaskType::WbsSummary
+                )
+            })
+            .collect();
+        let index: HashMap<u64, usize> = tasks
+            .iter()
+            .enumerate()
+            .map(|(i, task)| (task.task_id, i))
+            .collect();
+        let relationships: Vec<Relationship> = self
+            .relationships()?
+            .into_iter()
+            .filter(|link| {
+                index.contains_key(&link.task_id) && index.contains_key(&link.pred_task_id)
+            })
+            .collect();
+        let graph = LogicGraph::new(tasks.iter().map(|task| task.task_id), relationships);
+        let order = graph.topological_order()?;
+
+        let calendars: HashMap<u64, Calendar> = self
+            .calendars()?
+            .into_iter()
+            .map(|calendar| (calendar.clndr_id, calendar))
+            .collect();
+        let default_calendar = calendars
+            .values()
+            .find(|calendar| calendar.default)
+            .map(|c| c.clndr_id);
+        let options: HashMap<u64, SchedOptions> = self
+            .schedule_options()?
+            .into_iter()
+            .map(|options| (options.proj_id, options))
+            .collect();
+        let mut projects = HashMap::new();
+        for project in self.projects()? {
+            let calendar = project
+                .clndr_id
+                .or(default_calendar)
+                .and_then(|id| calendars.get(&id));
+            projects.insert(
+                project.proj_id,
+                project_info(&project, calendar, options.get(&project.proj_id)),
+            );
+        }
+        // activities of projects not in the file use the default calendar and
+        // the latest data date, the export date in files without projects
+        let fallback = ProjectInfo {
+            calendar: default_calendar.and_then(|id| calendars.get(&id)),
+            data_date: projects
+                .values()
+                .filter_map(|info| info.data_date)
+                .max()
+                .or_else(|| Some(self.header.export_date.and_time(NaiveTime::MIN))),
+            must_finish_by: None,
+            retained_logic: true,
+            lag_calendar: LagCalendar::Predecessor,
+            float_type: FloatType::Finish,
+        };
+        let project = |task: &Task| projects.get(&task.proj_id).unwrap_or(&fallback);
+        let calendar = |task: &Task| {
+            task.clndr_id
+                .and_then(|id| calendars.get(&id))
+                .or(project(task).calendar)
+        };
+        for task in &tasks {
+            if project(task).data_date.is_none() {
+                return Err(ScheduleError::MissingDataDate {
+                    proj_id: task.proj_id,
+                });
+            }
+        }
+        // activity calendar of predecessor and successor, project calendar
+        let lag_calendar =
+            |predecessor: &Task, successor: &Task| match project(successor).lag_calendar {
+                LagCalendar::Predecessor => calendar(predecessor),
+                LagCalendar::Successor => calendar(successor),
+                LagCalendar::TwentyFourHour => None,
+                LagCalendar::ProjectDefault => project(successor).calendar,
+            };
+
+        let mut early = vec![None; tasks.len()];
+        let mut driving = vec![Vec::new(); tasks.len()];
+        for &id in &order {
+            let task = &tasks[index[&id]];
+            let cal = calendar(task);
+            let data_date = project(task).data_date.unwrap_or_default();
+            let duration = task.remaining_duration;
+            if let Some(actual) = actual_dates(task) {
+                early[index[&id]] = Some(actual);
+                continue;
+            }
+
+            let mut start = data_date;
+            let mut bounds = Vec::new();
+            let in_progress = task.status == TaskStatus::InProgress && task.actual_start.is_some();
+            if !in_progress || project(task).retained_logic {
+                for link in graph.predecessors(id) {
+                    let predecessor = &tasks[index[&link.pred_task_id]];
+                    let Some((pred_start, pred_finish)) = early[index[&link.pred_task_id]] else {
+                        continue;
+                    };
+                    let lag = lag_calendar(predecessor, task);
+                    let bound = match link.link_type {
+                        LinkType::FinishToStart => add(lag, pred_finish, link.lag),
+                        LinkType::StartToStart => add(lag, pred_start, link.lag),
+                        LinkType::FinishToFinish => {
+                            add(cal, add(lag, pred_finish, link.lag), -duration)
+                        }
+                        LinkType::StartToFinish => {
+                            add(cal, add(lag, pred_start, link.lag), -duration)
+                        }
+                    };
+                    start = start.max(bound);
+                    bounds.push((link.task_pred_id, bound));
+                }
+            }
+            if !in_progress {
+                if let Some(date) = task.constraint_date {
+                    match task.constraint_type.as_deref() {
+                        Some("CS_MSOA") => start = start.max(date),
+                        Some("CS_MEOA") => start = start.max(add(cal, date, -duration)),
+                        Some("CS_MSO" | "CS_MANDSTART") => start = date,
+                        Some("CS_MEO" | "CS_MANDFIN") => start = add(cal, date, -duration),
+                        _ => {}
+                    }
+                }
+            }
+            // finish milestones finish when their predecessors do
+            let to_work_time = |time| match task.task_type {
+                TaskType::FinishMilestone => time,
+                _ => next_work_time(cal, time),
+            };
+            start = to_work_time(start);
+            driving[index[&id]] = bounds
+                .into_iter()
+                .filter(|&(_, bound)| to_work_time(bound) == start)
+                .map(|(task_pred_id, _)| task_pred_id)
+                .collect();
+            early[index[&id]] = Some((start, add(cal, start, duration)));
+        }
+        let early: Vec<(NaiveDateTime, NaiveDateTime)> =
+            early.into_iter().map(Option::unwrap).collect();
+
+        // open ends finish with their project
+        let mut finish_by: HashMap<u64, NaiveDateTime> = HashMap::new();
+        for (task, &(_, finish)) in tasks.iter().zip(&early) {
+            let latest = finish_by.entry(task.proj_id).or_insert(finish);
+            *latest = (*latest).max(finish);
+        }
+        for (proj_id, info) in &projects {
+            if let (Some(date), Some(latest)) = (info.must_finish_by, finish_by.get_mut(proj_id)) {
+                *latest = date;
+            }
+        }
+
+        let mut late = vec![(NaiveDateTime::default(), NaiveDateTime::default()); tasks.len()];
+        for &id in order.iter().rev() {
+            let i = index[&id];
+            let task = &tasks[i];
+            let cal = calendar(task);
+            if actual_dates(task).is_some() {
+                late[i] = early[i];
+                continue;
+            }
+            let duration = task.remaining_duration;
+            let mut finish = finish_by[&task.proj_id];
+            for link in graph.successors(id) {
+                let j = index[&link.task_id];
+                let successor = &tasks[j];
+                let (late_start, late_finish) = late[j];
+                let lag = lag_calendar(task, successor);
+                let bound = match link.link_type {
+                    LinkType::FinishToStart => add(lag, late_start, -link.lag),
+                    LinkType::FinishToFinish => add(lag, late_finish, -link.lag),
+                    LinkType::StartToStart => add(cal, add(lag, late_start, -link.lag), duration),
+                    LinkType::StartToFinish => add(cal, add(lag, late_finish, -link.lag), duration),
+                };
+                finish = finish.min(bound);
+            }
+            if let Some(date) = task.constraint_date {
+                match task.constraint_type.as_deref() {
+                    Some("CS_MSOB") => finish = finish.min(add(cal, date, duration)),
+                    Some("CS_MEOB") => finish = finish.min(date),
+                    Some("CS_MSO" | "CS_MANDSTART") => finish = add(cal, date, duration),
+                    Some("CS_MEO" | "CS_MANDFIN") => finish = date,
+                    _ => {}
+                }
+            }
+            // start milestones start when their successors do
+            if task.task_type != TaskType::StartMilestone {
+                finish = previous_work_time(cal, finish);
+            }
+            late[i] = (add(cal, finish, -duration), finish);
+        }
+
+        let mut scheduled = Vec::with_capacity(tasks.len());
+        for ((i, task), driving) in tasks.iter().enumerate().zip(driving) {
+            let cal = calendar(task);
+            let (early_start, early_finish) = early[i];
+            let (late_start, late_finish) = late[i];
+            let completed = actual_dates(task).is_some();
+            let total_float = (!completed).then(|| {
+                let start = between(cal, early_start, late_start);
+                let finish = between(cal, early_finish, late_finish);
+                match project(task).float_type {
+                    FloatType::Start => start,
+                    FloatType::Finish => finish,
+                    FloatType::Smallest => start.min(finish),
+                }
+            });
+            let free_float = (!completed).then(|| {
+                let mut free = between(cal, early_finish, finish_by[&task.proj_id]);
+                for link in graph.successors(task.task_id) {
+                    let j = index[&link.task_id];
+                    let (successor_start, successor_finish) = early[j];
+                    let lag = lag_calendar(task, &tasks[j]);
+                    let slack = match link.link_type {
+                        LinkType::FinishToStart => {
+                            between(cal, early_finish, add(lag, successor_start, -link.lag))
+                        }
+                        LinkType::StartToStart => {
+                            between(cal, early_start, add(lag, successor_start, -link.lag))
+                        }
+                        LinkType::FinishToFinish => {
+                            between(cal, early_finish, add(lag, successor_finish, -link.lag))
+                        }
+                        LinkType::StartToFinish => {
+                            between(cal, early_start, add(lag, successor_finish, -link.lag))
+                        }
+                    };
+                    free = free.min(slack);
+                }
+                free.max(0.0)
+            });
+            scheduled.push(ScheduledTask {
+                task_id: task.task_id,
+                early_start,
+                early_finish,
+                late_start,
+                late_finish,
+                total_float,
+                free_float,
+                driving,
+            });
+        }
+        Ok(scheduled)
+    }
+
+    /// Activities whose total float in the file differs by more than
+    /// `tolerance` hours from [`schedule`](Self::schedule), in `TASK` order
+    ///
+    /// Activities without total float in the file, like completed ones, are
+    /// skipped.
+    pub fn reconcile_float(&self, tolerance: f64) -> Result<Vec<FloatDiscrepancy>, ScheduleError> {
+        let tasks: HashMap<u64, Task> = self
+            .tasks()?
+            .into_iter()
+            .map(|task| (task.task_id, task))
+            .collect();
+        let graph = self.logic_graph()?;
+        let mut discrepancies = Vec::new();
+        for scheduled in self.schedule()? {
+            let task = &tasks[&scheduled.task_id];
+            let (Some(p6_total_float), Some(total_float)) =
+                (task.total_float, scheduled.total_float)
+            else {
+                continue;
+            };
+            if (p6_total_float - total_float).abs() <= tolerance {
+                continue;
+            }
+            let cause = if !out_of_sequence_links(task, &graph, &tasks).is_empty() {
+                Some(FloatAnomaly::OutOfSequence)
+            } else if task.constraint_type.is_some() {
+                Some(FloatAnomaly::Constraint)
+            } else {
+                None
+            };
+            discrepancies.push(FloatDiscrepancy {
+                task_id: task.task_id,
+                task_code: task.task_code.clone(),
+                p6_total_float,
+                total_float,
+                cause,
+            });
+        }
+        Ok(discrepancies)
+    }
+
+    /// Relationships determining the early start of their successor in
+    /// `TASKPRED` order, see [`ScheduledTask::driving`]
+    pub fn driving_relationships(&self) -> Result<Vec<Relationship>, ScheduleError> {
+        let driving: HashSet<u64> = self
+            .schedule()?
+            .into_iter()
+            .flat_map(|task| task.driving)
+            .collect();
+        Ok(self
+            .relationships()?
+            .into_iter()
+            .filter(|link| driving.contains(&link.task_pred_id))
+            .collect())
+    }
+
+    /// Activities on the longest path of each project ordered by early start,
+    /// see [`cpm`](crate::cpm)
+    ///
+    /// Projects come in the order of their first activity in `TASK`, ties in
+    /// driving relationships put all tied chains on the path.
+    pub fn longest_path(&self) -> Result<Vec<ScheduledTask>, ScheduleError> {
+        let scheduled = self.schedule()?;
+        let projects: HashMap<u64, u64> = self
+            .tasks()?
+            .into_iter()
+            .map(|task| (task.task_id, task.proj_id))
+            .collect();
+        let predecessors: HashMap<u64, u64> = self
+            .relationships()?
+            .into_iter()
+            .map(|link| (link.task_pred_id, link.pred_task_id))
+            .collect();
+        let index: HashMap<u64, usize> = scheduled
+            .iter()
+            .enumerate()
+            .map(|(i, task)| (task.task_id, i))
+            .collect();
+
+        // project order and latest early finish
+        let mut order: Vec<u64> = Vec::new();
+        let mut finish: HashMap<u64, NaiveDateTime> = HashMap::new();
+        for task in &scheduled {
+            let proj_id = projects[&task.task_id];
+            let latest = finish.entry(proj_id).or_insert_with(|| {
+                order.push(proj_id);
+                task.early_finish
+            });
+            *latest = (*latest).max(task.early_finish);
+        }
+
+        let mut on_path = vec![false; scheduled.len()];
+        let mut stack: Vec<usize> = (0..scheduled.len())
+            .filter(|&i| {
+                let task = &scheduled[i];
+                task.early_finish == finish[&projects[&task.task_id]]
+            })
+            .collect();
+        while let Some(i) = stack.pop() {
+            if on_path[i] {
+                continue;
+            }
+            on_path[i] = true;
+            let proj_id = projects[&scheduled[i].task_id];
+            for task_pred_id in &scheduled[i].driving {
+                let j = index[&predecessors[task_pred_id]];
+                if projects[&scheduled[j].task_id] == proj_id {
+                    stack.push(j);
+                }
+            }
+        }
+
+        let mut path: Vec<ScheduledTask> = scheduled
+            .into_iter()
+            .zip(on_path)
+            .filter_map(|(task, on_path)| on_path.then_some(task))
+            .collect();
+        path.sort_by_key(|task| {
+            let proj_id = projects[&task.task_id];
+            let project = order.iter().position(|&id| id == proj_id);
+            (project, task.early_start, task.early_finish)
+        });
+        Ok(path)
+    }
+}
+
+/// Start and finish of a completed activity, milestones have only one
+fn actual_dates(task: &Task) -> Option<(NaiveDateTime, NaiveDateTime)> {
+    if task.status != TaskStatus::Completed {
+        return None;
+    }
+    let start = task.actual_start.or(task.actual_finish)?;
+    Some((start, task.actual_finish.unwrap_or(start)))
+}
+
+/// Scheduling settings of a project
+struct ProjectInfo<'a> {
+    calendar: Option<&'a Calendar>,
+    data_date: Option<NaiveDateTime>,
+    must_finish_by: Option<NaiveDateTime>,
+    retained_logic: bool,
+    lag_calendar: LagCalendar,
+    float_type: FloatType,
+}
+
+fn project_info<'a>(
+    project: &Project,
+    calendar: Option<&'a Calendar>,
+    options: Option<&SchedOptions>,
+) -> ProjectInfo<'a> {
+    ProjectInfo {
+        calendar,
+        data_date: project.data_date.or(project.planned_start),
+        must_finish_by: project.must_finish_by,
+        retained_logic: options.map_or(project.retained_logic, |options| {
+            options.out_of_sequence == OutOfSequence::RetainedLogic
+        }),
+        lag_calendar: options
+            .map(|options| options.lag_calendar)
+            .unwrap_or_else(|| {
+                project
+                    .lag_calendar
+                    .as_deref()
+                    .and_then(LagCalendar::from_code)
+                    .unwrap_or(LagCalendar::Predecessor)
+            }),
+        float_type: options.map_or(FloatType::Finish, |options| options.float_type),
+    }
+}
+
+// without calendar time is counted continuously
+
+fn add(calendar: Option<&Calendar>, time: NaiveDateTime, hours: f64) -> NaiveDateTime {
+    match calendar {
+        Some(calendar) => calendar.add_work_hours(time, hours),
+        None => time + chrono::TimeDelta::minutes((hours * 60.0).round() as i64),
+    }
+}
+
+fn between(calendar: Option<&Calendar>, from: NaiveDateTime, to: NaiveDateTime) -> f64 {
+    match calendar {
+        Some(calendar) => calendar.work_hours_between(from, to),
+        None => (to - from).num_minutes() as f64 / 60.0,
+    }
+}
+
+fn next_work_time(calendar: Option<&Calendar>, time: NaiveDateTime) -> NaiveDateTime {
+    calendar.map_or(time, |calendar| calendar.next_work_time(time))
+}
+
+fn previous_work_time(calendar: Option<&Calendar>, time: NaiveDateTime) -> NaiveDateTime {
+    calendar.map_or(time, |calendar| calendar.previous_work_time(time))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::parse;
+    use crate::XerFile;
+
+    const WEEK: &str = "(0||0(s|08:00|f|12:00)())(0||1(s|13:00|f|17:00)())";
+
+    /// Standard calendar off on weekends and Easter Monday 2024-04-01, seven
+    /// day calendar without holidays, data date Thursday 2024-03-28 08:00
+    fn fixture(lag_calendar: &str) -> XerFile {
+        let day = |number: u32| format!("(0||{number}()({WEEK}))");
+        let standard: String = (2..=6).map(day).collect();
+        let seven_day: String = (1..=7).map(day).collect();
+        parse(&format!(
+            "%T\tCALENDAR\n%F\tclndr_id\tclndr_name\tdefault_flag\tclndr_data\n\
+             %R\t1\tStandard\tY\t(0||CalendarData()((0||DaysOfWeek()({standard}))\
+             (0||Exceptions()((0||0(d|45383)())))))\n\
+             %R\t2\tSeven day\tN\t(0||CalendarData()((0||DaysOfWeek()({seven_day}))))\n\
+             %T\tPROJECT\n%F\tproj_id\tproj_short_name\tclndr_id\tlast_recalc_date\t\
+             sched_calendar_on_relationship_lag\n%R\t1\tA\t1\t2024-03-28 08:00\t{lag_calendar}\n\
+             %T\tTASK\n%F\ttask_id\tproj_id\twbs_id\tclndr_id\ttask_code\ttask_name\ttask_type\t\
+             status_code\tremain_drtn_hr_cnt\tcstr_type\tcstr_date\n\
+             %R\t1\t1\t1\t1\tA1000\tDesign\tTT_Task\tTK_NotStart\t16\t\t\n\
+             %R\t2\t1\t1\t2\tA1010\tCure\tTT_Task\tTK_NotStart\t8\t\t\n\
+             %R\t3\t1\t1\t1\tA1020\tPermit\tTT_Task\tTK_NotStart\t8\tCS_MSOA\t2024-04-08 08:00\n\
+             %R\t4\t1\t1\t1\tA1030\tHandover\tTT_Task\tTK_NotStart\t8\tCS_MEOB\t2024-04-08 17:00\n\
+             %T\tTASKPRED\n%F\ttask_pred_id\ttask_id\tpred_task_id\tpred_type\tlag_hr_cnt\n\
+             %R\t1\t2\t1\tPR_FS\t8\n%R\t2\t3\t2\tPR_FS\t0\n%R\t3\t4\t3\tPR_FS\t0\n"
+        ))
+    }
+
+    fn dates(xer: &XerFile) -> Vec<[String; 4]> {
+        xer.schedule()
+            .unwrap()
+            .iter()
+            .map(|task| {
+                [
+                    task.early_start,
+                    task.early_finish,
+                    task.late_start,
+                    task.late_finish,
+                ]
+                .map(|date| date.format("%m-%d %H:%M").to_string())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn calendars_lags_and_constraints() {
+        let xer = fixture("rcal_Predecessor");
+        // the lag skips the weekend and holiday of the standard calendar
+        assert_eq!(
+            dates(&xer),
+            [
+                ["03-28 08:00", "03-29 17:00", "03-29 08:00", "04-02 17:00"],
+                ["04-03 08:00", "04-03 17:00", "04-04 08:00", "04-04 17:00"],
+                ["04-08 08:00", "04-08 17:00", "04-05 08:00", "04-05 17:00"],
+                ["04-09 08:00", "04-09 17:00", "04-08 08:00", "04-08 17:00"],
+            ]
+        );
+        let schedule = xer.schedule().unwrap();
+        let floats: Vec<_> = schedule.iter().map(|task| task.total_float).collect();
+        assert_eq!(floats, [Some(8.0), Some(8.0), Some(-8.0), Some(-8.0)]);
+        // the start on or after date drives, not the predecessor
+        assert_eq!(schedule[1].driving, [1]);
+        assert!(schedule[2].driving.is_empty());
+        assert_eq!(schedule[3].driving, [3]);
+    }
+
+    #[test]
+    fn lag_on_successor_calendar() {
+        let xer = fixture("rcal_Successor");
+        let dates = dates(&xer);
+        // Saturday is a work day of the seven day calendar
+        assert_eq!(dates[1][0], "03-31 08:00");
+        assert_eq!(dates[2][0], "04-08 08:00");
+    }
+
+    #[test]
+    fn longest_path_stays_within_its_project() {
+        // B1000 is driven by A1030, which does not drive the finish of A
+        let xer = parse(
+            "%T\tPROJECT\n%F\tproj_id\tproj_short_name\tlast_recalc_date\n\
+             %R\t1\tPRJ-A\t2024-03-04 08:00\n%R\t2\tPRJ-B\t2024-03-04 08:00\n\
+             %T\tTASK\n\
+             %F\ttask_id\tproj_id\twbs_id\ttask_code\ttask_name\ttask_type\tstatus_code\tremain_drtn_hr_cnt\n\
+             %R\t1\t1\t1\tA1000\tDesign\tTT_Task\tTK_NotStart\t48\n\
+             %R\t2\t1\t1\tA1010\tBuild\tTT_Task\tTK_NotStart\t24\n\
+             %R\t3\t1\t1\tA1020\tOrder\tTT_Task\tTK_NotStart\t8\n\
+             %R\t4\t1\t1\tA1030\tShip\tTT_Task\tTK_NotStart\t8\n\
+             %R\t5\t2\t2\tB1000\tInstall\tTT_Task\tTK_NotStart\t8\n\
+             %T\tTASKPRED\n%F\ttask_pred_id\ttask_id\tpred_task_id\tpred_type\tlag_hr_cnt\n\
+             %R\t1\t2\t1\tPR_FS\t0\n%R\t2\t4\t3\tPR_FS\t0\n%R\t3\t5\t4\tPR_FS\t8\n",
+        );
+        let schedule = xer.schedule().unwrap();
+        assert_eq!(schedule[4].driving, [3]);
+        let path: Vec<u64> = xer
+            .longest_path()
+            .unwrap()
+            .iter()
+            .map(|task| task.task_id)
+            .collect();
+        assert_eq!(path, [1, 2, 5]);
+    }
+
+    #[test]
+    fn activities_without_project_start_at_export_date() {
+        let xer = parse(
+            "%T\tTASK\n\
+             %F\ttask_id\tproj_id\twbs_id\ttask_code\ttask_name\ttask_type\tstatus_code\tremain_drtn_hr_cnt\n\
+             %R\t1\t1\t1\tA1000\tDesign\tTT_Task\tTK_NotStart\t48\n",
+        );
+        let schedule = xer.schedule().unwrap();
+        assert_eq!(schedule[0].early_start.to_string(), "2024-03-15 00:00:00");
+        assert_eq!(schedule[0].early_finish.to_string(), "2024-03-17 00:00:00");
+    }
+}
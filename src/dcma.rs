@@ -0,0 +1,437 @@
+//! DCMA 14-point schedule assessment
+//!
+//! [`XerFile::assess_dcma`] runs the checks of the Defense Contract
+//! Management Agency on all activities except level of effort and WBS
+//! summary ones. Planned dates (`target_start_date`, `target_end_date`) serve
+//! as baseline, days are work days of the activity calendar.
+//!
+//! | #  | Check              | Counted                                                  | Passes  |
+//! |----|--------------------|----------------------------------------------------------|---------|
+//! | 1  | Logic              | incomplete activities without predecessor or successor   | <= 5 %  |
+//! | 2  | Leads              | relationships of incomplete activities with negative lag | 0 %     |
+//! | 3  | Lags               | relationships of incomplete activities with positive lag | <= 5 %  |
+//! | 4  | Relationship types | finish to start relationships                            | >= 90 % |
+//! | 5  | Hard constraints   | incomplete activities with mandatory or on or before     | <= 5 %  |
+//! | 6  | High float         | incomplete activities with more than 44 days total float | <= 5 %  |
+//! | 7  | Negative float     | incomplete activities with negative total float          | 0 %     |
+//! | 8  | High duration      | incomplete activities planned longer than 44 days        | <= 5 %  |
+//! | 9  | Invalid dates      | forecasts before or actuals after the data date          | 0 %     |
+//! | 10 | Resources          | incomplete activities with duration but no assignment    | 0 %     |
+//! | 11 | Missed tasks       | activities due by the data date finished late or not     | <= 5 %  |
+//! | 12 | Critical path test | project finish slips as much as the most critical one    | yes     |
+//! | 13 | CPLI               | (critical path length + float) / critical path length    | >= 0.95 |
+//! | 14 | BEI                | activities finished / activities due by the data date    | >= 0.95 |
+//!
+//! ## Example
+//! ```
+//! use schedule_parser::XerFile;
+//!
+//! let data = "ERMHDR\t19.12\t2024-03-15\n\
+//!     %T\tPROJECT\n%F\tproj_id\tproj_short_name\tlast_recalc_date\n%R\t1\tA\t2024-03-04 08:00\n\
+//!     %T\tTASK\n%F\ttask_id\tproj_id\twbs_id\ttask_code\ttask_name\ttask_type\tstatus_code\tremain_drtn_hr_cnt\ttotal_float_hr_cnt\n\
+//!     %R\t1\t1\t1\tA1000\tDesign\tTT_Task\tTK_NotStart\t48\t0\n\
+//!     %R\t2\t1\t1\tA1010\tPermit\tTT_Task\tTK_NotStart\t24\t0\n\
+//!     %T\tTASKPRED\n%F\ttask_pred_id\ttask_id\tpred_task_id\tpred_type\tlag_hr_cnt\n%R\t1\t2\t1\tPR_FS\t8\n%E\n";
+//! let xer = XerFile::parse(data.as_bytes()).unwrap();
+//!
+//! let checks = xer.assess_dcma().unwrap();
+//! assert_eq!(checks.len(), 14);
+//! assert_eq!(checks[0].value, 100.0); // neither has both a predecessor and a successor
+//! assert!(!checks[2].passed); // the only relationship has a lag
+//! assert!(checks[11].passed);
+//! ```
+
+use std::collections::HashMap;
+use std::fmt;
+
+use chrono::NaiveDateTime;
+use serde::Serialize;
+
+use crate::constraints::is_hard_constraint;
+use crate::cpm::{ScheduleError, ScheduledTask};
+use crate::model::{Calendar, LinkType, Task, TaskStatus, TaskType};
+use crate::xer::XerFile;
+
+/// Activities with more float or duration than this many days fail checks
+/// 6 and 8
+const HIGH_DAYS: f64 = 44.0;
+
+/// Work days the most critical activity is delayed by in the critical path
+/// test
+const TEST_DELAY_DAYS: f64 = 100.0;
+
+/// Result of one of the 14 checks
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DcmaCheck {
+    /// 1 to 14
+    pub number: u8,
+    pub name: &'static str,
+    /// Activities or relationships counted, none for checks 12 and 13
+    pub count: Option<usize>,
+    /// Activities or relationships checked
+    pub total: Option<usize>,
+    /// Percent of `total` for checks 1 to 11, 1 or 0 for the critical path
+    /// test, the index for CPLI and BEI
+    pub value: f64,
+    pub threshold: Threshold,
+    pub passed: bool,
+}
+
+/// `1 Logic: 3 of 60 (5.00) <= 5 pass`
+impl fmt::Display for DcmaCheck {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}: ", self.number, self.name)?;
+        if let (Some(count), Some(total)) = (self.count, self.total) {
+            write!(f, "{count} of {total} ")?;
+        }
+        let result = if self.passed { "pass" } else { "fail" };
+        write!(f, "({:.2}) {} {result}", self.value, self.threshold)
+    }
+}
+
+/// Limit a [`DcmaCheck::value`] has to keep
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Threshold {
+    AtMost(f64),
+    AtLeast(f64),
+}
+
+impl Threshold {
+    pub fn passes(&self, value: f64) -> bool {
+        match *self {
+            Self::AtMost(limit) => value <= limit,
+            Self::AtLeast(limit) => value >= limit,
+        }
+    }
+}
+
+/// `<= 5` or `>= 0.95`
+impl fmt::Display for Threshold {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::AtMost(limit) => write!(f, "<= {limit}"),
+            Self::AtLeast(limit) => write!(f, ">= {limit}"),
+        }
+    }
+}
+
+impl XerFile {
+    /// The 14 checks in order, see [`dcma`](crate::dcma)
+    ///
+    /// The critical path test and CPLI recompute the schedule with
+    /// [`schedule`](Self::schedule).
+    pub fn assess_dcma(&self) -> Result<Vec<DcmaCheck>, ScheduleError> {
+        let tasks: Vec<Task> = self
+            .tasks()?
+            .into_iter()
+            .filter(|task| {
+                !matches!(
+                    task.task_type,
+                    TaskType::LevelOfEffort | TaskType::WbsSummary
+                )
+            })
+            .collect();
+        let by_id: HashMap<u64, &Task> = tasks.iter().map(|task| (task.task_id, task)).collect();
+        let graph = self.logic_graph()?;
+        let assignments = self.assignments()?;
+        let calendars: HashMap<u64, Calendar> = self
+            .calendars()?
+            .into_iter()
+            .map(|calendar| (calendar.clndr_id, calendar))
+            .collect();
+        let projects = self.projects()?;
+        let data_dates: HashMap<u64, NaiveDateTime> = projects
+            .iter()
+            .filter_map(|project| {
+                Some((
+                    project.proj_id,
+                    project.data_date.or(project.planned_start)?,
+                ))
+            })
+            .collect();
+        let day_hours = |task: &Task| {
+            task.clndr_id
+                .and_then(|id| calendars.get(&id))
+                .map_or(8.0, |calendar| calendar.day_hours)
+        };
+        let incomplete: Vec<&Task> = tasks
+            .iter()
+            .filter(|task| task.status != TaskStatus::Completed)
+            .collect();
+        let links: Vec<_> = incomplete
+            .iter()
+            .flat_map(|task| graph.predecessors(task.task_id))
+            .filter(|link| by_id.contains_key(&link.pred_task_id))
+            .collect();
+        let mut checks = Vec::new();
+
+        let count = count_tasks(&incomplete, |task| {
+            let has_predecessor = graph.predecessors(task.task_id).next().is_some();
+            let has_successor = graph.successors(task.task_id).next().is_some();
+            !has_predecessor || !has_successor
+        });
+        checks.push(percent(
+            1,
+            "Logic",
+            count,
+            incomplete.len(),
+            Threshold::AtMost(5.0),
+        ));
+        let leads = links.iter().filter(|link| link.lag < 0.0).count();
+        checks.push(percent(
+            2,
+            "Leads",
+            leads,
+            links.len(),
+            Threshold::AtMost(0.0),
+        ));
+        let lags = links.iter().filter(|link| link.lag > 0.0).count();
+        checks.push(percent(
+            3,
+            "Lags",
+            lags,
+            links.len(),
+            Threshold::AtMost(5.0),
+        ));
+        let finish_to_start = links
+            .iter()
+            .filter(|link| link.link_type == LinkType::FinishToStart)
+            .count();
+        checks.push(percent(
+            4,
+            "Relationship types",
+            finish_to_start,
+            links.len(),
+            Threshold::AtLeast(90.0),
+        ));
+        let count = count_tasks(&incomplete, |task| {
+            task.constraint_type
+                .as_deref()
+                .is_some_and(is_hard_constraint)
+        });
+        checks.push(percent(
+            5,
+            "Hard constraints",
+            count,
+            incomplete.len(),
+            Threshold::AtMost(5.0),
+        ));
+        let count = count_tasks(&incomplete, |task| {
+            task.total_float
+                .is_some_and(|float| float > HIGH_DAYS * day_hours(task))
+        });
+        checks.push(percent(
+            6,
+            "High float",
+            count,
+            incomplete.len(),
+            Threshold::AtMost(5.0),
+        ));
+        let count = count_tasks(&incomplete, |task| {
+            task.total_float.is_some_and(|float| float < 0.0)
+        });
+        checks.push(percent(
+            7,
+            "Negative float",
+            count,
+            incomplete.len(),
+            Threshold::AtMost(0.0),
+        ));
+        let count = count_tasks(&incomplete, |task| {
+            task.planned_duration > HIGH_DAYS * day_hours(task)
+        });
+        checks.push(percent(
+            8,
+            "High duration",
+            count,
+            incomplete.len(),
+            Threshold::AtMost(5.0),
+        ));
+
+        let all: Vec<&Task> = tasks.iter().collect();
+        let count = count_tasks(&all, |task| {
+            let Some(&data_date) = data_dates.get(&task.proj_id) else {
+                return false;
+            };
+            let actual_after = [task.actual_start, task.actual_finish]
+                .into_iter()
+                .flatten()
+                .any(|date| date > data_date);
+            let before = |dates: [Option<NaiveDateTime>; 2]| {
+                dates.into_iter().flatten().any(|date| date < data_date)
+            };
+            let forecast_before = (task.status != TaskStatus::Completed
+                && before([task.remaining_finish, task.early_finish]))
+                || (task.actual_start.is_none()
+                    && before([task.remaining_start, task.early_start]));
+            actual_after || forecast_before
+        });
+        checks.push(percent(
+            9,
+            "Invalid dates",
+            count,
+            all.len(),
+            Threshold::AtMost(0.0),
+        ));
+        let with_duration: Vec<&Task> = incomplete
+            .iter()
+            .copied()
+            .filter(|task| !task.is_milestone() && task.planned_duration > 0.0)
+            .collect();
+        let count = count_tasks(&with_duration, |task| {
+            assignments.for_task(task.task_id).next().is_none()
+        });
+        checks.push(percent(
+            10,
+            "Resources",
+            count,
+            with_duration.len(),
+            Threshold::AtMost(0.0),
+        ));
+
+        let due: Vec<&Task> = tasks
+            .iter()
+            .filter(|task| {
+                let data_date = data_dates.get(&task.proj_id);
+                task.planned_finish
+                    .zip(data_date)
+                    .is_some_and(|(finish, &data_date)| finish <= data_date)
+            })
+            .collect();
+        let missed = count_tasks(&due, |task| {
+            task.actual_finish
+                .zip(task.planned_finish)
+                .is_none_or(|(actual, planned)| actual > planned)
+        });
+        checks.push(percent(
+            11,
+            "Missed tasks",
+            missed,
+            due.len(),
+            Threshold::AtMost(5.0),
+        ));
+
+        let scheduled = self.schedule_tasks(tasks.clone())?;
+        let finish = project_finish(&scheduled);
+        // delay the incomplete activity with least float
+        let critical = incomplete
+            .iter()
+            .filter(|task| task.total_float.is_some())
+            .min_by(|a, b| a.total_float.unwrap().total_cmp(&b.total_float.unwrap()));
+        let passed = match (critical, finish) {
+            (Some(critical), Some(finish)) => {
+                let mut delayed = tasks.clone();
+                for task in &mut delayed {
+                    if task.task_id == critical.task_id {
+                        task.remaining_duration += TEST_DELAY_DAYS * day_hours(critical);
+                    }
+                }
+                let delayed = self.schedule_tasks(delayed)?;
+                let calendar = critical.clndr_id.and_then(|id| calendars.get(&id));
+                let hours = |from: NaiveDateTime, to: NaiveDateTime| match calendar {
+                    Some(calendar) => calendar.work_hours_between(from, to),
+                    None => (to - from).num_minutes() as f64 / 60.0,
+                };
+                // the project finish has to slip as much as the activity
+                let finish_of = |scheduled: &[ScheduledTask]| {
+                    scheduled
+                        .iter()
+                        .find(|task| task.task_id == critical.task_id)
+                        .map(|task| task.early_finish)
+                };
+                match (
+                    finish_of(&scheduled),
+                    finish_of(&delayed),
+                    project_finish(&delayed),
+                ) {
+                    (Some(before), Some(after), Some(delayed_finish)) => {
+                        let slip = hours(before, after);
+                        slip > 0.0 && hours(finish, delayed_finish) >= slip - 0.01
+                    }
+                    _ => false,
+                }
+            }
+            _ => false,
+        };
+        checks.push(DcmaCheck {
+            number: 12,
+            name: "Critical path test",
+            count: None,
+            total: None,
+            value: if passed { 1.0 } else { 0.0 },
+            threshold: Threshold::AtLeast(1.0),
+            passed,
+        });
+
+        // critical path from the earliest data date to the project finish
+        let start = data_dates.values().min().copied();
+        let cpli = match (critical, start, finish) {
+            (Some(critical), Some(start), Some(finish)) if finish > start => {
+                let length = match critical.clndr_id.and_then(|id| calendars.get(&id)) {
+                    Some(calendar) => calendar.work_hours_between(start, finish),
+                    None => (finish - start).num_minutes() as f64 / 60.0,
+                };
+                let float = critical.total_float.unwrap_or_default();
+                (length + float) / length
+            }
+            _ => 1.0,
+        };
+        checks.push(index(13, "CPLI", None, cpli));
+        let finished = count_tasks(&due, |task| task.actual_finish.is_some());
+        let bei = if due.is_empty() {
+            1.0
+        } else {
+            finished as f64 / due.len() as f64
+        };
+        checks.push(index(14, "BEI", Some((finished, due.len())), bei));
+        Ok(checks)
+    }
+}
+
+fn count_tasks(tasks: &[&Task], filter: impl Fn(&Task) -> bool) -> usize {
+    tasks.iter().filter(|task| filter(task)).count()
+}
+
+/// Latest early finish
+fn project_finish(scheduled: &[ScheduledTask]) -> Option<NaiveDateTime> {
+    scheduled.iter().map(|task| task.early_finish).max()
+}
+
+fn percent(
+    number: u8,
+    name: &'static str,
+    count: usize,
+    total: usize,
+    threshold: Threshold,
+) -> DcmaCheck {
+    let value = if total == 0 {
+        0.0
+    } else {
+        count as f64 * 100.0 / total as f64
+    };
+    // nothing to check passes
+    let passed = total == 0 || threshold.passes(value);
+    DcmaCheck {
+        number,
+        name,
+        count: Some(count),
+        total: Some(total),
+        value,
+        threshold,
+        passed,
+    }
+}
+
+fn index(number: u8, name: &'static str, counts: Option<(usize, usize)>, value: f64) -> DcmaCheck {
+    let threshold = Threshold::AtLeast(0.95);
+    DcmaCheck {
+        number,
+        name,
+        count: counts.map(|(count, _)| count),
+        total: counts.map(|(_, total)| total),
+        value,
+        threshold,
+        passed: threshold.passes(value),
+    }
+}
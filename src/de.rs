@@ -0,0 +1,258 @@
+//! Deserialize rows into user defined structs, fields are matched by column name
+//!
+//! Values are converted from their text form: numbers are parsed, `Y`/`N` are
+//! booleans and empty values are `None` for `Option` fields. Use
+//! [`datetime`] and [`opt_datetime`] with `#[serde(deserialize_with)]` for
+//! dates in P6 format.
+//!
+//! ## Example
+//! ```
+//! use std::io::Cursor;
+//! use schedule_parser::XerFile;
+//! use serde::Deserialize;
+//!
+//! #[derive(Deserialize)]
+//! struct MyTask {
+//!     task_code: String,
+//!     total_float_hr_cnt: Option<f64>,
+//! }
+//!
+//! let data = "ERMHDR\t19.12\t2024-03-15\n%T\tTASK\n%F\ttask_code\ttotal_float_hr_cnt\n%R\tA1000\t16\n%E\n";
+//! let xer = XerFile::parse(Cursor::new(data)).unwrap();
+//! let tasks: Vec<MyTask> = xer
+//!     .table("TASK")
+//!     .unwrap()
+//!     .deserialize()
+//!     .collect::<Result<_, _>>()
+//!     .unwrap();
+//! assert_eq!(tasks[0].task_code, "A1000");
+//! assert_eq!(tasks[0].total_float_hr_cnt, Some(16.0));
+//! ```
+
+use std::fmt;
+
+use chrono::NaiveDateTime;
+use serde::de::{self, DeserializeSeed, IntoDeserializer, MapAccess, Visitor};
+use serde::{forward_to_deserialize_any, Deserialize, Deserializer};
+
+use crate::error::ParseError;
+use crate::model::parse_datetime;
+
+/// Deserialize `(column, value)` pairs of a row of `table` into `T`
+pub(crate) fn from_columns<'a, T, I>(table: &str, columns: I) -> Result<T, ParseError>
+where
+    T: Deserialize<'a>,
+    I: Iterator<Item = (&'a str, &'a str)>,
+{
+    T::deserialize(RowDeserializer { columns }).map_err(|err| match err {
+        DeError::MissingColumn(column) => ParseError::MissingColumn {
+            table: table.into(),
+            column,
+        },
+        DeError::InvalidValue { column, value } => ParseError::InvalidValue {
+            table: table.into(),
+            column,
+            value,
+        },
+        DeError::Custom(message) => ParseError::Deserialize {
+            table: table.into(),
+            message,
+        },
+    })
+}
+
+/// Date in P6 format `2024-03-15 08:00`, for `#[serde(deserialize_with = "...")]`
+pub fn datetime<'de, D: Deserializer<'de>>(deserializer: D) -> Result<NaiveDateTime, D::Error> {
+    let value = <&str>::deserialize(deserializer)?;
+    parse_datetime(value)
+        .ok_or_else(|| de::Error::invalid_value(de::Unexpected::Str(value), &"P6 date"))
+}
+
+/// Optional date in P6 format, empty value is `None`
+pub fn opt_datetime<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<NaiveDateTime>, D::Error> {
+    let value = <&str>::deserialize(deserializer)?;
+    if value.is_empty() {
+        return Ok(None);
+    }
+    parse_datetime(value)
+        .map(Some)
+        .ok_or_else(|| de::Error::invalid_value(de::Unexpected::Str(value), &"P6 date"))
+}
+
+#[derive(Debug)]
+enum DeError {
+    MissingColumn(String),
+    InvalidValue { column: String, value: String },
+    Custom(String),
+}
+
+impl fmt::Display for DeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingColumn(column) => write!(f, "column {column} is missing"),
+            Self::InvalidValue { column, value } => {
+                write!(f, "{column}: invalid value {value:?}")
+            }
+            Self::Custom(message) => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for DeError {}
+
+impl de::Error for DeError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Self::Custom(msg.to_string())
+    }
+
+    fn missing_field(field: &'static str) -> Self {
+        Self::MissingColumn(field.into())
+    }
+}
+
+struct RowDeserializer<I> {
+    columns: I,
+}
+
+impl<'de, I: Iterator<Item = (&'de str, &'de str)>> Deserializer<'de> for RowDeserializer<I> {
+    type Error = DeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        visitor.visit_map(RowAccess {
+            columns: self.columns,
+            value: None,
+        })
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+struct RowAccess<'a, I> {
+    columns: I,
+    /// Column and value of the last key
+    value: Option<(&'a str, &'a str)>,
+}
+
+impl<'de, I: Iterator<Item = (&'de str, &'de str)>> MapAccess<'de> for RowAccess<'de, I> {
+    type Error = DeError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, DeError> {
+        let Some((column, value)) = self.columns.next() else {
+            return Ok(None);
+        };
+        self.value = Some((column, value));
+        seed.deserialize(column.into_deserializer()).map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, DeError> {
+        let (column, value) = self
+            .value
+            .take()
+            .ok_or_else(|| DeError::Custom("value requested before key".into()))?;
+        seed.deserialize(ValueDeserializer { value })
+            .map_err(|err| match err {
+                DeError::MissingColumn(_) => err,
+                _ => DeError::InvalidValue {
+                    column: column.into(),
+                    value: value.into(),
+                },
+            })
+    }
+}
+
+/// Single field converted from its text form
+struct ValueDeserializer<'a> {
+    value: &'a str,
+}
+
+impl ValueDeserializer<'_> {
+    fn parse<T: std::str::FromStr>(&self) -> Result<T, DeError> {
+        self.value
+            .parse()
+            .map_err(|_| DeError::Custom(format!("invalid value {:?}", self.value)))
+    }
+}
+
+macro_rules! deserialize_parsed {
+    ($($method:ident => $visit:ident,)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+                visitor.$visit(self.parse()?)
+            }
+        )*
+    };
+}
+
+impl<'de> Deserializer<'de> for ValueDeserializer<'de> {
+    type Error = DeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        visitor.visit_borrowed_str(self.value)
+    }
+
+    /// `Y`/`N` flag, empty is `false`
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        match self.value {
+            "Y" | "y" | "1" | "true" => visitor.visit_bool(true),
+            "N" | "n" | "0" | "false" | "" => visitor.visit_bool(false),
+            value => Err(DeError::Custom(format!("invalid flag {value:?}"))),
+        }
+    }
+
+    deserialize_parsed! {
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_i128 => visit_i128,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_u128 => visit_u128,
+        deserialize_f32 => visit_f32,
+        deserialize_f64 => visit_f64,
+        deserialize_char => visit_char,
+    }
+
+    /// Empty value is `None`
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        if self.value.is_empty() {
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    /// Unit variant named like the value, e.g. `TK_Active`
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        visitor.visit_enum(self.value.into_deserializer())
+    }
+
+    forward_to_deserialize_any! {
+        str string bytes byte_buf unit unit_struct seq tuple tuple_struct map
+        struct identifier ignored_any
+    }
+}
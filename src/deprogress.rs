@@ -0,0 +1,118 @@
+//! Removal of progress, e.g. to re-baseline a schedule as not started
+//!
+//! [`XerFile::remove_progress`] resets activities, assignments and expenses
+//! to their planned values:
+//!
+//! | Data        | Reset                                                             |
+//! |-------------|-------------------------------------------------------------------|
+//! | activities  | not started, actual dates and units cleared, remaining = original |
+//! | assignments | actual dates, units and costs cleared, remaining = planned        |
+//! | expenses    | actual costs cleared, remaining = planned                         |
+//! | steps       | not completed                                                     |
+//! | periods     | past period actuals in `TASKFIN` and `TRSRCFIN` removed           |
+//! | projects    | data date moved to the planned start                              |
+//!
+//! ## Example
+//! ```
+//! use schedule_parser::XerFile;
+//!
+//! let data = "ERMHDR\t19.12\t2024-03-15\n%T\tTASK\n\
+//!     %F\ttask_id\tstatus_code\tact_start_date\ttarget_drtn_hr_cnt\tremain_drtn_hr_cnt\n\
+//!     %R\t1\tTK_Active\t2024-03-01 08:00\t80\t24\n%E\n";
+//! let mut xer = XerFile::parse(data.as_bytes()).unwrap();
+//! xer.remove_progress();
+//! assert_eq!(xer.table("TASK").unwrap().rows[0], ["1", "TK_NotStart", "", "80", "80"]);
+//! ```
+
+use crate::parser::Table;
+use crate::xer::XerFile;
+
+/// New value of a column
+enum Reset {
+    Value(&'static str),
+    /// Value of another column of the row, e.g. original for remaining duration
+    Column(&'static str),
+}
+
+use Reset::{Column, Value};
+
+/// Table, column and its value without progress
+const RESETS: &[(&str, &str, Reset)] = &[
+    ("TASK", "status_code", Value("TK_NotStart")),
+    ("TASK", "phys_complete_pct", Value("0")),
+    ("TASK", "act_start_date", Value("")),
+    ("TASK", "act_end_date", Value("")),
+    ("TASK", "suspend_date", Value("")),
+    ("TASK", "resume_date", Value("")),
+    ("TASK", "restart_date", Column("target_start_date")),
+    ("TASK", "reend_date", Column("target_end_date")),
+    ("TASK", "remain_drtn_hr_cnt", Column("target_drtn_hr_cnt")),
+    ("TASK", "act_work_qty", Value("0")),
+    ("TASK", "remain_work_qty", Column("target_work_qty")),
+    ("TASK", "act_equip_qty", Value("0")),
+    ("TASK", "remain_equip_qty", Column("target_equip_qty")),
+    ("TASK", "act_this_per_work_qty", Value("0")),
+    ("TASK", "act_this_per_equip_qty", Value("0")),
+    ("TASKRSRC", "act_start_date", Value("")),
+    ("TASKRSRC", "act_end_date", Value("")),
+    ("TASKRSRC", "restart_date", Column("target_start_date")),
+    ("TASKRSRC", "reend_date", Column("target_end_date")),
+    ("TASKRSRC", "act_reg_qty", Value("0")),
+    ("TASKRSRC", "act_ot_qty", Value("0")),
+    ("TASKRSRC", "remain_qty", Column("target_qty")),
+    ("TASKRSRC", "remain_qty_per_hr", Column("target_qty_per_hr")),
+    ("TASKRSRC", "act_reg_cost", Value("0")),
+    ("TASKRSRC", "act_ot_cost", Value("0")),
+    ("TASKRSRC", "remain_cost", Column("target_cost")),
+    ("TASKRSRC", "act_this_per_qty", Value("0")),
+    ("TASKRSRC", "act_this_per_cost", Value("0")),
+    ("PROJCOST", "act_cost", Value("0")),
+    ("PROJCOST", "remain_cost", Column("target_cost")),
+    ("PROJCOST", "act_this_per_cost", Value("0")),
+    ("TASKPROC", "complete_flag", Value("N")),
+    ("PROJECT", "last_recalc_date", Column("plan_start_date")),
+];
+
+/// Tables of actuals by financial period, emptied
+const PERIOD_ACTUALS: &[&str] = &["TASKFIN", "TRSRCFIN"];
+
+impl XerFile {
+    /// Reset all progress in place, see [`deprogress`](crate::deprogress)
+    pub fn remove_progress(&mut self) {
+        for (table, column, reset) in RESETS {
+            if let Some(table) = self.tables.get_mut(*table) {
+                apply(table, column, reset);
+            }
+        }
+        for table in PERIOD_ACTUALS {
+            if let Some(table) = self.tables.get_mut(*table) {
+                table.rows.clear();
+            }
+        }
+    }
+}
+
+fn apply(table: &mut Table, column: &str, reset: &Reset) {
+    let Some(i) = table.column_index(column) else {
+        return;
+    };
+    let (value, source) = match *reset {
+        Value(value) => (value, None),
+        Column(source) => match table.column_index(source) {
+            Some(source) => ("", Some(source)),
+            None => return,
+        },
+    };
+    for row in &mut table.rows {
+        let value = source.map_or_else(
+            || value.to_string(),
+            |source| row.get(source).cloned().unwrap_or_default(),
+        );
+        if i < row.len() {
+            row[i] = value;
+        } else if !value.is_empty() {
+            row.resize(i, String::new());
+            row.push(value);
+        }
+    }
+}
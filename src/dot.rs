@@ -0,0 +1,189 @@
+//! Graphviz DOT export of the activity network
+//!
+//! [`XerFile::to_dot`] writes activities as boxes and relationships as
+//! arrows labelled with type and lag, left to right. [`DotOptions`] limits
+//! the network to critical activities, a WBS element or the neighbourhood
+//! of one activity, all limits apply together. Only relationships between
+//! shown activities are drawn, those between critical ones in red.
+//!
+//! Boxes are filled by the total float P6 exported:
+//!
+//! | Total float      | Fill   |
+//! |------------------|--------|
+//! | negative         | red    |
+//! | 0                | orange |
+//! | up to 80 hours   | yellow |
+//! | more             | green  |
+//! | none, completed  | white  |
+//!
+//! Activities are critical as defined for their project, by total float at
+//! or below the critical float or by being on the longest path.
+//!
+//! ## Example
+//! ```
+//! use schedule_parser::dot::DotOptions;
+//! use schedule_parser::XerFile;
+//!
+//! let data = "ERMHDR\t19.12\t2024-03-15\n%T\tTASK\n\
+//!     %F\ttask_id\tproj_id\twbs_id\ttask_code\ttask_name\ttask_type\tstatus_code\ttotal_float_hr_cnt\n\
+//!     %R\t1\t1\t1\tA1000\tDesign\tTT_Task\tTK_NotStart\t0\n\
+//!     %R\t2\t1\t1\tA1010\tBuild\tTT_Task\tTK_NotStart\t0\n\
+//!     %R\t3\t1\t1\tA1020\tHandover\tTT_FinMile\tTK_NotStart\t40\n\
+//!     %T\tTASKPRED\n%F\ttask_pred_id\ttask_id\tpred_task_id\tpred_type\tlag_hr_cnt\n\
+//!     %R\t1\t2\t1\tPR_FS\t8\n%R\t2\t3\t2\tPR_FS\t0\n%E\n";
+//! let xer = XerFile::parse(data.as_bytes()).unwrap();
+//!
+//! let dot = xer.to_dot(&DotOptions::default()).unwrap();
+//! assert!(dot.contains(r#""1" -> "2" [label="FS +8h", color="red""#));
+//!
+//! let options = DotOptions { around: Some((3, 1)), ..DotOptions::default() };
+//! let dot = xer.to_dot(&options).unwrap();
+//! assert!(!dot.contains("A1000"));
+//! assert!(dot.contains(r#""2" -> "3" [label="FS"]"#));
+//! ```
+
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt::Write as _;
+
+use crate::error::ParseError;
+use crate::model::{Task, TaskStatus};
+use crate::xer::XerFile;
+
+/// Limits of [`XerFile::to_dot`], by default the whole network
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DotOptions {
+    /// Only critical activities
+    pub critical: bool,
+    /// Only activities of the WBS element with this `wbs_id` and below
+    pub wbs_id: Option<u64>,
+    /// Only activities at most this many relationships away from the
+    /// activity with this `task_id`, either direction
+    pub around: Option<(u64, usize)>,
+}
+
+impl XerFile {
+    /// DOT graph of the activities selected by `options`, see [`dot`](crate::dot)
+    pub fn to_dot(&self, options: &DotOptions) -> Result<String, ParseError> {
+        let tasks = self.tasks()?;
+        let graph = self.logic_graph()?;
+        let projects: HashMap<u64, _> = self
+            .projects()?
+            .into_iter()
+            .map(|project| (project.proj_id, project))
+            .collect();
+        let is_critical = |task: &Task| match projects.get(&task.proj_id) {
+            Some(project) => project.is_critical(task),
+            None => task.total_float.is_some_and(|float| float <= 0.0),
+        };
+
+        let in_wbs: Option<HashSet<u64>> = match options.wbs_id {
+            Some(wbs_id) => Some(
+                self.wbs_tree()?
+                    .subtree(wbs_id)
+                    .map(|(_, element)| element.wbs_id)
+                    .collect(),
+            ),
+            None => None,
+        };
+        let nearby = options.around.map(|(task_id, hops)| {
+            // breadth first over predecessors and successors
+            let mut distance = HashMap::from([(task_id, 0)]);
+            let mut queue = VecDeque::from([task_id]);
+            while let Some(task) = queue.pop_front() {
+                let next = distance[&task] + 1;
+                if next > hops {
+                    continue;
+                }
+                let neighbours = graph
+                    .predecessors(task)
+                    .map(|link| link.pred_task_id)
+                    .chain(graph.successors(task).map(|link| link.task_id));
+                for neighbour in neighbours.collect::<Vec<_>>() {
+                    if let Entry::Vacant(entry) = distance.entry(neighbour) {
+                        entry.insert(next);
+                        queue.push_back(neighbour);
+                    }
+                }
+            }
+            distance
+        });
+
+        let shown: Vec<&Task> = tasks
+            .iter()
+            .filter(|task| !options.critical || is_critical(task))
+            .filter(|task| in_wbs.as_ref().is_none_or(|ids| ids.contains(&task.wbs_id)))
+            .filter(|task| {
+                nearby
+                    .as_ref()
+                    .is_none_or(|distance| distance.contains_key(&task.task_id))
+            })
+            .collect();
+        let critical: HashMap<u64, bool> = shown
+            .iter()
+            .map(|task| (task.task_id, is_critical(task)))
+            .collect();
+
+        let mut dot = String::from("digraph schedule {\n");
+        dot.push_str("  rankdir=LR;\n");
+        dot.push_str("  node [shape=box, style=\"rounded,filled\", fontname=\"Helvetica\"];\n");
+        dot.push_str("  edge [fontname=\"Helvetica\", fontsize=10];\n");
+        for task in &shown {
+            let float = match task.total_float {
+                Some(float) if task.status != TaskStatus::Completed => format!("\nTF {float}h"),
+                _ => String::new(),
+            };
+            let label = format!("{}\n{}{float}", task.task_code, task.task_name);
+            let _ = writeln!(
+                dot,
+                "  \"{}\" [label=\"{}\", fillcolor=\"{}\"];",
+                task.task_id,
+                escape(&label),
+                fill(task)
+            );
+        }
+        for link in graph.relationships() {
+            let (Some(&pred), Some(&succ)) = (
+                critical.get(&link.pred_task_id),
+                critical.get(&link.task_id),
+            ) else {
+                continue;
+            };
+            let mut label = link.link_type.abbr().to_string();
+            if link.lag != 0.0 {
+                let _ = write!(label, " {:+}h", link.lag);
+            }
+            let style = if pred && succ {
+                ", color=\"red\", penwidth=2"
+            } else {
+                ""
+            };
+            let _ = writeln!(
+                dot,
+                "  \"{}\" -> \"{}\" [label=\"{label}\"{style}];",
+                link.pred_task_id, link.task_id
+            );
+        }
+        dot.push_str("}\n");
+        Ok(dot)
+    }
+}
+
+/// Fill color of the float band of `task`, see [`dot`](crate::dot)
+fn fill(task: &Task) -> &'static str {
+    match task.total_float {
+        _ if task.status == TaskStatus::Completed => "white",
+        None => "white",
+        Some(float) if float < 0.0 => "#e06666",
+        Some(0.0) => "#f6b26b",
+        Some(float) if float <= 80.0 => "#ffd966",
+        Some(_) => "#93c47d",
+    }
+}
+
+/// `text` as the inside of a DOT string, line breaks kept
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
@@ -0,0 +1,274 @@
+//! Character encoding detection and transcoding to UTF-8
+//!
+//! P6 writes exports in the code page of its database, e.g. cp1251 for
+//! Cyrillic data, and some Windows tools save them as UTF-16.
+//! [`DecodeReader`] turns any of them into UTF-8 while the file is read.
+
+use std::io::{self, BufRead, Read, Seek, SeekFrom};
+
+pub(crate) use encoding_rs::UTF_8;
+use encoding_rs::{CoderResult, Decoder, UTF_16BE, UTF_16LE};
+
+use crate::header::Header;
+
+pub use encoding_rs::Encoding;
+
+/// Bytes looked at by [`detect`] in [`DecodeReader::detect`]
+pub(crate) const SAMPLE_LEN: usize = 1024 * 1024;
+/// Bytes read from the source at once
+const BUF_LEN: usize = 64 * 1024;
+
+/// Encoding named by code page `name`, a Windows code page number like `1251`
+/// or a label like `cp1251`, `windows-1251` or `Shift_JIS`
+pub fn codepage(name: &str) -> Option<&'static Encoding> {
+    let name = name.trim();
+    let number = name
+        .strip_prefix("CP")
+        .or_else(|| name.strip_prefix("cp"))
+        .unwrap_or(name);
+    let label = match number.parse::<u16>() {
+        Ok(65001) => "utf-8",
+        Ok(1200) => "utf-16le",
+        Ok(1201) => "utf-16be",
+        Ok(number @ 1250..=1258) => {
+            return Encoding::for_label(format!("windows-{number}").as_bytes())
+        }
+        Ok(874) => "windows-874",
+        Ok(866) => "ibm866",
+        Ok(932) => "shift_jis",
+        Ok(936) => "gbk",
+        Ok(949) => "euc-kr",
+        Ok(950) => "big5",
+        Ok(20866) => "koi8-r",
+        Ok(21866) => "koi8-u",
+        Ok(number @ 28592..=28606) => {
+            return Encoding::for_label(format!("iso-8859-{}", number - 28590).as_bytes())
+        }
+        Ok(_) => return None,
+        Err(_) => name,
+    };
+    Encoding::for_label(label.as_bytes())
+}
+
+/// Most likely encoding of `sample`, the start of an export
+///
+/// A BOM wins, then UTF-16 recognized by the zero bytes of `ERMHDR`, then the
+/// [code page](Header::codepage) named in the header. Otherwise UTF-8 if the
+/// sample is valid, or the best guess among legacy code pages.
+pub fn detect(sample: &[u8]) -> &'static Encoding {
+    if let Some((encoding, _)) = Encoding::for_bom(sample) {
+        return encoding;
+    }
+    if sample.starts_with(b"E\0R\0") {
+        return UTF_16LE;
+    }
+    if sample.starts_with(b"\0E\0R") {
+        return UTF_16BE;
+    }
+    let first_line = sample
+        .split(|&b| b == b'\n' || b == b'\r')
+        .next()
+        .unwrap_or_default();
+    if let Some(encoding) = Header::parse(&String::from_utf8_lossy(first_line))
+        .as_ref()
+        .and_then(Header::encoding)
+    {
+        return encoding;
+    }
+    match std::str::from_utf8(sample) {
+        Ok(_) => return UTF_8,
+        // multibyte char cut off at the end of the sample
+        Err(err) if err.error_len().is_none() => return UTF_8,
+        Err(_) => {}
+    }
+    let mut detector = chardetng::EncodingDetector::new();
+    detector.feed(sample, true);
+    detector.guess(None, false)
+}
+
+/// Reader yielding the text of `R` as UTF-8
+///
+/// UTF-8 input is passed through unchanged, other encodings are decoded in
+/// chunks as they are read. A BOM is removed from decoded input; invalid
+/// sequences in decoded input become `U+FFFD`.
+///
+/// ## Example
+/// ```no_run
+/// use std::fs::File;
+/// use schedule_parser::encoding::{DecodeReader, Encoding};
+/// use schedule_parser::XerReader;
+///
+/// let cp1251 = Encoding::for_label(b"cp1251").unwrap();
+/// let file = File::open("./data/schedule.xer").unwrap();
+/// let reader = XerReader::from_reader(DecodeReader::new(file, cp1251)).unwrap();
+/// ```
+pub struct DecodeReader<R> {
+    reader: R,
+    encoding: &'static Encoding,
+    /// `None` for UTF-8 input
+    decoder: Option<Decoder>,
+    /// Bytes read from `reader` but not decoded yet, from `in_pos`
+    input: Vec<u8>,
+    in_pos: usize,
+    /// Decoded text not consumed yet, from `out_pos`
+    output: Vec<u8>,
+    out_pos: usize,
+    /// Bytes read from `reader` since decoding started
+    raw_read: u64,
+    /// Decoded bytes consumed so far
+    position: u64,
+    eof: bool,
+    done: bool,
+}
+
+impl<R: Read> DecodeReader<R> {
+    /// Decode `reader` as `encoding`, a BOM of another encoding takes precedence
+    pub fn new(reader: R, encoding: &'static Encoding) -> Self {
+        Self {
+            reader,
+            encoding,
+            decoder: new_decoder(encoding),
+            input: Vec::new(),
+            in_pos: 0,
+            output: Vec::new(),
+            out_pos: 0,
+            raw_read: 0,
+            position: 0,
+            eof: false,
+            done: false,
+        }
+    }
+
+    /// Decode `reader` in the encoding [detected](detect) from its first megabyte
+    ///
+    /// Use [`DecodeReader::new`] to override the code page given in the header.
+    ///
+    /// Legacy code pages used only further into the file are not noticed,
+    /// pass the encoding to [`DecodeReader::new`] for such files.
+    pub fn detect(mut reader: R) -> io::Result<Self> {
+        let mut sample = Vec::with_capacity(SAMPLE_LEN);
+        (&mut reader)
+            .take(SAMPLE_LEN as u64)
+            .read_to_end(&mut sample)?;
+        let mut decoder = Self::new(reader, detect(&sample));
+        decoder.raw_read = sample.len() as u64;
+        decoder.input = sample;
+        Ok(decoder)
+    }
+
+    /// Encoding the input is decoded from
+    pub fn encoding(&self) -> &'static Encoding {
+        self.encoding
+    }
+
+    /// Forget all state after `reader` was rewound to the start
+    fn restart(&mut self) {
+        self.decoder = new_decoder(self.encoding);
+        self.input.clear();
+        self.in_pos = 0;
+        self.output.clear();
+        self.out_pos = 0;
+        self.raw_read = 0;
+        self.position = 0;
+        self.eof = false;
+        self.done = false;
+    }
+
+    /// Decode next chunk into `output`
+    fn refill(&mut self) -> io::Result<()> {
+        self.output.clear();
+        self.out_pos = 0;
+        if self.in_pos == self.input.len() && !self.eof {
+            self.input.resize(BUF_LEN, 0);
+            let read = loop {
+                match self.reader.read(&mut self.input) {
+                    Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                    result => break result?,
+                }
+            };
+            self.input.truncate(read);
+            self.in_pos = 0;
+            self.raw_read += read as u64;
+            self.eof = read == 0;
+        }
+
+        let Some(decoder) = &mut self.decoder else {
+            // UTF-8 is handed out as read
+            std::mem::swap(&mut self.input, &mut self.output);
+            self.out_pos = self.in_pos;
+            self.input.clear();
+            self.in_pos = 0;
+            self.done = self.eof;
+            return Ok(());
+        };
+        let input = &self.input[self.in_pos..];
+        let len = decoder
+            .max_utf8_buffer_length(input.len())
+            .unwrap_or(BUF_LEN * 3)
+            .max(16);
+        self.output.resize(len, 0);
+        let (result, read, written, _) = decoder.decode_to_utf8(input, &mut self.output, self.eof);
+        self.in_pos += read;
+        self.output.truncate(written);
+        self.done = self.eof && result == CoderResult::InputEmpty;
+        Ok(())
+    }
+}
+
+fn new_decoder(encoding: &'static Encoding) -> Option<Decoder> {
+    (encoding != UTF_8).then(|| encoding.new_decoder())
+}
+
+impl<R: Read> Read for DecodeReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let available = self.fill_buf()?;
+        let len = available.len().min(buf.len());
+        buf[..len].copy_from_slice(&available[..len]);
+        self.consume(len);
+        Ok(len)
+    }
+}
+
+impl<R: Read> BufRead for DecodeReader<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        while self.out_pos == self.output.len() && !self.done {
+            self.refill()?;
+        }
+        Ok(&self.output[self.out_pos..])
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.out_pos += amt;
+        self.position += amt as u64;
+    }
+}
+
+/// Positions count decoded bytes; only [`SeekFrom::Start`] and the current
+/// position are supported, seeking back decodes again from the start
+impl<R: Read + Seek> Seek for DecodeReader<R> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let target = match pos {
+            SeekFrom::Start(target) => target,
+            SeekFrom::Current(0) => return Ok(self.position),
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::Unsupported,
+                    "decoded input only seeks to absolute positions",
+                ))
+            }
+        };
+        if target < self.position {
+            let raw_read = i64::try_from(self.raw_read).map_err(io::Error::other)?;
+            self.reader.seek(SeekFrom::Current(-raw_read))?;
+            self.restart();
+        }
+        while self.position < target {
+            let available = self.fill_buf()?.len();
+            if available == 0 {
+                break;
+            }
+            self.consume(available.min((target - self.position) as usize));
+        }
+        Ok(self.position)
+    }
+}
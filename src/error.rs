@@ -0,0 +1,107 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+/// Failure while reading `.xer` data
+///
+/// Record level variants carry 1-based number of the line they were raised at
+/// and the offending line itself, field level variants carry table and column.
+#[derive(Debug)]
+pub enum ParseError {
+    /// Underlying reader failed, line is `0` if file could not be opened
+    Io { line: usize, source: io::Error },
+    /// First line is not a valid `ERMHDR` record
+    InvalidHeader { line: usize, content: String },
+    /// `%T` record without table name
+    MissingTableName { line: usize, content: String },
+    /// Record is valid by itself but not expected at this position, e.g. `%R` before `%F`
+    UnexpectedRecord { line: usize, content: String },
+    /// Line is not valid UTF-8, `content` is lossy decoded
+    InvalidUtf8 { line: usize, content: String },
+    /// Input ended in the middle of a table
+    UnexpectedEof { line: usize },
+    /// Row value count differs from the `%F` header, raised in [strict](crate::ParseOptions::strict) mode
+    RowWidth {
+        line: usize,
+        table: String,
+        columns: usize,
+        values: usize,
+    },
+    /// Typed record needs column absent in table header
+    MissingColumn { table: String, column: String },
+    /// Field can not be converted to expected type
+    InvalidValue {
+        table: String,
+        column: String,
+        value: String,
+    },
+    /// Row can not be deserialized into a user type, see [`Table::deserialize`](crate::Table::deserialize)
+    Deserialize { table: String, message: String },
+}
+
+impl ParseError {
+    /// Line number error was raised at, `None` for field level errors
+    pub fn line(&self) -> Option<usize> {
+        match self {
+            Self::Io { line, .. }
+            | Self::InvalidHeader { line, .. }
+            | Self::MissingTableName { line, .. }
+            | Self::UnexpectedRecord { line, .. }
+            | Self::InvalidUtf8 { line, .. }
+            | Self::UnexpectedEof { line }
+            | Self::RowWidth { line, .. } => Some(*line),
+            Self::MissingColumn { .. } | Self::InvalidValue { .. } | Self::Deserialize { .. } => {
+                None
+            }
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io { line: 0, source } => write!(f, "{source}"),
+            Self::Io { line, source } => write!(f, "line {line}: {source}"),
+            Self::InvalidHeader { line, content } => {
+                write!(f, "line {line}: invalid ERMHDR header: {content:?}")
+            }
+            Self::MissingTableName { line, content } => {
+                write!(f, "line {line}: table name is missing: {content:?}")
+            }
+            Self::UnexpectedRecord { line, content } => {
+                write!(f, "line {line}: unexpected record: {content:?}")
+            }
+            Self::InvalidUtf8 { line, content } => {
+                write!(f, "line {line}: invalid UTF-8: {content:?}")
+            }
+            Self::UnexpectedEof { line } => write!(f, "line {line}: unexpected end of file"),
+            Self::RowWidth {
+                line,
+                table,
+                columns,
+                values,
+            } => write!(
+                f,
+                "line {line}: {table}: row has {values} values, header has {columns} columns"
+            ),
+            Self::MissingColumn { table, column } => {
+                write!(f, "{table}: column {column} is missing")
+            }
+            Self::InvalidValue {
+                table,
+                column,
+                value,
+            } => write!(f, "{table}.{column}: invalid value {value:?}"),
+            Self::Deserialize { table, message } => write!(f, "{table}: {message}"),
+        }
+    }
+}
+
+impl Error for ParseError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
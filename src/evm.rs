@@ -0,0 +1,284 @@
+//! Earned value of an update against its baseline
+//!
+//! Activities are matched by activity ID like in [`compare`](crate::compare).
+//! The budget of an activity is the budgeted cost of its resource
+//! assignments and expenses in the baseline:
+//!
+//! - planned value is the budget times the share of the baseline planned
+//!   dates worked by the data date of the update
+//! - earned value is the budget times the percent complete in the update
+//! - actual cost is the actual cost of the activity in the update
+//!
+//! Work time is that of the activity calendar in the baseline, without
+//! calendar time is counted continuously.
+//!
+//! ## Example
+//! ```
+//! use schedule_parser::evm::PercentComplete;
+//! use schedule_parser::XerFile;
+//!
+//! let header = "ERMHDR\t19.12\t2024-03-15\n\
+//!     %T\tPROJECT\n%F\tproj_id\tproj_short_name\tlast_recalc_date\n%R\t1\tP1\t2024-03-11 00:00\n\
+//!     %T\tPROJWBS\n%F\twbs_id\tproj_id\twbs_short_name\twbs_name\tproj_node_flag\n%R\t1\t1\tP1\tPlant\tY\n\
+//!     %T\tTASK\n%F\ttask_id\tproj_id\twbs_id\ttask_code\ttask_name\ttask_type\tstatus_code\t\
+//!     phys_complete_pct\ttarget_start_date\ttarget_end_date\n";
+//! let expenses = "%T\tPROJCOST\n%F\tcost_item_id\ttask_id\tproj_id\tcost_name\ttarget_cost\tact_cost\n";
+//! let baseline = format!("{header}%R\t1\t1\t1\tA1000\tDesign\tTT_Task\tTK_NotStart\t0\t\
+//!     2024-03-01 00:00\t2024-03-21 00:00\n{expenses}%R\t1\t1\t1\tSurvey\t1000\t0\n%E\n");
+//! let update = format!("{header}%R\t1\t1\t1\tA1000\tDesign\tTT_Task\tTK_Active\t40\t\
+//!     2024-03-01 00:00\t2024-03-21 00:00\n{expenses}%R\t1\t1\t1\tSurvey\t1000\t500\n%E\n");
+//! let baseline = XerFile::parse(baseline.as_bytes()).unwrap();
+//! let update = XerFile::parse(update.as_bytes()).unwrap();
+//!
+//! let values = baseline.earned_value(&update, PercentComplete::Physical).unwrap();
+//! let project = &values[0];
+//! assert_eq!((project.planned_value, project.earned_value, project.actual_cost), (500.0, 400.0, 500.0));
+//! assert_eq!(project.schedule_performance_index, Some(0.8));
+//! ```
+
+use std::collections::HashMap;
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+use crate::cpm::ScheduleError;
+use crate::model::{Assignments, Calendar, Expenses, Task, TaskStatus};
+use crate::xer::XerFile;
+
+/// Percent complete used for earned value
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PercentComplete {
+    /// `complete_pct_type` of the activity, else the project default, else
+    /// duration percent complete
+    Activity,
+    /// Share of the planned duration no longer remaining
+    Duration,
+    /// `phys_complete_pct`
+    Physical,
+    /// Share of the units at completion that are actual
+    Units,
+}
+
+/// Earned value of a WBS element and its descendants
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EarnedValue {
+    pub wbs_id: u64,
+    /// Short names from the project node down, joined by `.`
+    pub wbs_code: String,
+    pub wbs_name: String,
+    /// `0` for project nodes
+    pub depth: usize,
+    /// Data date of the project in the update
+    pub data_date: NaiveDateTime,
+    pub budget_at_completion: f64,
+    pub planned_value: f64,
+    pub earned_value: f64,
+    pub actual_cost: f64,
+    /// Earned value minus planned value
+    pub schedule_variance: f64,
+    /// Earned value minus actual cost
+    pub cost_variance: f64,
+    /// Earned value over planned value, `None` without planned value
+    pub schedule_performance_index: Option<f64>,
+    /// Earned value over actual cost, `None` without actual cost
+    pub cost_performance_index: Option<f64>,
+}
+
+#[derive(Default)]
+struct Totals {
+    budget: f64,
+    planned: f64,
+    earned: f64,
+    actual: f64,
+}
+
+impl XerFile {
+    /// Earned value of `update` against `self` as baseline for every WBS
+    /// element of the update in WBS order, see [`evm`](crate::evm)
+    ///
+    /// Activities missing from the baseline earn nothing but their actual
+    /// cost counts. Projects without data date fall back to the planned
+    /// start, if there is none either an error is returned.
+    pub fn earned_value(
+        &self,
+        update: &XerFile,
+        percent_complete: PercentComplete,
+    ) -> Result<Vec<EarnedValue>, ScheduleError> {
+        // the first of activities with the same ID wins
+        let baseline: HashMap<String, Task> = self
+            .tasks()?
+            .into_iter()
+            .rev()
+            .map(|task| (task.task_code.clone(), task))
+            .collect();
+        let baseline_assignments = self.assignments()?;
+        let baseline_expenses = self.expenses()?;
+        let calendars: HashMap<u64, Calendar> = self
+            .calendars()?
+            .into_iter()
+            .map(|calendar| (calendar.clndr_id, calendar))
+            .collect();
+
+        let projects: HashMap<u64, _> = update
+            .projects()?
+            .into_iter()
+            .map(|project| (project.proj_id, project))
+            .collect();
+        let assignments = update.assignments()?;
+        let expenses = update.expenses()?;
+        let wbs = update.wbs_tree()?;
+
+        let mut data_dates: HashMap<u64, NaiveDateTime> = HashMap::new();
+        for project in projects.values() {
+            let date = project.data_date.or(project.planned_start).ok_or(
+                ScheduleError::MissingDataDate {
+                    proj_id: project.proj_id,
+                },
+            )?;
+            data_dates.insert(project.proj_id, date);
+        }
+
+        let mut totals: HashMap<u64, Totals> = HashMap::new();
+        for task in update.tasks()? {
+            let actual = actual_cost(&assignments, &expenses, task.task_id);
+            let (budget, planned, earned) = match baseline.get(&task.task_code) {
+                Some(old) => {
+                    let budget =
+                        budgeted_cost(&baseline_assignments, &baseline_expenses, old.task_id);
+                    let data_date = data_dates.get(&task.proj_id).copied();
+                    let calendar = old.clndr_id.and_then(|id| calendars.get(&id));
+                    let planned = data_date.map_or(0.0, |date| planned_share(old, calendar, date));
+                    let kind = match percent_complete {
+                        PercentComplete::Activity => {
+                            let code = task.percent_complete_type.as_deref().or_else(|| {
+                                projects
+                                    .get(&task.proj_id)?
+                                    .default_percent_complete_type
+                                    .as_deref()
+                            });
+                            match code {
+                                Some("CP_Phys") => PercentComplete::Physical,
+                                Some("CP_Units") => PercentComplete::Units,
+                                _ => PercentComplete::Duration,
+                            }
+                        }
+                        kind => kind,
+                    };
+                    let earned = percent(&task, &assignments, kind);
+                    (budget, budget * planned, budget * earned)
+                }
+                None => (0.0, 0.0, 0.0),
+            };
+            for element in wbs.path_to_root(task.wbs_id) {
+                let sums = totals.entry(element.wbs_id).or_default();
+                sums.budget += budget;
+                sums.planned += planned;
+                sums.earned += earned;
+                sums.actual += actual;
+            }
+        }
+
+        Ok(wbs
+            .preorder()
+            .filter_map(|(depth, element)| {
+                let sums = totals.get(&element.wbs_id)?;
+                let mut path: Vec<&str> = wbs
+                    .path_to_root(element.wbs_id)
+                    .map(|node| node.wbs_short_name.as_str())
+                    .collect();
+                path.reverse();
+                let ratio = |value: f64, base: f64| (base != 0.0).then(|| value / base);
+                Some(EarnedValue {
+                    wbs_id: element.wbs_id,
+                    wbs_code: path.join("."),
+                    wbs_name: element.wbs_name.clone(),
+                    depth,
+                    data_date: *data_dates.get(&element.proj_id)?,
+                    budget_at_completion: sums.budget,
+                    planned_value: sums.planned,
+                    earned_value: sums.earned,
+                    actual_cost: sums.actual,
+                    schedule_variance: sums.earned - sums.planned,
+                    cost_variance: sums.earned - sums.actual,
+                    schedule_performance_index: ratio(sums.earned, sums.planned),
+                    cost_performance_index: ratio(sums.earned, sums.actual),
+                })
+            })
+            .collect())
+    }
+}
+
+fn budgeted_cost(assignments: &Assignments, expenses: &Expenses, task_id: u64) -> f64 {
+    assignments
+        .for_task(task_id)
+        .map(|assignment| assignment.budgeted_cost)
+        .chain(
+            expenses
+                .for_task(task_id)
+                .map(|expense| expense.budgeted_cost),
+        )
+        .sum()
+}
+
+fn actual_cost(assignments: &Assignments, expenses: &Expenses, task_id: u64) -> f64 {
+    assignments
+        .for_task(task_id)
+        .map(|assignment| assignment.actual_cost)
+        .chain(
+            expenses
+                .for_task(task_id)
+                .map(|expense| expense.actual_cost),
+        )
+        .sum()
+}
+
+/// Share of the planned dates of `task` worked by `data_date`
+fn planned_share(task: &Task, calendar: Option<&Calendar>, data_date: NaiveDateTime) -> f64 {
+    let (Some(start), Some(finish)) = (task.planned_start, task.planned_finish) else {
+        return 0.0;
+    };
+    if data_date <= start {
+        return 0.0;
+    }
+    if data_date >= finish {
+        return 1.0;
+    }
+    let hours = |from: NaiveDateTime, to: NaiveDateTime| match calendar {
+        Some(calendar) => calendar.work_hours_between(from, to),
+        None => (to - from).num_minutes() as f64 / 60.0,
+    };
+    let total = hours(start, finish);
+    if total <= 0.0 {
+        return 1.0;
+    }
+    hours(start, data_date) / total
+}
+
+/// Percent complete of `task` as a fraction
+fn percent(task: &Task, assignments: &Assignments, kind: PercentComplete) -> f64 {
+    if task.status == TaskStatus::Completed {
+        return 1.0;
+    }
+    let fraction = match kind {
+        PercentComplete::Physical => task.physical_percent_complete / 100.0,
+        PercentComplete::Units => {
+            let (actual, total) = assignments.for_task(task.task_id).fold(
+                (0.0, 0.0),
+                |(actual, total), assignment| {
+                    (
+                        actual + assignment.actual_units,
+                        total + assignment.at_completion_units(),
+                    )
+                },
+            );
+            if total > 0.0 {
+                actual / total
+            } else {
+                0.0
+            }
+        }
+        PercentComplete::Duration | PercentComplete::Activity => task.duration_percent_complete(),
+    };
+    fraction.clamp(0.0, 1.0)
+}
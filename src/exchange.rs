@@ -0,0 +1,192 @@
+//! Conversion of all costs to another currency
+
+use crate::parser::Table;
+use crate::xer::XerFile;
+
+/// Cost amount, rate or earned value column, e.g. `target_cost` or `cost_per_qty2`
+pub(crate) fn is_cost(column: &str) -> bool {
+    let rate = column
+        .strip_prefix("cost_per_qty")
+        .is_some_and(|n| n.bytes().all(|b| b.is_ascii_digit()));
+    rate || column.ends_with("_cost") || matches!(column, "bcwp" | "bcws")
+}
+
+impl XerFile {
+    /// Multiply all costs and rates by `rate` and make `currency` the base
+    /// currency, e.g. `convert_currency(1.08, "USD")` for EUR to USD
+    ///
+    /// Amounts are rounded to the decimal digits of `currency` in `CURRTYPE`,
+    /// 2 if it is not there, but keep at least the digits they had. User
+    /// fields of type `FT_MONEY` are converted too. The `CURRTYPE` row of
+    /// `currency` gets exchange rate 1. Without one the row of the old base
+    /// currency is renamed, with `currency` as its symbol and name too.
+    ///
+    /// ## Example
+    /// ```
+    /// use schedule_parser::XerFile;
+    ///
+    /// let data = "ERMHDR\t19.12\t2024-03-15\tProject\tadmin\tAdministrator\tdb\tProject Management\tEUR\n\
+    ///     %T\tTASKRSRC\n%F\ttaskrsrc_id\ttarget_cost\tcost_per_qty\n%R\t1\t1000\t85.5\n%E\n";
+    /// let mut xer = XerFile::parse(data.as_bytes()).unwrap();
+    /// xer.convert_currency(1.08, "USD");
+    /// assert_eq!(xer.header.currency, "USD");
+    /// assert_eq!(xer.table("TASKRSRC").unwrap().rows[0], ["1", "1080", "92.34"]);
+    /// ```
+    pub fn convert_currency(&mut self, rate: f64, currency: &str) {
+        let digits = self
+            .table("CURRTYPE")
+            .and_then(|table| {
+                table
+                    .iter()
+                    .find(|row| row.get_opt("curr_short_name") == Some(currency))
+            })
+            .and_then(|row| row.get_opt("decimal_digit_cnt")?.parse().ok())
+            .unwrap_or(2);
+        let money: Vec<String> = self
+            .table("UDFTYPE")
+            .map(|types| {
+                types
+                    .iter()
+                    .filter(|row| row.get_opt("logical_data_type") == Some("FT_MONEY"))
+                    .filter_map(|row| row.get_opt("udf_type_id").map(String::from))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        for table in self.tables.values_mut() {
+            let columns: Vec<usize> = table
+                .header
+                .iter()
+                .enumerate()
+                .filter(|(_, column)| is_cost(column))
+                .map(|(i, _)| i)
+                .collect();
+            // exchange rates are per base currency
+            if table.name == "CURRTYPE" {
+                if let Some(i) = table.column_index("base_exch_rate") {
+                    convert(table, &[i], 1.0 / rate, 6, |_| true);
+                }
+            }
+            if table.name == "UDFVALUE" {
+                let kind = table.column_index("udf_type_id");
+                if let Some(i) = table.column_index("udf_number") {
+                    convert(table, &[i], rate, digits, |row| {
+                        kind.and_then(|kind| row.get(kind))
+                            .is_some_and(|kind| money.contains(kind))
+                    });
+                }
+            }
+            convert(table, &columns, rate, digits, |_| true);
+        }
+
+        if let Some(table) = self.tables.get_mut("CURRTYPE") {
+            set_base(table, &self.header.currency, currency);
+        }
+        self.header.currency = currency.into();
+    }
+}
+
+/// Make `currency` the base of `table` at rate 1, renaming the row of the
+/// old `base` if there is none for `currency`
+fn set_base(table: &mut Table, base: &str, currency: &str) {
+    let Some(name) = table.column_index("curr_short_name") else {
+        return;
+    };
+    let rate = table.column_index("base_exch_rate");
+    // the symbol and full name of the old base no longer apply
+    let labels = [
+        table.column_index("curr_symbol"),
+        table.column_index("curr_type"),
+    ];
+    let is = |row: &[String], short_name: &str| row.get(name).is_some_and(|n| n == short_name);
+    let row = match table.rows.iter().position(|row| is(row, currency)) {
+        Some(i) => &mut table.rows[i],
+        None => {
+            let Some(row) = table.rows.iter_mut().find(|row| is(row, base)) else {
+                return;
+            };
+            row[name] = currency.into();
+            for i in labels.into_iter().flatten() {
+                if let Some(value) = row.get_mut(i) {
+                    *value = currency.into();
+                }
+            }
+            row
+        }
+    };
+    if let Some(value) = rate.and_then(|i| row.get_mut(i)) {
+        *value = "1".into();
+    }
+}
+
+/// Multiply numbers in `columns` of rows matching `filter` by `factor`
+fn convert(
+    table: &mut Table,
+    columns: &[usize],
+    factor: f64,
+    digits: usize,
+    filter: impl Fn(&[String]) -> bool,
+) {
+    for row in &mut table.rows {
+        if !filter(row) {
+            continue;
+        }
+        for &i in columns {
+            let Some(value) = row.get_mut(i) else {
+                continue;
+            };
+            let Ok(amount) = value.parse::<f64>() else {
+                continue;
+            };
+            let own = value.split_once('.').map_or(0, |(_, frac)| frac.len());
+            *value = format_amount(amount * factor, digits.max(own));
+        }
+    }
+}
+
+/// `amount` rounded to `digits` without trailing zeros, e.g. `1080` or `92.34`
+fn format_amount(amount: f64, digits: usize) -> String {
+    let formatted = format!("{amount:.digits$}");
+    let trimmed = if formatted.contains('.') {
+        formatted.trim_end_matches('0').trim_end_matches('.')
+    } else {
+        &formatted
+    };
+    match trimmed {
+        "-0" => "0".into(),
+        trimmed => trimmed.into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::parse;
+
+    const CURRTYPE: &str = "%T\tCURRTYPE\n\
+        %F\tcurr_id\tdecimal_digit_cnt\tcurr_symbol\tcurr_type\tcurr_short_name\tbase_exch_rate\n\
+        %R\t1\t2\t€\tEuro\tEUR\t1\n";
+
+    #[test]
+    fn base_renamed_without_currtype_row() {
+        let mut xer = parse(CURRTYPE);
+        xer.convert_currency(1.1, "USD");
+        assert_eq!(
+            xer.table("CURRTYPE").unwrap().rows,
+            [["1", "2", "USD", "USD", "USD", "1"]]
+        );
+    }
+
+    #[test]
+    fn existing_currtype_row_becomes_base() {
+        let mut xer = parse(&format!(
+            "{CURRTYPE}%R\t2\t0\t¥\tYen\tJPY\t160.123\n\
+             %T\tPROJCOST\n%F\tcost_item_id\ttarget_cost\n%R\t1\t10.5\n"
+        ));
+        xer.convert_currency(160.0, "JPY");
+        let currencies = &xer.table("CURRTYPE").unwrap().rows;
+        assert_eq!(currencies[0], ["1", "2", "€", "Euro", "EUR", "0.00625"]);
+        assert_eq!(currencies[1], ["2", "0", "¥", "Yen", "JPY", "1"]);
+        // digits of JPY, but not fewer than the amount had
+        assert_eq!(xer.table("PROJCOST").unwrap().rows[0], ["1", "1680"]);
+    }
+}
@@ -0,0 +1,448 @@
+//! Row filter expressions, e.g. `status_code = 'TK_Active' && target_end_date < 2025-01-01`
+//!
+//! A [`Filter`] compares columns with literals and combines comparisons with
+//! `&&`, `||`, `!` (or `and`, `or`, `not`) and parentheses. The literal sets
+//! the type of a comparison:
+//!
+//! | Literal                          | Column values compared as |
+//! |----------------------------------|---------------------------|
+//! | `'text'`, `''` quotes escaped    | text, by code point       |
+//! | `40`, `-0.5`                     | numbers                   |
+//! | `2025-01-01`, `2025-01-01T08:00` | dates                     |
+//!
+//! Operators are `=` (or `==`), `!=` (or `<>`), `<`, `<=`, `>` and `>=`.
+//! Values that are empty or not of the type of the literal match no number or
+//! date comparison, `= ''` finds empty ones.
+//!
+//! ## Example
+//! ```
+//! use schedule_parser::filter::Filter;
+//! use schedule_parser::XerFile;
+//!
+//! let data = "ERMHDR\t19.12\t2024-03-15\n%T\tTASK\n\
+//!     %F\ttask_id\tstatus_code\ttarget_end_date\ttotal_float_hr_cnt\n\
+//!     %R\t1\tTK_Active\t2024-12-20 17:00\t-8\n\
+//!     %R\t2\tTK_Active\t2025-02-14 17:00\t0\n\
+//!     %R\t3\tTK_NotStart\t2024-11-29 17:00\t\n%E\n";
+//! let mut xer = XerFile::parse(data.as_bytes()).unwrap();
+//!
+//! let filter: Filter = "status_code = 'TK_Active' && target_end_date < 2025-01-01"
+//!     .parse()
+//!     .unwrap();
+//! assert_eq!(xer.filter_rows(&filter).unwrap(), ["TASK"]);
+//! assert_eq!(xer.tables["TASK"].rows.len(), 1);
+//!
+//! let filter: Filter = "total_float_hr_cnt >= 0 or not (task_id = '1')".parse().unwrap();
+//! let bound = filter.bind(&xer.tables["TASK"].header).unwrap();
+//! assert!(!bound.matches(&xer.tables["TASK"].rows[0]));
+//! assert!("total_float_hr_cnt < ".parse::<Filter>().is_err());
+//! ```
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{NaiveDate, NaiveDateTime};
+
+use crate::model::parse_datetime;
+use crate::xer::XerFile;
+
+/// Failure to parse or apply a [`Filter`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FilterError {
+    /// Invalid expression, `position` is a byte offset into it
+    Syntax { position: usize, message: String },
+    /// Compared column not in the header
+    MissingColumn { column: String },
+    /// No table has all compared columns, see [`XerFile::filter_rows`]
+    NoTable { columns: Vec<String> },
+}
+
+impl fmt::Display for FilterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Syntax { position, message } => write!(f, "filter at {position}: {message}"),
+            Self::MissingColumn { column } => write!(f, "filter compares missing column {column}"),
+            Self::NoTable { columns } => {
+                write!(f, "no table has all filter columns {}", columns.join(", "))
+            }
+        }
+    }
+}
+
+impl std::error::Error for FilterError {}
+
+/// Parsed filter expression, [bound](Filter::bind) to a header to test rows
+#[derive(Debug, Clone, PartialEq)]
+pub struct Filter {
+    expr: Expr<String>,
+}
+
+/// [`Filter`] with columns resolved to positions in a header
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoundFilter {
+    expr: Expr<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr<C> {
+    And(Box<Expr<C>>, Box<Expr<C>>),
+    Or(Box<Expr<C>>, Box<Expr<C>>),
+    Not(Box<Expr<C>>),
+    Compare { column: C, op: Op, literal: Literal },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl Op {
+    fn test(self, ordering: Ordering) -> bool {
+        match self {
+            Self::Eq => ordering == Ordering::Equal,
+            Self::Ne => ordering != Ordering::Equal,
+            Self::Lt => ordering == Ordering::Less,
+            Self::Le => ordering != Ordering::Greater,
+            Self::Gt => ordering == Ordering::Greater,
+            Self::Ge => ordering != Ordering::Less,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Literal {
+    Text(String),
+    Number(f64),
+    Date(NaiveDateTime),
+}
+
+impl Filter {
+    /// Names of the columns the expression compares, in order of appearance
+    pub fn columns(&self) -> Vec<&str> {
+        fn walk<'a>(expr: &'a Expr<String>, columns: &mut Vec<&'a str>) {
+            match expr {
+                Expr::And(left, right) | Expr::Or(left, right) => {
+                    walk(left, columns);
+                    walk(right, columns);
+                }
+                Expr::Not(inner) => walk(inner, columns),
+                Expr::Compare { column, .. } => {
+                    if !columns.contains(&column.as_str()) {
+                        columns.push(column);
+                    }
+                }
+            }
+        }
+        let mut columns = Vec::new();
+        walk(&self.expr, &mut columns);
+        columns
+    }
+
+    /// Resolve the columns in `header`, the first of duplicate names is used
+    pub fn bind(&self, header: &[String]) -> Result<BoundFilter, FilterError> {
+        fn bind(expr: &Expr<String>, header: &[String]) -> Result<Expr<usize>, FilterError> {
+            Ok(match expr {
+                Expr::And(left, right) => Expr::And(
+                    Box::new(bind(left, header)?),
+                    Box::new(bind(right, header)?),
+                ),
+                Expr::Or(left, right) => Expr::Or(
+                    Box::new(bind(left, header)?),
+                    Box::new(bind(right, header)?),
+                ),
+                Expr::Not(inner) => Expr::Not(Box::new(bind(inner, header)?)),
+                Expr::Compare {
+                    column,
+                    op,
+                    literal,
+                } => Expr::Compare {
+                    column: header
+                        .iter()
+                        .position(|name| name == column)
+                        .ok_or_else(|| FilterError::MissingColumn {
+                            column: column.clone(),
+                        })?,
+                    op: *op,
+                    literal: literal.clone(),
+                },
+            })
+        }
+        Ok(BoundFilter {
+            expr: bind(&self.expr, header)?,
+        })
+    }
+}
+
+impl FromStr for Filter {
+    type Err = FilterError;
+
+    fn from_str(text: &str) -> Result<Self, FilterError> {
+        let tokens = tokenize(text)?;
+        let mut parser = Parser {
+            tokens: &tokens,
+            next: 0,
+            end: text.len(),
+        };
+        let expr = parser.or()?;
+        if let Some((position, token)) = parser.tokens.get(parser.next) {
+            return Err(FilterError::Syntax {
+                position: *position,
+                message: format!("unexpected {token}"),
+            });
+        }
+        Ok(Self { expr })
+    }
+}
+
+impl BoundFilter {
+    /// Whether a row with `values` in header order matches, cut off values are empty
+    pub fn matches(&self, values: &[impl AsRef<str>]) -> bool {
+        fn eval(expr: &Expr<usize>, values: &[impl AsRef<str>]) -> bool {
+            match expr {
+                Expr::And(left, right) => eval(left, values) && eval(right, values),
+                Expr::Or(left, right) => eval(left, values) || eval(right, values),
+                Expr::Not(inner) => !eval(inner, values),
+                Expr::Compare {
+                    column,
+                    op,
+                    literal,
+                } => {
+                    let value = values.get(*column).map_or("", AsRef::as_ref);
+                    let ordering = match literal {
+                        Literal::Text(text) => Some(value.cmp(text.as_str())),
+                        Literal::Number(number) => value
+                            .parse::<f64>()
+                            .ok()
+                            .and_then(|value| value.partial_cmp(number)),
+                        Literal::Date(date) => parse_datetime(value).map(|value| value.cmp(date)),
+                    };
+                    ordering.is_some_and(|ordering| op.test(ordering))
+                }
+            }
+        }
+        eval(&self.expr, values)
+    }
+}
+
+impl XerFile {
+    /// Keep only the rows matching `filter` in every table with all its
+    /// columns, see [`XerFile::for_tables_with`]
+    pub fn filter_rows(&mut self, filter: &Filter) -> Result<Vec<String>, FilterError> {
+        let columns = filter.columns();
+        self.for_tables_with(
+            &columns,
+            |table| {
+                let bound = filter.bind(&table.header)?;
+                table.rows.retain(|row| bound.matches(row));
+                Ok(())
+            },
+            || FilterError::NoTable {
+                columns: columns.iter().map(|&column| column.into()).collect(),
+            },
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Literal(Literal),
+    Op(Op),
+    And,
+    Or,
+    Not,
+    Open,
+    Close,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Ident(name) => write!(f, "column {name}"),
+            Self::Literal(_) => f.write_str("value"),
+            Self::Op(_) => f.write_str("operator"),
+            Self::And => f.write_str("&&"),
+            Self::Or => f.write_str("||"),
+            Self::Not => f.write_str("!"),
+            Self::Open => f.write_str("("),
+            Self::Close => f.write_str(")"),
+        }
+    }
+}
+
+fn tokenize(text: &str) -> Result<Vec<(usize, Token)>, FilterError> {
+    let error = |position: usize, message: &str| FilterError::Syntax {
+        position,
+        message: message.to_string(),
+    };
+    let mut tokens = Vec::new();
+    let mut chars = text.char_indices().peekable();
+    while let Some(&(start, c)) = chars.peek() {
+        let rest = &text[start..];
+        let (token, len) = match c {
+            c if c.is_whitespace() => {
+                chars.next();
+                continue;
+            }
+            '(' => (Token::Open, 1),
+            ')' => (Token::Close, 1),
+            '&' if rest.starts_with("&&") => (Token::And, 2),
+            '|' if rest.starts_with("||") => (Token::Or, 2),
+            '!' if rest.starts_with("!=") => (Token::Op(Op::Ne), 2),
+            '!' => (Token::Not, 1),
+            '=' if rest.starts_with("==") => (Token::Op(Op::Eq), 2),
+            '=' => (Token::Op(Op::Eq), 1),
+            '<' if rest.starts_with("<=") => (Token::Op(Op::Le), 2),
+            '<' if rest.starts_with("<>") => (Token::Op(Op::Ne), 2),
+            '<' => (Token::Op(Op::Lt), 1),
+            '>' if rest.starts_with(">=") => (Token::Op(Op::Ge), 2),
+            '>' => (Token::Op(Op::Gt), 1),
+            '\'' => {
+                let mut value = String::new();
+                let mut end = None;
+                let mut quoted = rest.char_indices().skip(1).peekable();
+                while let Some((i, c)) = quoted.next() {
+                    if c != '\'' {
+                        value.push(c);
+                    } else if quoted.peek().is_some_and(|&(_, next)| next == '\'') {
+                        value.push('\'');
+                        quoted.next();
+                    } else {
+                        end = Some(i + 1);
+                        break;
+                    }
+                }
+                let end = end.ok_or_else(|| error(start, "unterminated text"))?;
+                (Token::Literal(Literal::Text(value)), end)
+            }
+            c if c.is_ascii_digit() || c == '-' || c == '.' => {
+                let len = rest
+                    .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | ':')))
+                    .unwrap_or(rest.len());
+                let word = &rest[..len];
+                let literal =
+                    if let Ok(date) = NaiveDateTime::parse_from_str(word, "%Y-%m-%dT%H:%M") {
+                        Literal::Date(date)
+                    } else if let Ok(date) = NaiveDate::parse_from_str(word, "%Y-%m-%d") {
+                        Literal::Date(date.into())
+                    } else if let Ok(number) = word.parse::<f64>() {
+                        Literal::Number(number)
+                    } else {
+                        return Err(error(start, &format!("invalid number or date {word}")));
+                    };
+                (Token::Literal(literal), len)
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let len = rest
+                    .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+                    .unwrap_or(rest.len());
+                let word = &rest[..len];
+                let token = match word.to_ascii_lowercase().as_str() {
+                    "and" => Token::And,
+                    "or" => Token::Or,
+                    "not" => Token::Not,
+                    _ => Token::Ident(word.to_string()),
+                };
+                (token, len)
+            }
+            c => return Err(error(start, &format!("unexpected character {c:?}"))),
+        };
+        tokens.push((start, token));
+        while chars.peek().is_some_and(|&(i, _)| i < start + len) {
+            chars.next();
+        }
+    }
+    Ok(tokens)
+}
+
+/// Recursive descent over the tokens, `||` binding weaker than `&&`
+struct Parser<'a> {
+    tokens: &'a [(usize, Token)],
+    next: usize,
+    /// Length of the expression, the position of errors at its end
+    end: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.next).map(|(_, token)| token)
+    }
+
+    fn position(&self) -> usize {
+        self.tokens
+            .get(self.next)
+            .map_or(self.end, |&(position, _)| position)
+    }
+
+    fn error(&self, expected: &str) -> FilterError {
+        let found = match self.peek() {
+            Some(token) => token.to_string(),
+            None => "end".to_string(),
+        };
+        FilterError::Syntax {
+            position: self.position(),
+            message: format!("expected {expected}, found {found}"),
+        }
+    }
+
+    fn or(&mut self) -> Result<Expr<String>, FilterError> {
+        let mut expr = self.and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.next += 1;
+            expr = Expr::Or(Box::new(expr), Box::new(self.and()?));
+        }
+        Ok(expr)
+    }
+
+    fn and(&mut self) -> Result<Expr<String>, FilterError> {
+        let mut expr = self.unary()?;
+        while self.peek() == Some(&Token::And) {
+            self.next += 1;
+            expr = Expr::And(Box::new(expr), Box::new(self.unary()?));
+        }
+        Ok(expr)
+    }
+
+    fn unary(&mut self) -> Result<Expr<String>, FilterError> {
+        match self.peek() {
+            Some(Token::Not) => {
+                self.next += 1;
+                Ok(Expr::Not(Box::new(self.unary()?)))
+            }
+            Some(Token::Open) => {
+                self.next += 1;
+                let expr = self.or()?;
+                if self.peek() != Some(&Token::Close) {
+                    return Err(self.error(")"));
+                }
+                self.next += 1;
+                Ok(expr)
+            }
+            Some(Token::Ident(column)) => {
+                let column = column.clone();
+                self.next += 1;
+                let Some(&Token::Op(op)) = self.peek() else {
+                    return Err(self.error("comparison operator"));
+                };
+                self.next += 1;
+                let Some(Token::Literal(literal)) = self.peek() else {
+                    return Err(self.error("value"));
+                };
+                let literal = literal.clone();
+                self.next += 1;
+                Ok(Expr::Compare {
+                    column,
+                    op,
+                    literal,
+                })
+            }
+            _ => Err(self.error("column")),
+        }
+    }
+}
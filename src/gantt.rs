@@ -0,0 +1,385 @@
+//! Self-contained HTML Gantt chart
+//!
+//! [`XerFile::to_html_gantt`] writes one HTML page with inline CSS and SVG,
+//! no scripts, fonts or other files to fetch, so it can be mailed or
+//! attached as is. Rows follow the WBS, each element with a summary bar over
+//! its activities, then its activities by start. Critical activities are
+//! red, completed ones grey, milestones diamonds. The activity names stay in
+//! place while the bars scroll sideways, hovering a bar shows its dates and
+//! total float. A dashed line marks the data date.
+//!
+//! Dates are chosen like in the [iCalendar export](crate::ics), WBS summaries
+//! and activities without dates are left out.
+//!
+//! ## Example
+//! ```
+//! use schedule_parser::XerFile;
+//!
+//! let data = "ERMHDR\t19.12\t2024-03-15\n\
+//!     %T\tPROJECT\n%F\tproj_id\tproj_short_name\tcritical_drtn_hr_cnt\n%R\t1\tP1\t0\n\
+//!     %T\tPROJWBS\n%F\twbs_id\tproj_id\tparent_wbs_id\twbs_short_name\twbs_name\tproj_node_flag\n\
+//!     %R\t1\t1\t\tP1\tPlant\tY\n%R\t2\t1\t1\tCIV\tCivil\tN\n\
+//!     %T\tTASK\n%F\ttask_id\tproj_id\twbs_id\ttask_code\ttask_name\ttask_type\tstatus_code\t\
+//!     early_start_date\tearly_end_date\ttotal_float_hr_cnt\n\
+//!     %R\t1\t1\t2\tA1000\tFoundations\tTT_Task\tTK_NotStart\t2024-03-18 08:00\t2024-03-22 17:00\t0\n\
+//!     %R\t2\t1\t2\tM1000\tHandover\tTT_FinMile\tTK_NotStart\t\t2024-03-22 17:00\t40\n%E\n";
+//! let xer = XerFile::parse(data.as_bytes()).unwrap();
+//!
+//! let html = xer.to_html_gantt().unwrap();
+//! assert!(html.starts_with("<!DOCTYPE html>"));
+//! assert!(html.contains("P1.CIV Civil"));
+//! assert!(html.contains(r#"<rect class="bar critical""#));
+//! assert!(html.contains(r#"<polygon class="milestone""#));
+//! ```
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+
+use chrono::{Datelike, Months, NaiveDate, NaiveDateTime};
+
+use crate::error::ParseError;
+use crate::ics::dates;
+use crate::model::{Task, TaskStatus, TaskType};
+use crate::xer::XerFile;
+use crate::xml::escape;
+
+const ROW_HEIGHT: f64 = 22.0;
+const HEADER_HEIGHT: f64 = 32.0;
+const LABEL_WIDTH: f64 = 420.0;
+const DAY_WIDTH: f64 = 6.0;
+const INDENT: f64 = 14.0;
+/// Room left of the first date for milestone diamonds
+const PADDING: f64 = 8.0;
+/// Characters of labels that fit next to the bars
+const LABEL_LEN: usize = 60;
+
+const STYLE: &str = "\
+body { font: 13px sans-serif; margin: 16px; color: #222; }
+h1 { font-size: 18px; margin: 0 0 4px; }
+p { margin: 0 0 12px; color: #555; }
+.chart { display: flex; border: 1px solid #ccc; }
+.labels { flex: none; border-right: 1px solid #ccc; background: #fafafa; }
+.bars { overflow-x: auto; }
+svg { display: block; font: 12px sans-serif; }
+.wbs { font-weight: bold; }
+.month { fill: #555; }
+.grid { stroke: #e4e4e4; }
+.data-date { stroke: #3c78d8; stroke-dasharray: 4 3; }
+.summary { fill: #444; }
+.bar { fill: #6d9eeb; }
+.bar.critical, .milestone.critical { fill: #e06666; }
+.bar.done, .milestone.done { fill: #b7b7b7; }
+.milestone { fill: #222; }
+.legend span { display: inline-block; width: 12px; height: 12px; margin: 0 4px 0 12px; vertical-align: middle; }
+";
+
+/// Row of the chart
+enum Line<'a> {
+    Wbs {
+        depth: usize,
+        label: String,
+        range: (NaiveDateTime, NaiveDateTime),
+    },
+    Task {
+        depth: usize,
+        task: &'a Task,
+        range: (NaiveDateTime, NaiveDateTime),
+        critical: bool,
+    },
+}
+
+impl XerFile {
+    /// HTML page with a Gantt chart of all activities, see [`gantt`](crate::gantt)
+    pub fn to_html_gantt(&self) -> Result<String, ParseError> {
+        let projects: HashMap<u64, _> = self
+            .projects()?
+            .into_iter()
+            .map(|project| (project.proj_id, project))
+            .collect();
+        let wbs = self.wbs_tree()?;
+        let tasks = self.tasks()?;
+
+        let mut by_wbs: HashMap<Option<u64>, Vec<_>> = HashMap::new();
+        for task in &tasks {
+            if task.task_type == TaskType::WbsSummary {
+                continue;
+            }
+            let Some(range) = dates(task) else {
+                continue;
+            };
+            let critical = projects
+                .get(&task.proj_id)
+                .is_some_and(|project| project.is_critical(task));
+            let section = wbs.get(task.wbs_id).map(|element| element.wbs_id);
+            by_wbs
+                .entry(section)
+                .or_default()
+                .push((task, range, critical));
+        }
+        for tasks in by_wbs.values_mut() {
+            tasks.sort_by_key(|&(task, range, _)| (range, task.task_code.as_str()));
+        }
+        // dates of each element over its activities and those below
+        let mut ranges: HashMap<u64, (NaiveDateTime, NaiveDateTime)> = HashMap::new();
+        for (&section, tasks) in &by_wbs {
+            let Some(wbs_id) = section else {
+                continue;
+            };
+            for element in wbs.path_to_root(wbs_id) {
+                for &(_, (start, finish), _) in tasks {
+                    let range = ranges.entry(element.wbs_id).or_insert((start, finish));
+                    range.0 = range.0.min(start);
+                    range.1 = range.1.max(finish);
+                }
+            }
+        }
+
+        let mut codes: HashMap<u64, String> = HashMap::new();
+        let mut lines = Vec::new();
+        for (depth, element) in wbs.preorder() {
+            let code = match element.parent_wbs_id.and_then(|id| codes.get(&id)) {
+                Some(parent) => format!("{parent}.{}", element.wbs_short_name),
+                None => element.wbs_short_name.clone(),
+            };
+            let Some(&range) = ranges.get(&element.wbs_id) else {
+                codes.insert(element.wbs_id, code);
+                continue;
+            };
+            lines.push(Line::Wbs {
+                depth,
+                label: format!("{code} {}", element.wbs_name),
+                range,
+            });
+            codes.insert(element.wbs_id, code);
+            for &(task, range, critical) in by_wbs.get(&Some(element.wbs_id)).into_iter().flatten()
+            {
+                lines.push(Line::Task {
+                    depth: depth + 1,
+                    task,
+                    range,
+                    critical,
+                });
+            }
+        }
+        if let Some(tasks) = by_wbs.get(&None) {
+            if let (Some(first), Some(last)) = (tasks.first(), tasks.iter().map(|t| t.1 .1).max()) {
+                lines.push(Line::Wbs {
+                    depth: 0,
+                    label: "Other activities".to_string(),
+                    range: (first.1 .0, last),
+                });
+            }
+            for &(task, range, critical) in tasks {
+                lines.push(Line::Task {
+                    depth: 1,
+                    task,
+                    range,
+                    critical,
+                });
+            }
+        }
+
+        let mut names: Vec<&str> = projects
+            .values()
+            .map(|project| project.proj_short_name.as_str())
+            .collect();
+        names.sort_unstable();
+        let title = if names.is_empty() {
+            "Schedule".to_string()
+        } else {
+            names.join(", ")
+        };
+        let data_date = projects
+            .values()
+            .filter_map(|project| project.data_date)
+            .min();
+
+        let mut html = String::from("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n");
+        html.push_str("<meta charset=\"utf-8\">\n");
+        let _ = writeln!(html, "<title>{}</title>", escape(&title));
+        let _ = writeln!(html, "<style>\n{STYLE}</style>\n</head>\n<body>");
+        let _ = writeln!(html, "<h1>{}</h1>", escape(&title));
+        let _ = write!(
+            html,
+            "<p>Exported {}",
+            self.header.export_date.format("%Y-%m-%d")
+        );
+        if let Some(data_date) = data_date {
+            let _ = write!(html, ", data date {}", data_date.format("%Y-%m-%d %H:%M"));
+        }
+        let activities = lines
+            .iter()
+            .filter(|line| matches!(line, Line::Task { .. }))
+            .count();
+        let _ = writeln!(html, ", {activities} activities");
+        html.push_str(
+            "<span class=\"legend\"><span style=\"background:#e06666\"></span>critical\
+             <span style=\"background:#6d9eeb\"></span>not critical\
+             <span style=\"background:#b7b7b7\"></span>completed\
+             <span style=\"background:#444\"></span>WBS</span></p>\n",
+        );
+
+        let bounds = lines
+            .iter()
+            .map(|line| match line {
+                Line::Wbs { range, .. } | Line::Task { range, .. } => *range,
+            })
+            .reduce(|a, b| (a.0.min(b.0), a.1.max(b.1)));
+        let Some((first, last)) = bounds else {
+            html.push_str("<p>No activities with dates.</p>\n</body>\n</html>\n");
+            return Ok(html);
+        };
+        let origin = first.date().with_day(1).unwrap_or(first.date());
+        let end = last
+            .date()
+            .with_day(1)
+            .and_then(|date| date.checked_add_months(Months::new(1)))
+            .unwrap_or(last.date());
+        let x = |date: NaiveDateTime| {
+            PADDING
+                + (date - origin.and_hms_opt(0, 0, 0).unwrap_or_default()).num_minutes() as f64
+                    / 1440.0
+                    * DAY_WIDTH
+        };
+        let width = PADDING * 2.0 + (end - origin).num_days() as f64 * DAY_WIDTH;
+        let height = HEADER_HEIGHT + lines.len() as f64 * ROW_HEIGHT;
+        let row_top = |row: usize| HEADER_HEIGHT + row as f64 * ROW_HEIGHT;
+
+        html.push_str("<div class=\"chart\">\n");
+        let _ = writeln!(
+            html,
+            "<svg class=\"labels\" width=\"{LABEL_WIDTH}\" height=\"{height}\">"
+        );
+        let _ = writeln!(
+            html,
+            "<text class=\"wbs\" x=\"6\" y=\"{}\">Activity</text>",
+            HEADER_HEIGHT - 10.0
+        );
+        for (row, line) in lines.iter().enumerate() {
+            let baseline = row_top(row) + ROW_HEIGHT - 7.0;
+            let (class, depth, label) = match line {
+                Line::Wbs { depth, label, .. } => (" class=\"wbs\"", *depth, label.clone()),
+                Line::Task { depth, task, .. } => {
+                    ("", *depth, format!("{} {}", task.task_code, task.task_name))
+                }
+            };
+            let _ = writeln!(
+                html,
+                "<text{class} x=\"{}\" y=\"{baseline}\">{}</text>",
+                6.0 + depth as f64 * INDENT,
+                escape(&cut(&label, LABEL_LEN.saturating_sub(depth * 2)))
+            );
+        }
+        html.push_str("</svg>\n<div class=\"bars\">\n");
+        let _ = writeln!(html, "<svg width=\"{width}\" height=\"{height}\">");
+        let mut month = origin;
+        while month < end {
+            let left = x(month.and_hms_opt(0, 0, 0).unwrap_or_default());
+            let _ = writeln!(
+                html,
+                "<line class=\"grid\" x1=\"{left}\" y1=\"0\" x2=\"{left}\" y2=\"{height}\"/>\
+                 <text class=\"month\" x=\"{}\" y=\"{}\">{}</text>",
+                left + 4.0,
+                HEADER_HEIGHT - 10.0,
+                month.format("%b %Y")
+            );
+            month = next_month(month);
+        }
+        for (row, line) in lines.iter().enumerate() {
+            let top = row_top(row);
+            match line {
+                Line::Wbs { label, range, .. } => {
+                    let _ = writeln!(
+                        html,
+                        "<rect class=\"summary\" x=\"{}\" y=\"{}\" width=\"{}\" height=\"6\">\
+                         <title>{}: {}</title></rect>",
+                        x(range.0),
+                        top + 8.0,
+                        (x(range.1) - x(range.0)).max(1.0),
+                        escape(label),
+                        span(*range)
+                    );
+                }
+                Line::Task {
+                    task,
+                    range,
+                    critical,
+                    ..
+                } => {
+                    let mut class = String::new();
+                    if *critical {
+                        class.push_str(" critical");
+                    }
+                    if task.status == TaskStatus::Completed {
+                        class.push_str(" done");
+                    }
+                    let mut tooltip =
+                        format!("{} {}: {}", task.task_code, task.task_name, span(*range));
+                    if let Some(float) = task.total_float {
+                        let _ = write!(tooltip, ", total float {float}h");
+                    }
+                    let tooltip = escape(&tooltip);
+                    if task.is_milestone() {
+                        let (cx, cy) = (x(range.0), top + ROW_HEIGHT / 2.0);
+                        let _ = writeln!(
+                            html,
+                            "<polygon class=\"milestone{class}\" points=\"{},{cy} {cx},{} {},{cy} {cx},{}\">\
+                             <title>{tooltip}</title></polygon>",
+                            cx - 6.0,
+                            cy - 6.0,
+                            cx + 6.0,
+                            cy + 6.0
+                        );
+                    } else {
+                        let _ = writeln!(
+                            html,
+                            "<rect class=\"bar{class}\" x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" rx=\"2\">\
+                             <title>{tooltip}</title></rect>",
+                            x(range.0),
+                            top + 4.0,
+                            (x(range.1) - x(range.0)).max(1.0),
+                            ROW_HEIGHT - 8.0
+                        );
+                    }
+                }
+            }
+        }
+        if let Some(data_date) = data_date {
+            let left = x(data_date);
+            let _ = writeln!(
+                html,
+                "<line class=\"data-date\" x1=\"{left}\" y1=\"0\" x2=\"{left}\" y2=\"{height}\"/>"
+            );
+        }
+        html.push_str("</svg>\n</div>\n</div>\n</body>\n</html>\n");
+        Ok(html)
+    }
+}
+
+/// `text` cut to `max_len` characters, `…` marking the cut
+fn cut(text: &str, max_len: usize) -> String {
+    if text.chars().count() <= max_len {
+        return text.to_string();
+    }
+    let mut cut: String = text.chars().take(max_len.saturating_sub(1)).collect();
+    cut.push('…');
+    cut
+}
+
+fn next_month(date: NaiveDate) -> NaiveDate {
+    date.checked_add_months(Months::new(1))
+        .unwrap_or(NaiveDate::MAX)
+}
+
+/// `start - finish`, one date for milestones
+fn span((start, finish): (NaiveDateTime, NaiveDateTime)) -> String {
+    if start == finish {
+        start.format("%Y-%m-%d %H:%M").to_string()
+    } else {
+        format!(
+            "{} - {}",
+            start.format("%Y-%m-%d %H:%M"),
+            finish.format("%Y-%m-%d %H:%M")
+        )
+    }
+}
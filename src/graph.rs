@@ -0,0 +1,304 @@
+//! Activity network built from `TASKPRED`
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::model::Relationship;
+
+/// Predecessor/successor lookups over relationships, nodes are `task_id`s
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogicGraph {
+    tasks: Vec<u64>,
+    relationships: Vec<Relationship>,
+    predecessors: HashMap<u64, Vec<usize>>,
+    successors: HashMap<u64, Vec<usize>>,
+}
+
+impl LogicGraph {
+    /// Build graph of `tasks`, tasks only mentioned by relationships are added as well
+    pub fn new(tasks: impl IntoIterator<Item = u64>, relationships: Vec<Relationship>) -> Self {
+        let mut graph = Self {
+            tasks: Vec::new(),
+            relationships: Vec::new(),
+            predecessors: HashMap::new(),
+            successors: HashMap::new(),
+        };
+        for task in tasks {
+            graph.add_task(task);
+        }
+        for (i, rel) in relationships.iter().enumerate() {
+            graph.add_task(rel.pred_task_id);
+            graph.add_task(rel.task_id);
+            graph.predecessors.get_mut(&rel.task_id).unwrap().push(i);
+            graph.successors.get_mut(&rel.pred_task_id).unwrap().push(i);
+        }
+        graph.relationships = relationships;
+        graph
+    }
+
+    fn add_task(&mut self, task: u64) {
+        if !self.predecessors.contains_key(&task) {
+            self.tasks.push(task);
+            self.predecessors.insert(task, Vec::new());
+            self.successors.insert(task, Vec::new());
+        }
+    }
+
+    /// Task ids in insertion order
+    pub fn tasks(&self) -> &[u64] {
+        &self.tasks
+    }
+
+    pub fn relationships(&self) -> &[Relationship] {
+        &self.relationships
+    }
+
+    pub fn contains(&self, task: u64) -> bool {
+        self.predecessors.contains_key(&task)
+    }
+
+    /// Relationships where `task` is successor
+    pub fn predecessors(&self, task: u64) -> impl Iterator<Item = &Relationship> {
+        self.links(&self.predecessors, task)
+    }
+
+    /// Relationships where `task` is predecessor
+    pub fn successors(&self, task: u64) -> impl Iterator<Item = &Relationship> {
+        self.links(&self.successors, task)
+    }
+
+    fn links<'a>(
+        &'a self,
+        map: &'a HashMap<u64, Vec<usize>>,
+        task: u64,
+    ) -> impl Iterator<Item = &'a Relationship> {
+        map.get(&task)
+            .map_or(&[][..], Vec::as_slice)
+            .iter()
+            .map(|&i| &self.relationships[i])
+    }
+
+    /// Tasks ordered so every predecessor comes before its successors
+    ///
+    /// Ties keep insertion order. Fails if the network contains a loop.
+    pub fn topological_order(&self) -> Result<Vec<u64>, LogicLoop> {
+        let mut in_degree: HashMap<u64, usize> = self
+            .tasks
+            .iter()
+            .map(|&task| (task, self.predecessors[&task].len()))
+            .collect();
+        let mut queue: VecDeque<u64> = self
+            .tasks
+            .iter()
+            .copied()
+            .filter(|task| in_degree[task] == 0)
+            .collect();
+
+        let mut order = Vec::with_capacity(self.tasks.len());
+        while let Some(task) = queue.pop_front() {
+            order.push(task);
+            for rel in self.successors(task) {
+                let degree = in_degree.get_mut(&rel.task_id).unwrap();
+                *degree -= 1;
+                if *degree == 0 {
+                    queue.push_back(rel.task_id);
+                }
+            }
+        }
+
+        if order.len() == self.tasks.len() {
+            Ok(order)
+        } else {
+            let tasks = self
+                .tasks
+                .iter()
+                .copied()
+                .filter(|task| in_degree[task] > 0)
+                .collect();
+            Err(LogicLoop { tasks })
+        }
+    }
+
+    /// One loop of every group of tasks reachable from each other
+    ///
+    /// Each loop is the shortest from the group's first task in insertion
+    /// order back to it, without repeating that task at the end. Loops come in
+    /// the order of their first tasks.
+    pub fn loops(&self) -> Vec<Vec<u64>> {
+        let position: HashMap<u64, usize> = self
+            .tasks
+            .iter()
+            .enumerate()
+            .map(|(i, &task)| (task, i))
+            .collect();
+        let mut loops: Vec<Vec<u64>> = self
+            .strongly_connected()
+            .into_iter()
+            .filter_map(|group| {
+                let start = *group.iter().min_by_key(|task| position[*task])?;
+                let group: HashSet<u64> = group.into_iter().collect();
+                self.shortest_loop(start, &group)
+            })
+            .collect();
+        loops.sort_by_key(|tasks| position[&tasks[0]]);
+        loops
+    }
+
+    /// Tarjan's strongly connected components, iterative to handle long chains
+    fn strongly_connected(&self) -> Vec<Vec<u64>> {
+        let mut index: HashMap<u64, usize> = HashMap::new();
+        let mut low: HashMap<u64, usize> = HashMap::new();
+        let mut stack = Vec::new();
+        let mut on_stack = HashSet::new();
+        let mut groups = Vec::new();
+
+        for &root in &self.tasks {
+            if index.contains_key(&root) {
+                continue;
+            }
+            // task and the number of its successors visited
+            let mut calls = vec![(root, 0)];
+            while let Some(&mut (task, ref mut next)) = calls.last_mut() {
+                if *next == 0 && !index.contains_key(&task) {
+                    let i = index.len();
+                    index.insert(task, i);
+                    low.insert(task, i);
+                    stack.push(task);
+                    on_stack.insert(task);
+                }
+                let successor = self.successors[&task]
+                    .get(*next)
+                    .map(|&i| self.relationships[i].task_id);
+                *next += 1;
+                match successor {
+                    Some(successor) if !index.contains_key(&successor) => {
+                        calls.push((successor, 0));
+                    }
+                    Some(successor) => {
+                        if on_stack.contains(&successor) {
+                            let lowest = low[&task].min(index[&successor]);
+                            low.insert(task, lowest);
+                        }
+                    }
+                    None => {
+                        calls.pop();
+                        if let Some(&(parent, _)) = calls.last() {
+                            let lowest = low[&parent].min(low[&task]);
+                            low.insert(parent, lowest);
+                        }
+                        if low[&task] == index[&task] {
+                            let mut group = Vec::new();
+                            while let Some(member) = stack.pop() {
+                                on_stack.remove(&member);
+                                group.push(member);
+                                if member == task {
+                                    break;
+                                }
+                            }
+                            groups.push(group);
+                        }
+                    }
+                }
+            }
+        }
+        groups
+    }
+
+    /// Breadth first path from `start` back to it within `group`
+    fn shortest_loop(&self, start: u64, group: &HashSet<u64>) -> Option<Vec<u64>> {
+        let mut previous: HashMap<u64, u64> = HashMap::new();
+        let mut queue = VecDeque::from([start]);
+        while let Some(task) = queue.pop_front() {
+            for rel in self.successors(task) {
+                let successor = rel.task_id;
+                if successor == start {
+                    let mut path = vec![task];
+                    while let Some(&before) = previous.get(path.last().unwrap()) {
+                        path.push(before);
+                    }
+                    path.reverse();
+                    return Some(path);
+                }
+                if group.contains(&successor)
+                    && successor != start
+                    && !previous.contains_key(&successor)
+                {
+                    previous.insert(successor, task);
+                    queue.push_back(successor);
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Network can not be ordered because of circular logic
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LogicLoop {
+    /// Tasks on a loop or downstream of one
+    pub tasks: Vec<u64>,
+}
+
+impl fmt::Display for LogicLoop {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} tasks are part of a logic loop", self.tasks.len())
+    }
+}
+
+impl std::error::Error for LogicLoop {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::LinkType;
+
+    fn graph(tasks: &[u64], links: &[(u64, u64)]) -> LogicGraph {
+        let relationships = links
+            .iter()
+            .enumerate()
+            .map(|(i, &(pred_task_id, task_id))| Relationship {
+                task_pred_id: i as u64 + 1,
+                task_id,
+                pred_task_id,
+                proj_id: None,
+                pred_proj_id: None,
+                link_type: LinkType::FinishToStart,
+                lag: 0.0,
+            })
+            .collect();
+        LogicGraph::new(tasks.iter().copied(), relationships)
+    }
+
+    #[test]
+    fn loops_of_separate_groups_and_self_links() {
+        // 10 -> 11 leads into the loop of 1, 2, 3 which leads into 4, 5
+        let graph = graph(
+            &[10, 11, 1, 2, 3, 4, 5, 6, 7],
+            &[
+                (10, 11),
+                (11, 1),
+                (1, 2),
+                (2, 3),
+                (3, 1),
+                (2, 1),
+                (3, 4),
+                (4, 5),
+                (5, 4),
+                (6, 6),
+                (6, 7),
+            ],
+        );
+        assert_eq!(graph.loops(), [vec![1, 2], vec![4, 5], vec![6]]);
+        let err = graph.topological_order().unwrap_err();
+        assert_eq!(err.tasks, [1, 2, 3, 4, 5, 6, 7]);
+    }
+
+    #[test]
+    fn no_loops_in_a_chain() {
+        let graph = graph(&[3, 1, 2], &[(1, 2), (2, 3), (1, 3)]);
+        assert!(graph.loops().is_empty());
+        assert_eq!(graph.topological_order().unwrap(), [1, 2, 3]);
+    }
+}
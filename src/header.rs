@@ -0,0 +1,65 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::encoding::{codepage, Encoding};
+
+/// Export info from the first line of the file
+///
+/// |ERMHDR|19.12  |2024-03-15   |Project    |user|user_name|dbxDatabaseNoName|Project Management|EUR     |
+/// |------|-------|-------------|-----------|----|---------|-----------------|------------------|--------|
+/// |      |version|export_date  |export_type|user|user_name|database         |module            |currency|
+///
+/// Some older exports add the code page of the database after the currency.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Header {
+    /// P6 version file was exported from, e.g. `19.12`
+    pub version: String,
+    pub export_date: NaiveDate,
+    /// What was exported, `Project` for project exports
+    pub export_type: String,
+    /// Login of exporting user
+    pub user: String,
+    /// Full name of exporting user
+    pub user_name: String,
+    pub database: String,
+    /// Exporting product, e.g. `Project Management`
+    pub module: String,
+    /// Base currency short name, e.g. `EUR`
+    pub currency: String,
+    /// Code page the file was written in, e.g. `1251` or `Shift_JIS`
+    pub codepage: Option<String>,
+}
+
+impl Header {
+    /// Parse `ERMHDR` line, `None` if line is not a header
+    ///
+    /// Older exports may omit trailing fields, those are left empty.
+    pub fn parse(line: &str) -> Option<Self> {
+        let mut fields = line.split('\t');
+        if fields.next()? != "ERMHDR" {
+            return None;
+        }
+        let version = fields.next()?.to_string();
+        let export_date = NaiveDate::parse_from_str(fields.next()?, "%Y-%m-%d").ok()?;
+        let mut next = || fields.next().unwrap_or_default().to_string();
+
+        Some(Self {
+            version,
+            export_date,
+            export_type: next(),
+            user: next(),
+            user_name: next(),
+            database: next(),
+            module: next(),
+            currency: next(),
+            codepage: fields
+                .find(|field| codepage(field).is_some())
+                .map(String::from),
+        })
+    }
+
+    /// Encoding named by [`Header::codepage`]
+    pub fn encoding(&self) -> Option<&'static Encoding> {
+        codepage(self.codepage.as_deref()?)
+    }
+}
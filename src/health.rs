@@ -0,0 +1,238 @@
+//! Weighted health score of the schedule
+//!
+//! [`XerFile::health_score`] runs the checks of [`check`](crate::check) and
+//! [`constraints`](crate::constraints) and scores each from 100, nothing
+//! found, down to 0, every activity or relationship found. The health score
+//! is the average of the metric scores weighted by [`HealthWeights`], metrics
+//! weighted 0 are left out. Scores of successive updates show the trend.
+//!
+//! | Metric             | Counts                                         | Out of        |
+//! |--------------------|------------------------------------------------|---------------|
+//! | `open_ends`        | activities without predecessor or successor    | activities    |
+//! | `hard_constraints` | activities with a hard constraint              | activities    |
+//! | `negative_float`   | activities with negative total float           | activities    |
+//! | `logic_loops`      | activities on logic loops                      | activities    |
+//! | `out_of_sequence`  | relationships progress ignored                 | relationships |
+//! | `leads`            | relationships with negative lag                | relationships |
+//! | `lags`             | relationships with positive lag                | relationships |
+//! | `long_durations`   | activities over the remaining duration limit   | activities    |
+//! | `high_float`       | activities over the total float limit          | activities    |
+//!
+//! Activities are those that are neither level of effort nor WBS summary.
+//!
+//! ## Example
+//! ```
+//! use schedule_parser::health::HealthOptions;
+//! use schedule_parser::XerFile;
+//!
+//! let data = "ERMHDR\t19.12\t2024-03-15\n%T\tTASK\n\
+//!     %F\ttask_id\tproj_id\twbs_id\ttask_code\ttask_name\ttask_type\tstatus_code\n\
+//!     %R\t1\t1\t1\tM1000\tStart\tTT_Mile\tTK_NotStart\n\
+//!     %R\t2\t1\t1\tA1000\tDesign\tTT_Task\tTK_NotStart\n\
+//!     %T\tTASKPRED\n%F\ttask_pred_id\ttask_id\tpred_task_id\tpred_type\tlag_hr_cnt\n%R\t1\t2\t1\tPR_FS\t-8\n%E\n";
+//! let xer = XerFile::parse(data.as_bytes()).unwrap();
+//!
+//! let mut options = HealthOptions::default();
+//! options.weights.lags = 0.0;
+//! let health = xer.health_score(&options).unwrap();
+//! assert_eq!(health.metrics[0].name, "open_ends");
+//! assert_eq!(health.metrics[0].score, 50.0);
+//! // open ends score 50, leads 0, the other six 100
+//! assert_eq!(health.score, 81.25);
+//! ```
+
+use std::collections::HashSet;
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+use crate::check::CheckOptions;
+use crate::constraints::is_hard_constraint;
+use crate::error::ParseError;
+use crate::model::TaskType;
+use crate::xer::XerFile;
+
+/// Limits and weights of [`XerFile::health_score`]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HealthOptions {
+    pub checks: CheckOptions,
+    pub weights: HealthWeights,
+}
+
+/// Weight of each metric in the health score, all 1 by default
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HealthWeights {
+    pub open_ends: f64,
+    pub hard_constraints: f64,
+    pub negative_float: f64,
+    pub logic_loops: f64,
+    pub out_of_sequence: f64,
+    pub leads: f64,
+    pub lags: f64,
+    pub long_durations: f64,
+    pub high_float: f64,
+}
+
+impl Default for HealthWeights {
+    fn default() -> Self {
+        Self {
+            open_ends: 1.0,
+            hard_constraints: 1.0,
+            negative_float: 1.0,
+            logic_loops: 1.0,
+            out_of_sequence: 1.0,
+            leads: 1.0,
+            lags: 1.0,
+            long_durations: 1.0,
+            high_float: 1.0,
+        }
+    }
+}
+
+/// Result of [`XerFile::health_score`]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HealthScore {
+    /// Latest data date of the projects
+    pub data_date: Option<NaiveDateTime>,
+    /// Weighted average of the metric scores, 100 if all weights are 0
+    pub score: f64,
+    /// In the order of [`health`](crate::health)
+    pub metrics: Vec<HealthMetric>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HealthMetric {
+    pub name: &'static str,
+    pub count: usize,
+    pub total: usize,
+    /// 100 minus the percentage `count` is of `total`, 100 if `total` is 0
+    pub score: f64,
+    pub weight: f64,
+}
+
+impl XerFile {
+    /// Checks scored and weighted, see [`health`](crate::health)
+    pub fn health_score(&self, options: &HealthOptions) -> Result<HealthScore, ParseError> {
+        let tasks: Vec<_> = self
+            .tasks()?
+            .into_iter()
+            .filter(|task| {
+                !matches!(
+                    task.task_type,
+                    TaskType::LevelOfEffort | TaskType::WbsSummary
+                )
+            })
+            .collect();
+        let relationships = self.relationships()?;
+        let activities = tasks.len();
+        let links = relationships.len();
+
+        let hard_constraints = tasks
+            .iter()
+            .filter(|task| {
+                [&task.constraint_type, &task.secondary_constraint_type]
+                    .into_iter()
+                    .flatten()
+                    .any(|code| is_hard_constraint(code))
+            })
+            .count();
+        let on_loops: HashSet<String> = self.logic_loops()?.into_iter().flatten().collect();
+
+        let weights = &options.weights;
+        let checks = &options.checks;
+        let metrics = vec![
+            metric(
+                "open_ends",
+                self.open_ends()?.len(),
+                activities,
+                weights.open_ends,
+            ),
+            metric(
+                "hard_constraints",
+                hard_constraints,
+                activities,
+                weights.hard_constraints,
+            ),
+            metric(
+                "negative_float",
+                self.negative_float()?.len(),
+                activities,
+                weights.negative_float,
+            ),
+            metric(
+                "logic_loops",
+                on_loops.len(),
+                activities,
+                weights.logic_loops,
+            ),
+            metric(
+                "out_of_sequence",
+                self.out_of_sequence()?.len(),
+                links,
+                weights.out_of_sequence,
+            ),
+            metric(
+                "leads",
+                relationships.iter().filter(|link| link.lag < 0.0).count(),
+                links,
+                weights.leads,
+            ),
+            metric(
+                "lags",
+                relationships.iter().filter(|link| link.lag > 0.0).count(),
+                links,
+                weights.lags,
+            ),
+            metric(
+                "long_durations",
+                self.long_durations(checks.max_remaining_days)?.len(),
+                activities,
+                weights.long_durations,
+            ),
+            metric(
+                "high_float",
+                self.high_float(checks.max_float_days)?.len(),
+                activities,
+                weights.high_float,
+            ),
+        ];
+
+        let weight: f64 = metrics.iter().map(|metric| metric.weight).sum();
+        let score = if weight > 0.0 {
+            metrics
+                .iter()
+                .map(|metric| metric.score * metric.weight)
+                .sum::<f64>()
+                / weight
+        } else {
+            100.0
+        };
+        let data_date = self
+            .projects()?
+            .into_iter()
+            .filter_map(|project| project.data_date)
+            .max();
+        Ok(HealthScore {
+            data_date,
+            score,
+            metrics,
+        })
+    }
+}
+
+fn metric(name: &'static str, count: usize, total: usize, weight: f64) -> HealthMetric {
+    let score = if total == 0 {
+        100.0
+    } else {
+        100.0 - (count as f64 * 100.0 / total as f64).min(100.0)
+    };
+    HealthMetric {
+        name,
+        count,
+        total,
+        score,
+        weight,
+    }
+}
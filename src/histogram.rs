@@ -0,0 +1,201 @@
+//! Resource loading per period for histograms
+//!
+//! [`XerFile::resource_loading`] spreads the units of every resource
+//! assignment evenly over the work time between its dates: budgeted units
+//! over the planned dates, actual units from the actual start to the actual
+//! finish or remaining start, remaining units over the remaining dates. Work
+//! time is that of the resource calendar for resource dependent activities,
+//! else the activity calendar. Without calendar time is counted continuously.
+//!
+//! ## Example
+//! ```
+//! use chrono::NaiveDate;
+//! use schedule_parser::histogram::Period;
+//! use schedule_parser::XerFile;
+//!
+//! let data = "ERMHDR\t19.12\t2024-03-15\n\
+//!     %T\tRSRC\n%F\trsrc_id\trsrc_short_name\trsrc_name\trsrc_type\n%R\t1\tENG\tEngineer\tRT_Labor\n\
+//!     %T\tTASK\n%F\ttask_id\tproj_id\twbs_id\ttask_code\ttask_name\ttask_type\tstatus_code\n\
+//!     %R\t1\t1\t1\tA1000\tDesign\tTT_Task\tTK_NotStart\n\
+//!     %T\tTASKRSRC\n%F\ttaskrsrc_id\ttask_id\tproj_id\trsrc_id\ttarget_qty\ttarget_start_date\ttarget_end_date\n\
+//!     %R\t1\t1\t1\t1\t96\t2024-03-30 00:00\t2024-04-03 00:00\n%E\n";
+//! let xer = XerFile::parse(data.as_bytes()).unwrap();
+//!
+//! let loading = xer.resource_loading(Period::Month).unwrap();
+//! assert_eq!(loading.len(), 2);
+//! assert_eq!(loading[0].period_start, NaiveDate::from_ymd_opt(2024, 3, 1).unwrap());
+//! assert_eq!(loading[0].planned_units, 48.0);
+//! assert_eq!(loading[1].planned_units, 48.0);
+//! ```
+
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{Datelike, Days, Months, NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+
+use crate::error::ParseError;
+use crate::model::{Calendar, Resource, TaskType};
+use crate::xer::XerFile;
+
+/// Length of a histogram bar
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Period {
+    Day,
+    /// Starting on Monday
+    Week,
+    Month,
+}
+
+impl Period {
+    /// First day of the period containing `date`
+    pub fn start(&self, date: NaiveDate) -> NaiveDate {
+        match self {
+            Self::Day => date,
+            Self::Week => date - Days::new(u64::from(date.weekday().num_days_from_monday())),
+            Self::Month => date.with_day(1).unwrap(),
+        }
+    }
+
+    /// First day of the period after the one starting at `start`
+    pub fn next(&self, start: NaiveDate) -> NaiveDate {
+        match self {
+            Self::Day => start + Days::new(1),
+            Self::Week => start + Days::new(7),
+            Self::Month => start + Months::new(1),
+        }
+    }
+}
+
+/// Units of a resource in one period
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Loading {
+    pub rsrc_id: u64,
+    pub rsrc_short_name: String,
+    pub period_start: NaiveDate,
+    /// Budgeted units, hours for labor and nonlabor resources
+    pub planned_units: f64,
+    pub actual_units: f64,
+    pub remaining_units: f64,
+}
+
+impl XerFile {
+    /// Units per resource and period in `RSRC` order, periods without units
+    /// are left out, see [`histogram`](crate::histogram)
+    ///
+    /// Role assignments without resource are skipped.
+    pub fn resource_loading(&self, period: Period) -> Result<Vec<Loading>, ParseError> {
+        let resources: Vec<Resource> = self.records()?;
+        let position: HashMap<u64, usize> = resources
+            .iter()
+            .enumerate()
+            .map(|(i, resource)| (resource.rsrc_id, i))
+            .collect();
+        let calendars: HashMap<u64, Calendar> = self
+            .calendars()?
+            .into_iter()
+            .map(|calendar| (calendar.clndr_id, calendar))
+            .collect();
+        let tasks: HashMap<u64, _> = self
+            .tasks()?
+            .into_iter()
+            .map(|task| (task.task_id, task))
+            .collect();
+
+        // units by resource and period: planned, actual, remaining
+        let mut units: BTreeMap<(usize, NaiveDate), [f64; 3]> = BTreeMap::new();
+        for assignment in self.assignments()?.iter() {
+            let Some(&resource) = assignment.rsrc_id.as_ref().and_then(|id| position.get(id))
+            else {
+                continue;
+            };
+            let task = tasks.get(&assignment.task_id);
+            let calendar = match task {
+                Some(task) if task.task_type == TaskType::ResourceDependent => {
+                    resources[resource].clndr_id.or(task.clndr_id)
+                }
+                Some(task) => task.clndr_id,
+                None => resources[resource].clndr_id,
+            }
+            .and_then(|id| calendars.get(&id));
+
+            let actual_finish = assignment.actual_finish.or(assignment.remaining_start);
+            let spreads = [
+                (
+                    assignment.budgeted_units,
+                    assignment.planned_start,
+                    assignment.planned_finish,
+                ),
+                (
+                    assignment.actual_units,
+                    assignment.actual_start,
+                    actual_finish,
+                ),
+                (
+                    assignment.remaining_units,
+                    assignment.remaining_start,
+                    assignment.remaining_finish,
+                ),
+            ];
+            for (kind, (total, start, finish)) in spreads.into_iter().enumerate() {
+                let Some(start) = start else {
+                    continue;
+                };
+                if total == 0.0 {
+                    continue;
+                }
+                let finish = finish.unwrap_or(start).max(start);
+                for (period_start, share) in spread(calendar, period, start, finish) {
+                    units.entry((resource, period_start)).or_default()[kind] += total * share;
+                }
+            }
+        }
+
+        Ok(units
+            .into_iter()
+            .map(
+                |((resource, period_start), [planned, actual, remaining])| Loading {
+                    rsrc_id: resources[resource].rsrc_id,
+                    rsrc_short_name: resources[resource].rsrc_short_name.clone(),
+                    period_start,
+                    planned_units: planned,
+                    actual_units: actual,
+                    remaining_units: remaining,
+                },
+            )
+            .collect())
+    }
+}
+
+/// Share of the work time from `start` to `finish` in each period, all in
+/// the first one if there is no work time
+fn spread(
+    calendar: Option<&Calendar>,
+    period: Period,
+    start: NaiveDateTime,
+    finish: NaiveDateTime,
+) -> Vec<(NaiveDate, f64)> {
+    let hours = |from: NaiveDateTime, to: NaiveDateTime| match calendar {
+        Some(calendar) => calendar.work_hours_between(from, to),
+        None => (to - from).num_minutes() as f64 / 60.0,
+    };
+    let total = hours(start, finish);
+    let first = period.start(start.date());
+    if total <= 0.0 {
+        return vec![(first, 1.0)];
+    }
+    let mut shares = Vec::new();
+    let mut period_start = first;
+    let mut from = start;
+    while from < finish {
+        let next = period.next(period_start).and_hms_opt(0, 0, 0).unwrap();
+        let to = next.min(finish);
+        let share = hours(from, to) / total;
+        if share > 0.0 {
+            shares.push((period_start, share));
+        }
+        period_start = next.date();
+        from = to;
+    }
+    shares
+}
@@ -0,0 +1,176 @@
+//! iCalendar (`.ics`) export of activity dates
+//!
+//! [`XerFile::to_ics`] writes every activity as a `VEVENT` from its start to
+//! its finish, milestones as events at their date. The summary is activity
+//! ID and name, the description the WBS path. Dates are actual dates where
+//! there are some, else early, remaining or planned dates, in floating local
+//! time like in P6. Event UIDs are made of project and activity ID, so a
+//! calendar subscribed to the export of later updates moves events instead
+//! of adding new ones. Activities without dates are left out.
+//!
+//! ## Example
+//! ```
+//! use schedule_parser::ics::EventFilter;
+//! use schedule_parser::XerFile;
+//!
+//! let data = "ERMHDR\t19.12\t2024-03-15\n\
+//!     %T\tPROJECT\n%F\tproj_id\tproj_short_name\n%R\t1\tP1\n\
+//!     %T\tPROJWBS\n%F\twbs_id\tproj_id\tparent_wbs_id\twbs_short_name\twbs_name\tproj_node_flag\n\
+//!     %R\t1\t1\t\tP1\tPlant\tY\n%R\t2\t1\t1\tCIV\tCivil\tN\n\
+//!     %T\tTASK\n%F\ttask_id\tproj_id\twbs_id\ttask_code\ttask_name\ttask_type\tstatus_code\t\
+//!     early_start_date\tearly_end_date\n\
+//!     %R\t1\t1\t2\tA1000\tFoundations\tTT_Task\tTK_NotStart\t2024-03-18 08:00\t2024-03-22 17:00\n\
+//!     %R\t2\t1\t2\tM1000\tHandover\tTT_FinMile\tTK_NotStart\t\t2024-03-22 17:00\n%E\n";
+//! let xer = XerFile::parse(data.as_bytes()).unwrap();
+//!
+//! let ics = xer.to_ics(EventFilter::Milestones).unwrap();
+//! assert!(ics.contains("SUMMARY:M1000 Handover\r\n"));
+//! assert!(ics.contains("DTSTART:20240322T170000\r\n"));
+//! assert!(ics.contains("DESCRIPTION:P1.CIV Civil\r\n"));
+//! assert!(!ics.contains("A1000"));
+//! ```
+
+use std::collections::HashMap;
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+use crate::error::ParseError;
+use crate::model::{Task, TaskType};
+use crate::xer::XerFile;
+
+/// Activities [`XerFile::to_ics`] writes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventFilter {
+    /// All but WBS summaries
+    All,
+    /// Start and finish milestones
+    Milestones,
+}
+
+impl XerFile {
+    /// iCalendar of the activities selected by `filter`, see [`ics`](crate::ics)
+    pub fn to_ics(&self, filter: EventFilter) -> Result<String, ParseError> {
+        let projects: HashMap<u64, String> = self
+            .projects()?
+            .into_iter()
+            .map(|project| (project.proj_id, project.proj_short_name))
+            .collect();
+        let wbs = self.wbs_tree()?;
+        let stamp = self.header.export_date.format("%Y%m%dT000000Z");
+
+        let mut ics = String::new();
+        line(&mut ics, "BEGIN:VCALENDAR");
+        line(&mut ics, "VERSION:2.0");
+        line(&mut ics, "PRODID:-//schedule_parser//EN");
+        line(&mut ics, "CALSCALE:GREGORIAN");
+        for task in self.tasks()? {
+            let selected = match filter {
+                EventFilter::All => task.task_type != TaskType::WbsSummary,
+                EventFilter::Milestones => task.is_milestone(),
+            };
+            if !selected {
+                continue;
+            }
+            let Some((start, finish)) = dates(&task) else {
+                continue;
+            };
+            let project = projects
+                .get(&task.proj_id)
+                .cloned()
+                .unwrap_or_else(|| task.proj_id.to_string());
+            let mut path: Vec<&str> = wbs
+                .path_to_root(task.wbs_id)
+                .map(|element| element.wbs_short_name.as_str())
+                .collect();
+            path.reverse();
+            let description = match wbs.get(task.wbs_id) {
+                Some(element) => format!("{} {}", path.join("."), element.wbs_name),
+                None => String::new(),
+            };
+
+            line(&mut ics, "BEGIN:VEVENT");
+            line(
+                &mut ics,
+                &format!(
+                    "UID:{}@schedule_parser",
+                    text(&format!("{project}-{}", task.task_code))
+                ),
+            );
+            line(&mut ics, &format!("DTSTAMP:{stamp}"));
+            line(
+                &mut ics,
+                &format!("DTSTART:{}", start.format("%Y%m%dT%H%M%S")),
+            );
+            if finish > start {
+                line(
+                    &mut ics,
+                    &format!("DTEND:{}", finish.format("%Y%m%dT%H%M%S")),
+                );
+            }
+            line(
+                &mut ics,
+                &format!(
+                    "SUMMARY:{}",
+                    text(&format!("{} {}", task.task_code, task.task_name))
+                ),
+            );
+            if !description.is_empty() {
+                line(&mut ics, &format!("DESCRIPTION:{}", text(&description)));
+            }
+            line(&mut ics, "END:VEVENT");
+        }
+        line(&mut ics, "END:VCALENDAR");
+        Ok(ics)
+    }
+}
+
+/// Start and finish of the event of `task`, the same date twice for milestones
+pub(crate) fn dates(task: &Task) -> Option<(NaiveDateTime, NaiveDateTime)> {
+    let start = task.start().or(task.remaining_start).or(task.planned_start);
+    let finish = task
+        .finish()
+        .or(task.remaining_finish)
+        .or(task.planned_finish);
+    match task.task_type {
+        TaskType::StartMilestone => start.or(finish).map(|date| (date, date)),
+        TaskType::FinishMilestone => finish.or(start).map(|date| (date, date)),
+        _ => {
+            let start = start.or(finish)?;
+            Some((start, finish.unwrap_or(start).max(start)))
+        }
+    }
+}
+
+/// `value` with backslashes, separators and line breaks escaped
+fn text(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' | ';' | ',' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Content line ended by CRLF, folded after 75 bytes
+fn line(ics: &mut String, content: &str) {
+    let mut length = 0;
+    for c in content.chars() {
+        if length + c.len_utf8() > 75 {
+            ics.push_str("\r\n ");
+            // the leading space counts
+            length = 1;
+        }
+        ics.push(c);
+        length += c.len_utf8();
+    }
+    ics.push_str("\r\n");
+}
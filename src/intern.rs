@@ -0,0 +1,180 @@
+//! Shared storage for repetitive field values
+//!
+//! Columns like `status_code`, `task_type` or `clndr_id` hold a handful of
+//! distinct values over hundreds of thousands of rows. Interned tables keep
+//! one allocation per distinct value instead of one per field.
+//!
+//! ## Example
+//! ```
+//! use std::io::Cursor;
+//! use schedule_parser::XerReader;
+//!
+//! let data = "ERMHDR\t19.12\t2024-03-15\n%T\tTASK\n%F\ttask_id\tstatus_code\n%R\t1\tTK_Active\n%R\t2\tTK_Active\n%E\n";
+//! let mut tables = XerReader::from_reader(Cursor::new(data)).unwrap().tables().interned();
+//! let task = tables.next().unwrap().unwrap();
+//! assert_eq!(task.row(1).unwrap().get("status_code").unwrap(), "TK_Active");
+//! assert_eq!(tables.interner().len(), 3);
+//! ```
+
+use std::collections::{HashMap, HashSet};
+use std::io::BufRead;
+use std::sync::Arc;
+
+use serde::Deserialize;
+
+use crate::error::ParseError;
+use crate::parser::{column_index, Row, Table, TableIterator};
+
+/// Set of distinct values handed out as shared `Arc<str>`
+#[derive(Debug, Default)]
+pub struct Interner {
+    values: HashSet<Arc<str>>,
+}
+
+impl Interner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Shared copy of `value`, allocated on first use only
+    pub fn intern(&mut self, value: &str) -> Arc<str> {
+        if let Some(shared) = self.values.get(value) {
+            return shared.clone();
+        }
+        let shared: Arc<str> = value.into();
+        self.values.insert(shared.clone());
+        shared
+    }
+
+    /// Number of distinct values
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
+/// Table with field values shared through an [`Interner`]
+#[derive(Debug)]
+pub struct InternedTable {
+    pub name: String,
+    pub header: Vec<String>,
+    pub rows: Vec<Vec<Arc<str>>>,
+    /// Input ended inside this table, see [`Table::truncated`]
+    pub truncated: bool,
+    /// Column name to position in `header`
+    index: HashMap<String, usize>,
+}
+
+impl InternedTable {
+    pub(crate) fn new(
+        name: String,
+        header: Vec<String>,
+        index: HashMap<String, usize>,
+        rows: Vec<Vec<Arc<str>>>,
+    ) -> Self {
+        Self {
+            name,
+            header,
+            rows,
+            truncated: false,
+            index,
+        }
+    }
+
+    /// Intern values of `table`
+    pub fn from_table(table: &Table, interner: &mut Interner) -> Self {
+        let rows = table
+            .rows
+            .iter()
+            .map(|row| row.iter().map(|value| interner.intern(value)).collect())
+            .collect();
+        let index = column_index(&table.header);
+        let mut interned = Self::new(table.name.clone(), table.header.clone(), index, rows);
+        interned.truncated = table.truncated;
+        interned
+    }
+
+    pub fn row_count(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Position of `column` in the header
+    pub fn column_index(&self, column: &str) -> Option<usize> {
+        self.index.get(column).copied()
+    }
+
+    /// Row `i` with access by column name
+    pub fn row(&self, i: usize) -> Option<Row<'_, Arc<str>>> {
+        self.rows.get(i).map(|values| self.row_of(values))
+    }
+
+    /// Rows with access by column name
+    pub fn iter(&self) -> impl Iterator<Item = Row<'_, Arc<str>>> {
+        self.rows.iter().map(|values| self.row_of(values))
+    }
+
+    /// Deserialize rows into user type `T`, see [`de`](crate::de)
+    pub fn deserialize<'a, T: Deserialize<'a>>(
+        &'a self,
+    ) -> impl Iterator<Item = Result<T, ParseError>> + 'a {
+        self.iter().map(|row| row.deserialize())
+    }
+
+    /// Copy with an own `String` per field, e.g. for the typed models
+    pub fn to_table(&self) -> Table {
+        let mut table = Table::new(
+            self.name.clone(),
+            self.header.clone(),
+            self.rows
+                .iter()
+                .map(|row| row.iter().map(|value| value.to_string()).collect())
+                .collect(),
+        );
+        table.truncated = self.truncated;
+        table
+    }
+
+    fn row_of<'a>(&'a self, values: &'a [Arc<str>]) -> Row<'a, Arc<str>> {
+        Row::new(&self.name, &self.header, &self.index, values)
+    }
+}
+
+/// Tables with values interned while they are read, see [`TableIterator::interned`]
+pub struct InternedTables<R> {
+    tables: TableIterator<R>,
+    interner: Interner,
+}
+
+impl<R: BufRead> InternedTables<R> {
+    pub(crate) fn new(tables: TableIterator<R>) -> Self {
+        Self {
+            tables,
+            interner: Interner::new(),
+        }
+    }
+
+    /// Values interned so far
+    pub fn interner(&self) -> &Interner {
+        &self.interner
+    }
+}
+
+impl<R: BufRead> Iterator for InternedTables<R> {
+    type Item = Result<InternedTable, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let table = match self.tables.next_table() {
+            Ok(Some(table)) => table,
+            Ok(None) => return None,
+            Err(err) => return Some(Err(err)),
+        };
+        Some(table.into_interned(&mut self.interner))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.tables.size_hint()
+    }
+}
@@ -0,0 +1,113 @@
+//! Primary and foreign keys of P6 tables
+//!
+//! Ids are numbers unique within their table. Most tables keep their id in a
+//! column named after it, e.g. `task_id` in `TASK`, and other tables refer to
+//! it by the same name. Link tables like `TASKACTV` have no id of their own.
+//!
+//! ## Example
+//! ```
+//! use schedule_parser::keys::{foreign_table, primary_key};
+//!
+//! assert_eq!(primary_key("TASK"), Some("task_id"));
+//! assert_eq!(foreign_table("TASKPRED", "pred_task_id"), Some("TASK"));
+//! assert_eq!(foreign_table("TASK", "task_id"), None);
+//! ```
+
+use std::collections::HashMap;
+
+use crate::parser::Table;
+
+/// Tables with id column
+const PRIMARY_KEYS: &[(&str, &str)] = &[
+    ("ACCOUNT", "acct_id"),
+    ("ACTVCODE", "actv_code_id"),
+    ("ACTVTYPE", "actv_code_type_id"),
+    ("CALENDAR", "clndr_id"),
+    ("COSTTYPE", "cost_type_id"),
+    ("CURRTYPE", "curr_id"),
+    ("FINDATES", "fin_dates_id"),
+    ("FINTMPL", "fintmpl_id"),
+    ("LOCATION", "location_id"),
+    ("MEMOTYPE", "memo_type_id"),
+    ("NONWORK", "nonwork_type_id"),
+    ("OBS", "obs_id"),
+    ("PCATTYPE", "proj_catg_type_id"),
+    ("PCATVAL", "proj_catg_id"),
+    ("PHASE", "phase_id"),
+    ("POBS", "pobs_id"),
+    ("PROJCOST", "cost_item_id"),
+    ("PROJECT", "proj_id"),
+    ("PROJWBS", "wbs_id"),
+    ("RCATTYPE", "rsrc_catg_type_id"),
+    ("RCATVAL", "rsrc_catg_id"),
+    ("ROLERATE", "role_rate_id"),
+    ("ROLES", "role_id"),
+    ("RSRC", "rsrc_id"),
+    ("RSRCCURVDATA", "curv_id"),
+    ("RSRCRATE", "rsrc_rate_id"),
+    ("SCHEDOPTIONS", "schedoptions_id"),
+    ("SHIFT", "shift_id"),
+    ("SHIFTPER", "shift_period_id"),
+    ("TASK", "task_id"),
+    ("TASKMEMO", "memo_id"),
+    ("TASKPRED", "task_pred_id"),
+    ("TASKPROC", "proc_id"),
+    ("TASKRSRC", "taskrsrc_id"),
+    ("UDFCODE", "udf_code_id"),
+    ("UDFTYPE", "udf_type_id"),
+    ("UMEASURE", "unit_id"),
+    ("USERS", "user_id"),
+    ("WBSMEMO", "wbs_memo_id"),
+];
+
+/// References not named after the id they refer to
+const ALIASES: &[(&str, &str)] = &[
+    ("base_clndr_id", "CALENDAR"),
+    ("orig_proj_id", "PROJECT"),
+    ("parent_acct_id", "ACCOUNT"),
+    ("parent_actv_code_id", "ACTVCODE"),
+    ("parent_obs_id", "OBS"),
+    ("parent_proj_catg_id", "PCATVAL"),
+    ("parent_role_id", "ROLES"),
+    ("parent_rsrc_catg_id", "RCATVAL"),
+    ("parent_rsrc_id", "RSRC"),
+    ("parent_wbs_id", "PROJWBS"),
+    ("pred_proj_id", "PROJECT"),
+    ("pred_task_id", "TASK"),
+];
+
+/// Id column of `table`, `None` for link tables and tables not known
+pub fn primary_key(table: &str) -> Option<&'static str> {
+    PRIMARY_KEYS
+        .iter()
+        .find(|(name, _)| *name == table)
+        .map(|(_, column)| *column)
+}
+
+/// Table `column` of `table` refers to, e.g. `TASK` for `TASKPRED.pred_task_id`
+///
+/// `UDFVALUE.fk_id` refers to the table named by its `UDFTYPE` and is not covered.
+pub fn foreign_table(table: &str, column: &str) -> Option<&'static str> {
+    if primary_key(table) == Some(column) {
+        return None;
+    }
+    if let Some((table, _)) = PRIMARY_KEYS.iter().find(|(_, id)| *id == column) {
+        return Some(table);
+    }
+    ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == column)
+        .map(|(_, table)| *table)
+}
+
+/// Table named by each `udf_type_id` of `UDFTYPE`, which `UDFVALUE.fk_id` refers to
+pub(crate) fn udf_tables(udf_types: Option<&Table>) -> HashMap<&str, &str> {
+    udf_types
+        .map(|types| {
+            types
+                .iter()
+                .filter_map(|row| Some((row.get_opt("udf_type_id")?, row.get_opt("table_name")?)))
+                .collect()
+        })
+        .unwrap_or_default()
+}
@@ -0,0 +1,130 @@
+//! Lag and lead usage of the relationships
+//!
+//! [`XerFile::lag_analysis`] counts relationships, lags and leads per link
+//! type and lists every relationship with a lag, e.g. to show compliance
+//! with contract limits on lag usage. Lags are in hours as stored by P6.
+//!
+//! ## Example
+//! ```
+//! use schedule_parser::model::LinkType;
+//! use schedule_parser::XerFile;
+//!
+//! let data = "ERMHDR\t19.12\t2024-03-15\n%T\tTASK\n\
+//!     %F\ttask_id\tproj_id\twbs_id\ttask_code\ttask_name\ttask_type\tstatus_code\n\
+//!     %R\t1\t1\t1\tA1000\tDesign\tTT_Task\tTK_NotStart\n\
+//!     %R\t2\t1\t1\tA1010\tBuild\tTT_Task\tTK_NotStart\n\
+//!     %T\tTASKPRED\n%F\ttask_pred_id\ttask_id\tpred_task_id\tpred_type\tlag_hr_cnt\n\
+//!     %R\t1\t2\t1\tPR_SS\t-8\n%R\t2\t2\t1\tPR_FF\t80\n%E\n";
+//! let xer = XerFile::parse(data.as_bytes()).unwrap();
+//!
+//! let analysis = xer.lag_analysis(Some(40.0)).unwrap();
+//! let ss = &analysis.summary[1];
+//! assert_eq!((ss.link_type, ss.relationships, ss.leads), (LinkType::StartToStart, 1, 1));
+//! assert_eq!(analysis.lags.len(), 2);
+//! assert!(analysis.lags[1].over_limit);
+//! ```
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use crate::error::ParseError;
+use crate::model::LinkType;
+use crate::xer::XerFile;
+
+/// Result of [`XerFile::lag_analysis`]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LagAnalysis {
+    /// Finish to start, start to start, finish to finish, start to finish
+    pub summary: Vec<LinkTypeLags>,
+    /// Relationships with a lag or lead in `TASKPRED` order
+    pub lags: Vec<Lag>,
+}
+
+/// Relationships of one link type
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LinkTypeLags {
+    pub link_type: LinkType,
+    pub relationships: usize,
+    /// Positive lags
+    pub lags: usize,
+    /// Negative lags
+    pub leads: usize,
+    /// Lags over the limit
+    pub over_limit: usize,
+}
+
+/// Relationship with a lag or lead
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Lag {
+    pub task_pred_id: u64,
+    /// Activity ID of the successor, its `task_id` if not in the file
+    pub task_code: String,
+    pub task_name: String,
+    /// Activity ID of the predecessor
+    pub predecessor: String,
+    pub link_type: LinkType,
+    /// Hours, negative for leads
+    pub lag: f64,
+    pub over_limit: bool,
+}
+
+const LINK_TYPES: [LinkType; 4] = [
+    LinkType::FinishToStart,
+    LinkType::StartToStart,
+    LinkType::FinishToFinish,
+    LinkType::StartToFinish,
+];
+
+impl XerFile {
+    /// Lag usage, lags over `max_lag` hours are flagged, see
+    /// [`lags`](crate::lags)
+    pub fn lag_analysis(&self, max_lag: Option<f64>) -> Result<LagAnalysis, ParseError> {
+        let tasks: HashMap<u64, (String, String)> = self
+            .tasks()?
+            .into_iter()
+            .map(|task| (task.task_id, (task.task_code, task.task_name)))
+            .collect();
+        let code = |id: u64| {
+            tasks
+                .get(&id)
+                .map_or_else(|| id.to_string(), |(code, _)| code.clone())
+        };
+
+        let mut summary: Vec<LinkTypeLags> = LINK_TYPES
+            .into_iter()
+            .map(|link_type| LinkTypeLags {
+                link_type,
+                relationships: 0,
+                lags: 0,
+                leads: 0,
+                over_limit: 0,
+            })
+            .collect();
+        let mut lags = Vec::new();
+        for link in self.relationships()? {
+            let over_limit = max_lag.is_some_and(|max| link.lag > max);
+            let i = LINK_TYPES.iter().position(|t| *t == link.link_type);
+            let counts = &mut summary[i.unwrap()];
+            counts.relationships += 1;
+            counts.lags += usize::from(link.lag > 0.0);
+            counts.leads += usize::from(link.lag < 0.0);
+            counts.over_limit += usize::from(over_limit);
+            if link.lag != 0.0 {
+                lags.push(Lag {
+                    task_pred_id: link.task_pred_id,
+                    task_code: code(link.task_id),
+                    task_name: tasks
+                        .get(&link.task_id)
+                        .map(|(_, name)| name.clone())
+                        .unwrap_or_default(),
+                    predecessor: code(link.pred_task_id),
+                    link_type: link.link_type,
+                    lag: link.lag,
+                    over_limit,
+                });
+            }
+        }
+        Ok(LagAnalysis { summary, lags })
+    }
+}
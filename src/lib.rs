@@ -0,0 +1,69 @@
+//! Parser for Primavera P6 `.xer` schedule exports
+
+#[cfg(feature = "arrow")]
+pub mod arrow;
+#[cfg(feature = "tokio")]
+pub mod async_reader;
+pub mod check;
+pub mod compare;
+pub mod constraints;
+pub mod cpm;
+pub mod dcma;
+pub mod de;
+pub mod deprogress;
+pub mod dot;
+pub mod encoding;
+pub mod error;
+pub mod evm;
+pub mod exchange;
+pub mod filter;
+pub mod gantt;
+pub mod graph;
+pub mod header;
+pub mod health;
+pub mod histogram;
+pub mod ics;
+pub mod intern;
+pub mod keys;
+pub mod lags;
+pub mod merge;
+pub mod mermaid;
+pub mod mmap;
+pub mod model;
+pub mod mspdi;
+pub mod options;
+#[cfg(feature = "parallel")]
+pub mod parallel;
+pub mod parser;
+#[cfg(feature = "pmxml")]
+pub mod pmxml;
+#[cfg(feature = "polars")]
+pub mod polars;
+pub mod projection;
+pub mod renumber;
+pub mod schema;
+pub mod scrub;
+pub mod shift;
+pub mod slice;
+pub mod slippage;
+pub mod sort;
+pub mod split;
+#[cfg(test)]
+mod testing;
+pub mod tree;
+pub mod writer;
+pub mod xer;
+mod xml;
+
+#[cfg(feature = "tokio")]
+pub use async_reader::AsyncXerReader;
+pub use encoding::DecodeReader;
+pub use error::ParseError;
+pub use header::Header;
+pub use mmap::MmapReader;
+pub use model::FromRow;
+pub use options::{ParseOptions, UnknownRecords, Warning};
+pub use parser::{Row, Table, TableIterator, TableReader, XerReader};
+pub use slice::{RowSlice, TableSlice, XerSlice};
+pub use writer::XerWriter;
+pub use xer::XerFile;
@@ -0,0 +1,292 @@
+//! Combination of several files into one, e.g. for portfolio exports
+
+use std::collections::{HashMap, HashSet};
+
+use crate::keys::{foreign_table, primary_key, udf_tables};
+use crate::parser::Table;
+use crate::xer::XerFile;
+
+/// Columns identifying the same dictionary entry in different files, e.g. a
+/// resource by its short name
+const NATURAL_KEYS: &[(&str, &[&str])] = &[
+    ("ACCOUNT", &["acct_short_name"]),
+    ("ACTVCODE", &["actv_code_type_id", "short_name"]),
+    ("ACTVTYPE", &["actv_code_type"]),
+    ("CALENDAR", &["clndr_name"]),
+    ("COSTTYPE", &["cost_type"]),
+    ("CURRTYPE", &["curr_short_name"]),
+    ("FINDATES", &["start_date", "end_date"]),
+    ("FINTMPL", &["fintmpl_name"]),
+    ("MEMOTYPE", &["memo_type"]),
+    ("OBS", &["obs_name"]),
+    ("PCATTYPE", &["proj_catg_type"]),
+    ("PCATVAL", &["proj_catg_type_id", "proj_catg_short_name"]),
+    ("RCATTYPE", &["rsrc_catg_type"]),
+    ("RCATVAL", &["rsrc_catg_type_id", "rsrc_catg_short_name"]),
+    ("ROLERATE", &["role_id", "start_date"]),
+    ("ROLES", &["role_short_name"]),
+    ("RSRC", &["rsrc_short_name"]),
+    ("RSRCRATE", &["rsrc_id", "start_date"]),
+    ("SHIFT", &["shift_name"]),
+    ("UDFTYPE", &["table_name", "udf_type_label"]),
+    ("UMEASURE", &["unit_abbrev"]),
+];
+
+/// Dictionaries with project specific entries, only entries without
+/// `proj_id` are shared
+const GLOBAL_ONLY: &[&str] = &["ACTVTYPE", "CALENDAR"];
+
+impl XerFile {
+    /// Add all tables and rows of `other`, keeping the header of `self`
+    ///
+    /// Dictionary entries both files have, e.g. resources with the same short
+    /// name, calendars with the same name or UDF types with the same label,
+    /// are taken from `self`. Rows of `other` whose id is already taken get
+    /// the next free id, references to them are rewritten. Columns missing in
+    /// `self` are added.
+    ///
+    /// ## Example
+    /// ```
+    /// use schedule_parser::XerFile;
+    ///
+    /// let a = "ERMHDR\t19.12\t2024-03-15\n%T\tRSRC\n%F\trsrc_id\trsrc_short_name\n%R\t1\tENG\n\
+    ///     %T\tTASKRSRC\n%F\ttaskrsrc_id\trsrc_id\n%R\t1\t1\n%E\n";
+    /// let b = "ERMHDR\t19.12\t2024-03-15\n%T\tRSRC\n%F\trsrc_id\trsrc_short_name\n%R\t1\tCRANE\n%R\t2\tENG\n\
+    ///     %T\tTASKRSRC\n%F\ttaskrsrc_id\trsrc_id\n%R\t1\t1\n%R\t2\t2\n%E\n";
+    /// let mut xer = XerFile::parse(a.as_bytes()).unwrap();
+    /// xer.merge(&XerFile::parse(b.as_bytes()).unwrap());
+    ///
+    /// assert_eq!(xer.table("RSRC").unwrap().rows, [["1", "ENG"], ["2", "CRANE"]]);
+    /// // id 1 is taken, 2 is kept
+    /// assert_eq!(xer.table("TASKRSRC").unwrap().rows, [["1", "1"], ["3", "2"], ["2", "1"]]);
+    /// ```
+    pub fn merge(&mut self, other: &XerFile) {
+        // new id by id in `other`, by table
+        let mut ids: HashMap<&str, HashMap<String, String>> = HashMap::new();
+        // rows of `other` in the columns of `self`, references not rewritten yet
+        let mut added = Vec::new();
+
+        for (name, table) in &other.tables {
+            let target = self
+                .tables
+                .entry(name.clone())
+                .or_insert_with(|| Table::new(name.clone(), table.header.clone(), Vec::new()));
+            add_columns(target, &table.header);
+            let positions: Vec<usize> = table
+                .header
+                .iter()
+                .filter_map(|column| target.column_index(column))
+                .collect();
+            let width = target.header.len();
+
+            let key = primary_key(name).and_then(|key| target.column_index(key));
+            let natural = natural_key(target);
+            let mut known: HashMap<Vec<String>, String> = HashMap::new();
+            let mut taken: HashSet<String> = HashSet::new();
+            if let Some(key) = key {
+                // references of `self` are not mapped
+                let unmapped = HashMap::new();
+                for row in &target.rows {
+                    let id = row.get(key).cloned().unwrap_or_default();
+                    if let Some(value) = natural.as_ref().and_then(|n| n.value(row, &unmapped)) {
+                        known.insert(value, id.clone());
+                    }
+                    taken.insert(id);
+                }
+            }
+            // rows with the entry of `self` they are the same as, if any
+            let candidates: Vec<(Vec<String>, Option<String>)> = table
+                .rows
+                .iter()
+                .map(|values| {
+                    let mut row = vec![String::new(); width];
+                    for (value, &i) in values.iter().zip(&positions) {
+                        row[i] = value.clone();
+                    }
+                    let existing = key
+                        .and(natural.as_ref())
+                        .and_then(|n| n.value(&row, &ids))
+                        .and_then(|value| known.get(&value).cloned());
+                    (row, existing)
+                })
+                .collect();
+            // above the ids of both files, so no new id is one kept later
+            let kept = key.into_iter().flat_map(|key| {
+                candidates
+                    .iter()
+                    .filter(|(_, existing)| existing.is_none())
+                    .map(move |(row, _)| &row[key])
+            });
+            let mut next = taken
+                .iter()
+                .chain(kept)
+                .filter_map(|id| id.parse::<u64>().ok())
+                .max();
+
+            let mut rows = Vec::new();
+            for (mut row, existing) in candidates {
+                if let Some(key) = key {
+                    let id = row[key].clone();
+                    if let Some(existing) = existing {
+                        ids.entry(name).or_default().insert(id, existing);
+                        continue;
+                    }
+                    if taken.contains(&id) && !id.is_empty() {
+                        let free = next.map_or(1, |n| n + 1);
+                        next = Some(free);
+                        row[key] = free.to_string();
+                        ids.entry(name).or_default().insert(id, row[key].clone());
+                    }
+                    taken.insert(row[key].clone());
+                }
+                rows.push(row);
+            }
+            added.push((name.as_str(), rows));
+        }
+
+        // UDF values refer by `fk_id` to the table their type is for
+        let udf_tables = udf_tables(other.table("UDFTYPE"));
+
+        for (name, rows) in added {
+            let target = &mut self.tables[name];
+            let references: Vec<(usize, &HashMap<String, String>)> = target
+                .header
+                .iter()
+                .enumerate()
+                .filter_map(|(i, column)| Some((i, ids.get(foreign_table(name, column)?)?)))
+                .collect();
+            let udf = (name == "UDFVALUE")
+                .then(|| {
+                    target
+                        .column_index("udf_type_id")
+                        .zip(target.column_index("fk_id"))
+                })
+                .flatten();
+            // link tables have no id, rows both files have are added once
+            let mut existing: HashSet<Vec<String>> = match primary_key(name) {
+                Some(_) => HashSet::new(),
+                None => target.rows.iter().cloned().collect(),
+            };
+            for mut row in rows {
+                if let Some((kind, fk)) = udf {
+                    let map = udf_tables
+                        .get(row[kind].as_str())
+                        .and_then(|table| ids.get(table));
+                    if let Some(new) = map.and_then(|map| map.get(&row[fk])) {
+                        row[fk] = new.clone();
+                    }
+                }
+                for (i, map) in &references {
+                    if let Some(new) = map.get(&row[*i]) {
+                        row[*i] = new.clone();
+                    }
+                }
+                if primary_key(name).is_none() && !existing.insert(row.clone()) {
+                    continue;
+                }
+                target.rows.push(row);
+            }
+        }
+    }
+}
+
+/// Natural key of a dictionary table with the positions of its columns
+struct NaturalKey {
+    table: &'static str,
+    columns: Vec<(usize, &'static str)>,
+    /// Position of `proj_id` for [`GLOBAL_ONLY`] tables
+    project: Option<usize>,
+}
+
+fn natural_key(table: &Table) -> Option<NaturalKey> {
+    let (name, columns) = NATURAL_KEYS.iter().find(|(name, _)| *name == table.name)?;
+    let columns = columns
+        .iter()
+        .map(|column| Some((table.column_index(column)?, *column)))
+        .collect::<Option<_>>()?;
+    let project = GLOBAL_ONLY
+        .contains(name)
+        .then(|| table.column_index("proj_id"))
+        .flatten();
+    Some(NaturalKey {
+        table: name,
+        columns,
+        project,
+    })
+}
+
+impl NaturalKey {
+    /// Key of `row` with references already mapped by `ids`, `None` for project entries
+    fn value(
+        &self,
+        row: &[String],
+        ids: &HashMap<&str, HashMap<String, String>>,
+    ) -> Option<Vec<String>> {
+        if self
+            .project
+            .is_some_and(|i| row.get(i).is_some_and(|id| !id.is_empty()))
+        {
+            return None;
+        }
+        let value = self
+            .columns
+            .iter()
+            .map(|&(i, column)| {
+                let value = row.get(i).cloned().unwrap_or_default();
+                foreign_table(self.table, column)
+                    .and_then(|table| ids.get(table)?.get(&value).cloned())
+                    .unwrap_or(value)
+            })
+            .collect();
+        Some(value)
+    }
+}
+
+/// Append columns of `header` missing in `table`
+fn add_columns(table: &mut Table, header: &[String]) {
+    let missing: Vec<&String> = header
+        .iter()
+        .filter(|column| table.column_index(column).is_none())
+        .collect();
+    if missing.is_empty() {
+        return;
+    }
+    let mut columns = table.header.clone();
+    columns.extend(missing.into_iter().cloned());
+    let mut merged = Table::new(table.name.clone(), columns, std::mem::take(&mut table.rows));
+    merged.truncated = table.truncated;
+    *table = merged;
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::{column, parse};
+
+    #[test]
+    fn new_ids_skip_ids_kept_from_other() {
+        let mut xer = parse("%T\tTASK\n%F\ttask_id\n%R\t1\n%R\t2\n");
+        xer.merge(&parse(
+            "%T\tTASK\n%F\ttask_id\n%R\t3\n%R\t2\n\
+             %T\tTASKPRED\n%F\ttask_pred_id\ttask_id\tpred_task_id\n%R\t1\t3\t2\n",
+        ));
+        assert_eq!(column(&xer, "TASK", "task_id"), ["1", "2", "3", "4"]);
+        assert_eq!(column(&xer, "TASKPRED", "task_id"), ["3"]);
+        assert_eq!(column(&xer, "TASKPRED", "pred_task_id"), ["4"]);
+    }
+
+    #[test]
+    fn dictionary_entries_are_shared_and_references_follow() {
+        let mut xer = parse(
+            "%T\tCALENDAR\n%F\tclndr_id\tclndr_name\tproj_id\n\
+             %R\t7\tStandard\t\n",
+        );
+        xer.merge(&parse(
+            "%T\tCALENDAR\n%F\tclndr_id\tclndr_name\tproj_id\n\
+             %R\t7\tNights\t\n%R\t8\tStandard\t\n%R\t9\tStandard\t100\n\
+             %T\tTASK\n%F\ttask_id\tclndr_id\n%R\t1\t7\n%R\t2\t8\n%R\t3\t9\n",
+        ));
+        // the project calendar named like a global one is not shared
+        assert_eq!(column(&xer, "CALENDAR", "clndr_id"), ["7", "10", "9"]);
+        assert_eq!(column(&xer, "TASK", "clndr_id"), ["10", "7", "9"]);
+    }
+}
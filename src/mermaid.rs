@@ -0,0 +1,230 @@
+//! Mermaid Gantt export for Markdown documents
+//!
+//! [`XerFile::to_mermaid_gantt`] writes a `gantt` diagram with one section
+//! per WBS element and one bar per activity, milestones as diamonds,
+//! completed activities as done, activities in progress as active and
+//! critical ones as crit. Dates are chosen like in the
+//! [iCalendar export](crate::ics). WBS summaries and activities without
+//! dates are left out.
+//!
+//! Mermaid gets slow and unreadable with hundreds of bars, so only the
+//! [`GanttOptions::max_activities`] starting first are drawn, the title and a
+//! comment tell how many were left out. Names are cut to
+//! [`GanttOptions::max_name_len`] characters and stripped of the characters
+//! Mermaid reads as syntax.
+//!
+//! ## Example
+//! ```
+//! use schedule_parser::mermaid::GanttOptions;
+//! use schedule_parser::XerFile;
+//!
+//! let data = "ERMHDR\t19.12\t2024-03-15\n\
+//!     %T\tPROJECT\n%F\tproj_id\tproj_short_name\tcritical_drtn_hr_cnt\n%R\t1\tP1\t0\n\
+//!     %T\tPROJWBS\n%F\twbs_id\tproj_id\tparent_wbs_id\twbs_short_name\twbs_name\tproj_node_flag\n\
+//!     %R\t1\t1\t\tP1\tPlant\tY\n%R\t2\t1\t1\tCIV\tCivil\tN\n\
+//!     %T\tTASK\n%F\ttask_id\tproj_id\twbs_id\ttask_code\ttask_name\ttask_type\tstatus_code\t\
+//!     early_start_date\tearly_end_date\ttotal_float_hr_cnt\n\
+//!     %R\t1\t1\t2\tA1000\tFoundations: pour\tTT_Task\tTK_NotStart\t2024-03-18 08:00\t2024-03-22 17:00\t0\n\
+//!     %R\t2\t1\t2\tM1000\tHandover\tTT_FinMile\tTK_NotStart\t\t2024-03-22 17:00\t40\n%E\n";
+//! let xer = XerFile::parse(data.as_bytes()).unwrap();
+//!
+//! let gantt = xer.to_mermaid_gantt(&GanttOptions::default()).unwrap();
+//! assert!(gantt.starts_with("gantt\n"));
+//! assert!(gantt.contains("    section P1.CIV Civil\n"));
+//! assert!(gantt.contains("    A1000 Foundations pour :crit, t1, 2024-03-18 08:00, 2024-03-22 17:00\n"));
+//! assert!(gantt.contains("    M1000 Handover :milestone, t2, 2024-03-22 17:00, 0d\n"));
+//!
+//! let options = GanttOptions { max_activities: 1, ..GanttOptions::default() };
+//! let gantt = xer.to_mermaid_gantt(&options).unwrap();
+//! assert!(gantt.contains("    %% 1 more activities left out\n"));
+//! assert!(!gantt.contains("M1000"));
+//! ```
+
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write as _;
+
+use chrono::NaiveDateTime;
+
+use crate::error::ParseError;
+use crate::ics::dates;
+use crate::model::{Task, TaskStatus, TaskType};
+use crate::xer::XerFile;
+
+/// Selection and limits of [`XerFile::to_mermaid_gantt`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct GanttOptions {
+    /// Only activities of the WBS element with this `wbs_id` and below
+    pub wbs_id: Option<u64>,
+    /// Only critical activities
+    pub critical: bool,
+    /// Only milestones
+    pub milestones: bool,
+    /// Most activities drawn, [`Default`] 100
+    pub max_activities: usize,
+    /// Most characters of activity and WBS names, [`Default`] 50
+    pub max_name_len: usize,
+}
+
+impl Default for GanttOptions {
+    fn default() -> Self {
+        Self {
+            wbs_id: None,
+            critical: false,
+            milestones: false,
+            max_activities: 100,
+            max_name_len: 50,
+        }
+    }
+}
+
+impl XerFile {
+    /// Mermaid `gantt` diagram of the activities selected by `options`, see
+    /// [`mermaid`](crate::mermaid)
+    pub fn to_mermaid_gantt(&self, options: &GanttOptions) -> Result<String, ParseError> {
+        let projects: HashMap<u64, _> = self
+            .projects()?
+            .into_iter()
+            .map(|project| (project.proj_id, project))
+            .collect();
+        let wbs = self.wbs_tree()?;
+        let in_wbs: Option<HashSet<u64>> = options.wbs_id.map(|wbs_id| {
+            wbs.subtree(wbs_id)
+                .map(|(_, element)| element.wbs_id)
+                .collect()
+        });
+
+        let tasks = self.tasks()?;
+        let mut selected: Vec<(&Task, NaiveDateTime, NaiveDateTime, bool)> = tasks
+            .iter()
+            .filter(|task| task.task_type != TaskType::WbsSummary)
+            .filter(|task| !options.milestones || task.is_milestone())
+            .filter(|task| in_wbs.as_ref().is_none_or(|ids| ids.contains(&task.wbs_id)))
+            .filter_map(|task| {
+                let critical = projects
+                    .get(&task.proj_id)
+                    .is_some_and(|project| project.is_critical(task));
+                let (start, finish) = dates(task)?;
+                Some((task, start, finish, critical))
+            })
+            .filter(|&(_, _, _, critical)| !options.critical || critical)
+            .collect();
+        selected.sort_by_key(|&(task, start, finish, _)| (start, finish, task.task_code.as_str()));
+        let left_out = selected.len().saturating_sub(options.max_activities);
+        selected.truncate(options.max_activities);
+
+        let mut codes: HashMap<u64, String> = HashMap::new();
+        for (_, element) in wbs.preorder() {
+            let code = match element.parent_wbs_id.and_then(|id| codes.get(&id)) {
+                Some(parent) => format!("{parent}.{}", element.wbs_short_name),
+                None => element.wbs_short_name.clone(),
+            };
+            codes.insert(element.wbs_id, code);
+        }
+        let mut title = match options.wbs_id.and_then(|id| wbs.get(id)) {
+            Some(element) => format!("{} {}", codes[&element.wbs_id], element.wbs_name),
+            None => {
+                let mut names: Vec<&str> = projects
+                    .values()
+                    .map(|project| project.proj_short_name.as_str())
+                    .collect();
+                names.sort_unstable();
+                names.join(", ")
+            }
+        };
+        if left_out > 0 {
+            let total = selected.len() + left_out;
+            let _ = write!(title, " (first {} of {total} activities)", selected.len());
+        }
+
+        let mut gantt = String::from("gantt\n");
+        if !title.trim().is_empty() {
+            let _ = writeln!(gantt, "    title {}", text(&title, usize::MAX));
+        }
+        gantt.push_str("    dateFormat YYYY-MM-DD HH:mm\n");
+        gantt.push_str("    axisFormat %Y-%m-%d\n");
+        if left_out > 0 {
+            let _ = writeln!(gantt, "    %% {left_out} more activities left out");
+        }
+
+        // sections in WBS order, activities of elements not in the tree last
+        let mut sections: Vec<(Option<u64>, Vec<_>)> = wbs
+            .preorder()
+            .map(|(_, element)| (Some(element.wbs_id), Vec::new()))
+            .collect();
+        sections.push((None, Vec::new()));
+        let index: HashMap<Option<u64>, usize> = sections
+            .iter()
+            .enumerate()
+            .map(|(i, (wbs_id, _))| (*wbs_id, i))
+            .collect();
+        for item in selected {
+            let section = wbs.get(item.0.wbs_id).map(|element| element.wbs_id);
+            sections[index[&section]].1.push(item);
+        }
+        for (wbs_id, tasks) in sections {
+            if tasks.is_empty() {
+                continue;
+            }
+            let section = match wbs_id.and_then(|id| wbs.get(id)) {
+                Some(element) => format!("{} {}", codes[&element.wbs_id], element.wbs_name),
+                None => "Other".to_string(),
+            };
+            let _ = writeln!(
+                gantt,
+                "    section {}",
+                text(&section, options.max_name_len)
+            );
+            for (task, start, finish, critical) in tasks {
+                let mut tags = Vec::new();
+                if critical {
+                    tags.push("crit");
+                }
+                match task.status {
+                    TaskStatus::Completed => tags.push("done"),
+                    TaskStatus::InProgress => tags.push("active"),
+                    TaskStatus::NotStarted => {}
+                }
+                let end = if task.is_milestone() {
+                    tags.push("milestone");
+                    "0d".to_string()
+                } else {
+                    date(finish)
+                };
+                let _ = writeln!(
+                    gantt,
+                    "    {} {} :{}t{}, {}, {end}",
+                    text(&task.task_code, usize::MAX),
+                    text(&task.task_name, options.max_name_len),
+                    tags.iter()
+                        .map(|tag| format!("{tag}, "))
+                        .collect::<String>(),
+                    task.task_id,
+                    date(start),
+                );
+            }
+        }
+        Ok(gantt)
+    }
+}
+
+fn date(date: NaiveDateTime) -> String {
+    date.format("%Y-%m-%d %H:%M").to_string()
+}
+
+/// `value` on one line without `:`, `;` and `#`, cut to `max_len` characters
+fn text(value: &str, max_len: usize) -> String {
+    let cleaned: String = value
+        .chars()
+        .map(|c| match c {
+            ':' | ';' | '#' | '\n' | '\r' | '\t' => ' ',
+            c => c,
+        })
+        .collect();
+    let cleaned = cleaned.split_whitespace().collect::<Vec<_>>().join(" ");
+    if cleaned.chars().count() <= max_len {
+        return cleaned;
+    }
+    let mut cut: String = cleaned.chars().take(max_len.saturating_sub(1)).collect();
+    cut.push('…');
+    cut
+}
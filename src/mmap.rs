@@ -0,0 +1,93 @@
+//! Memory mapped input for huge files
+
+use std::fs::File;
+use std::io::{self, BufRead, Read, Seek, SeekFrom};
+use std::path::Path;
+
+use memmap2::Mmap;
+
+use crate::error::ParseError;
+use crate::parser::XerReader;
+use crate::slice::XerSlice;
+
+/// File mapped into memory, read straight from the page cache
+///
+/// Works as a [`BufRead`] source for [`XerReader`] without copying through
+/// an intermediate buffer, or as text for zero-copy [`XerSlice`] parsing.
+///
+/// The file must not be modified by other processes while it is mapped.
+///
+/// ## Example
+/// ```no_run
+/// use schedule_parser::MmapReader;
+///
+/// let map = MmapReader::open("./data/schedule.xer").unwrap();
+/// let xer = map.slice().unwrap();
+/// println!("{} tables", xer.tables.len());
+/// ```
+pub struct MmapReader {
+    map: Mmap,
+    pos: usize,
+}
+
+impl MmapReader {
+    /// Map file at `path`
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let file = File::open(path)?;
+        // SAFETY: mapping is read only, concurrent modification is excluded
+        // by contract in the type docs
+        let map = unsafe { Mmap::map(&file)? };
+        Ok(Self { map, pos: 0 })
+    }
+
+    /// Whole file content
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.map
+    }
+
+    /// Parse whole file borrowing values from the mapping
+    pub fn slice(&self) -> Result<XerSlice<'_>, ParseError> {
+        XerSlice::parse_bytes(&self.map)
+    }
+}
+
+impl Read for MmapReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.fill_buf()?.read(buf)?;
+        self.consume(n);
+        Ok(n)
+    }
+}
+
+impl BufRead for MmapReader {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        Ok(&self.map[self.pos..])
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.pos = (self.pos + amt).min(self.map.len());
+    }
+}
+
+impl Seek for MmapReader {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let target = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::End(offset) => (self.map.len() as u64).checked_add_signed(offset),
+            SeekFrom::Current(offset) => (self.pos as u64).checked_add_signed(offset),
+        };
+        let target = target.ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidInput, "seek before start of file")
+        })?;
+        self.pos = target.min(self.map.len() as u64) as usize;
+        Ok(target)
+    }
+}
+
+impl XerReader<MmapReader> {
+    /// Map file at `path` into memory instead of reading it through `BufReader`
+    pub fn mmap<P: AsRef<Path>>(path: P) -> Result<Self, ParseError> {
+        let reader = MmapReader::open(path).map_err(|source| ParseError::Io { line: 0, source })?;
+        Self::from_reader(reader)
+    }
+}
@@ -0,0 +1,97 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::{Assignments, Costs, Currency, Expenses, Fields, FromRow};
+use crate::error::ParseError;
+use crate::parser::Table;
+use crate::tree::{Tree, TreeNode};
+
+/// Cost account from the `ACCOUNT` table
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Account {
+    pub acct_id: u64,
+    pub parent_acct_id: Option<u64>,
+    pub acct_name: String,
+    /// Cost account ID shown to users
+    pub acct_short_name: String,
+    /// `acct_seq_num`
+    pub seq_num: i64,
+}
+
+impl FromRow for Account {
+    const TABLE: &'static str = "ACCOUNT";
+
+    fn from_row(table: &Table, row: &[String]) -> Result<Self, ParseError> {
+        let f = Fields::new(table, row);
+        Ok(Self {
+            acct_id: f.parse("acct_id")?,
+            parent_acct_id: f.opt_parse("parent_acct_id")?,
+            acct_name: f.string("acct_name")?,
+            acct_short_name: f.string("acct_short_name")?,
+            seq_num: f.opt_parse("acct_seq_num")?.unwrap_or_default(),
+        })
+    }
+}
+
+impl TreeNode for Account {
+    fn id(&self) -> u64 {
+        self.acct_id
+    }
+
+    fn parent_id(&self) -> Option<u64> {
+        self.parent_acct_id
+    }
+
+    fn seq(&self) -> i64 {
+        self.seq_num
+    }
+}
+
+/// Cost account hierarchy
+pub type AccountTree = Tree<Account>;
+
+impl AccountTree {
+    /// Short names from the root down to `acct_id` joined with `.`, e.g. `PC.CON.MAT`
+    pub fn path(&self, acct_id: u64) -> Option<String> {
+        self.get(acct_id)?;
+        let mut names: Vec<_> = self
+            .path_to_root(acct_id)
+            .map(|account| account.acct_short_name.as_str())
+            .collect();
+        names.reverse();
+        Some(names.join("."))
+    }
+
+    /// Resource and expense costs per account, including costs of sub accounts
+    ///
+    /// Items without account or with an unknown account are left out.
+    pub fn costs(
+        &self,
+        assignments: &Assignments,
+        expenses: &Expenses,
+        currency: &Currency,
+    ) -> HashMap<u64, Costs> {
+        let mut totals: HashMap<u64, Costs> = HashMap::new();
+        let items = assignments
+            .iter()
+            .map(|item| (item.acct_id, item.costs(currency)))
+            .chain(
+                expenses
+                    .iter()
+                    .map(|item| (item.acct_id, item.costs(currency))),
+            );
+        for (acct_id, costs) in items {
+            let Some(acct_id) = acct_id else {
+                continue;
+            };
+            for account in self.path_to_root(acct_id) {
+                let total = totals
+                    .entry(account.acct_id)
+                    .or_insert_with(|| Costs::zero(&currency.curr_short_name));
+                *total = total.clone() + costs.clone();
+            }
+        }
+        totals
+    }
+}
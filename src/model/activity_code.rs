@@ -0,0 +1,162 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::{Fields, FromRow};
+use crate::error::ParseError;
+use crate::parser::Table;
+
+/// Activity code dictionary from the `ACTVTYPE` table
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActivityCodeType {
+    pub actv_code_type_id: u64,
+    /// Code type name, e.g. `Phase`
+    pub actv_code_type: String,
+    /// Owning project for project level codes
+    pub proj_id: Option<u64>,
+    /// `actv_code_type_scope`, e.g. `AS_Global`, `AS_EPS`, `AS_Project`
+    pub scope: Option<String>,
+    pub seq_num: i64,
+}
+
+impl FromRow for ActivityCodeType {
+    const TABLE: &'static str = "ACTVTYPE";
+
+    fn from_row(table: &Table, row: &[String]) -> Result<Self, ParseError> {
+        let f = Fields::new(table, row);
+        Ok(Self {
+            actv_code_type_id: f.parse("actv_code_type_id")?,
+            actv_code_type: f.string("actv_code_type")?,
+            proj_id: f.opt_parse("proj_id")?,
+            scope: f.opt_string("actv_code_type_scope"),
+            seq_num: f.opt_parse("seq_num")?.unwrap_or_default(),
+        })
+    }
+}
+
+/// Activity code value from the `ACTVCODE` table
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActivityCode {
+    pub actv_code_id: u64,
+    pub parent_actv_code_id: Option<u64>,
+    pub actv_code_type_id: u64,
+    /// Code value shown to users, e.g. `DES`
+    pub short_name: String,
+    /// Description, e.g. `Design phase`
+    pub actv_code_name: String,
+    pub seq_num: i64,
+}
+
+impl FromRow for ActivityCode {
+    const TABLE: &'static str = "ACTVCODE";
+
+    fn from_row(table: &Table, row: &[String]) -> Result<Self, ParseError> {
+        let f = Fields::new(table, row);
+        Ok(Self {
+            actv_code_id: f.parse("actv_code_id")?,
+            parent_actv_code_id: f.opt_parse("parent_actv_code_id")?,
+            actv_code_type_id: f.parse("actv_code_type_id")?,
+            short_name: f.string("short_name")?,
+            actv_code_name: f.string("actv_code_name")?,
+            seq_num: f.opt_parse("seq_num")?.unwrap_or_default(),
+        })
+    }
+}
+
+/// Code assigned to an activity from the `TASKACTV` table
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaskActivityCode {
+    pub task_id: u64,
+    pub actv_code_type_id: u64,
+    pub actv_code_id: u64,
+    pub proj_id: Option<u64>,
+}
+
+impl FromRow for TaskActivityCode {
+    const TABLE: &'static str = "TASKACTV";
+
+    fn from_row(table: &Table, row: &[String]) -> Result<Self, ParseError> {
+        let f = Fields::new(table, row);
+        Ok(Self {
+            task_id: f.parse("task_id")?,
+            actv_code_type_id: f.parse("actv_code_type_id")?,
+            actv_code_id: f.parse("actv_code_id")?,
+            proj_id: f.opt_parse("proj_id")?,
+        })
+    }
+}
+
+/// Code types, values and their assignments joined together
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ActivityCodes {
+    types: HashMap<u64, ActivityCodeType>,
+    codes: HashMap<u64, ActivityCode>,
+    by_task: HashMap<u64, Vec<u64>>,
+}
+
+impl ActivityCodes {
+    pub fn new(
+        types: Vec<ActivityCodeType>,
+        codes: Vec<ActivityCode>,
+        assignments: Vec<TaskActivityCode>,
+    ) -> Self {
+        let mut by_task: HashMap<u64, Vec<u64>> = HashMap::new();
+        for assignment in assignments {
+            by_task
+                .entry(assignment.task_id)
+                .or_default()
+                .push(assignment.actv_code_id);
+        }
+        Self {
+            types: types
+                .into_iter()
+                .map(|t| (t.actv_code_type_id, t))
+                .collect(),
+            codes: codes.into_iter().map(|c| (c.actv_code_id, c)).collect(),
+            by_task,
+        }
+    }
+
+    pub fn code_type(&self, actv_code_type_id: u64) -> Option<&ActivityCodeType> {
+        self.types.get(&actv_code_type_id)
+    }
+
+    pub fn code(&self, actv_code_id: u64) -> Option<&ActivityCode> {
+        self.codes.get(&actv_code_id)
+    }
+
+    /// Code types ordered by `seq_num`
+    pub fn types(&self) -> Vec<&ActivityCodeType> {
+        let mut types: Vec<_> = self.types.values().collect();
+        types.sort_by_key(|t| (t.seq_num, t.actv_code_type_id));
+        types
+    }
+
+    /// Codes of activity `task_id` grouped by code type, types ordered by `seq_num`
+    pub fn for_task(&self, task_id: u64) -> Vec<(&ActivityCodeType, Vec<&ActivityCode>)> {
+        let mut groups: Vec<(&ActivityCodeType, Vec<&ActivityCode>)> = Vec::new();
+        let codes = self.by_task.get(&task_id).map_or(&[][..], Vec::as_slice);
+        for code in codes.iter().filter_map(|id| self.codes.get(id)) {
+            let Some(code_type) = self.types.get(&code.actv_code_type_id) else {
+                continue;
+            };
+            match groups
+                .iter_mut()
+                .find(|(t, _)| t.actv_code_type_id == code_type.actv_code_type_id)
+            {
+                Some((_, group)) => group.push(code),
+                None => groups.push((code_type, vec![code])),
+            }
+        }
+        groups.sort_by_key(|(t, _)| (t.seq_num, t.actv_code_type_id));
+        groups
+    }
+
+    /// Code of activity `task_id` for code type named `type_name`
+    pub fn code_for(&self, task_id: u64, type_name: &str) -> Option<&ActivityCode> {
+        self.for_task(task_id)
+            .into_iter()
+            .find(|(t, _)| t.actv_code_type == type_name)
+            .and_then(|(_, codes)| codes.into_iter().next())
+    }
+}
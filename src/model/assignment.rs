@@ -0,0 +1,186 @@
+use std::collections::HashMap;
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+use super::{Costs, Currency, Expenses, Fields, FromRow};
+use crate::error::ParseError;
+use crate::parser::Table;
+
+/// Resource assignment from the `TASKRSRC` table
+///
+/// Units are hours for labor and nonlabor resources, unit of measure for materials.
+/// Costs are in base currency of the file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Assignment {
+    pub taskrsrc_id: u64,
+    pub task_id: u64,
+    pub proj_id: u64,
+    /// Empty for role only assignments
+    pub rsrc_id: Option<u64>,
+    pub role_id: Option<u64>,
+    /// Cost account
+    pub acct_id: Option<u64>,
+    /// `target_qty`
+    pub budgeted_units: f64,
+    /// `act_reg_qty` + `act_ot_qty`
+    pub actual_units: f64,
+    /// `remain_qty`
+    pub remaining_units: f64,
+    /// `cost_per_qty`
+    pub price_per_unit: f64,
+    /// `target_cost`
+    pub budgeted_cost: f64,
+    /// `act_reg_cost` + `act_ot_cost`
+    pub actual_cost: f64,
+    /// `remain_cost`
+    pub remaining_cost: f64,
+    /// `target_start_date`
+    pub planned_start: Option<NaiveDateTime>,
+    /// `target_end_date`
+    pub planned_finish: Option<NaiveDateTime>,
+    /// `act_start_date`
+    pub actual_start: Option<NaiveDateTime>,
+    /// `act_end_date`
+    pub actual_finish: Option<NaiveDateTime>,
+    /// `restart_date`
+    pub remaining_start: Option<NaiveDateTime>,
+    /// `reend_date`
+    pub remaining_finish: Option<NaiveDateTime>,
+}
+
+impl Assignment {
+    /// Actual plus remaining units
+    pub fn at_completion_units(&self) -> f64 {
+        self.actual_units + self.remaining_units
+    }
+
+    /// Actual plus remaining cost
+    pub fn at_completion_cost(&self) -> f64 {
+        self.actual_cost + self.remaining_cost
+    }
+
+    /// Costs tagged with base `currency`
+    pub fn costs(&self, currency: &Currency) -> Costs {
+        Costs {
+            budgeted: currency.money(self.budgeted_cost),
+            actual: currency.money(self.actual_cost),
+            remaining: currency.money(self.remaining_cost),
+        }
+    }
+}
+
+impl FromRow for Assignment {
+    const TABLE: &'static str = "TASKRSRC";
+
+    fn from_row(table: &Table, row: &[String]) -> Result<Self, ParseError> {
+        let f = Fields::new(table, row);
+        Ok(Self {
+            taskrsrc_id: f.parse("taskrsrc_id")?,
+            task_id: f.parse("task_id")?,
+            proj_id: f.parse("proj_id")?,
+            rsrc_id: f.opt_parse("rsrc_id")?,
+            role_id: f.opt_parse("role_id")?,
+            acct_id: f.opt_parse("acct_id")?,
+            budgeted_units: f.num("target_qty")?,
+            actual_units: f.num("act_reg_qty")? + f.num("act_ot_qty")?,
+            remaining_units: f.num("remain_qty")?,
+            price_per_unit: f.num("cost_per_qty")?,
+            budgeted_cost: f.num("target_cost")?,
+            actual_cost: f.num("act_reg_cost")? + f.num("act_ot_cost")?,
+            remaining_cost: f.num("remain_cost")?,
+            planned_start: f.opt_datetime("target_start_date")?,
+            planned_finish: f.opt_datetime("target_end_date")?,
+            actual_start: f.opt_datetime("act_start_date")?,
+            actual_finish: f.opt_datetime("act_end_date")?,
+            remaining_start: f.opt_datetime("restart_date")?,
+            remaining_finish: f.opt_datetime("reend_date")?,
+        })
+    }
+}
+
+/// Assignments indexed by task and by resource
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Assignments {
+    items: Vec<Assignment>,
+    by_task: HashMap<u64, Vec<usize>>,
+    by_resource: HashMap<u64, Vec<usize>>,
+}
+
+impl Assignments {
+    pub fn new(items: Vec<Assignment>) -> Self {
+        let mut by_task: HashMap<u64, Vec<usize>> = HashMap::new();
+        let mut by_resource: HashMap<u64, Vec<usize>> = HashMap::new();
+        for (i, item) in items.iter().enumerate() {
+            by_task.entry(item.task_id).or_default().push(i);
+            if let Some(rsrc_id) = item.rsrc_id {
+                by_resource.entry(rsrc_id).or_default().push(i);
+            }
+        }
+        Self {
+            items,
+            by_task,
+            by_resource,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Assignment> {
+        self.items.iter()
+    }
+
+    /// Assignments of activity `task_id`
+    pub fn for_task(&self, task_id: u64) -> impl Iterator<Item = &Assignment> {
+        self.lookup(&self.by_task, task_id)
+    }
+
+    /// Assignments of resource `rsrc_id`
+    pub fn for_resource(&self, rsrc_id: u64) -> impl Iterator<Item = &Assignment> {
+        self.lookup(&self.by_resource, rsrc_id)
+    }
+
+    /// Total costs of activity `task_id`
+    pub fn task_costs(&self, task_id: u64, currency: &Currency) -> Costs {
+        total(self.for_task(task_id), currency)
+    }
+
+    /// Total resource and expense costs of activity `task_id`
+    pub fn task_costs_with_expenses(
+        &self,
+        task_id: u64,
+        expenses: &Expenses,
+        currency: &Currency,
+    ) -> Costs {
+        self.task_costs(task_id, currency) + expenses.task_costs(task_id, currency)
+    }
+
+    /// Total costs of resource `rsrc_id`
+    pub fn resource_costs(&self, rsrc_id: u64, currency: &Currency) -> Costs {
+        total(self.for_resource(rsrc_id), currency)
+    }
+
+    fn lookup<'a>(
+        &'a self,
+        map: &'a HashMap<u64, Vec<usize>>,
+        id: u64,
+    ) -> impl Iterator<Item = &'a Assignment> {
+        map.get(&id)
+            .map_or(&[][..], Vec::as_slice)
+            .iter()
+            .map(|&i| &self.items[i])
+    }
+}
+
+fn total<'a>(assignments: impl Iterator<Item = &'a Assignment>, currency: &Currency) -> Costs {
+    Costs::sum(
+        assignments.map(|item| item.costs(currency)),
+        &currency.curr_short_name,
+    )
+}
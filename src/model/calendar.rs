@@ -0,0 +1,560 @@
+use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta, Weekday};
+use serde::{Deserialize, Serialize};
+
+use super::{Fields, FromRow};
+use crate::error::ParseError;
+use crate::parser::Table;
+
+/// Work calendar from the `CALENDAR` table
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Calendar {
+    pub clndr_id: u64,
+    pub clndr_name: String,
+    /// Owning project for project calendars
+    pub proj_id: Option<u64>,
+    pub base_clndr_id: Option<u64>,
+    /// `default_flag`
+    pub default: bool,
+    /// `clndr_type`, e.g. `CA_Base`, `CA_Project`, `CA_Rsrc`
+    pub clndr_type: Option<String>,
+    /// `day_hr_cnt`, hours per day used to convert durations to days
+    pub day_hours: f64,
+    /// `week_hr_cnt`
+    pub week_hours: f64,
+    /// Standard work week from `clndr_data`, index `0` is Monday
+    pub week: [Vec<WorkPeriod>; 7],
+    /// Dates overriding standard week, sorted by date
+    pub exceptions: Vec<Exception>,
+}
+
+impl Calendar {
+    /// Standard work periods of `weekday`
+    pub fn weekday(&self, weekday: Weekday) -> &[WorkPeriod] {
+        &self.week[weekday.num_days_from_monday() as usize]
+    }
+
+    /// Work periods of `date` taking exceptions into account
+    pub fn work_periods(&self, date: NaiveDate) -> &[WorkPeriod] {
+        match self.exception(date) {
+            Some(exception) => &exception.periods,
+            None => self.weekday(date.weekday()),
+        }
+    }
+
+    pub fn exception(&self, date: NaiveDate) -> Option<&Exception> {
+        self.exceptions
+            .binary_search_by_key(&date, |exception| exception.date)
+            .ok()
+            .map(|i| &self.exceptions[i])
+    }
+
+    pub fn is_work_day(&self, date: NaiveDate) -> bool {
+        !self.work_periods(date).is_empty()
+    }
+
+    /// Work hours of `date`
+    pub fn work_hours(&self, date: NaiveDate) -> f64 {
+        self.work_periods(date)
+            .iter()
+            .fold(0.0, |hours, period| hours + period.hours())
+    }
+
+    /// Time `hours` of work after `time`, before it for negative `hours`
+    ///
+    /// Calendars without work in their standard week count elapsed time,
+    /// like the 24 hour calendar P6 offers for lags.
+    pub fn add_work_hours(&self, time: NaiveDateTime, hours: f64) -> NaiveDateTime {
+        let mut remaining = (hours * 60.0).round() as i64;
+        if remaining < 0 {
+            return self.subtract_minutes(time, -remaining);
+        }
+        if remaining == 0 {
+            return time;
+        }
+        if !self.has_work() {
+            return time + TimeDelta::minutes(remaining);
+        }
+        // periods of the day before may run past midnight
+        let mut date = time.date().pred_opt().unwrap_or(time.date());
+        loop {
+            for (start, finish) in self.intervals(date) {
+                if finish <= time {
+                    continue;
+                }
+                let begin = start.max(time);
+                let available = (finish - begin).num_minutes();
+                if remaining <= available {
+                    return begin + TimeDelta::minutes(remaining);
+                }
+                remaining -= available;
+            }
+            let Some(next) = date.succ_opt() else {
+                return time;
+            };
+            date = next;
+        }
+    }
+
+    /// Time `hours` of work before `time`
+    pub fn subtract_work_hours(&self, time: NaiveDateTime, hours: f64) -> NaiveDateTime {
+        self.add_work_hours(time, -hours)
+    }
+
+    fn subtract_minutes(&self, time: NaiveDateTime, mut remaining: i64) -> NaiveDateTime {
+        if !self.has_work() {
+            return time - TimeDelta::minutes(remaining);
+        }
+        let mut date = time.date();
+        loop {
+            let intervals: Vec<_> = self.intervals(date).collect();
+            for &(start, finish) in intervals.iter().rev() {
+                if start >= time {
+                    continue;
+                }
+                let end = finish.min(time);
+                let available = (end - start).num_minutes();
+                if remaining <= available {
+                    return end - TimeDelta::minutes(remaining);
+                }
+                remaining -= available;
+            }
+            let Some(previous) = date.pred_opt() else {
+                return time;
+            };
+            date = previous;
+        }
+    }
+
+    /// `time` if it is within work time, else the start of the next work period
+    pub fn next_work_time(&self, time: NaiveDateTime) -> NaiveDateTime {
+        if !self.has_work() {
+            return time;
+        }
+        let mut date = time.date().pred_opt().unwrap_or(time.date());
+        loop {
+            if let Some((start, _)) = self.intervals(date).find(|(_, finish)| *finish > time) {
+                return start.max(time);
+            }
+            let Some(next) = date.succ_opt() else {
+                return time;
+            };
+            date = next;
+        }
+    }
+
+    /// `time` if it is within or at the end of work time, else the finish of
+    /// the previous work period
+    pub fn previous_work_time(&self, time: NaiveDateTime) -> NaiveDateTime {
+        if !self.has_work() {
+            return time;
+        }
+        let mut date = time.date();
+        loop {
+            let last = self
+                .intervals(date)
+                .filter(|(start, _)| *start < time)
+                .last();
+            if let Some((_, finish)) = last {
+                return finish.min(time);
+            }
+            let Some(previous) = date.pred_opt() else {
+                return time;
+            };
+            date = previous;
+        }
+    }
+
+    /// Work hours from `from` to `to`, negative if `to` is earlier
+    pub fn work_hours_between(&self, from: NaiveDateTime, to: NaiveDateTime) -> f64 {
+        if to < from {
+            return -self.work_hours_between(to, from);
+        }
+        if !self.has_work() {
+            return (to - from).num_minutes() as f64 / 60.0;
+        }
+        let mut minutes = 0;
+        let mut date = from.date().pred_opt().unwrap_or(from.date());
+        while date <= to.date() {
+            for (start, finish) in self.intervals(date) {
+                let overlap = (finish.min(to) - start.max(from)).num_minutes();
+                minutes += overlap.max(0);
+            }
+            let Some(next) = date.succ_opt() else {
+                break;
+            };
+            date = next;
+        }
+        minutes as f64 / 60.0
+    }
+
+    /// Standard week has work, otherwise searching for work time never ends
+    fn has_work(&self) -> bool {
+        self.week.iter().any(|periods| !periods.is_empty())
+    }
+
+    /// Work periods of `date` as start and finish time, in order
+    fn intervals(
+        &self,
+        date: NaiveDate,
+    ) -> impl Iterator<Item = (NaiveDateTime, NaiveDateTime)> + '_ {
+        self.work_periods(date).iter().map(move |period| {
+            let start = date.and_time(period.start);
+            (start, start + period.duration())
+        })
+    }
+}
+
+impl FromRow for Calendar {
+    const TABLE: &'static str = "CALENDAR";
+
+    fn from_row(table: &Table, row: &[String]) -> Result<Self, ParseError> {
+        let f = Fields::new(table, row);
+        let (week, exceptions) = match f.opt_str("clndr_data") {
+            Some(data) => parse_clndr_data(data).ok_or_else(|| ParseError::InvalidValue {
+                table: table.name.clone(),
+                column: "clndr_data".into(),
+                value: data.into(),
+            })?,
+            None => Default::default(),
+        };
+        Ok(Self {
+            clndr_id: f.parse("clndr_id")?,
+            clndr_name: f.string("clndr_name")?,
+            proj_id: f.opt_parse("proj_id")?,
+            base_clndr_id: f.opt_parse("base_clndr_id")?,
+            default: f.flag("default_flag")?,
+            clndr_type: f.opt_string("clndr_type"),
+            day_hours: f.num("day_hr_cnt")?,
+            week_hours: f.num("week_hr_cnt")?,
+            week,
+            exceptions,
+        })
+    }
+}
+
+/// Continuous work interval within a day
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorkPeriod {
+    pub start: NaiveTime,
+    /// `00:00` finish means midnight at the end of the day
+    pub finish: NaiveTime,
+}
+
+impl WorkPeriod {
+    pub fn duration(&self) -> TimeDelta {
+        let duration = self.finish - self.start;
+        if duration <= TimeDelta::zero() {
+            duration + TimeDelta::days(1)
+        } else {
+            duration
+        }
+    }
+
+    pub fn hours(&self) -> f64 {
+        self.duration().num_minutes() as f64 / 60.0
+    }
+}
+
+/// Date with non-standard work periods, no periods means holiday
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Exception {
+    pub date: NaiveDate,
+    pub periods: Vec<WorkPeriod>,
+}
+
+impl Exception {
+    pub fn is_holiday(&self) -> bool {
+        self.periods.is_empty()
+    }
+}
+
+/// ## `clndr_data` structure
+/// Every node is `(level||name(attributes)(children))`, attributes are `key|value` pairs:
+/// ```text
+/// (0||CalendarData()(
+///   (0||DaysOfWeek()(
+///     (0||1()())                                          Sunday, no work
+///     (0||2()((0||0(s|08:00|f|12:00)())(0||1(s|13:00|f|17:00)())))
+///     ...))
+///   (0||Exceptions()(
+///     (0||0(d|45292)())                                   holiday
+///     (0||1(d|45301)((0||0(s|09:00|f|13:00)())))))))      short day
+/// ```
+/// Weekdays are numbered from Sunday = `1`, dates are days since 1899-12-30.
+fn parse_clndr_data(data: &str) -> Option<([Vec<WorkPeriod>; 7], Vec<Exception>)> {
+    let mut cursor = Cursor { rest: data };
+    let root = cursor.node()?;
+
+    let mut week: [Vec<WorkPeriod>; 7] = Default::default();
+    let mut exceptions = Vec::new();
+    for section in &root.children {
+        match section.name {
+            "DaysOfWeek" => {
+                for day in &section.children {
+                    let number: u32 = day.name.parse().ok().filter(|n| (1..=7).contains(n))?;
+                    // 1 is Sunday, shift to Monday based index
+                    let index = (number + 5) % 7;
+                    week[index as usize] = periods(day)?;
+                }
+            }
+            "Exceptions" => {
+                for exception in &section.children {
+                    let serial: i64 = exception.attr("d")?.parse().ok()?;
+                    let date = NaiveDate::from_ymd_opt(1899, 12, 30)?
+                        .checked_add_signed(TimeDelta::days(serial))?;
+                    exceptions.push(Exception {
+                        date,
+                        periods: periods(exception)?,
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+    exceptions.sort_by_key(|exception| exception.date);
+    Some((week, exceptions))
+}
+
+fn periods(node: &Node) -> Option<Vec<WorkPeriod>> {
+    node.children
+        .iter()
+        .map(|period| {
+            Some(WorkPeriod {
+                start: parse_time(period.attr("s")?)?,
+                finish: parse_time(period.attr("f")?)?,
+            })
+        })
+        .collect()
+}
+
+fn parse_time(value: &str) -> Option<NaiveTime> {
+    match value {
+        "24:00" => Some(NaiveTime::MIN),
+        _ => NaiveTime::parse_from_str(value, "%H:%M").ok(),
+    }
+}
+
+struct Node<'a> {
+    name: &'a str,
+    attrs: Vec<(&'a str, &'a str)>,
+    children: Vec<Node<'a>>,
+}
+
+impl<'a> Node<'a> {
+    fn attr(&self, key: &str) -> Option<&'a str> {
+        self.attrs
+            .iter()
+            .find(|(name, _)| *name == key)
+            .map(|(_, value)| *value)
+    }
+}
+
+struct Cursor<'a> {
+    rest: &'a str,
+}
+
+impl<'a> Cursor<'a> {
+    fn skip_whitespace(&mut self) {
+        // exports sometimes wrap long values with line breaks and DEL characters
+        self.rest = self
+            .rest
+            .trim_start_matches(|c: char| c.is_whitespace() || c == '\x7f');
+    }
+
+    fn expect(&mut self, token: &str) -> Option<()> {
+        self.skip_whitespace();
+        self.rest = self.rest.strip_prefix(token)?;
+        Some(())
+    }
+
+    fn until(&mut self, token: char) -> Option<&'a str> {
+        let (head, tail) = self.rest.split_once(token)?;
+        self.rest = tail;
+        Some(head.trim())
+    }
+
+    fn node(&mut self) -> Option<Node<'a>> {
+        self.expect("(")?;
+        self.until('|')?; // nesting level, same information as parentheses
+        self.expect("|")?;
+        let name = self.until('(')?;
+        let attrs = self.until(')')?;
+        let mut pairs = attrs.split('|').map(str::trim);
+        let mut attrs = Vec::new();
+        while let Some(key) = pairs.next().filter(|key| !key.is_empty()) {
+            attrs.push((key, pairs.next()?));
+        }
+
+        self.expect("(")?;
+        let mut children = Vec::new();
+        loop {
+            self.skip_whitespace();
+            if self.rest.starts_with(')') {
+                break;
+            }
+            children.push(self.node()?);
+        }
+        self.expect(")")?;
+        self.expect(")")?;
+        Some(Node {
+            name,
+            attrs,
+            children,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: &str = "((0||0(s|08:00|f|12:00)())(0||1(s|13:00|f|17:00)()))";
+
+    /// Monday to Friday 8 to 5 with lunch, 2024-04-01 off and 2024-04-08 short
+    fn standard() -> Calendar {
+        let data = format!(
+            "(0||CalendarData()((0||DaysOfWeek()((0||1()())(0||2(){DAY})(0||3(){DAY})\
+             (0||4(){DAY})(0||5(){DAY})(0||6(){DAY})(0||7()())))\r\n\x7f\x7f\
+             (0||Exceptions()((0||0(d|45390)((0||0(s|09:00|f|13:00)())))\
+             (0||1(d|45383)())))))"
+        );
+        let (week, exceptions) = parse_clndr_data(&data).unwrap();
+        Calendar {
+            clndr_id: 1,
+            clndr_name: "Standard".into(),
+            proj_id: None,
+            base_clndr_id: None,
+            default: true,
+            clndr_type: Some("CA_Base".into()),
+            day_hours: 8.0,
+            week_hours: 40.0,
+            week,
+            exceptions,
+        }
+    }
+
+    fn at(date: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(date, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    #[test]
+    fn clndr_data_days_of_week_and_exceptions() {
+        let calendar = standard();
+        let time = |value| NaiveTime::parse_from_str(value, "%H:%M").unwrap();
+        assert_eq!(
+            calendar.weekday(Weekday::Mon),
+            [
+                WorkPeriod {
+                    start: time("08:00"),
+                    finish: time("12:00"),
+                },
+                WorkPeriod {
+                    start: time("13:00"),
+                    finish: time("17:00"),
+                },
+            ]
+        );
+        assert!(calendar.weekday(Weekday::Sat).is_empty());
+        assert!(calendar.weekday(Weekday::Sun).is_empty());
+        assert_eq!(calendar.work_hours(at("2024-04-05 00:00").date()), 8.0);
+
+        // sorted by date, serial 45383 is 2024-04-01
+        let dates: Vec<_> = calendar.exceptions.iter().map(|e| e.date).collect();
+        assert_eq!(
+            dates,
+            [
+                NaiveDate::from_ymd_opt(2024, 4, 1).unwrap(),
+                NaiveDate::from_ymd_opt(2024, 4, 8).unwrap(),
+            ]
+        );
+        assert!(calendar.exceptions[0].is_holiday());
+        assert!(!calendar.is_work_day(dates[0]));
+        assert_eq!(calendar.work_hours(dates[1]), 4.0);
+    }
+
+    #[test]
+    fn invalid_clndr_data_is_rejected() {
+        assert!(parse_clndr_data("(0||CalendarData()((0||DaysOfWeek()((0||8()())))))").is_none());
+        assert!(
+            parse_clndr_data("(0||CalendarData()((0||Exceptions()((0||0(d|x)())))))").is_none()
+        );
+        assert!(parse_clndr_data("(0||CalendarData()(").is_none());
+    }
+
+    #[test]
+    fn add_work_hours_across_breaks_weekends_and_holidays() {
+        let calendar = standard();
+        let add = |from, hours| calendar.add_work_hours(at(from), hours);
+        assert_eq!(add("2024-04-02 08:00", 4.0), at("2024-04-02 12:00"));
+        assert_eq!(add("2024-04-02 08:00", 5.0), at("2024-04-02 14:00"));
+        assert_eq!(add("2024-04-02 12:30", 1.0), at("2024-04-02 14:00"));
+        assert_eq!(add("2024-04-02 17:00", 1.0), at("2024-04-03 09:00"));
+        // weekend and Easter Monday
+        assert_eq!(add("2024-03-29 17:00", 8.0), at("2024-04-02 17:00"));
+        // short day on Monday 2024-04-08
+        assert_eq!(add("2024-04-05 16:00", 2.0), at("2024-04-08 10:00"));
+        assert_eq!(add("2024-04-02 10:00", 0.0), at("2024-04-02 10:00"));
+    }
+
+    #[test]
+    fn subtract_work_hours_across_breaks_weekends_and_holidays() {
+        let calendar = standard();
+        let subtract = |from, hours| calendar.subtract_work_hours(at(from), hours);
+        assert_eq!(subtract("2024-04-02 17:00", 8.0), at("2024-04-02 08:00"));
+        assert_eq!(subtract("2024-04-02 14:00", 2.0), at("2024-04-02 11:00"));
+        assert_eq!(subtract("2024-04-02 08:00", 1.0), at("2024-03-29 16:00"));
+        assert_eq!(subtract("2024-04-09 08:00", 5.0), at("2024-04-05 16:00"));
+        assert_eq!(
+            calendar.add_work_hours(at("2024-04-03 08:00"), -1.0),
+            at("2024-04-02 16:00")
+        );
+    }
+
+    #[test]
+    fn work_hours_between_counts_work_time_only() {
+        let calendar = standard();
+        let between = |from, to| calendar.work_hours_between(at(from), at(to));
+        assert_eq!(between("2024-03-29 12:00", "2024-04-02 12:00"), 8.0);
+        assert_eq!(between("2024-04-02 12:00", "2024-03-29 12:00"), -8.0);
+        assert_eq!(between("2024-04-02 12:00", "2024-04-02 13:00"), 0.0);
+        assert_eq!(between("2024-04-08 08:00", "2024-04-08 17:00"), 4.0);
+    }
+
+    #[test]
+    fn next_and_previous_work_time() {
+        let calendar = standard();
+        assert_eq!(
+            calendar.next_work_time(at("2024-03-30 10:00")),
+            at("2024-04-02 08:00")
+        );
+        assert_eq!(
+            calendar.next_work_time(at("2024-04-02 12:00")),
+            at("2024-04-02 13:00")
+        );
+        assert_eq!(
+            calendar.previous_work_time(at("2024-04-01 10:00")),
+            at("2024-03-29 17:00")
+        );
+        assert_eq!(
+            calendar.previous_work_time(at("2024-04-02 17:00")),
+            at("2024-04-02 17:00")
+        );
+    }
+
+    #[test]
+    fn calendar_without_work_counts_elapsed_time() {
+        let calendar = Calendar {
+            week: Default::default(),
+            exceptions: Vec::new(),
+            ..standard()
+        };
+        assert_eq!(
+            calendar.add_work_hours(at("2024-03-30 10:00"), 30.0),
+            at("2024-03-31 16:00")
+        );
+        assert_eq!(
+            calendar.work_hours_between(at("2024-03-30 10:00"), at("2024-03-31 16:00")),
+            30.0
+        );
+    }
+}
@@ -0,0 +1,215 @@
+use std::fmt;
+use std::ops::{Add, Neg, Sub};
+
+use serde::{Deserialize, Serialize};
+
+use super::{Fields, FromRow};
+use crate::error::ParseError;
+use crate::parser::Table;
+
+/// Currency from the `CURRTYPE` table
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Currency {
+    pub curr_id: u64,
+    /// Currency ID, e.g. `EUR`, matches [`Header::currency`](crate::Header) for base currency
+    pub curr_short_name: String,
+    /// Currency name, e.g. `Euro`
+    pub curr_type: String,
+    pub curr_symbol: String,
+    /// `decimal_digit_cnt`
+    pub decimal_digits: usize,
+    pub decimal_symbol: String,
+    pub digit_group_symbol: String,
+}
+
+impl Currency {
+    /// Base currency known only by name from `ERMHDR`
+    pub fn from_short_name(short_name: &str) -> Self {
+        Self {
+            curr_id: 0,
+            curr_short_name: short_name.into(),
+            curr_type: short_name.into(),
+            curr_symbol: short_name.into(),
+            decimal_digits: 2,
+            decimal_symbol: ".".into(),
+            digit_group_symbol: ",".into(),
+        }
+    }
+
+    pub fn money(&self, amount: f64) -> Money {
+        Money::new(amount, &self.curr_short_name)
+    }
+
+    /// Amount with currency symbol and separators, e.g. `€1,234.50`
+    pub fn format(&self, amount: f64) -> String {
+        let digits = format!("{:.*}", self.decimal_digits, amount.abs());
+        let (int, frac) = digits.split_once('.').unwrap_or((&digits, ""));
+        let mut grouped = String::new();
+        for (i, c) in int.chars().enumerate() {
+            if i > 0 && (int.len() - i) % 3 == 0 {
+                grouped.push_str(&self.digit_group_symbol);
+            }
+            grouped.push(c);
+        }
+        let sign = if amount < 0.0 { "-" } else { "" };
+        match frac {
+            "" => format!("{sign}{}{grouped}", self.curr_symbol),
+            _ => format!(
+                "{sign}{}{grouped}{}{frac}",
+                self.curr_symbol, self.decimal_symbol
+            ),
+        }
+    }
+}
+
+impl FromRow for Currency {
+    const TABLE: &'static str = "CURRTYPE";
+
+    fn from_row(table: &Table, row: &[String]) -> Result<Self, ParseError> {
+        let f = Fields::new(table, row);
+        Ok(Self {
+            curr_id: f.parse("curr_id")?,
+            curr_short_name: f.string("curr_short_name")?,
+            curr_type: f.string("curr_type")?,
+            curr_symbol: f.string("curr_symbol")?,
+            decimal_digits: f.opt_parse("decimal_digit_cnt")?.unwrap_or(2),
+            decimal_symbol: f.opt_string("decimal_symbol").unwrap_or(".".into()),
+            digit_group_symbol: f.opt_string("digit_group_symbol").unwrap_or(",".into()),
+        })
+    }
+}
+
+/// Amount tagged with currency short name
+///
+/// Arithmetic operators panic on currency mismatch, use `checked_` methods
+/// when amounts may come from different files.
+#[derive(Debug, Clone, PartialEq, PartialOrd, Serialize, Deserialize)]
+pub struct Money {
+    pub amount: f64,
+    pub currency: String,
+}
+
+impl Money {
+    pub fn new(amount: f64, currency: &str) -> Self {
+        Self {
+            amount,
+            currency: currency.into(),
+        }
+    }
+
+    pub fn zero(currency: &str) -> Self {
+        Self::new(0.0, currency)
+    }
+
+    pub fn checked_add(&self, other: &Money) -> Result<Money, CurrencyMismatch> {
+        self.same_currency(other)?;
+        Ok(Money::new(self.amount + other.amount, &self.currency))
+    }
+
+    pub fn checked_sub(&self, other: &Money) -> Result<Money, CurrencyMismatch> {
+        self.same_currency(other)?;
+        Ok(Money::new(self.amount - other.amount, &self.currency))
+    }
+
+    fn same_currency(&self, other: &Money) -> Result<(), CurrencyMismatch> {
+        if self.currency == other.currency {
+            Ok(())
+        } else {
+            Err(CurrencyMismatch {
+                left: self.currency.clone(),
+                right: other.currency.clone(),
+            })
+        }
+    }
+}
+
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:.2} {}", self.amount, self.currency)
+    }
+}
+
+impl Add for Money {
+    type Output = Money;
+
+    fn add(self, other: Money) -> Money {
+        self.checked_add(&other).unwrap()
+    }
+}
+
+impl Sub for Money {
+    type Output = Money;
+
+    fn sub(self, other: Money) -> Money {
+        self.checked_sub(&other).unwrap()
+    }
+}
+
+impl Neg for Money {
+    type Output = Money;
+
+    fn neg(self) -> Money {
+        Money::new(-self.amount, &self.currency)
+    }
+}
+
+/// Amounts in different currencies were combined
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CurrencyMismatch {
+    pub left: String,
+    pub right: String,
+}
+
+impl fmt::Display for CurrencyMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "can not combine {} and {}", self.left, self.right)
+    }
+}
+
+impl std::error::Error for CurrencyMismatch {}
+
+/// Budgeted, actual and remaining cost rolled up in one currency
+///
+/// Adding panics on currency mismatch as for [`Money`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Costs {
+    pub budgeted: Money,
+    pub actual: Money,
+    pub remaining: Money,
+}
+
+impl Costs {
+    pub fn zero(currency: &str) -> Self {
+        Self {
+            budgeted: Money::zero(currency),
+            actual: Money::zero(currency),
+            remaining: Money::zero(currency),
+        }
+    }
+
+    /// Sum of `costs`, all in `currency`
+    pub fn sum(costs: impl IntoIterator<Item = Costs>, currency: &str) -> Self {
+        costs.into_iter().fold(Self::zero(currency), Add::add)
+    }
+
+    /// Actual plus remaining
+    pub fn at_completion(&self) -> Money {
+        self.actual.clone() + self.remaining.clone()
+    }
+
+    pub fn checked_add(&self, other: &Costs) -> Result<Costs, CurrencyMismatch> {
+        Ok(Self {
+            budgeted: self.budgeted.checked_add(&other.budgeted)?,
+            actual: self.actual.checked_add(&other.actual)?,
+            remaining: self.remaining.checked_add(&other.remaining)?,
+        })
+    }
+}
+
+impl Add for Costs {
+    type Output = Costs;
+
+    fn add(self, other: Costs) -> Costs {
+        self.checked_add(&other).unwrap()
+    }
+}
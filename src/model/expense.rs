@@ -0,0 +1,155 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::{Costs, Currency, Fields, FromRow};
+use crate::error::ParseError;
+use crate::parser::Table;
+
+/// Project expense from the `PROJCOST` table
+///
+/// Costs are in base currency of the file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Expense {
+    pub cost_item_id: u64,
+    pub task_id: u64,
+    pub proj_id: u64,
+    /// Cost account
+    pub acct_id: Option<u64>,
+    /// Expense category, see `COSTTYPE`
+    pub cost_type_id: Option<u64>,
+    pub cost_name: String,
+    pub vendor_name: String,
+    pub po_number: String,
+    /// `target_qty`
+    pub budgeted_units: f64,
+    /// `cost_per_qty`
+    pub price_per_unit: f64,
+    /// `target_cost`
+    pub budgeted_cost: f64,
+    /// `act_cost`
+    pub actual_cost: f64,
+    /// `remain_cost`
+    pub remaining_cost: f64,
+    /// `cost_load_type`
+    pub accrual: AccrualType,
+}
+
+impl Expense {
+    /// Actual plus remaining cost
+    pub fn at_completion_cost(&self) -> f64 {
+        self.actual_cost + self.remaining_cost
+    }
+
+    /// Costs tagged with base `currency`
+    pub fn costs(&self, currency: &Currency) -> Costs {
+        Costs {
+            budgeted: currency.money(self.budgeted_cost),
+            actual: currency.money(self.actual_cost),
+            remaining: currency.money(self.remaining_cost),
+        }
+    }
+}
+
+impl FromRow for Expense {
+    const TABLE: &'static str = "PROJCOST";
+
+    fn from_row(table: &Table, row: &[String]) -> Result<Self, ParseError> {
+        let f = Fields::new(table, row);
+        Ok(Self {
+            cost_item_id: f.parse("cost_item_id")?,
+            task_id: f.parse("task_id")?,
+            proj_id: f.parse("proj_id")?,
+            acct_id: f.opt_parse("acct_id")?,
+            cost_type_id: f.opt_parse("cost_type_id")?,
+            cost_name: f.string("cost_name")?,
+            vendor_name: f.opt_string("vendor_name").unwrap_or_default(),
+            po_number: f.opt_string("po_number").unwrap_or_default(),
+            budgeted_units: f.num("target_qty")?,
+            price_per_unit: f.num("cost_per_qty")?,
+            budgeted_cost: f.num("target_cost")?,
+            actual_cost: f.num("act_cost")?,
+            remaining_cost: f.num("remain_cost")?,
+            accrual: match f.opt_str("cost_load_type") {
+                Some(_) => f.code("cost_load_type", AccrualType::from_code)?,
+                None => AccrualType::Uniform,
+            },
+        })
+    }
+}
+
+/// How expense cost is spread over the activity
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum AccrualType {
+    /// At activity start
+    Start,
+    /// At activity finish
+    End,
+    /// Evenly over the activity duration
+    Uniform,
+}
+
+impl AccrualType {
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code {
+            "CL_Start" => Some(Self::Start),
+            "CL_End" => Some(Self::End),
+            "CL_Uniform" => Some(Self::Uniform),
+            _ => None,
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Start => "CL_Start",
+            Self::End => "CL_End",
+            Self::Uniform => "CL_Uniform",
+        }
+    }
+}
+
+/// Expenses indexed by task
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Expenses {
+    items: Vec<Expense>,
+    by_task: HashMap<u64, Vec<usize>>,
+}
+
+impl Expenses {
+    pub fn new(items: Vec<Expense>) -> Self {
+        let mut by_task: HashMap<u64, Vec<usize>> = HashMap::new();
+        for (i, item) in items.iter().enumerate() {
+            by_task.entry(item.task_id).or_default().push(i);
+        }
+        Self { items, by_task }
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Expense> {
+        self.items.iter()
+    }
+
+    /// Expenses of activity `task_id`
+    pub fn for_task(&self, task_id: u64) -> impl Iterator<Item = &Expense> {
+        self.by_task
+            .get(&task_id)
+            .map_or(&[][..], Vec::as_slice)
+            .iter()
+            .map(|&i| &self.items[i])
+    }
+
+    /// Total expense costs of activity `task_id`
+    pub fn task_costs(&self, task_id: u64, currency: &Currency) -> Costs {
+        Costs::sum(
+            self.for_task(task_id).map(|item| item.costs(currency)),
+            &currency.curr_short_name,
+        )
+    }
+}
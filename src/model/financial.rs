@@ -0,0 +1,218 @@
+use std::collections::HashMap;
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+use super::{Fields, FromRow};
+use crate::error::ParseError;
+use crate::parser::Table;
+
+/// Financial period calendar from the `FINTMPL` table
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FinancialTemplate {
+    pub fintmpl_id: u64,
+    pub fintmpl_name: String,
+    /// `default_flag`
+    pub default: bool,
+}
+
+impl FromRow for FinancialTemplate {
+    const TABLE: &'static str = "FINTMPL";
+
+    fn from_row(table: &Table, row: &[String]) -> Result<Self, ParseError> {
+        let f = Fields::new(table, row);
+        Ok(Self {
+            fintmpl_id: f.parse("fintmpl_id")?,
+            fintmpl_name: f.string("fintmpl_name")?,
+            default: f.flag("default_flag")?,
+        })
+    }
+}
+
+/// Financial period from the `FINDATES` table
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FinancialPeriod {
+    pub fin_dates_id: u64,
+    pub fin_dates_name: String,
+    /// Financial period calendar, absent in older exports
+    pub fintmpl_id: Option<u64>,
+    pub start_date: NaiveDateTime,
+    pub end_date: NaiveDateTime,
+}
+
+impl FromRow for FinancialPeriod {
+    const TABLE: &'static str = "FINDATES";
+
+    fn from_row(table: &Table, row: &[String]) -> Result<Self, ParseError> {
+        let f = Fields::new(table, row);
+        let required = |column: &str| {
+            f.opt_datetime(column)?
+                .ok_or_else(|| ParseError::InvalidValue {
+                    table: table.name.clone(),
+                    column: column.into(),
+                    value: String::new(),
+                })
+        };
+        Ok(Self {
+            fin_dates_id: f.parse("fin_dates_id")?,
+            fin_dates_name: f.string("fin_dates_name")?,
+            fintmpl_id: f.opt_parse("fintmpl_id")?,
+            start_date: required("start_date")?,
+            end_date: required("end_date")?,
+        })
+    }
+}
+
+/// Stored past period actuals of an activity from the `TASKFIN` table
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaskPeriodActuals {
+    pub fin_dates_id: u64,
+    pub task_id: u64,
+    pub proj_id: Option<u64>,
+    /// `act_work_qty`, labor units
+    pub labor_units: f64,
+    /// `act_work_cost`
+    pub labor_cost: f64,
+    /// `act_equip_qty`, nonlabor units
+    pub nonlabor_units: f64,
+    /// `act_equip_cost`
+    pub nonlabor_cost: f64,
+    /// `act_mat_cost`
+    pub material_cost: f64,
+    /// `act_expense_cost`
+    pub expense_cost: f64,
+    /// `bcwp`, earned value
+    pub earned_value: f64,
+    /// `bcws`, planned value
+    pub planned_value: f64,
+}
+
+impl TaskPeriodActuals {
+    /// Sum of all actual costs in the period
+    pub fn total_cost(&self) -> f64 {
+        self.labor_cost + self.nonlabor_cost + self.material_cost + self.expense_cost
+    }
+}
+
+impl FromRow for TaskPeriodActuals {
+    const TABLE: &'static str = "TASKFIN";
+
+    fn from_row(table: &Table, row: &[String]) -> Result<Self, ParseError> {
+        let f = Fields::new(table, row);
+        Ok(Self {
+            fin_dates_id: f.parse("fin_dates_id")?,
+            task_id: f.parse("task_id")?,
+            proj_id: f.opt_parse("proj_id")?,
+            labor_units: f.num("act_work_qty")?,
+            labor_cost: f.num("act_work_cost")?,
+            nonlabor_units: f.num("act_equip_qty")?,
+            nonlabor_cost: f.num("act_equip_cost")?,
+            material_cost: f.num("act_mat_cost")?,
+            expense_cost: f.num("act_expense_cost")?,
+            earned_value: f.num("bcwp")?,
+            planned_value: f.num("bcws")?,
+        })
+    }
+}
+
+/// Stored past period actuals of a resource assignment from the `TRSRCFIN` table
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AssignmentPeriodActuals {
+    pub fin_dates_id: u64,
+    pub taskrsrc_id: u64,
+    pub task_id: u64,
+    pub proj_id: Option<u64>,
+    /// `act_qty` + `act_ot_qty`
+    pub units: f64,
+    /// `act_cost` + `act_ot_cost`
+    pub cost: f64,
+}
+
+impl FromRow for AssignmentPeriodActuals {
+    const TABLE: &'static str = "TRSRCFIN";
+
+    fn from_row(table: &Table, row: &[String]) -> Result<Self, ParseError> {
+        let f = Fields::new(table, row);
+        Ok(Self {
+            fin_dates_id: f.parse("fin_dates_id")?,
+            taskrsrc_id: f.parse("taskrsrc_id")?,
+            task_id: f.parse("task_id")?,
+            proj_id: f.opt_parse("proj_id")?,
+            units: f.num("act_qty")? + f.num("act_ot_qty")?,
+            cost: f.num("act_cost")? + f.num("act_ot_cost")?,
+        })
+    }
+}
+
+/// Financial periods joined with stored period actuals
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FinancialPeriods {
+    periods: HashMap<u64, FinancialPeriod>,
+    by_task: HashMap<u64, Vec<TaskPeriodActuals>>,
+    by_assignment: HashMap<u64, Vec<AssignmentPeriodActuals>>,
+}
+
+impl FinancialPeriods {
+    pub fn new(
+        periods: Vec<FinancialPeriod>,
+        task_actuals: Vec<TaskPeriodActuals>,
+        assignment_actuals: Vec<AssignmentPeriodActuals>,
+    ) -> Self {
+        let mut by_task: HashMap<u64, Vec<TaskPeriodActuals>> = HashMap::new();
+        for actuals in task_actuals {
+            by_task.entry(actuals.task_id).or_default().push(actuals);
+        }
+        let mut by_assignment: HashMap<u64, Vec<AssignmentPeriodActuals>> = HashMap::new();
+        for actuals in assignment_actuals {
+            by_assignment
+                .entry(actuals.taskrsrc_id)
+                .or_default()
+                .push(actuals);
+        }
+        Self {
+            periods: periods.into_iter().map(|p| (p.fin_dates_id, p)).collect(),
+            by_task,
+            by_assignment,
+        }
+    }
+
+    /// Periods ordered by start date
+    pub fn periods(&self) -> Vec<&FinancialPeriod> {
+        let mut periods: Vec<_> = self.periods.values().collect();
+        periods.sort_by_key(|p| p.start_date);
+        periods
+    }
+
+    pub fn period(&self, fin_dates_id: u64) -> Option<&FinancialPeriod> {
+        self.periods.get(&fin_dates_id)
+    }
+
+    /// Time phased actuals of activity `task_id` ordered by period
+    pub fn task_series(&self, task_id: u64) -> Vec<(&FinancialPeriod, &TaskPeriodActuals)> {
+        self.series(&self.by_task, task_id, |a| a.fin_dates_id)
+    }
+
+    /// Time phased actuals of assignment `taskrsrc_id` ordered by period
+    pub fn assignment_series(
+        &self,
+        taskrsrc_id: u64,
+    ) -> Vec<(&FinancialPeriod, &AssignmentPeriodActuals)> {
+        self.series(&self.by_assignment, taskrsrc_id, |a| a.fin_dates_id)
+    }
+
+    fn series<'a, T>(
+        &'a self,
+        map: &'a HashMap<u64, Vec<T>>,
+        id: u64,
+        period: fn(&T) -> u64,
+    ) -> Vec<(&'a FinancialPeriod, &'a T)> {
+        let mut series: Vec<_> = map
+            .get(&id)
+            .map_or(&[][..], Vec::as_slice)
+            .iter()
+            .filter_map(|actuals| Some((self.periods.get(&period(actuals))?, actuals)))
+            .collect();
+        series.sort_by_key(|(p, _)| p.start_date);
+        series
+    }
+}
@@ -0,0 +1,188 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::{Fields, FromRow};
+use crate::error::ParseError;
+use crate::parser::Table;
+
+/// Notebook topic from the `MEMOTYPE` table
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MemoType {
+    pub memo_type_id: u64,
+    /// Topic name, e.g. `Notes`
+    pub memo_type: String,
+    pub seq_num: i64,
+}
+
+impl FromRow for MemoType {
+    const TABLE: &'static str = "MEMOTYPE";
+
+    fn from_row(table: &Table, row: &[String]) -> Result<Self, ParseError> {
+        let f = Fields::new(table, row);
+        Ok(Self {
+            memo_type_id: f.parse("memo_type_id")?,
+            memo_type: f.string("memo_type")?,
+            seq_num: f.opt_parse("seq_num")?.unwrap_or_default(),
+        })
+    }
+}
+
+/// Activity notebook entry from the `TASKMEMO` table
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaskMemo {
+    pub memo_id: u64,
+    pub task_id: u64,
+    pub memo_type_id: u64,
+    pub proj_id: Option<u64>,
+    /// HTML as stored by P6
+    pub task_memo: String,
+}
+
+impl TaskMemo {
+    /// Memo with HTML markup removed
+    pub fn plain_text(&self) -> String {
+        strip_html(&self.task_memo)
+    }
+}
+
+impl FromRow for TaskMemo {
+    const TABLE: &'static str = "TASKMEMO";
+
+    fn from_row(table: &Table, row: &[String]) -> Result<Self, ParseError> {
+        let f = Fields::new(table, row);
+        Ok(Self {
+            memo_id: f.parse("memo_id")?,
+            task_id: f.parse("task_id")?,
+            memo_type_id: f.parse("memo_type_id")?,
+            proj_id: f.opt_parse("proj_id")?,
+            task_memo: f.string("task_memo")?,
+        })
+    }
+}
+
+/// Notebook topics joined with activity memos
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Notebooks {
+    topics: HashMap<u64, MemoType>,
+    by_task: HashMap<u64, Vec<TaskMemo>>,
+}
+
+impl Notebooks {
+    pub fn new(topics: Vec<MemoType>, memos: Vec<TaskMemo>) -> Self {
+        let mut by_task: HashMap<u64, Vec<TaskMemo>> = HashMap::new();
+        for memo in memos {
+            by_task.entry(memo.task_id).or_default().push(memo);
+        }
+        Self {
+            topics: topics.into_iter().map(|t| (t.memo_type_id, t)).collect(),
+            by_task,
+        }
+    }
+
+    pub fn topic(&self, memo_type_id: u64) -> Option<&MemoType> {
+        self.topics.get(&memo_type_id)
+    }
+
+    /// Memos of activity `task_id` ordered by topic
+    pub fn for_task(&self, task_id: u64) -> Vec<(Option<&MemoType>, &TaskMemo)> {
+        let mut memos: Vec<_> = self
+            .by_task
+            .get(&task_id)
+            .map_or(&[][..], Vec::as_slice)
+            .iter()
+            .map(|memo| (self.topic(memo.memo_type_id), memo))
+            .collect();
+        memos.sort_by_key(|(topic, memo)| (topic.map(|t| t.seq_num), memo.memo_id));
+        memos
+    }
+
+    /// `(topic name, text)` of activity `task_id`, HTML is removed if `strip_html` is set
+    pub fn texts_for_task(&self, task_id: u64, strip_html: bool) -> Vec<(&str, String)> {
+        self.for_task(task_id)
+            .into_iter()
+            .map(|(topic, memo)| {
+                let text = if strip_html {
+                    memo.plain_text()
+                } else {
+                    memo.task_memo.clone()
+                };
+                (topic.map_or("", |t| t.memo_type.as_str()), text)
+            })
+            .collect()
+    }
+}
+
+/// Convert HTML stored by P6 to plain text
+///
+/// Tags are dropped, block level tags become line breaks, entities are
+/// decoded and whitespace is collapsed the way browsers render it.
+pub fn strip_html(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut rest = html;
+    while let Some(c) = rest.chars().next() {
+        match c {
+            '<' => {
+                let (tag, end) = match rest.find('>') {
+                    Some(i) => (&rest[1..i], i + 1),
+                    None => (&rest[1..], rest.len()),
+                };
+                let tag = tag.trim_start_matches('/');
+                let name: String = tag
+                    .chars()
+                    .take_while(char::is_ascii_alphanumeric)
+                    .collect::<String>()
+                    .to_ascii_lowercase();
+                if matches!(
+                    name.as_str(),
+                    "br" | "p" | "div" | "li" | "tr" | "h1" | "h2" | "h3" | "h4" | "h5" | "h6"
+                ) {
+                    text.push('\n');
+                }
+                rest = &rest[end..];
+            }
+            '&' => {
+                let (decoded, len) = decode_entity(rest);
+                text.push_str(&decoded);
+                rest = &rest[len..];
+            }
+            _ => {
+                text.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+
+    // collapse whitespace within lines, drop empty lines
+    text.lines()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Decode entity at the start of `s`, returns decoded text and consumed length
+fn decode_entity(s: &str) -> (String, usize) {
+    let Some(end) = s.find(';').filter(|&end| end <= 10) else {
+        return ("&".into(), 1);
+    };
+    let entity = &s[1..end];
+    let decoded = match entity {
+        "amp" => Some('&'),
+        "lt" => Some('<'),
+        "gt" => Some('>'),
+        "quot" => Some('"'),
+        "apos" => Some('\''),
+        "nbsp" => Some(' '),
+        _ => entity
+            .strip_prefix("#x")
+            .or_else(|| entity.strip_prefix("#X"))
+            .map(|hex| u32::from_str_radix(hex, 16).ok())
+            .unwrap_or_else(|| entity.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+            .and_then(char::from_u32),
+    };
+    match decoded {
+        Some(c) => (c.to_string(), end + 1),
+        None => ("&".into(), 1),
+    }
+}
@@ -0,0 +1,163 @@
+//! Typed records for well known P6 tables
+
+use std::str::FromStr;
+
+use chrono::{NaiveDate, NaiveDateTime};
+
+use crate::error::ParseError;
+use crate::parser::{Row, Table};
+
+pub mod account;
+pub mod activity_code;
+pub mod assignment;
+pub mod calendar;
+pub mod currency;
+pub mod expense;
+pub mod financial;
+pub mod memo;
+pub mod obs;
+pub mod project;
+pub mod project_code;
+pub mod relationship;
+pub mod resource;
+pub mod role;
+pub mod schedule_options;
+pub mod step;
+pub mod task;
+pub mod udf;
+pub mod wbs;
+
+pub use account::{Account, AccountTree};
+pub use activity_code::{ActivityCode, ActivityCodeType, ActivityCodes, TaskActivityCode};
+pub use assignment::{Assignment, Assignments};
+pub use calendar::{Calendar, Exception, WorkPeriod};
+pub use currency::{Costs, Currency, CurrencyMismatch, Money};
+pub use expense::{AccrualType, Expense, Expenses};
+pub use financial::{
+    AssignmentPeriodActuals, FinancialPeriod, FinancialPeriods, FinancialTemplate,
+    TaskPeriodActuals,
+};
+pub use memo::{strip_html, MemoType, Notebooks, TaskMemo};
+pub use obs::{Obs, ObsTree};
+pub use project::Project;
+pub use project_code::{ProjectCode, ProjectCodeAssignment, ProjectCodeType, ProjectCodes};
+pub use relationship::{LinkType, Relationship};
+pub use resource::{Resource, ResourceRate, ResourceRates, ResourceTree, ResourceType};
+pub use role::{Role, RoleRate, RoleRates, RoleTree};
+pub use schedule_options::{FloatType, LagCalendar, OutOfSequence, SchedOptions};
+pub use step::{Step, Steps};
+pub use task::{Task, TaskStatus, TaskType};
+pub use udf::{UdfData, UdfDataType, UdfType, UdfValue, Udfs};
+pub use wbs::{Wbs, WbsRollup, WbsTree};
+
+/// Record which can be built from a row of its table
+pub trait FromRow: Sized {
+    /// Name of table records are stored in, e.g. `TASK`
+    const TABLE: &'static str;
+
+    fn from_row(table: &Table, row: &[String]) -> Result<Self, ParseError>;
+}
+
+/// Parse date in P6 format `2024-03-15 08:00`, seconds and time part are optional
+pub fn parse_datetime(value: &str) -> Option<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M")
+        .or_else(|_| NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S"))
+        .or_else(|_| NaiveDate::parse_from_str(value, "%Y-%m-%d").map(|date| date.into()))
+        .ok()
+}
+
+/// Rate in effect at `date` from `rates` sorted by effective date
+///
+/// P6 applies the earliest rate to dates before the first effective date.
+pub(crate) fn effective<T>(
+    rates: &[T],
+    date: NaiveDateTime,
+    start: fn(&T) -> Option<NaiveDateTime>,
+) -> Option<&T> {
+    rates
+        .iter()
+        .rev()
+        .find(|rate| start(rate).is_none_or(|start| start <= date))
+        .or(rates.first())
+}
+
+/// Column lookup and conversion for a single row
+///
+/// Required getters fail on missing column or empty value, `opt_` getters
+/// return `None` for both since column sets differ between P6 versions.
+pub(crate) struct Fields<'a> {
+    row: Row<'a>,
+}
+
+impl<'a> Fields<'a> {
+    pub(crate) fn new(table: &'a Table, row: &'a [String]) -> Self {
+        Self {
+            row: table.row_of(row),
+        }
+    }
+
+    fn invalid(&self, column: &str, value: &str) -> ParseError {
+        ParseError::InvalidValue {
+            table: self.row.table().into(),
+            column: column.into(),
+            value: value.into(),
+        }
+    }
+
+    pub(crate) fn str(&self, column: &str) -> Result<&'a str, ParseError> {
+        self.row.get(column)
+    }
+
+    pub(crate) fn opt_str(&self, column: &str) -> Option<&'a str> {
+        self.row.get_opt(column)
+    }
+
+    pub(crate) fn string(&self, column: &str) -> Result<String, ParseError> {
+        self.str(column).map(Into::into)
+    }
+
+    pub(crate) fn opt_string(&self, column: &str) -> Option<String> {
+        self.opt_str(column).map(Into::into)
+    }
+
+    pub(crate) fn parse<T: FromStr>(&self, column: &str) -> Result<T, ParseError> {
+        let value = self.str(column)?;
+        value.parse().map_err(|_| self.invalid(column, value))
+    }
+
+    pub(crate) fn opt_parse<T: FromStr>(&self, column: &str) -> Result<Option<T>, ParseError> {
+        self.opt_str(column)
+            .map(|value| value.parse().map_err(|_| self.invalid(column, value)))
+            .transpose()
+    }
+
+    /// Numeric value where empty means zero
+    pub(crate) fn num(&self, column: &str) -> Result<f64, ParseError> {
+        Ok(self.opt_parse(column)?.unwrap_or_default())
+    }
+
+    pub(crate) fn opt_datetime(&self, column: &str) -> Result<Option<NaiveDateTime>, ParseError> {
+        self.opt_str(column)
+            .map(|value| parse_datetime(value).ok_or_else(|| self.invalid(column, value)))
+            .transpose()
+    }
+
+    /// `Y`/`N` flag, empty is `false`
+    pub(crate) fn flag(&self, column: &str) -> Result<bool, ParseError> {
+        match self.opt_str(column) {
+            None | Some("N") => Ok(false),
+            Some("Y") => Ok(true),
+            Some(value) => Err(self.invalid(column, value)),
+        }
+    }
+
+    /// Enumerated value like `TK_Active`
+    pub(crate) fn code<T>(
+        &self,
+        column: &str,
+        from_code: fn(&str) -> Option<T>,
+    ) -> Result<T, ParseError> {
+        let value = self.str(column)?;
+        from_code(value).ok_or_else(|| self.invalid(column, value))
+    }
+}
@@ -0,0 +1,53 @@
+use serde::{Deserialize, Serialize};
+
+use super::{Fields, FromRow, Wbs};
+use crate::error::ParseError;
+use crate::parser::Table;
+use crate::tree::{Tree, TreeNode};
+
+/// Organizational breakdown structure element from the `OBS` table
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Obs {
+    pub obs_id: u64,
+    pub parent_obs_id: Option<u64>,
+    pub obs_name: String,
+    pub seq_num: i64,
+}
+
+impl FromRow for Obs {
+    const TABLE: &'static str = "OBS";
+
+    fn from_row(table: &Table, row: &[String]) -> Result<Self, ParseError> {
+        let f = Fields::new(table, row);
+        Ok(Self {
+            obs_id: f.parse("obs_id")?,
+            parent_obs_id: f.opt_parse("parent_obs_id")?,
+            obs_name: f.string("obs_name")?,
+            seq_num: f.opt_parse("seq_num")?.unwrap_or_default(),
+        })
+    }
+}
+
+impl TreeNode for Obs {
+    fn id(&self) -> u64 {
+        self.obs_id
+    }
+
+    fn parent_id(&self) -> Option<u64> {
+        self.parent_obs_id
+    }
+
+    fn seq(&self) -> i64 {
+        self.seq_num
+    }
+}
+
+/// Responsibility hierarchy
+pub type ObsTree = Tree<Obs>;
+
+impl ObsTree {
+    /// Responsible manager of `wbs` element
+    pub fn responsible_for(&self, wbs: &Wbs) -> Option<&Obs> {
+        self.get(wbs.obs_id?)
+    }
+}
@@ -0,0 +1,78 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+use super::{Fields, FromRow, Task};
+use crate::error::ParseError;
+use crate::parser::Table;
+
+/// Project from the `PROJECT` table
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Project {
+    pub proj_id: u64,
+    /// Project ID shown to users
+    pub proj_short_name: String,
+    /// Default calendar for new activities
+    pub clndr_id: Option<u64>,
+    /// `plan_start_date`
+    pub planned_start: Option<NaiveDateTime>,
+    /// `plan_end_date`, must finish by date
+    pub must_finish_by: Option<NaiveDateTime>,
+    /// `scd_end_date`, finish calculated by the last schedule run
+    pub scheduled_finish: Option<NaiveDateTime>,
+    /// `last_recalc_date`
+    pub data_date: Option<NaiveDateTime>,
+    /// `critical_drtn_hr_cnt`, activities with total float below it are critical
+    pub critical_float: f64,
+    /// `critical_path_type`, `CT_TotFloat` or `CT_DrivPath`
+    pub critical_path_type: Option<String>,
+    /// `sched_retained_logic`
+    pub retained_logic: bool,
+    /// `sched_progress_override`
+    pub progress_override: bool,
+    /// `sched_calendar_on_relationship_lag`, e.g. `rcal_Predecessor`
+    pub lag_calendar: Option<String>,
+    /// `sched_open_critical_flag`, open ends are critical
+    pub open_ends_critical: bool,
+    /// `sched_lag_early_start_flag`, start to start lag from early start
+    pub lag_from_early_start: bool,
+    /// `def_complete_pct_type`, e.g. `CP_Drtn`
+    pub default_percent_complete_type: Option<String>,
+}
+
+impl Project {
+    /// Whether `task` is critical by the project's definition, on the longest
+    /// path or with total float at or below the critical float
+    pub fn is_critical(&self, task: &Task) -> bool {
+        if self.critical_path_type.as_deref() == Some("CT_DrivPath") {
+            task.driving_path
+        } else {
+            task.total_float
+                .is_some_and(|float| float <= self.critical_float)
+        }
+    }
+}
+
+impl FromRow for Project {
+    const TABLE: &'static str = "PROJECT";
+
+    fn from_row(table: &Table, row: &[String]) -> Result<Self, ParseError> {
+        let f = Fields::new(table, row);
+        Ok(Self {
+            proj_id: f.parse("proj_id")?,
+            proj_short_name: f.string("proj_short_name")?,
+            clndr_id: f.opt_parse("clndr_id")?,
+            planned_start: f.opt_datetime("plan_start_date")?,
+            must_finish_by: f.opt_datetime("plan_end_date")?,
+            scheduled_finish: f.opt_datetime("scd_end_date")?,
+            data_date: f.opt_datetime("last_recalc_date")?,
+            critical_float: f.num("critical_drtn_hr_cnt")?,
+            critical_path_type: f.opt_string("critical_path_type"),
+            retained_logic: f.flag("sched_retained_logic")?,
+            progress_override: f.flag("sched_progress_override")?,
+            lag_calendar: f.opt_string("sched_calendar_on_relationship_lag"),
+            open_ends_critical: f.flag("sched_open_critical_flag")?,
+            lag_from_early_start: f.flag("sched_lag_early_start_flag")?,
+            default_percent_complete_type: f.opt_string("def_complete_pct_type"),
+        })
+    }
+}
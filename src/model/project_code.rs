@@ -0,0 +1,169 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::{Fields, FromRow};
+use crate::error::ParseError;
+use crate::parser::Table;
+
+/// Project code dictionary from the `PCATTYPE` table
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProjectCodeType {
+    pub proj_catg_type_id: u64,
+    /// Code type name, e.g. `Region`
+    pub proj_catg_type: String,
+    pub seq_num: i64,
+}
+
+impl FromRow for ProjectCodeType {
+    const TABLE: &'static str = "PCATTYPE";
+
+    fn from_row(table: &Table, row: &[String]) -> Result<Self, ParseError> {
+        let f = Fields::new(table, row);
+        Ok(Self {
+            proj_catg_type_id: f.parse("proj_catg_type_id")?,
+            proj_catg_type: f.string("proj_catg_type")?,
+            seq_num: f.opt_parse("seq_num")?.unwrap_or_default(),
+        })
+    }
+}
+
+/// Project code value from the `PCATVAL` table
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProjectCode {
+    pub proj_catg_id: u64,
+    pub parent_proj_catg_id: Option<u64>,
+    pub proj_catg_type_id: u64,
+    /// Code value shown to users, e.g. `EU`
+    pub proj_catg_short_name: String,
+    /// Description, e.g. `Europe`
+    pub proj_catg_name: String,
+    pub seq_num: i64,
+}
+
+impl FromRow for ProjectCode {
+    const TABLE: &'static str = "PCATVAL";
+
+    fn from_row(table: &Table, row: &[String]) -> Result<Self, ParseError> {
+        let f = Fields::new(table, row);
+        Ok(Self {
+            proj_catg_id: f.parse("proj_catg_id")?,
+            parent_proj_catg_id: f.opt_parse("parent_proj_catg_id")?,
+            proj_catg_type_id: f.parse("proj_catg_type_id")?,
+            proj_catg_short_name: f.string("proj_catg_short_name")?,
+            proj_catg_name: f.string("proj_catg_name")?,
+            seq_num: f.opt_parse("seq_num")?.unwrap_or_default(),
+        })
+    }
+}
+
+/// Code assigned to a project from the `PROJPCAT` table
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProjectCodeAssignment {
+    pub proj_id: u64,
+    pub proj_catg_type_id: u64,
+    pub proj_catg_id: u64,
+}
+
+impl FromRow for ProjectCodeAssignment {
+    const TABLE: &'static str = "PROJPCAT";
+
+    fn from_row(table: &Table, row: &[String]) -> Result<Self, ParseError> {
+        let f = Fields::new(table, row);
+        Ok(Self {
+            proj_id: f.parse("proj_id")?,
+            proj_catg_type_id: f.parse("proj_catg_type_id")?,
+            proj_catg_id: f.parse("proj_catg_id")?,
+        })
+    }
+}
+
+/// Project code types, values and their assignments joined together
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProjectCodes {
+    types: HashMap<u64, ProjectCodeType>,
+    codes: HashMap<u64, ProjectCode>,
+    by_project: HashMap<u64, Vec<u64>>,
+}
+
+impl ProjectCodes {
+    pub fn new(
+        types: Vec<ProjectCodeType>,
+        codes: Vec<ProjectCode>,
+        assignments: Vec<ProjectCodeAssignment>,
+    ) -> Self {
+        let mut by_project: HashMap<u64, Vec<u64>> = HashMap::new();
+        for assignment in assignments {
+            by_project
+                .entry(assignment.proj_id)
+                .or_default()
+                .push(assignment.proj_catg_id);
+        }
+        Self {
+            types: types
+                .into_iter()
+                .map(|t| (t.proj_catg_type_id, t))
+                .collect(),
+            codes: codes.into_iter().map(|c| (c.proj_catg_id, c)).collect(),
+            by_project,
+        }
+    }
+
+    pub fn code_type(&self, proj_catg_type_id: u64) -> Option<&ProjectCodeType> {
+        self.types.get(&proj_catg_type_id)
+    }
+
+    pub fn code(&self, proj_catg_id: u64) -> Option<&ProjectCode> {
+        self.codes.get(&proj_catg_id)
+    }
+
+    /// Code types ordered by `seq_num`
+    pub fn types(&self) -> Vec<&ProjectCodeType> {
+        let mut types: Vec<_> = self.types.values().collect();
+        types.sort_by_key(|t| (t.seq_num, t.proj_catg_type_id));
+        types
+    }
+
+    /// `(code type, code)` pairs of project `proj_id`, ordered by type `seq_num`
+    pub fn for_project(&self, proj_id: u64) -> Vec<(&ProjectCodeType, &ProjectCode)> {
+        let codes = self.by_project.get(&proj_id).map_or(&[][..], Vec::as_slice);
+        let mut pairs: Vec<_> = codes
+            .iter()
+            .filter_map(|id| self.codes.get(id))
+            .filter_map(|code| Some((self.types.get(&code.proj_catg_type_id)?, code)))
+            .collect();
+        pairs.sort_by_key(|(t, _)| (t.seq_num, t.proj_catg_type_id));
+        pairs
+    }
+
+    /// Code of project `proj_id` for code type named `type_name`
+    pub fn code_for(&self, proj_id: u64, type_name: &str) -> Option<&ProjectCode> {
+        self.for_project(proj_id)
+            .into_iter()
+            .find(|(t, _)| t.proj_catg_type == type_name)
+            .map(|(_, code)| code)
+    }
+
+    /// Projects grouped by their code of type `type_name`, codes ordered by `seq_num`
+    ///
+    /// Projects without a code of that type are left out.
+    pub fn group_by(&self, type_name: &str) -> Vec<(&ProjectCode, Vec<u64>)> {
+        let mut groups: Vec<(&ProjectCode, Vec<u64>)> = Vec::new();
+        let mut projects: Vec<_> = self.by_project.keys().copied().collect();
+        projects.sort_unstable();
+        for proj_id in projects {
+            let Some(code) = self.code_for(proj_id, type_name) else {
+                continue;
+            };
+            match groups
+                .iter_mut()
+                .find(|(c, _)| c.proj_catg_id == code.proj_catg_id)
+            {
+                Some((_, group)) => group.push(proj_id),
+                None => groups.push((code, vec![proj_id])),
+            }
+        }
+        groups.sort_by_key(|(c, _)| (c.seq_num, c.proj_catg_id));
+        groups
+    }
+}
@@ -0,0 +1,73 @@
+use serde::{Deserialize, Serialize};
+
+use super::{Fields, FromRow};
+use crate::error::ParseError;
+use crate::parser::Table;
+
+/// Logic link from the `TASKPRED` table
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Relationship {
+    pub task_pred_id: u64,
+    /// Successor
+    pub task_id: u64,
+    /// Predecessor
+    pub pred_task_id: u64,
+    pub proj_id: Option<u64>,
+    pub pred_proj_id: Option<u64>,
+    /// `pred_type`
+    pub link_type: LinkType,
+    /// `lag_hr_cnt`, negative for leads
+    pub lag: f64,
+}
+
+impl FromRow for Relationship {
+    const TABLE: &'static str = "TASKPRED";
+
+    fn from_row(table: &Table, row: &[String]) -> Result<Self, ParseError> {
+        let f = Fields::new(table, row);
+        Ok(Self {
+            task_pred_id: f.parse("task_pred_id")?,
+            task_id: f.parse("task_id")?,
+            pred_task_id: f.parse("pred_task_id")?,
+            proj_id: f.opt_parse("proj_id")?,
+            pred_proj_id: f.opt_parse("pred_proj_id")?,
+            link_type: f.code("pred_type", LinkType::from_code)?,
+            lag: f.num("lag_hr_cnt")?,
+        })
+    }
+}
+
+/// `pred_type` of a relationship
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum LinkType {
+    FinishToStart,
+    StartToStart,
+    FinishToFinish,
+    StartToFinish,
+}
+
+impl LinkType {
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code {
+            "PR_FS" => Some(Self::FinishToStart),
+            "PR_SS" => Some(Self::StartToStart),
+            "PR_FF" => Some(Self::FinishToFinish),
+            "PR_SF" => Some(Self::StartToFinish),
+            _ => None,
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::FinishToStart => "PR_FS",
+            Self::StartToStart => "PR_SS",
+            Self::FinishToFinish => "PR_FF",
+            Self::StartToFinish => "PR_SF",
+        }
+    }
+
+    /// Short name as shown in P6, e.g. `FS`
+    pub fn abbr(&self) -> &'static str {
+        &self.code()[3..]
+    }
+}
@@ -0,0 +1,184 @@
+use std::collections::HashMap;
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+use super::{effective, Fields, FromRow};
+use crate::error::ParseError;
+use crate::parser::Table;
+use crate::tree::{Tree, TreeNode};
+
+/// Resource from the `RSRC` table
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Resource {
+    pub rsrc_id: u64,
+    pub parent_rsrc_id: Option<u64>,
+    pub rsrc_name: String,
+    /// Resource ID shown to users
+    pub rsrc_short_name: String,
+    pub rsrc_type: ResourceType,
+    pub clndr_id: Option<u64>,
+    /// Primary role
+    pub role_id: Option<u64>,
+    /// Unit of measure for material resources, see `UMEASURE`
+    pub unit_id: Option<u64>,
+    pub curr_id: Option<u64>,
+    /// `def_qty_per_hr`, default units per hour
+    pub default_units_per_hour: f64,
+    /// `ot_factor`
+    pub overtime_factor: f64,
+    /// `rsrc_seq_num`
+    pub seq_num: i64,
+    /// `active_flag`
+    pub active: bool,
+}
+
+impl Resource {
+    /// Rate of the resource in effect at `date`
+    pub fn rate_at<'a>(
+        &self,
+        rates: &'a ResourceRates,
+        date: NaiveDateTime,
+    ) -> Option<&'a ResourceRate> {
+        rates.rate_for(self.rsrc_id, date)
+    }
+}
+
+impl FromRow for Resource {
+    const TABLE: &'static str = "RSRC";
+
+    fn from_row(table: &Table, row: &[String]) -> Result<Self, ParseError> {
+        let f = Fields::new(table, row);
+        Ok(Self {
+            rsrc_id: f.parse("rsrc_id")?,
+            parent_rsrc_id: f.opt_parse("parent_rsrc_id")?,
+            rsrc_name: f.string("rsrc_name")?,
+            rsrc_short_name: f.string("rsrc_short_name")?,
+            rsrc_type: f.code("rsrc_type", ResourceType::from_code)?,
+            clndr_id: f.opt_parse("clndr_id")?,
+            role_id: f.opt_parse("role_id")?,
+            unit_id: f.opt_parse("unit_id")?,
+            curr_id: f.opt_parse("curr_id")?,
+            default_units_per_hour: f.num("def_qty_per_hr")?,
+            overtime_factor: f.num("ot_factor")?,
+            seq_num: f.opt_parse("rsrc_seq_num")?.unwrap_or_default(),
+            active: f.flag("active_flag")?,
+        })
+    }
+}
+
+impl TreeNode for Resource {
+    fn id(&self) -> u64 {
+        self.rsrc_id
+    }
+
+    fn parent_id(&self) -> Option<u64> {
+        self.parent_rsrc_id
+    }
+
+    fn seq(&self) -> i64 {
+        self.seq_num
+    }
+}
+
+/// Resource hierarchy, [`Tree::get`] looks resources up by `rsrc_id`
+pub type ResourceTree = Tree<Resource>;
+
+/// `rsrc_type` of a resource
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ResourceType {
+    Labor,
+    Nonlabor,
+    Material,
+}
+
+impl ResourceType {
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code {
+            "RT_Labor" => Some(Self::Labor),
+            "RT_Equip" => Some(Self::Nonlabor),
+            "RT_Mat" => Some(Self::Material),
+            _ => None,
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Labor => "RT_Labor",
+            Self::Nonlabor => "RT_Equip",
+            Self::Material => "RT_Mat",
+        }
+    }
+}
+
+/// Price of a resource effective from `start_date`, from the `RSRCRATE` table
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResourceRate {
+    pub rsrc_rate_id: u64,
+    pub rsrc_id: u64,
+    /// `cost_per_qty` .. `cost_per_qty5`, standard price is the first one
+    pub prices: [f64; 5],
+    /// Empty means effective since the beginning
+    pub start_date: Option<NaiveDateTime>,
+    /// `max_qty_per_hr`
+    pub max_units_per_hour: f64,
+    pub shift_period_id: Option<u64>,
+}
+
+impl ResourceRate {
+    /// Standard price per unit
+    pub fn price(&self) -> f64 {
+        self.prices[0]
+    }
+}
+
+impl FromRow for ResourceRate {
+    const TABLE: &'static str = "RSRCRATE";
+
+    fn from_row(table: &Table, row: &[String]) -> Result<Self, ParseError> {
+        let f = Fields::new(table, row);
+        Ok(Self {
+            rsrc_rate_id: f.parse("rsrc_rate_id")?,
+            rsrc_id: f.parse("rsrc_id")?,
+            prices: [
+                f.num("cost_per_qty")?,
+                f.num("cost_per_qty2")?,
+                f.num("cost_per_qty3")?,
+                f.num("cost_per_qty4")?,
+                f.num("cost_per_qty5")?,
+            ],
+            start_date: f.opt_datetime("start_date")?,
+            max_units_per_hour: f.num("max_qty_per_hr")?,
+            shift_period_id: f.opt_parse("shift_period_id")?,
+        })
+    }
+}
+
+/// Resource rates grouped by resource and ordered by effective date
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ResourceRates {
+    by_resource: HashMap<u64, Vec<ResourceRate>>,
+}
+
+impl ResourceRates {
+    pub fn new(rates: Vec<ResourceRate>) -> Self {
+        let mut by_resource: HashMap<u64, Vec<ResourceRate>> = HashMap::new();
+        for rate in rates {
+            by_resource.entry(rate.rsrc_id).or_default().push(rate);
+        }
+        for rates in by_resource.values_mut() {
+            rates.sort_by_key(|rate| rate.start_date);
+        }
+        Self { by_resource }
+    }
+
+    /// All rates of `rsrc_id` ordered by effective date
+    pub fn rates(&self, rsrc_id: u64) -> &[ResourceRate] {
+        self.by_resource.get(&rsrc_id).map_or(&[], Vec::as_slice)
+    }
+
+    /// Rate of `rsrc_id` in effect at `date`
+    pub fn rate_for(&self, rsrc_id: u64, date: NaiveDateTime) -> Option<&ResourceRate> {
+        effective(self.rates(rsrc_id), date, |rate| rate.start_date)
+    }
+}
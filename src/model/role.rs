@@ -0,0 +1,122 @@
+use std::collections::HashMap;
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+use super::{effective, Fields, FromRow};
+use crate::error::ParseError;
+use crate::parser::Table;
+use crate::tree::{Tree, TreeNode};
+
+/// Role from the `ROLES` table
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Role {
+    pub role_id: u64,
+    pub parent_role_id: Option<u64>,
+    pub role_name: String,
+    /// Role ID shown to users
+    pub role_short_name: String,
+    pub seq_num: i64,
+}
+
+impl FromRow for Role {
+    const TABLE: &'static str = "ROLES";
+
+    fn from_row(table: &Table, row: &[String]) -> Result<Self, ParseError> {
+        let f = Fields::new(table, row);
+        Ok(Self {
+            role_id: f.parse("role_id")?,
+            parent_role_id: f.opt_parse("parent_role_id")?,
+            role_name: f.string("role_name")?,
+            role_short_name: f.string("role_short_name")?,
+            seq_num: f.opt_parse("seq_num")?.unwrap_or_default(),
+        })
+    }
+}
+
+impl TreeNode for Role {
+    fn id(&self) -> u64 {
+        self.role_id
+    }
+
+    fn parent_id(&self) -> Option<u64> {
+        self.parent_role_id
+    }
+
+    fn seq(&self) -> i64 {
+        self.seq_num
+    }
+}
+
+/// Role hierarchy
+pub type RoleTree = Tree<Role>;
+
+/// Price of a role effective from `start_date`, from the `ROLERATE` table
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RoleRate {
+    pub role_rate_id: u64,
+    pub role_id: u64,
+    /// `cost_per_qty` .. `cost_per_qty5`, standard price is the first one
+    pub prices: [f64; 5],
+    /// Empty means effective since the beginning
+    pub start_date: Option<NaiveDateTime>,
+    /// `max_qty_per_hr`
+    pub max_units_per_hour: f64,
+}
+
+impl RoleRate {
+    /// Standard price per unit
+    pub fn price(&self) -> f64 {
+        self.prices[0]
+    }
+}
+
+impl FromRow for RoleRate {
+    const TABLE: &'static str = "ROLERATE";
+
+    fn from_row(table: &Table, row: &[String]) -> Result<Self, ParseError> {
+        let f = Fields::new(table, row);
+        Ok(Self {
+            role_rate_id: f.parse("role_rate_id")?,
+            role_id: f.parse("role_id")?,
+            prices: [
+                f.num("cost_per_qty")?,
+                f.num("cost_per_qty2")?,
+                f.num("cost_per_qty3")?,
+                f.num("cost_per_qty4")?,
+                f.num("cost_per_qty5")?,
+            ],
+            start_date: f.opt_datetime("start_date")?,
+            max_units_per_hour: f.num("max_qty_per_hr")?,
+        })
+    }
+}
+
+/// Role rates grouped by role and ordered by effective date
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RoleRates {
+    by_role: HashMap<u64, Vec<RoleRate>>,
+}
+
+impl RoleRates {
+    pub fn new(rates: Vec<RoleRate>) -> Self {
+        let mut by_role: HashMap<u64, Vec<RoleRate>> = HashMap::new();
+        for rate in rates {
+            by_role.entry(rate.role_id).or_default().push(rate);
+        }
+        for rates in by_role.values_mut() {
+            rates.sort_by_key(|rate| rate.start_date);
+        }
+        Self { by_role }
+    }
+
+    /// All rates of `role_id` ordered by effective date
+    pub fn rates(&self, role_id: u64) -> &[RoleRate] {
+        self.by_role.get(&role_id).map_or(&[], Vec::as_slice)
+    }
+
+    /// Rate of `role_id` in effect at `date`
+    pub fn rate_for(&self, role_id: u64, date: NaiveDateTime) -> Option<&RoleRate> {
+        effective(self.rates(role_id), date, |rate| rate.start_date)
+    }
+}
@@ -0,0 +1,137 @@
+use serde::{Deserialize, Serialize};
+
+use super::{Fields, FromRow};
+use crate::error::ParseError;
+use crate::parser::Table;
+
+/// Scheduling options of a project from the `SCHEDOPTIONS` table
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SchedOptions {
+    pub schedoptions_id: u64,
+    pub proj_id: u64,
+    /// `sched_retained_logic` and `sched_progress_override`
+    pub out_of_sequence: OutOfSequence,
+    /// `sched_calendar_on_relationship_lag`
+    pub lag_calendar: LagCalendar,
+    /// `sched_float_type`
+    pub float_type: FloatType,
+    /// `sched_open_critical_flag`, activities without successors are critical
+    pub open_ends_critical: bool,
+    /// `sched_lag_early_start_flag`, start to start lag counts from early start
+    pub lag_from_early_start: bool,
+    /// `sched_use_expect_end_flag`
+    pub use_expected_finish: bool,
+    /// `sched_use_project_end_date_for_float`
+    pub float_to_project_finish: bool,
+    /// Activities with total float at or below this many hours are critical
+    ///
+    /// Stored in `PROJECT.critical_drtn_hr_cnt`, filled by
+    /// [`XerFile::schedule_options`](crate::XerFile::schedule_options).
+    pub critical_float: Option<f64>,
+}
+
+impl FromRow for SchedOptions {
+    const TABLE: &'static str = "SCHEDOPTIONS";
+
+    fn from_row(table: &Table, row: &[String]) -> Result<Self, ParseError> {
+        let f = Fields::new(table, row);
+        let out_of_sequence = match (
+            f.flag("sched_retained_logic")?,
+            f.flag("sched_progress_override")?,
+        ) {
+            (true, _) => OutOfSequence::RetainedLogic,
+            (false, true) => OutOfSequence::ProgressOverride,
+            (false, false) => OutOfSequence::ActualDates,
+        };
+        Ok(Self {
+            schedoptions_id: f.parse("schedoptions_id")?,
+            proj_id: f.parse("proj_id")?,
+            out_of_sequence,
+            lag_calendar: match f.opt_str("sched_calendar_on_relationship_lag") {
+                Some(_) => f.code("sched_calendar_on_relationship_lag", LagCalendar::from_code)?,
+                None => LagCalendar::Predecessor,
+            },
+            float_type: match f.opt_str("sched_float_type") {
+                Some(_) => f.code("sched_float_type", FloatType::from_code)?,
+                None => FloatType::Finish,
+            },
+            open_ends_critical: f.flag("sched_open_critical_flag")?,
+            lag_from_early_start: f.flag("sched_lag_early_start_flag")?,
+            use_expected_finish: f.flag("sched_use_expect_end_flag")?,
+            float_to_project_finish: f.flag("sched_use_project_end_date_for_float")?,
+            critical_float: f.opt_parse("critical_drtn_hr_cnt")?,
+        })
+    }
+}
+
+/// How out of sequence progress is scheduled
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum OutOfSequence {
+    /// Remaining work waits for predecessors
+    RetainedLogic,
+    /// Logic of progressed activities is ignored
+    ProgressOverride,
+    /// Actual dates drive successors
+    ActualDates,
+}
+
+/// Calendar used to count relationship lag
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum LagCalendar {
+    Predecessor,
+    Successor,
+    /// 24 hour calendar
+    TwentyFourHour,
+    ProjectDefault,
+}
+
+impl LagCalendar {
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code {
+            "rcal_Predecessor" => Some(Self::Predecessor),
+            "rcal_Successor" => Some(Self::Successor),
+            "rcal_24Hour" => Some(Self::TwentyFourHour),
+            "rcal_ProjDefault" => Some(Self::ProjectDefault),
+            _ => None,
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Predecessor => "rcal_Predecessor",
+            Self::Successor => "rcal_Successor",
+            Self::TwentyFourHour => "rcal_24Hour",
+            Self::ProjectDefault => "rcal_ProjDefault",
+        }
+    }
+}
+
+/// How total float is computed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum FloatType {
+    /// Late start minus early start
+    Start,
+    /// Late finish minus early finish
+    Finish,
+    /// Smallest of start and finish float
+    Smallest,
+}
+
+impl FloatType {
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code {
+            "FT_SF" => Some(Self::Start),
+            "FT_FF" => Some(Self::Finish),
+            "FT_SmallestFloat" => Some(Self::Smallest),
+            _ => None,
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Start => "FT_SF",
+            Self::Finish => "FT_FF",
+            Self::Smallest => "FT_SmallestFloat",
+        }
+    }
+}
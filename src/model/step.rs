@@ -0,0 +1,96 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::{Fields, FromRow};
+use crate::error::ParseError;
+use crate::parser::Table;
+
+/// Activity step from the `TASKPROC` table
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Step {
+    pub proc_id: u64,
+    pub task_id: u64,
+    pub proj_id: Option<u64>,
+    pub seq_num: i64,
+    pub proc_name: String,
+    /// `proc_wt`
+    pub weight: f64,
+    /// `complete_flag`
+    pub complete: bool,
+    /// `complete_pct`
+    pub percent_complete: f64,
+}
+
+impl Step {
+    /// Percent complete, 100 for steps flagged complete
+    pub fn progress(&self) -> f64 {
+        if self.complete {
+            100.0
+        } else {
+            self.percent_complete
+        }
+    }
+}
+
+impl FromRow for Step {
+    const TABLE: &'static str = "TASKPROC";
+
+    fn from_row(table: &Table, row: &[String]) -> Result<Self, ParseError> {
+        let f = Fields::new(table, row);
+        Ok(Self {
+            proc_id: f.parse("proc_id")?,
+            task_id: f.parse("task_id")?,
+            proj_id: f.opt_parse("proj_id")?,
+            seq_num: f.opt_parse("seq_num")?.unwrap_or_default(),
+            proc_name: f.string("proc_name")?,
+            weight: f.num("proc_wt")?,
+            complete: f.flag("complete_flag")?,
+            percent_complete: f.num("complete_pct")?,
+        })
+    }
+}
+
+/// Steps grouped by task and ordered by `seq_num`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Steps {
+    by_task: HashMap<u64, Vec<Step>>,
+}
+
+impl Steps {
+    pub fn new(steps: Vec<Step>) -> Self {
+        let mut by_task: HashMap<u64, Vec<Step>> = HashMap::new();
+        for step in steps {
+            by_task.entry(step.task_id).or_default().push(step);
+        }
+        for steps in by_task.values_mut() {
+            steps.sort_by_key(|step| (step.seq_num, step.proc_id));
+        }
+        Self { by_task }
+    }
+
+    /// Steps of activity `task_id`
+    pub fn for_task(&self, task_id: u64) -> &[Step] {
+        self.by_task.get(&task_id).map_or(&[], Vec::as_slice)
+    }
+
+    /// Weighted percent complete of the steps of `task_id`, `None` without steps
+    ///
+    /// Steps count equally when all weights are zero.
+    pub fn percent_complete(&self, task_id: u64) -> Option<f64> {
+        let steps = self.for_task(task_id);
+        if steps.is_empty() {
+            return None;
+        }
+        let total_weight = steps.iter().fold(0.0, |sum, step| sum + step.weight);
+        let weighted = if total_weight > 0.0 {
+            steps
+                .iter()
+                .fold(0.0, |sum, step| sum + step.weight * step.progress())
+                / total_weight
+        } else {
+            steps.iter().fold(0.0, |sum, step| sum + step.progress()) / steps.len() as f64
+        };
+        Some(weighted)
+    }
+}
@@ -0,0 +1,208 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+use super::{Fields, FromRow, Step, Steps};
+use crate::error::ParseError;
+use crate::parser::Table;
+
+/// Activity from the `TASK` table
+///
+/// Durations and floats are in hours as stored by P6.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Task {
+    pub task_id: u64,
+    pub proj_id: u64,
+    pub wbs_id: u64,
+    pub clndr_id: Option<u64>,
+    /// Activity ID shown to users
+    pub task_code: String,
+    pub task_name: String,
+    pub task_type: TaskType,
+    /// `status_code`
+    pub status: TaskStatus,
+    /// `phys_complete_pct`
+    pub physical_percent_complete: f64,
+    /// `target_drtn_hr_cnt`
+    pub planned_duration: f64,
+    /// `remain_drtn_hr_cnt`
+    pub remaining_duration: f64,
+    /// `total_float_hr_cnt`, empty for completed activities
+    pub total_float: Option<f64>,
+    /// `free_float_hr_cnt`
+    pub free_float: Option<f64>,
+    /// `target_start_date`
+    pub planned_start: Option<NaiveDateTime>,
+    /// `target_end_date`
+    pub planned_finish: Option<NaiveDateTime>,
+    /// `early_start_date`
+    pub early_start: Option<NaiveDateTime>,
+    /// `early_end_date`
+    pub early_finish: Option<NaiveDateTime>,
+    /// `late_start_date`
+    pub late_start: Option<NaiveDateTime>,
+    /// `late_end_date`
+    pub late_finish: Option<NaiveDateTime>,
+    /// `act_start_date`
+    pub actual_start: Option<NaiveDateTime>,
+    /// `act_end_date`
+    pub actual_finish: Option<NaiveDateTime>,
+    /// `restart_date`
+    pub remaining_start: Option<NaiveDateTime>,
+    /// `reend_date`
+    pub remaining_finish: Option<NaiveDateTime>,
+    /// `cstr_type`, e.g. `CS_MSOA`
+    pub constraint_type: Option<String>,
+    /// `cstr_date`
+    pub constraint_date: Option<NaiveDateTime>,
+    /// `cstr_type2`
+    pub secondary_constraint_type: Option<String>,
+    /// `cstr_date2`
+    pub secondary_constraint_date: Option<NaiveDateTime>,
+    /// `complete_pct_type`, e.g. `CP_Phys`
+    pub percent_complete_type: Option<String>,
+    /// `driving_path_flag`, activity is on the longest path
+    pub driving_path: bool,
+}
+
+impl Task {
+    /// Actual start if started, otherwise early start
+    pub fn start(&self) -> Option<NaiveDateTime> {
+        self.actual_start.or(self.early_start)
+    }
+
+    /// Actual finish if completed, otherwise early finish
+    pub fn finish(&self) -> Option<NaiveDateTime> {
+        self.actual_finish.or(self.early_finish)
+    }
+
+    /// Steps of the activity ordered by `seq_num`
+    pub fn steps<'a>(&self, steps: &'a Steps) -> &'a [Step] {
+        steps.for_task(self.task_id)
+    }
+
+    /// Weighted percent complete of the steps of the activity
+    pub fn step_percent_complete(&self, steps: &Steps) -> Option<f64> {
+        steps.percent_complete(self.task_id)
+    }
+
+    /// Share of the planned duration no longer remaining, `1` if completed
+    pub fn duration_percent_complete(&self) -> f64 {
+        match self.status {
+            TaskStatus::Completed => 1.0,
+            TaskStatus::NotStarted => 0.0,
+            TaskStatus::InProgress if self.planned_duration <= 0.0 => 0.0,
+            TaskStatus::InProgress => {
+                (1.0 - self.remaining_duration / self.planned_duration).clamp(0.0, 1.0)
+            }
+        }
+    }
+
+    pub fn is_milestone(&self) -> bool {
+        matches!(
+            self.task_type,
+            TaskType::StartMilestone | TaskType::FinishMilestone
+        )
+    }
+}
+
+impl FromRow for Task {
+    const TABLE: &'static str = "TASK";
+
+    fn from_row(table: &Table, row: &[String]) -> Result<Self, ParseError> {
+        let f = Fields::new(table, row);
+        Ok(Self {
+            task_id: f.parse("task_id")?,
+            proj_id: f.parse("proj_id")?,
+            wbs_id: f.parse("wbs_id")?,
+            clndr_id: f.opt_parse("clndr_id")?,
+            task_code: f.string("task_code")?,
+            task_name: f.string("task_name")?,
+            task_type: f.code("task_type", TaskType::from_code)?,
+            status: f.code("status_code", TaskStatus::from_code)?,
+            physical_percent_complete: f.num("phys_complete_pct")?,
+            planned_duration: f.num("target_drtn_hr_cnt")?,
+            remaining_duration: f.num("remain_drtn_hr_cnt")?,
+            total_float: f.opt_parse("total_float_hr_cnt")?,
+            free_float: f.opt_parse("free_float_hr_cnt")?,
+            planned_start: f.opt_datetime("target_start_date")?,
+            planned_finish: f.opt_datetime("target_end_date")?,
+            early_start: f.opt_datetime("early_start_date")?,
+            early_finish: f.opt_datetime("early_end_date")?,
+            late_start: f.opt_datetime("late_start_date")?,
+            late_finish: f.opt_datetime("late_end_date")?,
+            actual_start: f.opt_datetime("act_start_date")?,
+            actual_finish: f.opt_datetime("act_end_date")?,
+            remaining_start: f.opt_datetime("restart_date")?,
+            remaining_finish: f.opt_datetime("reend_date")?,
+            constraint_type: f.opt_string("cstr_type"),
+            constraint_date: f.opt_datetime("cstr_date")?,
+            secondary_constraint_type: f.opt_string("cstr_type2"),
+            secondary_constraint_date: f.opt_datetime("cstr_date2")?,
+            percent_complete_type: f.opt_string("complete_pct_type"),
+            driving_path: f.flag("driving_path_flag")?,
+        })
+    }
+}
+
+/// `status_code` of an activity
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TaskStatus {
+    NotStarted,
+    InProgress,
+    Completed,
+}
+
+impl TaskStatus {
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code {
+            "TK_NotStart" => Some(Self::NotStarted),
+            "TK_Active" => Some(Self::InProgress),
+            "TK_Complete" => Some(Self::Completed),
+            _ => None,
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::NotStarted => "TK_NotStart",
+            Self::InProgress => "TK_Active",
+            Self::Completed => "TK_Complete",
+        }
+    }
+}
+
+/// `task_type` of an activity
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TaskType {
+    TaskDependent,
+    ResourceDependent,
+    LevelOfEffort,
+    StartMilestone,
+    FinishMilestone,
+    WbsSummary,
+}
+
+impl TaskType {
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code {
+            "TT_Task" => Some(Self::TaskDependent),
+            "TT_Rsrc" => Some(Self::ResourceDependent),
+            "TT_LOE" => Some(Self::LevelOfEffort),
+            "TT_Mile" => Some(Self::StartMilestone),
+            "TT_FinMile" => Some(Self::FinishMilestone),
+            "TT_WBS" => Some(Self::WbsSummary),
+            _ => None,
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::TaskDependent => "TT_Task",
+            Self::ResourceDependent => "TT_Rsrc",
+            Self::LevelOfEffort => "TT_LOE",
+            Self::StartMilestone => "TT_Mile",
+            Self::FinishMilestone => "TT_FinMile",
+            Self::WbsSummary => "TT_WBS",
+        }
+    }
+}
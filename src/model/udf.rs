@@ -0,0 +1,198 @@
+use std::collections::HashMap;
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+use super::{Fields, FromRow};
+use crate::error::ParseError;
+use crate::parser::Table;
+
+/// User defined field definition from the `UDFTYPE` table
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UdfType {
+    pub udf_type_id: u64,
+    /// Table the field extends, e.g. `TASK`, `PROJWBS`
+    pub table_name: String,
+    /// Internal name, e.g. `user_field_1`
+    pub udf_type_name: String,
+    /// Title shown to users
+    pub udf_type_label: String,
+    pub data_type: UdfDataType,
+}
+
+impl FromRow for UdfType {
+    const TABLE: &'static str = "UDFTYPE";
+
+    fn from_row(table: &Table, row: &[String]) -> Result<Self, ParseError> {
+        let f = Fields::new(table, row);
+        Ok(Self {
+            udf_type_id: f.parse("udf_type_id")?,
+            table_name: f.string("table_name")?,
+            udf_type_name: f.string("udf_type_name")?,
+            udf_type_label: f.string("udf_type_label")?,
+            data_type: f.code("logical_data_type", UdfDataType::from_code)?,
+        })
+    }
+}
+
+/// `logical_data_type` of a user defined field
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum UdfDataType {
+    Text,
+    StartDate,
+    FinishDate,
+    Cost,
+    Number,
+    Integer,
+    Indicator,
+    Code,
+}
+
+impl UdfDataType {
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code {
+            "FT_TEXT" => Some(Self::Text),
+            "FT_START_DATE" => Some(Self::StartDate),
+            "FT_END_DATE" => Some(Self::FinishDate),
+            "FT_MONEY" => Some(Self::Cost),
+            "FT_FLOAT_2_DECIMALS" => Some(Self::Number),
+            "FT_INT" => Some(Self::Integer),
+            "FT_STATICTYPE" => Some(Self::Indicator),
+            "FT_CODE" => Some(Self::Code),
+            _ => None,
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Text => "FT_TEXT",
+            Self::StartDate => "FT_START_DATE",
+            Self::FinishDate => "FT_END_DATE",
+            Self::Cost => "FT_MONEY",
+            Self::Number => "FT_FLOAT_2_DECIMALS",
+            Self::Integer => "FT_INT",
+            Self::Indicator => "FT_STATICTYPE",
+            Self::Code => "FT_CODE",
+        }
+    }
+}
+
+/// Stored user defined field value from the `UDFVALUE` table
+///
+/// Only one of value columns is filled depending on field type, see [`UdfValue::decode`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UdfValue {
+    pub udf_type_id: u64,
+    /// Id of the record value belongs to, table is defined by [`UdfType::table_name`]
+    pub fk_id: u64,
+    pub proj_id: Option<u64>,
+    pub udf_date: Option<NaiveDateTime>,
+    pub udf_number: Option<f64>,
+    pub udf_text: Option<String>,
+    pub udf_code_id: Option<u64>,
+}
+
+impl UdfValue {
+    /// Pick value column matching `data_type`
+    pub fn decode(&self, data_type: UdfDataType) -> Option<UdfData> {
+        match data_type {
+            UdfDataType::Text => self.udf_text.clone().map(UdfData::Text),
+            UdfDataType::Indicator => self.udf_text.clone().map(UdfData::Indicator),
+            UdfDataType::StartDate | UdfDataType::FinishDate => self.udf_date.map(UdfData::Date),
+            UdfDataType::Cost => self.udf_number.map(UdfData::Cost),
+            UdfDataType::Number => self.udf_number.map(UdfData::Number),
+            UdfDataType::Integer => self.udf_number.map(|n| UdfData::Integer(n as i64)),
+            UdfDataType::Code => self.udf_code_id.map(UdfData::Code),
+        }
+    }
+}
+
+impl FromRow for UdfValue {
+    const TABLE: &'static str = "UDFVALUE";
+
+    fn from_row(table: &Table, row: &[String]) -> Result<Self, ParseError> {
+        let f = Fields::new(table, row);
+        Ok(Self {
+            udf_type_id: f.parse("udf_type_id")?,
+            fk_id: f.parse("fk_id")?,
+            proj_id: f.opt_parse("proj_id")?,
+            udf_date: f.opt_datetime("udf_date")?,
+            udf_number: f.opt_parse("udf_number")?,
+            udf_text: f.opt_string("udf_text"),
+            udf_code_id: f.opt_parse("udf_code_id")?,
+        })
+    }
+}
+
+/// Decoded user defined field value
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum UdfData {
+    Text(String),
+    Date(NaiveDateTime),
+    Cost(f64),
+    Number(f64),
+    Integer(i64),
+    /// Indicator icon name
+    Indicator(String),
+    /// `udf_code_id` of `UDFCODE` table
+    Code(u64),
+}
+
+/// User defined fields with values looked up by owning record and field title
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Udfs {
+    types: HashMap<u64, UdfType>,
+    values: HashMap<(u64, u64), UdfValue>,
+}
+
+impl Udfs {
+    pub fn new(types: Vec<UdfType>, values: Vec<UdfValue>) -> Self {
+        Self {
+            types: types.into_iter().map(|t| (t.udf_type_id, t)).collect(),
+            values: values
+                .into_iter()
+                .map(|v| ((v.udf_type_id, v.fk_id), v))
+                .collect(),
+        }
+    }
+
+    /// Field definitions of `table_name`
+    pub fn types(&self, table_name: &str) -> impl Iterator<Item = &UdfType> + '_ {
+        let table_name = table_name.to_string();
+        self.types
+            .values()
+            .filter(move |t| t.table_name == table_name)
+    }
+
+    /// Value of field titled `title` for record `fk_id` of `table_name`
+    pub fn get(&self, table_name: &str, fk_id: u64, title: &str) -> Option<UdfData> {
+        let udf_type = self
+            .types
+            .values()
+            .find(|t| t.table_name == table_name && t.udf_type_label == title)?;
+        self.values
+            .get(&(udf_type.udf_type_id, fk_id))?
+            .decode(udf_type.data_type)
+    }
+
+    /// All decoded values of record `fk_id` of `table_name` keyed by field title
+    pub fn values(&self, table_name: &str, fk_id: u64) -> Vec<(&str, UdfData)> {
+        let mut values: Vec<_> = self
+            .types(table_name)
+            .filter_map(|t| {
+                let value = self.values.get(&(t.udf_type_id, fk_id))?;
+                Some((t.udf_type_label.as_str(), value.decode(t.data_type)?))
+            })
+            .collect();
+        values.sort_by_key(|(title, _)| *title);
+        values
+    }
+
+    pub fn for_task(&self, task_id: u64, title: &str) -> Option<UdfData> {
+        self.get("TASK", task_id, title)
+    }
+
+    pub fn for_wbs(&self, wbs_id: u64, title: &str) -> Option<UdfData> {
+        self.get("PROJWBS", wbs_id, title)
+    }
+}
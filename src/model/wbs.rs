@@ -0,0 +1,159 @@
+use std::collections::HashMap;
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+use super::{Assignments, Costs, Currency, Expenses, Fields, FromRow, Task};
+use crate::error::ParseError;
+use crate::parser::Table;
+use crate::tree::{Tree, TreeNode};
+
+/// WBS element from the `PROJWBS` table
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Wbs {
+    pub wbs_id: u64,
+    pub proj_id: u64,
+    /// Responsible manager
+    pub obs_id: Option<u64>,
+    /// Parent element, for project node points to EPS node absent in the export
+    pub parent_wbs_id: Option<u64>,
+    pub seq_num: i64,
+    /// WBS code shown to users
+    pub wbs_short_name: String,
+    pub wbs_name: String,
+    /// `proj_node_flag`, element represents the project itself
+    pub proj_node: bool,
+    /// `status_code`, e.g. `WS_Open`
+    pub status: Option<String>,
+}
+
+impl FromRow for Wbs {
+    const TABLE: &'static str = "PROJWBS";
+
+    fn from_row(table: &Table, row: &[String]) -> Result<Self, ParseError> {
+        let f = Fields::new(table, row);
+        Ok(Self {
+            wbs_id: f.parse("wbs_id")?,
+            proj_id: f.parse("proj_id")?,
+            obs_id: f.opt_parse("obs_id")?,
+            parent_wbs_id: f.opt_parse("parent_wbs_id")?,
+            seq_num: f.opt_parse("seq_num")?.unwrap_or_default(),
+            wbs_short_name: f.string("wbs_short_name")?,
+            wbs_name: f.string("wbs_name")?,
+            proj_node: f.flag("proj_node_flag")?,
+            status: f.opt_string("status_code"),
+        })
+    }
+}
+
+impl TreeNode for Wbs {
+    fn id(&self) -> u64 {
+        self.wbs_id
+    }
+
+    fn parent_id(&self) -> Option<u64> {
+        self.parent_wbs_id
+    }
+
+    fn seq(&self) -> i64 {
+        self.seq_num
+    }
+}
+
+/// WBS hierarchy, one root per project
+pub type WbsTree = Tree<Wbs>;
+
+/// Dates, durations and costs of a WBS element and its descendants
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WbsRollup {
+    pub wbs_id: u64,
+    /// `0` for roots
+    pub depth: usize,
+    pub activities: usize,
+    /// Earliest actual or early start
+    pub start: Option<NaiveDateTime>,
+    /// Latest actual or early finish
+    pub finish: Option<NaiveDateTime>,
+    /// Sum of the planned durations in hours
+    pub planned_duration: f64,
+    /// Sum of the remaining durations in hours
+    pub remaining_duration: f64,
+    /// Resource assignment and expense costs
+    pub costs: Costs,
+    /// Duration percent complete of the activities weighted by planned
+    /// duration, `0..=100`
+    pub percent_complete: f64,
+}
+
+impl WbsTree {
+    /// Rollup of `tasks` for every element in preorder
+    ///
+    /// Activities of elements not in the tree are left out. Without planned
+    /// durations the activities weigh the same in percent complete.
+    pub fn rollup(
+        &self,
+        tasks: &[Task],
+        assignments: &Assignments,
+        expenses: &Expenses,
+        currency: &Currency,
+    ) -> Vec<WbsRollup> {
+        #[derive(Default)]
+        struct Sums {
+            activities: usize,
+            start: Option<NaiveDateTime>,
+            finish: Option<NaiveDateTime>,
+            planned: f64,
+            remaining: f64,
+            costs: Option<Costs>,
+            // duration weighted and unweighted percent complete
+            weighted: f64,
+            unweighted: f64,
+        }
+
+        let mut sums: HashMap<u64, Sums> = HashMap::new();
+        for task in tasks {
+            let costs = assignments.task_costs_with_expenses(task.task_id, expenses, currency);
+            let percent = task.duration_percent_complete();
+            for element in self.path_to_root(task.wbs_id) {
+                let sums = sums.entry(element.wbs_id).or_default();
+                sums.activities += 1;
+                sums.start = sums.start.into_iter().chain(task.start()).min();
+                sums.finish = sums.finish.into_iter().chain(task.finish()).max();
+                sums.planned += task.planned_duration;
+                sums.remaining += task.remaining_duration;
+                sums.weighted += percent * task.planned_duration;
+                sums.unweighted += percent;
+                sums.costs = Some(match sums.costs.take() {
+                    Some(total) => total + costs.clone(),
+                    None => costs.clone(),
+                });
+            }
+        }
+
+        self.preorder()
+            .map(|(depth, element)| {
+                let sums = sums.remove(&element.wbs_id).unwrap_or_default();
+                let percent = if sums.planned > 0.0 {
+                    sums.weighted / sums.planned
+                } else if sums.activities > 0 {
+                    sums.unweighted / sums.activities as f64
+                } else {
+                    0.0
+                };
+                WbsRollup {
+                    wbs_id: element.wbs_id,
+                    depth,
+                    activities: sums.activities,
+                    start: sums.start,
+                    finish: sums.finish,
+                    planned_duration: sums.planned,
+                    remaining_duration: sums.remaining,
+                    costs: sums
+                        .costs
+                        .unwrap_or_else(|| Costs::zero(&currency.curr_short_name)),
+                    percent_complete: percent * 100.0,
+                }
+            })
+            .collect()
+    }
+}
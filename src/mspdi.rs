@@ -0,0 +1,379 @@
+//! Microsoft Project XML (MSPDI) export
+//!
+//! [`XerFile::to_mspdi`] writes all projects of the file as one Microsoft
+//! Project document:
+//!
+//! - WBS elements become summary tasks with their activities and child
+//!   elements below, the activity ID goes to `Text1`
+//! - durations and lags are in hours, shown in days, remaining durations and
+//!   percent complete carry the progress
+//! - relationships, calendars with their exceptions, resources and their
+//!   assignments are kept, roles and costs are not
+//!
+//! ## Example
+//! ```
+//! use schedule_parser::XerFile;
+//!
+//! let data = "ERMHDR\t19.12\t2024-03-15\n\
+//!     %T\tPROJWBS\n%F\twbs_id\tproj_id\twbs_short_name\twbs_name\tproj_node_flag\n%R\t1\t1\tP1\tPlant\tY\n\
+//!     %T\tTASK\n%F\ttask_id\tproj_id\twbs_id\ttask_code\ttask_name\ttask_type\tstatus_code\ttarget_drtn_hr_cnt\n\
+//!     %R\t1\t1\t1\tA1000\tDesign & survey\tTT_Task\tTK_NotStart\t40\n\
+//!     %R\t2\t1\t1\tA1010\tBuild\tTT_Task\tTK_NotStart\t80\n\
+//!     %T\tTASKPRED\n%F\ttask_pred_id\ttask_id\tpred_task_id\tpred_type\tlag_hr_cnt\n%R\t1\t2\t1\tPR_FS\t8\n%E\n";
+//! let xer = XerFile::parse(data.as_bytes()).unwrap();
+//!
+//! let xml = xer.to_mspdi().unwrap();
+//! assert!(xml.contains("<Name>Design &amp; survey</Name>"));
+//! assert!(xml.contains("<Duration>PT40H0M0S</Duration>"));
+//! assert!(xml.contains("<PredecessorUID>2</PredecessorUID>"));
+//! ```
+
+use std::collections::HashMap;
+
+use chrono::{NaiveDateTime, NaiveTime};
+
+use crate::error::ParseError;
+use crate::model::{
+    Calendar, LinkType, Resource, ResourceType, Task, TaskType, WbsTree, WorkPeriod,
+};
+use crate::xer::XerFile;
+use crate::xml::XmlWriter;
+
+impl XerFile {
+    /// Microsoft Project XML of the file, see [`mspdi`](crate::mspdi)
+    pub fn to_mspdi(&self) -> Result<String, ParseError> {
+        let projects = self.projects()?;
+        let calendars = self.calendars()?;
+        let tasks = self.tasks()?;
+        let graph = self.logic_graph()?;
+        let resources: Vec<Resource> = self.records()?;
+        let assignments = self.assignments()?;
+        let wbs = self.wbs_tree()?;
+
+        let calendar_uids: HashMap<u64, usize> = calendars
+            .iter()
+            .enumerate()
+            .map(|(i, calendar)| (calendar.clndr_id, i + 1))
+            .collect();
+        let project_calendar = projects
+            .first()
+            .and_then(|project| project.clndr_id)
+            .or_else(|| calendars.iter().find(|c| c.default).map(|c| c.clndr_id));
+        let day_hours = project_calendar
+            .and_then(|id| calendars.iter().find(|c| c.clndr_id == id))
+            .map_or(8.0, |calendar| calendar.day_hours);
+
+        // summary tasks for WBS elements followed by their activities,
+        // activities of elements not in the file last
+        let mut by_wbs: HashMap<u64, Vec<&Task>> = HashMap::new();
+        for task in &tasks {
+            if task.task_type != TaskType::WbsSummary {
+                by_wbs.entry(task.wbs_id).or_default().push(task);
+            }
+        }
+        let mut rows: Vec<(usize, Row)> = Vec::new();
+        for (depth, element) in wbs.preorder() {
+            rows.push((depth + 1, Row::Summary(element.wbs_id)));
+            for task in by_wbs.remove(&element.wbs_id).unwrap_or_default() {
+                rows.push((depth + 2, Row::Activity(task)));
+            }
+        }
+        for task in &tasks {
+            if task.task_type != TaskType::WbsSummary && by_wbs.contains_key(&task.wbs_id) {
+                rows.push((1, Row::Activity(task)));
+            }
+        }
+        let task_uids: HashMap<u64, usize> = rows
+            .iter()
+            .enumerate()
+            .filter_map(|(i, (_, row))| match row {
+                Row::Activity(task) => Some((task.task_id, i + 1)),
+                Row::Summary(_) => None,
+            })
+            .collect();
+
+        let mut xml = XmlWriter::new();
+        xml.open(
+            "Project",
+            &[("xmlns", "http://schemas.microsoft.com/project")],
+        );
+        xml.element("SaveVersion", 14);
+        if let Some(project) = projects.first() {
+            xml.element("Name", &project.proj_short_name);
+            xml.element("Title", &project.proj_short_name);
+        }
+        xml.element("ScheduleFromStart", 1);
+        xml.optional(
+            "StartDate",
+            tasks.iter().filter_map(Task::start).min().map(datetime),
+        );
+        xml.optional(
+            "FinishDate",
+            tasks.iter().filter_map(Task::finish).max().map(datetime),
+        );
+        xml.optional(
+            "StatusDate",
+            projects
+                .iter()
+                .filter_map(|project| project.data_date)
+                .max()
+                .map(datetime),
+        );
+        xml.optional(
+            "CalendarUID",
+            project_calendar.and_then(|id| calendar_uids.get(&id)),
+        );
+        xml.element("MinutesPerDay", (day_hours * 60.0).round());
+        xml.element("DurationFormat", 7);
+
+        xml.open("Calendars", &[]);
+        for (i, calendar) in calendars.iter().enumerate() {
+            write_calendar(&mut xml, i + 1, calendar, &calendar_uids);
+        }
+        xml.close();
+
+        xml.open("Tasks", &[]);
+        let mut outline: Vec<usize> = Vec::new();
+        for (i, (level, row)) in rows.iter().enumerate() {
+            outline.truncate(*level);
+            while outline.len() < *level {
+                outline.push(0);
+            }
+            outline[level - 1] += 1;
+            let outline_number: Vec<String> = outline.iter().map(usize::to_string).collect();
+
+            xml.open("Task", &[]);
+            xml.element("UID", i + 1);
+            xml.element("ID", i + 1);
+            match row {
+                Row::Summary(wbs_id) => {
+                    let element = wbs.get(*wbs_id).unwrap();
+                    xml.element("Name", &element.wbs_name);
+                    xml.element("WBS", wbs_code(&wbs, *wbs_id));
+                    xml.element("OutlineNumber", outline_number.join("."));
+                    xml.element("OutlineLevel", level);
+                    xml.element("Summary", 1);
+                }
+                Row::Activity(task) => {
+                    xml.element("Name", &task.task_name);
+                    if wbs.get(task.wbs_id).is_some() {
+                        xml.element("WBS", wbs_code(&wbs, task.wbs_id));
+                    }
+                    xml.element("OutlineNumber", outline_number.join("."));
+                    xml.element("OutlineLevel", level);
+                    write_task(&mut xml, task, &calendar_uids);
+                    for link in graph.predecessors(task.task_id) {
+                        let Some(uid) = task_uids.get(&link.pred_task_id) else {
+                            continue;
+                        };
+                        xml.open("PredecessorLink", &[]);
+                        xml.element("PredecessorUID", uid);
+                        xml.element("Type", link_type(link.link_type));
+                        // tenths of minutes
+                        xml.element("LinkLag", (link.lag * 600.0).round());
+                        xml.element("LagFormat", 7);
+                        xml.close();
+                    }
+                }
+            }
+            xml.close();
+        }
+        xml.close();
+
+        let resource_uids: HashMap<u64, usize> = resources
+            .iter()
+            .enumerate()
+            .map(|(i, resource)| (resource.rsrc_id, i + 1))
+            .collect();
+        xml.open("Resources", &[]);
+        for (i, resource) in resources.iter().enumerate() {
+            xml.open("Resource", &[]);
+            xml.element("UID", i + 1);
+            xml.element("ID", i + 1);
+            xml.element("Name", &resource.rsrc_name);
+            xml.element("Initials", &resource.rsrc_short_name);
+            let work = resource.rsrc_type != ResourceType::Material;
+            xml.element("Type", u8::from(work));
+            xml.optional(
+                "CalendarUID",
+                resource.clndr_id.and_then(|id| calendar_uids.get(&id)),
+            );
+            xml.close();
+        }
+        xml.close();
+
+        xml.open("Assignments", &[]);
+        let mut uid = 0;
+        for assignment in assignments.iter() {
+            let (Some(task), Some(resource)) = (
+                task_uids.get(&assignment.task_id),
+                assignment.rsrc_id.and_then(|id| resource_uids.get(&id)),
+            ) else {
+                continue;
+            };
+            uid += 1;
+            xml.open("Assignment", &[]);
+            xml.element("UID", uid);
+            xml.element("TaskUID", task);
+            xml.element("ResourceUID", resource);
+            xml.element("Work", duration(assignment.at_completion_units()));
+            xml.element("ActualWork", duration(assignment.actual_units));
+            xml.element("RemainingWork", duration(assignment.remaining_units));
+            xml.optional(
+                "Start",
+                assignment
+                    .actual_start
+                    .or(assignment.remaining_start)
+                    .or(assignment.planned_start)
+                    .map(datetime),
+            );
+            xml.optional(
+                "Finish",
+                assignment
+                    .actual_finish
+                    .or(assignment.remaining_finish)
+                    .or(assignment.planned_finish)
+                    .map(datetime),
+            );
+            xml.close();
+        }
+        xml.close();
+
+        xml.close();
+        Ok(xml.finish())
+    }
+}
+
+enum Row<'a> {
+    Summary(u64),
+    Activity(&'a Task),
+}
+
+fn write_task(xml: &mut XmlWriter, task: &Task, calendar_uids: &HashMap<u64, usize>) {
+    // fixed duration
+    xml.element("Type", 1);
+    xml.element("Summary", 0);
+    xml.element("Milestone", u8::from(task.is_milestone()));
+    xml.optional("Start", task.start().or(task.planned_start).map(datetime));
+    xml.optional(
+        "Finish",
+        task.finish().or(task.planned_finish).map(datetime),
+    );
+    xml.element("Duration", duration(task.planned_duration));
+    xml.element("DurationFormat", 7);
+    xml.element("RemainingDuration", duration(task.remaining_duration));
+    xml.element(
+        "PercentComplete",
+        (task.duration_percent_complete() * 100.0).round(),
+    );
+    xml.optional("ActualStart", task.actual_start.map(datetime));
+    xml.optional("ActualFinish", task.actual_finish.map(datetime));
+    let constraint = task.constraint_type.as_deref().and_then(constraint_type);
+    if let Some(constraint) = constraint {
+        xml.element("ConstraintType", constraint);
+        xml.optional("ConstraintDate", task.constraint_date.map(datetime));
+    }
+    xml.optional(
+        "CalendarUID",
+        task.clndr_id.and_then(|id| calendar_uids.get(&id)),
+    );
+    xml.element("Text1", &task.task_code);
+}
+
+fn write_calendar(
+    xml: &mut XmlWriter,
+    uid: usize,
+    calendar: &Calendar,
+    calendar_uids: &HashMap<u64, usize>,
+) {
+    xml.open("Calendar", &[]);
+    xml.element("UID", uid);
+    xml.element("Name", &calendar.clndr_name);
+    let base = calendar.base_clndr_id.and_then(|id| calendar_uids.get(&id));
+    xml.element("IsBaseCalendar", u8::from(base.is_none()));
+    xml.optional("BaseCalendarUID", base);
+    xml.open("WeekDays", &[]);
+    for (i, periods) in calendar.week.iter().enumerate() {
+        xml.open("WeekDay", &[]);
+        // 1 is Sunday, the week starts on Monday
+        xml.element("DayType", (i + 1) % 7 + 1);
+        write_periods(xml, periods);
+        xml.close();
+    }
+    for exception in &calendar.exceptions {
+        xml.open("WeekDay", &[]);
+        xml.element("DayType", 0);
+        xml.open("TimePeriod", &[]);
+        xml.element(
+            "FromDate",
+            datetime(exception.date.and_time(NaiveTime::MIN)),
+        );
+        xml.element(
+            "ToDate",
+            datetime(exception.date.and_hms_opt(23, 59, 0).unwrap()),
+        );
+        xml.close();
+        write_periods(xml, &exception.periods);
+        xml.close();
+    }
+    xml.close();
+    xml.close();
+}
+
+fn write_periods(xml: &mut XmlWriter, periods: &[WorkPeriod]) {
+    xml.element("DayWorking", u8::from(!periods.is_empty()));
+    if periods.is_empty() {
+        return;
+    }
+    xml.open("WorkingTimes", &[]);
+    for period in periods {
+        xml.open("WorkingTime", &[]);
+        xml.element("FromTime", period.start.format("%H:%M:%S"));
+        xml.element("ToTime", period.finish.format("%H:%M:%S"));
+        xml.close();
+    }
+    xml.close();
+}
+
+/// WBS short names from the project node down, joined by `.`
+fn wbs_code(wbs: &WbsTree, wbs_id: u64) -> String {
+    let mut path: Vec<&str> = wbs
+        .path_to_root(wbs_id)
+        .map(|node| node.wbs_short_name.as_str())
+        .collect();
+    path.reverse();
+    path.join(".")
+}
+
+fn datetime(date: NaiveDateTime) -> String {
+    date.format("%Y-%m-%dT%H:%M:%S").to_string()
+}
+
+/// Hours as `PT40H30M0S`
+fn duration(hours: f64) -> String {
+    let minutes = (hours.max(0.0) * 60.0).round() as u64;
+    format!("PT{}H{}M0S", minutes / 60, minutes % 60)
+}
+
+fn link_type(link_type: LinkType) -> u8 {
+    match link_type {
+        LinkType::FinishToFinish => 0,
+        LinkType::FinishToStart => 1,
+        LinkType::StartToFinish => 2,
+        LinkType::StartToStart => 3,
+    }
+}
+
+/// MS Project constraint of a `cstr_type`
+fn constraint_type(code: &str) -> Option<u8> {
+    match code {
+        "CS_ALAP" => Some(1),
+        "CS_MSO" | "CS_MANDSTART" => Some(2),
+        "CS_MEO" | "CS_MANDFIN" => Some(3),
+        "CS_MSOA" => Some(4),
+        "CS_MSOB" => Some(5),
+        "CS_MEOA" => Some(6),
+        "CS_MEOB" => Some(7),
+        _ => None,
+    }
+}
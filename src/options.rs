@@ -0,0 +1,156 @@
+//! Parser settings and the warnings collected in lenient mode
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// Settings for [`XerReader`](crate::XerReader), see [`XerReader::with_options`](crate::XerReader::with_options)
+///
+/// ## Example
+/// ```
+/// use std::io::Cursor;
+/// use schedule_parser::{ParseOptions, XerReader};
+///
+/// let data = "ERMHDR\t19.12\t2024-03-15\n%T\tCURRTYPE\n%F\tcurr_id\tcurr_short_name\n%R\t1\tEUR\tjunk\n%E\n";
+/// let mut tables = XerReader::from_reader(Cursor::new(data))
+///     .unwrap()
+///     .with_options(ParseOptions { lenient: true, ..Default::default() })
+///     .tables();
+/// let table = tables.next().unwrap().unwrap();
+/// assert_eq!(table.rows[0], ["1", "EUR"]);
+/// assert_eq!(tables.warnings()[0].line, 4);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ParseOptions {
+    /// Repair or skip malformed records instead of failing, each one is
+    /// reported as a [`Warning`]
+    ///
+    /// Rows are cut or padded to the width of the header, stray lines and
+    /// tables without name or columns are skipped, invalid UTF-8 is replaced.
+    pub lenient: bool,
+    /// Fail on rows whose value count differs from the `%F` header with
+    /// [`ParseError::RowWidth`](crate::ParseError::RowWidth)
+    ///
+    /// By default such rows are kept as they are, in lenient mode they are repaired.
+    pub strict: bool,
+    /// Skip a table failing to parse up to the next `%T` instead of failing,
+    /// it is reported as [`WarningKind::TableSkipped`]
+    ///
+    /// Applies to tables read with [`TableIterator`](crate::TableIterator) and
+    /// [`XerFile::parse_with_options`](crate::XerFile::parse_with_options).
+    /// Unless lenient, rows followed by a stray record instead of `%T` or `%E`
+    /// count as garbled too.
+    pub recover: bool,
+    /// Handling of records with a prefix other than `%T`, `%F`, `%R` and `%E`
+    pub unknown_records: UnknownRecords,
+}
+
+/// What to do with records of unknown type, e.g. `%X` lines of vendor exports
+///
+/// ## Example
+/// ```
+/// use std::io::Cursor;
+/// use schedule_parser::{ParseOptions, UnknownRecords, XerReader};
+///
+/// let data = "ERMHDR\t19.12\t2024-03-15\n%T\tCURRTYPE\n%F\tcurr_id\n%R\t1\n%X\tvendor\n%R\t2\n%E\n";
+/// let options = ParseOptions { unknown_records: UnknownRecords::Warn, ..Default::default() };
+/// let mut tables = XerReader::from_reader(Cursor::new(data))
+///     .unwrap()
+///     .with_options(options)
+///     .tables();
+/// assert_eq!(tables.next().unwrap().unwrap().rows.len(), 2);
+/// assert_eq!(tables.warnings()[0].to_string(), r#"line 5: skipped unknown record: "%X\tvendor""#);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UnknownRecords {
+    /// Fail with [`ParseError::UnexpectedRecord`](crate::ParseError::UnexpectedRecord),
+    /// in lenient mode the line is skipped with a warning like any stray line
+    #[default]
+    Error,
+    /// Skip the line and report it as [`WarningKind::UnknownRecord`]
+    Warn,
+    /// Skip the line silently
+    Ignore,
+}
+
+/// Anomaly repaired or skipped in lenient mode
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Warning {
+    /// 1-based number of the offending line
+    pub line: usize,
+    pub kind: WarningKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum WarningKind {
+    /// Line is no record expected at this position, it was skipped
+    UnexpectedRecord { content: String },
+    /// `%T` record without table name, the table was skipped
+    MissingTableName { content: String },
+    /// `%T` record not followed by `%F`, the table was skipped
+    MissingColumns { table: String },
+    /// Record of unknown type, it was skipped, see [`UnknownRecords::Warn`]
+    UnknownRecord { content: String },
+    /// Table failed to parse with `error` and was skipped, `table` is `None`
+    /// if the error came before its name, see [`ParseOptions::recover`]
+    TableSkipped {
+        table: Option<String>,
+        error: String,
+    },
+    /// Line is not valid UTF-8, invalid bytes were replaced by `U+FFFD`
+    InvalidUtf8,
+    /// Row has more values than columns, extra values were dropped
+    RowTruncated {
+        table: String,
+        columns: usize,
+        values: usize,
+    },
+    /// Row has fewer values than columns, it was padded with empty values
+    RowPadded {
+        table: String,
+        columns: usize,
+        values: usize,
+    },
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: ", self.line)?;
+        match &self.kind {
+            WarningKind::UnexpectedRecord { content } => {
+                write!(f, "skipped unexpected record: {content:?}")
+            }
+            WarningKind::MissingTableName { content } => {
+                write!(f, "skipped table without name: {content:?}")
+            }
+            WarningKind::MissingColumns { table } => {
+                write!(f, "skipped table {table} without %F record")
+            }
+            WarningKind::UnknownRecord { content } => {
+                write!(f, "skipped unknown record: {content:?}")
+            }
+            WarningKind::TableSkipped {
+                table: Some(table),
+                error,
+            } => write!(f, "skipped table {table}: {error}"),
+            WarningKind::TableSkipped { table: None, error } => {
+                write!(f, "skipped up to next table: {error}")
+            }
+            WarningKind::InvalidUtf8 => write!(f, "replaced invalid UTF-8"),
+            WarningKind::RowTruncated {
+                table,
+                columns,
+                values,
+            } => write!(
+                f,
+                "{table}: dropped {} of {values} values",
+                values - columns
+            ),
+            WarningKind::RowPadded {
+                table,
+                columns,
+                values,
+            } => write!(f, "{table}: padded {values} values to {columns} columns"),
+        }
+    }
+}
@@ -0,0 +1,145 @@
+//! Parallel parsing of tables, enabled by the `parallel` feature
+
+use std::borrow::Cow;
+use std::path::Path;
+
+use indexmap::IndexMap;
+use rayon::prelude::*;
+
+use crate::encoding::{detect, SAMPLE_LEN, UTF_8};
+use crate::error::ParseError;
+use crate::mmap::MmapReader;
+use crate::parser::Table;
+use crate::slice::{lines_inclusive, parse_tables, split_header, to_str, trim_line};
+use crate::xer::XerFile;
+
+impl XerFile {
+    /// Map file at `path` and parse its tables on all cores
+    ///
+    /// Files not in UTF-8 are decoded in memory first.
+    pub fn from_path_parallel<P: AsRef<Path>>(path: P) -> Result<Self, ParseError> {
+        let map = MmapReader::open(path).map_err(|source| ParseError::Io { line: 0, source })?;
+        let data = map.as_bytes();
+        let encoding = detect(&data[..data.len().min(SAMPLE_LEN)]);
+        if encoding == UTF_8 {
+            return Self::parse_parallel(to_str(data)?);
+        }
+        Self::parse_parallel(&encoding.decode(data).0)
+    }
+
+    /// Parse tables of `data` in parallel, result is the same as of [`XerFile::parse`]
+    ///
+    /// Table boundaries are found in a quick pass over line starts, then each
+    /// table is split into rows on a worker thread of the rayon pool. NUL
+    /// bytes are dropped first, on a copy of `data` if it has any.
+    pub fn parse_parallel(data: &str) -> Result<Self, ParseError> {
+        let data = drop_nul(data.as_bytes());
+        // dropping NUL bytes keeps UTF-8 valid
+        let (header, body) = split_header(to_str(&data)?)?;
+
+        let chunks = table_chunks(body);
+        let results: Vec<Result<Vec<Table>, ParseError>> = chunks
+            .par_iter()
+            .map(|&(text, line)| {
+                let tables = parse_tables(text, line)?;
+                Ok(tables.iter().map(|table| table.to_table()).collect())
+            })
+            .collect();
+
+        // report the first error in file order
+        let mut tables = IndexMap::new();
+        for (i, result) in results.into_iter().enumerate() {
+            for mut table in result? {
+                // chunks before the last one end at the next `%T`, not with the file
+                if let Some(&(next, line)) = chunks.get(i + 1) {
+                    if lines_inclusive(chunks[i].0).nth(1).is_none() {
+                        let content = lines_inclusive(next).next().map(trim_line);
+                        return Err(ParseError::UnexpectedRecord {
+                            line,
+                            content: content.unwrap_or_default().into(),
+                        });
+                    }
+                    table.truncated = false;
+                }
+                tables.insert(table.name.clone(), table);
+            }
+        }
+        Ok(Self { header, tables })
+    }
+}
+
+/// `data` without NUL bytes, lines of NUL bytes only are dropped as by `XerReader`
+fn drop_nul(data: &[u8]) -> Cow<'_, [u8]> {
+    if memchr::memchr(0, data).is_none() {
+        return Cow::Borrowed(data);
+    }
+    let mut clean = Vec::with_capacity(data.len());
+    let mut rest = data;
+    while !rest.is_empty() {
+        let end = match memchr::memchr2(b'\n', b'\r', rest) {
+            Some(i) if rest[i..].starts_with(b"\r\n") => i + 2,
+            Some(i) => i + 1,
+            None => rest.len(),
+        };
+        let (line, tail) = rest.split_at(end);
+        let content = line.strip_suffix(b"\n").unwrap_or(line);
+        let content = content.strip_suffix(b"\r").unwrap_or(content);
+        if content.is_empty() || content.iter().any(|&b| b != 0) {
+            clean.extend(line.iter().filter(|&&b| b != 0));
+        }
+        rest = tail;
+    }
+    Cow::Owned(clean)
+}
+
+/// Text of every table in `body` with the line number it starts at
+///
+/// Lines before the first `%T` are dropped, and so is everything after `%E`.
+/// The last chunk keeps the `%E` line, so a chunk without it is cut off.
+fn table_chunks(body: &str) -> Vec<(&str, usize)> {
+    let mut chunks = Vec::new();
+    // start offset and line number of the table being collected
+    let mut current: Option<(usize, usize)> = None;
+    let mut offset = 0;
+    let mut end = body.len();
+    for (i, line) in lines_inclusive(body).enumerate() {
+        let number = i + 2;
+        if line.starts_with("%E") {
+            end = offset + line.len();
+            break;
+        }
+        if line.starts_with("%T") {
+            if let Some((start, line)) = current {
+                chunks.push((&body[start..offset], line));
+            }
+            current = Some((offset, number));
+        }
+        offset += line.len();
+    }
+    if let Some((start, line)) = current {
+        chunks.push((&body[start..end], line));
+    }
+    chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nul_padding_parses_as_with_xer_reader() {
+        let data = "ERMHDR\t19.12\t2024-03-15\n%T\tTASK\n%F\ttask_id\ttask_name\n\
+                    %R\t1\tDe\0sign\n\0\0\n%R\t2\tBuild\nsecond line\n\0\n%T\tCALENDAR\n\
+                    %F\tclndr_id\n%R\t1\n%E\n\0\0\0";
+        let parallel = XerFile::parse_parallel(data).unwrap();
+        let serial = XerFile::parse(data.as_bytes()).unwrap();
+        assert_eq!(parallel.header, serial.header);
+        assert!(parallel.tables.keys().eq(serial.tables.keys()));
+        for (table, expected) in parallel.tables.values().zip(serial.tables.values()) {
+            assert_eq!(table.header, expected.header);
+            assert_eq!(table.rows, expected.rows);
+            assert_eq!(table.truncated, expected.truncated);
+        }
+        assert_eq!(parallel.tables["TASK"].rows[0], ["1", "Design"]);
+    }
+}
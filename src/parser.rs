@@ -0,0 +1,1148 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, Seek, SeekFrom};
+use std::path::Path;
+
+use serde::ser::SerializeMap;
+use serde::{Deserialize, Serialize, Serializer};
+
+use crate::encoding::{DecodeReader, Encoding};
+use crate::error::ParseError;
+use crate::header::Header;
+use crate::intern::{InternedTable, InternedTables, Interner};
+use crate::model::FromRow;
+use crate::options::{ParseOptions, UnknownRecords, Warning, WarningKind};
+
+/// Intermediary structure for parsed tsv data
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(from = "TableData")]
+pub struct Table {
+    pub name: String,
+    pub header: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+    /// Input ended inside this table without `%E`, its last row may be cut off
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub truncated: bool,
+    /// Column name to position in `header`
+    #[serde(skip)]
+    index: HashMap<String, usize>,
+}
+
+/// Serialized form of [`Table`], index is rebuilt on load
+#[derive(Deserialize)]
+struct TableData {
+    name: String,
+    header: Vec<String>,
+    rows: Vec<Vec<String>>,
+    #[serde(default)]
+    truncated: bool,
+}
+
+impl From<TableData> for Table {
+    fn from(data: TableData) -> Self {
+        let mut table = Self::new(data.name, data.header, data.rows);
+        table.truncated = data.truncated;
+        table
+    }
+}
+
+impl Table {
+    pub fn new(name: String, header: Vec<String>, rows: Vec<Vec<String>>) -> Self {
+        let index = column_index(&header);
+        Self {
+            name,
+            header,
+            rows,
+            truncated: false,
+            index,
+        }
+    }
+
+    /// Deserialize rows into user type `T`, struct fields are matched by column name
+    ///
+    /// See [`de`](crate::de) for how values are converted.
+    pub fn deserialize<'a, T: Deserialize<'a>>(
+        &'a self,
+    ) -> impl Iterator<Item = Result<T, ParseError>> + 'a {
+        self.iter().map(|row| row.deserialize())
+    }
+
+    pub fn row_count(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Position of `column` in the header
+    pub fn column_index(&self, column: &str) -> Option<usize> {
+        self.index.get(column).copied()
+    }
+
+    /// Remove `column` from the header and all rows, `false` if there is none
+    pub fn drop_column(&mut self, column: &str) -> bool {
+        let Some(i) = self.column_index(column) else {
+            return false;
+        };
+        self.header.remove(i);
+        for row in &mut self.rows {
+            if i < row.len() {
+                row.remove(i);
+            }
+        }
+        self.index = column_index(&self.header);
+        true
+    }
+
+    /// Replace the header, rows must already be in its order
+    pub(crate) fn set_header(&mut self, header: Vec<String>) {
+        self.index = column_index(&header);
+        self.header = header;
+    }
+
+    /// Row `i` with access by column name
+    pub fn row(&self, i: usize) -> Option<Row<'_>> {
+        self.rows.get(i).map(|values| self.row_of(values))
+    }
+
+    /// Rows with access by column name
+    pub fn iter(&self) -> impl Iterator<Item = Row<'_>> {
+        self.rows.iter().map(|values| self.row_of(values))
+    }
+
+    pub(crate) fn row_of<'a>(&'a self, values: &'a [String]) -> Row<'a> {
+        Row::new(&self.name, &self.header, &self.index, values)
+    }
+
+    /// Convert rows to typed records, e.g. `table.records::<Task>()`
+    pub fn records<T: FromRow>(&self) -> impl Iterator<Item = Result<T, ParseError>> + '_ {
+        self.rows.iter().map(|row| T::from_row(self, row))
+    }
+}
+
+/// First occurrence wins for duplicate column names
+pub(crate) fn column_index(header: &[String]) -> HashMap<String, usize> {
+    let mut index = HashMap::with_capacity(header.len());
+    for (i, column) in header.iter().enumerate() {
+        index.entry(column.clone()).or_insert(i);
+    }
+    index
+}
+
+/// Tab separated fields of `line`
+///
+/// Tabs are found with `memchr`; as `\t` is ASCII every cut lands on a char
+/// boundary of the already validated line.
+pub(crate) fn split_fields(line: &str) -> impl Iterator<Item = &str> {
+    let mut start = 0;
+    memchr::memchr_iter(b'\t', line.as_bytes())
+        .chain(std::iter::once(line.len()))
+        .map(move |end| {
+            let field = &line[start..end];
+            start = end + 1;
+            field
+        })
+}
+
+/// Row of a [`Table`] with values looked up by column name
+///
+/// ## Example
+/// ```
+/// use std::io::Cursor;
+/// use schedule_parser::XerFile;
+///
+/// let data = "ERMHDR\t19.12\t2024-03-15\n%T\tTASK\n%F\ttask_id\ttask_code\tsuspend_date\n%R\t1\tA1000\n%E\n";
+/// let xer = XerFile::parse(Cursor::new(data)).unwrap();
+/// let row = xer.table("TASK").unwrap().row(0).unwrap();
+/// assert_eq!(row.get("task_code").unwrap(), "A1000");
+/// assert_eq!(row.get_opt("suspend_date"), None);
+/// assert!(row.get("resume_date").is_err());
+/// ```
+///
+/// Values are `String`s for [`Table`] and shared `Arc<str>` for
+/// [`InternedTable`](crate::intern::InternedTable).
+#[derive(Debug)]
+pub struct Row<'a, V = String> {
+    table: &'a str,
+    header: &'a [String],
+    index: &'a HashMap<String, usize>,
+    values: &'a [V],
+}
+
+impl<V> Clone for Row<'_, V> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<V> Copy for Row<'_, V> {}
+
+impl<'a, V: AsRef<str>> Row<'a, V> {
+    pub(crate) fn new(
+        table: &'a str,
+        header: &'a [String],
+        index: &'a HashMap<String, usize>,
+        values: &'a [V],
+    ) -> Self {
+        Self {
+            table,
+            header,
+            index,
+            values,
+        }
+    }
+
+    /// Name of the table the row belongs to
+    pub fn table(&self) -> &'a str {
+        self.table
+    }
+
+    /// Column names of the table
+    pub fn columns(&self) -> &'a [String] {
+        self.header
+    }
+
+    /// Values in column order, trailing empty values may be cut off
+    pub fn values(&self) -> &'a [V] {
+        self.values
+    }
+
+    /// Value of `column`, fails if the table has no such column
+    ///
+    /// Trailing empty fields may be cut off in the row so those are `""`.
+    pub fn get(&self, column: &str) -> Result<&'a str, ParseError> {
+        self.raw(column).ok_or_else(|| ParseError::MissingColumn {
+            table: self.table.into(),
+            column: column.into(),
+        })
+    }
+
+    /// Value of `column`, `None` if the column is missing or the value empty
+    pub fn get_opt(&self, column: &str) -> Option<&'a str> {
+        self.raw(column).filter(|value| !value.is_empty())
+    }
+
+    /// `(column, value)` pairs for every column of the header
+    pub fn iter(&self) -> impl Iterator<Item = (&'a str, &'a str)> + use<'a, V> {
+        let values = self.values;
+        self.header
+            .iter()
+            .enumerate()
+            .map(move |(i, column)| (column.as_str(), values.get(i).map_or("", V::as_ref)))
+    }
+
+    /// Deserialize into user type `T`, see [`Table::deserialize`]
+    pub fn deserialize<T: Deserialize<'a>>(self) -> Result<T, ParseError> {
+        crate::de::from_columns(self.table, self.iter())
+    }
+
+    fn raw(&self, column: &str) -> Option<&'a str> {
+        let index = *self.index.get(column)?;
+        Some(self.values.get(index).map_or("", V::as_ref))
+    }
+}
+
+/// Serialized as map of column to value
+impl<V: AsRef<str>> Serialize for Row<'_, V> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.header.len()))?;
+        for (column, value) in self.iter() {
+            map.serialize_entry(column, value)?;
+        }
+        map.end()
+    }
+}
+
+/// Entry point for reading `.xer` files
+///
+/// Lines following a `%R` record that do not start with `%` belong to it:
+/// memo and UDF text fields may contain line breaks, which are kept in the value.
+///
+/// ## Example
+/// ```no_run
+/// use schedule_parser::XerReader;
+///
+/// let reader = XerReader::from_path("./data/schedule.xer").unwrap();
+/// println!("exported at {}", reader.header().export_date);
+///
+/// for table in reader {
+///     let table = table.unwrap();
+///     println!("{} {} rows", table.name, table.rows.len());
+/// }
+/// ```
+///
+/// Any [`BufRead`] source works, e.g. in-memory buffer:
+/// ```
+/// use std::io::Cursor;
+/// use schedule_parser::XerReader;
+///
+/// let data = "ERMHDR\t19.12\t2024-03-15\n%T\tCURRTYPE\n%F\tcurr_id\n%R\t1\n%E\n";
+/// let tables: Vec<_> = XerReader::from_reader(Cursor::new(data))
+///     .unwrap()
+///     .into_iter()
+///     .collect::<Result<_, _>>()
+///     .unwrap();
+/// assert_eq!(tables[0].name, "CURRTYPE");
+/// ```
+pub struct XerReader<R> {
+    header: Header,
+    lines: LineReader<R>,
+    only: Option<Vec<String>>,
+    row_counts: Option<Vec<(String, usize)>>,
+}
+
+impl XerReader<DecodeReader<File>> {
+    /// Open file at `path`, text is converted to UTF-8 from the [detected](crate::encoding::detect) encoding
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, ParseError> {
+        let io = |source| ParseError::Io { line: 0, source };
+        let file = File::open(path).map_err(io)?;
+        Self::from_reader(DecodeReader::detect(file).map_err(io)?)
+    }
+
+    /// Open file at `path` written in `encoding`, e.g. `Encoding::for_label(b"cp1251")`
+    pub fn from_path_with_encoding<P: AsRef<Path>>(
+        path: P,
+        encoding: &'static Encoding,
+    ) -> Result<Self, ParseError> {
+        let file = File::open(path).map_err(|source| ParseError::Io { line: 0, source })?;
+        Self::from_reader(DecodeReader::new(file, encoding))
+    }
+}
+
+impl<R: BufRead> XerReader<R> {
+    /// Wrap any buffered reader: file, `Cursor`, stdin, network stream
+    ///
+    /// Header line is read right away.
+    pub fn from_reader(reader: R) -> Result<Self, ParseError> {
+        let mut lines = LineReader::new(reader);
+        let header = parse_header(lines.next()?)?;
+        Ok(Self {
+            header,
+            lines,
+            only: None,
+            row_counts: None,
+        })
+    }
+
+    /// Export info from the `ERMHDR` line
+    pub fn header(&self) -> &Header {
+        &self.header
+    }
+
+    /// Yield only tables named in `names`, e.g. `&["TASK", "TASKPRED"]`
+    ///
+    /// Rows of other tables are skipped without decoding or allocating them.
+    pub fn only_tables(mut self, names: &[&str]) -> Self {
+        self.only = Some(names.iter().map(|name| name.to_string()).collect());
+        self
+    }
+
+    /// Parse with `options`, e.g. in lenient mode
+    pub fn with_options(mut self, options: ParseOptions) -> Self {
+        self.lines.options = options;
+        self
+    }
+
+    /// `(table, row count)` in file order, available after [`XerReader::prescan`]
+    pub fn row_counts(&self) -> Option<&[(String, usize)]> {
+        self.row_counts.as_deref()
+    }
+
+    /// Consume reader and iterate over tables
+    pub fn tables(self) -> TableIterator<R> {
+        TableIterator {
+            header: self.header,
+            lines: self.lines,
+            only: self.only,
+            row_counts: self.row_counts,
+            position: 0,
+            current: None,
+            started: false,
+            done: false,
+        }
+    }
+}
+
+impl<R: BufRead + Seek> XerReader<R> {
+    /// Count rows of every table in a quick pass over the input and rewind
+    ///
+    /// Lines are only checked for their prefix, so this is much cheaper than
+    /// parsing. Afterwards [`TableIterator`] reports exact `size_hint`, and
+    /// [`TableReader::row_count`] and table row buffers are sized up front.
+    pub fn prescan(mut self) -> Result<Self, ParseError> {
+        let line = self.lines.number;
+        let io = |source| ParseError::Io { line, source };
+        let reader = &mut self.lines.reader;
+        let start = reader.stream_position().map_err(io)?;
+
+        let mut counts: Vec<(String, usize)> = Vec::new();
+        let mut buf = Vec::new();
+        let mut after_cr = self.lines.after_cr;
+        loop {
+            buf.clear();
+            if read_line(reader, &mut buf, &mut after_cr).map_err(io)? == 0 {
+                break;
+            }
+            if buf.starts_with(b"%R") {
+                if let Some((_, count)) = counts.last_mut() {
+                    *count += 1;
+                }
+            } else if buf.starts_with(b"%T") {
+                let name = buf
+                    .split(|&b| b == b'\t')
+                    .nth(1)
+                    .unwrap_or_default()
+                    .trim_ascii_end();
+                counts.push((String::from_utf8_lossy(name).into_owned(), 0));
+            }
+        }
+
+        reader.seek(SeekFrom::Start(start)).map_err(io)?;
+        self.row_counts = Some(counts);
+        Ok(self)
+    }
+}
+
+impl<R: BufRead> IntoIterator for XerReader<R> {
+    type Item = Result<Table, ParseError>;
+    type IntoIter = TableIterator<R>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.tables()
+    }
+}
+
+/// ## Source file structure
+/// - first line is info, see [`Header`]
+/// - each table starts with `%T`
+/// - each header starts with `%F`
+/// - each row starts with `%R`
+/// - file ends with `%E`
+///
+/// ## Example
+/// |ERMHDR|19.12       |2024-03-15  |Project     |user        |user_name   |dbxDatabaseNoName|Project Management|EUR|
+/// |------|------------|------------|------------|------------|------------|-----------------|------------------|---|
+/// |%T    |`TABLE1`    |            |            |            |            |                 |                  |   |
+/// |%F    |`column_1`  |`column_2`  |`column_3`  |            |            |                 |                  |   |
+/// |%R    |1           |2           |€           |            |            |                 |                  |   |
+/// |%R    |10          |2           |$           |            |            |                 |                  |   |
+/// |%R    |11          |2           |A$          |            |            |                 |                  |   |
+/// |%R    |13          |2           |R$          |            |            |                 |                  |   |
+/// |%T    |`TABLE2`    |            |            |            |            |                 |                  |   |
+/// |%F    |`column_1`  |`column_2`  |`column_3`  |`column_4`  |            |                 |                  |   |
+/// |%R    |11          |20005       |VAC         |Vacation    |            |                 |                  |   |
+/// |%R    |12          |4           |JURY        |Jury Duty   |            |                 |                  |   |
+/// |%R    |13          |3           |HOL         |Holiday     |            |                 |                  |   |
+/// |%T    |`TABLE3`    |            |            |            |            |                 |                  |   |
+/// |%F    |`column_1`  |`column_2`  |`column_3`  |`column_4`  |`column_5`  |                 |                  |   |
+/// |%R    |565         |            |            |0           |Enterprise  |                 |                  |   |
+/// |%E    |            |            |            |            |            |                 |                  |   |
+pub struct TableIterator<R> {
+    header: Header,
+    lines: LineReader<R>,
+    only: Option<Vec<String>>,
+    row_counts: Option<Vec<(String, usize)>>,
+    /// Number of `%T` records read so far
+    position: usize,
+    /// Line and name of the table being read
+    current: Option<(usize, String)>,
+    started: bool,
+    done: bool,
+}
+
+impl<R: BufRead> TableIterator<R> {
+    /// Export info from the `ERMHDR` line
+    pub fn header(&self) -> &Header {
+        &self.header
+    }
+
+    /// Next table, tables failing to parse are skipped in [recover](ParseOptions::recover) mode
+    fn read_table(&mut self) -> Result<Option<Table>, ParseError> {
+        let mut result = self.try_read_table();
+        loop {
+            match result {
+                Err(err) if self.lines.options.recover && !matches!(err, ParseError::Io { .. }) => {
+                    result = self.skip_table(err).and_then(|()| self.try_read_table());
+                }
+                result => {
+                    self.current = None;
+                    return result;
+                }
+            }
+        }
+    }
+
+    fn try_read_table(&mut self) -> Result<Option<Table>, ParseError> {
+        let Some((name, header)) = self.next_table_header()? else {
+            return Ok(None);
+        };
+        let mut rows: Vec<Vec<String>> = Vec::with_capacity(self.row_count().unwrap_or(0));
+        while let Some(line) = self.lines.next_record("%R")? {
+            let mut row = split_fields(line).skip(1).map(String::from).collect();
+            self.lines.check_row(&name, header.len(), &mut row)?;
+            rows.push(row);
+        }
+        let options = &self.lines.options;
+        if options.recover && !options.lenient && !self.lines.at_table_end()? {
+            // a stray record ends the rows early, the rest of the table is garbled
+            if let Some((line, content)) = self.lines.next()? {
+                return Err(ParseError::UnexpectedRecord { line, content });
+            }
+        }
+        let mut table = Table::new(name, header, rows);
+        table.truncated = self.lines.eof;
+        Ok(Some(table))
+    }
+
+    /// Report `err` and skip lines up to the next `%T`
+    fn skip_table(&mut self, err: ParseError) -> Result<(), ParseError> {
+        let table = self.current.take();
+        let line = match &table {
+            Some((line, _)) => *line,
+            None => err.line().unwrap_or(self.lines.line),
+        };
+        let error = err.to_string();
+        // `%T` or `%E` in place of `%F` starts the next table
+        if let ParseError::UnexpectedRecord { line, content } = err {
+            if is_table_end(content.as_bytes()) {
+                self.lines.push_back(line, content);
+            }
+        }
+        let kind = WarningKind::TableSkipped {
+            table: table.map(|(_, name)| name),
+            error,
+        };
+        self.lines.warn(line, kind);
+        self.lines.skip_to_table()
+    }
+
+    /// Input ended without `%E`, known once all tables are read
+    ///
+    /// The last table is marked [`truncated`](Table::truncated) then.
+    pub fn is_truncated(&self) -> bool {
+        self.lines.eof
+    }
+
+    /// Anomalies repaired or skipped so far in [lenient](ParseOptions::lenient) mode
+    pub fn warnings(&self) -> &[Warning] {
+        &self.lines.warnings
+    }
+
+    /// Take warnings collected so far, e.g. to report them table by table
+    pub fn take_warnings(&mut self) -> Vec<Warning> {
+        std::mem::take(&mut self.lines.warnings)
+    }
+
+    /// Next table with rows read one by one, for tables too large to hold in memory
+    ///
+    /// Rows left unread in the previous [`TableReader`] are skipped. Mixing with
+    /// [`Iterator::next`] is fine.
+    ///
+    /// ## Example
+    /// ```no_run
+    /// use schedule_parser::XerReader;
+    ///
+    /// let mut tables = XerReader::from_path("./data/schedule.xer").unwrap().tables();
+    /// while let Some(mut table) = tables.next_table().unwrap() {
+    ///     while let Some(row) = table.next_row().unwrap() {
+    ///         println!("{}: {:?}", row.table(), row.values());
+    ///     }
+    /// }
+    /// ```
+    pub fn next_table(&mut self) -> Result<Option<TableReader<'_, R>>, ParseError> {
+        if self.done {
+            return Ok(None);
+        }
+        let result = self.next_table_header();
+        self.done = !matches!(result, Ok(Some(_)));
+        let Some((name, header)) = result? else {
+            return Ok(None);
+        };
+        Ok(Some(TableReader {
+            row_count: self.row_count(),
+            index: column_index(&header),
+            name,
+            header,
+            values: Vec::new(),
+            lines: &mut self.lines,
+        }))
+    }
+
+    /// Tables with values interned while they are read, see [`intern`](crate::intern)
+    pub fn interned(self) -> InternedTables<R> {
+        InternedTables::new(self)
+    }
+
+    /// Prescanned row count of the table read last
+    fn row_count(&self) -> Option<usize> {
+        let counts = self.row_counts.as_ref()?;
+        counts
+            .get(self.position.checked_sub(1)?)
+            .map(|(_, count)| *count)
+    }
+
+    fn is_wanted(&self, name: &str) -> bool {
+        self.only
+            .as_ref()
+            .is_none_or(|only| only.iter().any(|n| n == name))
+    }
+
+    /// Name and columns of the next wanted table
+    fn next_table_header(&mut self) -> Result<Option<(String, Vec<String>)>, ParseError> {
+        // rows left unread by a dropped `TableReader`
+        self.lines.skip_while("%R")?;
+        loop {
+            let Some(name) = self.read_table_name()? else {
+                return Ok(None);
+            };
+            if !self.is_wanted(&name) {
+                self.lines.skip_while("%F")?;
+                self.lines.skip_while("%R")?;
+            } else if let Some(columns) = self.read_columns(&name)? {
+                return Ok(Some((name, columns)));
+            }
+        }
+    }
+
+    /// Name from the next `%T` line, `None` at the end of file
+    fn read_table_name(&mut self) -> Result<Option<String>, ParseError> {
+        loop {
+            // skip lines until first table starts, afterwards only `%T` and `%E` may come
+            let (number, line) = loop {
+                let Some((number, line)) = self.lines.next()? else {
+                    return Ok(None);
+                };
+                if line.starts_with("%T") {
+                    break (number, line);
+                }
+                if line.starts_with("%E") {
+                    return Ok(None);
+                }
+                if !self.started {
+                    continue;
+                }
+                if !self.lines.options.lenient {
+                    return Err(ParseError::UnexpectedRecord {
+                        line: number,
+                        content: line,
+                    });
+                }
+                self.lines
+                    .warn(number, WarningKind::UnexpectedRecord { content: line });
+            };
+            self.started = true;
+            self.position += 1;
+            self.current = None;
+            match table_name(number, line) {
+                Err(ParseError::MissingTableName { line, content })
+                    if self.lines.options.lenient =>
+                {
+                    self.lines
+                        .warn(line, WarningKind::MissingTableName { content });
+                    self.lines.skip_while("%F")?;
+                    self.lines.skip_while("%R")?;
+                }
+                name => {
+                    let name = name?;
+                    self.current = Some((number, name.clone()));
+                    return Ok(Some(name));
+                }
+            }
+        }
+    }
+
+    /// Column names from the `%F` line, `None` if the table is skipped in lenient mode
+    fn read_columns(&mut self, table: &str) -> Result<Option<Vec<String>>, ParseError> {
+        match self.lines.next()? {
+            Some((number, line)) if self.lines.options.lenient && !line.starts_with("%F") => {
+                let kind = WarningKind::MissingColumns {
+                    table: table.into(),
+                };
+                self.lines.warn(number, kind);
+                self.lines.push_back(number, line);
+                self.lines.skip_while("%R")?;
+                Ok(None)
+            }
+            line => parse_columns(line).map(Some),
+        }
+    }
+}
+
+/// Header from the first line of the file
+pub(crate) fn parse_header(line: Option<(usize, String)>) -> Result<Header, ParseError> {
+    match line {
+        Some((number, line)) => Header::parse(&line).ok_or(ParseError::InvalidHeader {
+            line: number,
+            content: line,
+        }),
+        None => Err(ParseError::UnexpectedEof { line: 1 }),
+    }
+}
+
+/// Name from `%T` line `line`
+pub(crate) fn table_name(number: usize, line: String) -> Result<String, ParseError> {
+    match line.split('\t').nth(1) {
+        Some(name) if !name.is_empty() => Ok(name.into()),
+        _ => Err(ParseError::MissingTableName {
+            line: number,
+            content: line,
+        }),
+    }
+}
+
+/// Column names from the line after `%T`, none if the file ends after `%T`
+pub(crate) fn parse_columns(line: Option<(usize, String)>) -> Result<Vec<String>, ParseError> {
+    match line {
+        Some((_, line)) if line.starts_with("%F") => {
+            Ok(split_fields(&line).skip(1).map(String::from).collect())
+        }
+        Some((number, line)) => Err(ParseError::UnexpectedRecord {
+            line: number,
+            content: line,
+        }),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// Line `number` read into `buf` as text
+pub(crate) fn decode(number: usize, buf: &[u8]) -> Result<&str, ParseError> {
+    std::str::from_utf8(buf).map_err(|_| ParseError::InvalidUtf8 {
+        line: number,
+        content: String::from_utf8_lossy(buf).into_owned(),
+    })
+}
+
+/// Line starts with `%` but is none of `%T`, `%F`, `%R` and `%E`
+pub(crate) fn is_unknown_record(line: &[u8]) -> bool {
+    line.first() == Some(&b'%') && !matches!(line.get(1), Some(b'T' | b'F' | b'R' | b'E'))
+}
+
+/// Line starts the next table or ends the file
+fn is_table_end(line: &[u8]) -> bool {
+    line.starts_with(b"%T") || line.starts_with(b"%E")
+}
+
+/// UTF-8 byte order mark some Windows tools put at the start of the file
+pub(crate) const BOM: &[u8] = b"\xEF\xBB\xBF";
+
+/// Append bytes of `chunk` up to the next line break to `buf`
+///
+/// `\n`, `\r\n` and a lone `\r` all end a line. Returns the number of bytes
+/// to consume and whether the line is complete. `after_cr` is kept between
+/// calls so a `\n` right after a `\r` at the end of the previous chunk is skipped.
+pub(crate) fn take_line(chunk: &[u8], buf: &mut Vec<u8>, after_cr: &mut bool) -> (usize, bool) {
+    let start = usize::from(std::mem::take(after_cr) && chunk.first() == Some(&b'\n'));
+    match memchr::memchr2(b'\n', b'\r', &chunk[start..]) {
+        Some(i) => {
+            let end = start + i;
+            buf.extend_from_slice(&chunk[start..end]);
+            *after_cr = chunk[end] == b'\r';
+            (end + 1, true)
+        }
+        None => {
+            buf.extend_from_slice(&chunk[start..]);
+            (chunk.len(), false)
+        }
+    }
+}
+
+/// Drop NUL bytes from line `buf`, `false` if the line consisted of them only
+pub(crate) fn drop_nul(buf: &mut Vec<u8>) -> bool {
+    if buf.is_empty() || memchr::memchr(0, buf).is_none() {
+        return true;
+    }
+    buf.retain(|&b| b != 0);
+    !buf.is_empty()
+}
+
+/// Append next line to `buf` without line terminator, see [`take_line`]
+///
+/// Lines of NUL bytes only are skipped. Returns the number of lines read,
+/// `0` at the end of input.
+pub(crate) fn read_line<R: BufRead>(
+    reader: &mut R,
+    buf: &mut Vec<u8>,
+    after_cr: &mut bool,
+) -> std::io::Result<usize> {
+    let mut lines = 0;
+    loop {
+        let chunk = match reader.fill_buf() {
+            Ok(chunk) => chunk,
+            Err(err) if err.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err),
+        };
+        if chunk.is_empty() {
+            // last line without line break
+            return Ok(if drop_nul(buf) && !buf.is_empty() {
+                lines + 1
+            } else {
+                0
+            });
+        }
+        let (used, complete) = take_line(chunk, buf, after_cr);
+        reader.consume(used);
+        if complete {
+            lines += 1;
+            if drop_nul(buf) {
+                return Ok(lines);
+            }
+        }
+    }
+}
+
+/// Table with rows read lazily, see [`TableIterator::next_table`]
+pub struct TableReader<'a, R> {
+    name: String,
+    row_count: Option<usize>,
+    header: Vec<String>,
+    index: HashMap<String, usize>,
+    /// Values of the last read row, buffers are reused
+    values: Vec<String>,
+    lines: &'a mut LineReader<R>,
+}
+
+impl<R: BufRead> TableReader<'_, R> {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Column names from the `%F` line
+    pub fn columns(&self) -> &[String] {
+        &self.header
+    }
+
+    /// Total number of rows, known if the reader was [prescanned](XerReader::prescan)
+    pub fn row_count(&self) -> Option<usize> {
+        self.row_count
+    }
+
+    /// Input ended inside this table without `%E`, known after the last row
+    pub fn is_truncated(&self) -> bool {
+        self.lines.eof
+    }
+
+    /// Next row, `Ok(None)` after the last row of the table
+    pub fn next_row(&mut self) -> Result<Option<Row<'_>>, ParseError> {
+        let Some(line) = self.lines.next_record("%R")? else {
+            return Ok(None);
+        };
+        let mut count = 0;
+        for (i, value) in split_fields(line).skip(1).enumerate() {
+            match self.values.get_mut(i) {
+                Some(buf) => {
+                    buf.clear();
+                    buf.push_str(value);
+                }
+                None => self.values.push(value.into()),
+            }
+            count = i + 1;
+        }
+        self.values.truncate(count);
+        self.lines
+            .check_row(&self.name, self.header.len(), &mut self.values)?;
+        Ok(Some(Row::new(
+            &self.name,
+            &self.header,
+            &self.index,
+            &self.values,
+        )))
+    }
+
+    /// Read remaining rows into a table with values shared through `interner`
+    pub fn into_interned(mut self, interner: &mut Interner) -> Result<InternedTable, ParseError> {
+        let mut rows = Vec::with_capacity(self.row_count.unwrap_or(0));
+        while let Some(row) = self.next_row()? {
+            rows.push(
+                row.values()
+                    .iter()
+                    .map(|value| interner.intern(value))
+                    .collect(),
+            );
+        }
+        let mut table = InternedTable::new(self.name, self.header, self.index, rows);
+        table.truncated = self.lines.eof;
+        Ok(table)
+    }
+
+    /// Read remaining rows into a [`Table`]
+    pub fn into_table(mut self) -> Result<Table, ParseError> {
+        let mut rows = Vec::with_capacity(self.row_count.unwrap_or(0));
+        while let Some(row) = self.next_row()? {
+            rows.push(row.values().to_vec());
+        }
+        Ok(Table {
+            name: self.name,
+            header: self.header,
+            rows,
+            truncated: self.lines.eof,
+            index: self.index,
+        })
+    }
+}
+
+impl<R: BufRead> Iterator for TableIterator<R> {
+    type Item = Result<Table, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let table = self.read_table().transpose();
+        // stop at the end of file or at the first error
+        self.done = !matches!(table, Some(Ok(_)));
+        table
+    }
+
+    /// Exact after [`XerReader::prescan`]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match &self.row_counts {
+            _ if self.done => (0, Some(0)),
+            Some(counts) => {
+                let remaining = counts
+                    .iter()
+                    .skip(self.position)
+                    .filter(|(name, _)| self.is_wanted(name))
+                    .count();
+                // malformed tables may be skipped
+                let options = &self.lines.options;
+                let lower = if options.lenient || options.recover {
+                    0
+                } else {
+                    remaining
+                };
+                (lower, Some(remaining))
+            }
+            None => (0, None),
+        }
+    }
+}
+
+/// Line by line reader with one line lookahead
+///
+/// Also holds the parse options and collects warnings, as it is shared by
+/// [`TableIterator`] and [`TableReader`].
+struct LineReader<R> {
+    reader: R,
+    options: ParseOptions,
+    warnings: Vec<Warning>,
+    buf: Vec<u8>,
+    /// Number of the last read line
+    number: usize,
+    /// Number of the first line of the record in `buf`
+    line: usize,
+    /// Last line ended with `\r`
+    after_cr: bool,
+    /// End of input was reached
+    eof: bool,
+    /// Record in `buf` was looked at but not consumed
+    held: bool,
+}
+
+impl<R: BufRead> LineReader<R> {
+    fn new(reader: R) -> Self {
+        Self {
+            reader,
+            options: ParseOptions::default(),
+            warnings: Vec::new(),
+            buf: Vec::new(),
+            number: 0,
+            line: 0,
+            after_cr: false,
+            eof: false,
+            held: false,
+        }
+    }
+
+    /// Next line without line terminator, `Ok(None)` at the end of file
+    fn next(&mut self) -> Result<Option<(usize, String)>, ParseError> {
+        if !self.fill()? {
+            return Ok(None);
+        }
+        self.decode().map(|line| Some((self.line, line)))
+    }
+
+    /// Next line borrowed from `buf` if it starts with `prefix`
+    ///
+    /// Other lines are kept for the next call. The prefix is checked on the
+    /// raw bytes, so no `String` is allocated per line.
+    fn next_record(&mut self, prefix: &str) -> Result<Option<&str>, ParseError> {
+        if !self.fill()? {
+            return Ok(None);
+        }
+        if !self.buf.starts_with(prefix.as_bytes()) {
+            self.held = true; // belongs to next table
+            return Ok(None);
+        }
+        self.text().map(Some)
+    }
+
+    /// Skip lines starting with `prefix` without decoding them
+    fn skip_while(&mut self, prefix: &str) -> Result<(), ParseError> {
+        while self.fill()? {
+            if !self.buf.starts_with(prefix.as_bytes()) {
+                self.held = true;
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Skip lines up to the next `%T` or `%E` without decoding them
+    fn skip_to_table(&mut self) -> Result<(), ParseError> {
+        while self.fill()? {
+            if is_table_end(&self.buf) {
+                self.held = true;
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Next record is `%T`, `%E` or the end of file, the record is kept
+    fn at_table_end(&mut self) -> Result<bool, ParseError> {
+        if !self.fill()? {
+            return Ok(true);
+        }
+        self.held = true;
+        Ok(is_table_end(&self.buf))
+    }
+
+    /// Record kept in `buf` or the next one, `false` at the end of file
+    fn fill(&mut self) -> Result<bool, ParseError> {
+        if self.held {
+            self.held = false;
+            return Ok(true);
+        }
+        self.read_raw()
+    }
+
+    /// Read next record into `buf` without line terminator, `false` at the end of file
+    ///
+    /// Records of unknown type are skipped unless [`UnknownRecords::Error`] is set.
+    fn read_raw(&mut self) -> Result<bool, ParseError> {
+        loop {
+            if !self.read_record()? {
+                return Ok(false);
+            }
+            let policy = self.options.unknown_records;
+            if policy == UnknownRecords::Error || !is_unknown_record(&self.buf) {
+                return Ok(true);
+            }
+            if policy == UnknownRecords::Warn {
+                let content = String::from_utf8_lossy(&self.buf).into_owned();
+                self.warn(self.line, WarningKind::UnknownRecord { content });
+            }
+        }
+    }
+
+    /// Read next line into `buf`, see [`read_raw`](Self::read_raw)
+    ///
+    /// Text fields may contain line breaks, so lines not starting with `%`
+    /// after a `%R` line are part of that row and kept with their line breaks.
+    fn read_record(&mut self) -> Result<bool, ParseError> {
+        self.buf.clear();
+        let io = |line: usize| move |source| ParseError::Io { line, source };
+        let lines = read_line(&mut self.reader, &mut self.buf, &mut self.after_cr)
+            .map_err(io(self.number + 1))?;
+        if lines == 0 {
+            self.eof = true;
+            return Ok(false);
+        }
+        if self.number == 0 && self.buf.starts_with(BOM) {
+            self.buf.drain(..BOM.len());
+        }
+        self.number += lines;
+        self.line = self.number;
+
+        if self.buf.starts_with(b"%R") {
+            while let Some(newline) = self.continuation().map_err(io(self.number + 1))? {
+                self.buf.extend_from_slice(newline);
+                self.number += read_line(&mut self.reader, &mut self.buf, &mut self.after_cr)
+                    .map_err(io(self.number + 1))?;
+            }
+        }
+        Ok(true)
+    }
+
+    /// Line break ending the record in `buf` if the next line continues it
+    fn continuation(&mut self) -> std::io::Result<Option<&'static [u8]>> {
+        let mut newline: &'static [u8] = if self.after_cr { b"\r" } else { b"\n" };
+        loop {
+            let chunk = match self.reader.fill_buf() {
+                Ok(chunk) => chunk,
+                Err(err) if err.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(err) => return Err(err),
+            };
+            match chunk.first() {
+                Some(b'\n') if self.after_cr => {
+                    self.reader.consume(1);
+                    self.after_cr = false;
+                    newline = b"\r\n";
+                }
+                None | Some(b'%' | 0) => return Ok(None),
+                Some(_) => return Ok(Some(newline)),
+            }
+        }
+    }
+
+    /// Line in `buf` as text, invalid UTF-8 is replaced in lenient mode
+    fn text(&mut self) -> Result<&str, ParseError> {
+        if self.options.lenient && std::str::from_utf8(&self.buf).is_err() {
+            self.warn(self.line, WarningKind::InvalidUtf8);
+            self.buf = String::from_utf8_lossy(&self.buf).into_owned().into_bytes();
+        }
+        decode(self.line, &self.buf)
+    }
+
+    /// Line in `buf` as owned text
+    fn decode(&mut self) -> Result<String, ParseError> {
+        self.text().map(String::from)
+    }
+
+    fn warn(&mut self, line: usize, kind: WarningKind) {
+        self.warnings.push(Warning { line, kind });
+    }
+
+    /// Check width of `row` of `table` against `columns` in strict mode, cut
+    /// or pad it in lenient mode
+    fn check_row<V: Default>(
+        &mut self,
+        table: &str,
+        columns: usize,
+        row: &mut Vec<V>,
+    ) -> Result<(), ParseError> {
+        let values = row.len();
+        if values == columns || !(self.options.lenient || self.options.strict) {
+            return Ok(());
+        }
+        let table = table.into();
+        if !self.options.lenient {
+            return Err(ParseError::RowWidth {
+                line: self.line,
+                table,
+                columns,
+                values,
+            });
+        }
+        let kind = if values > columns {
+            WarningKind::RowTruncated {
+                table,
+                columns,
+                values,
+            }
+        } else {
+            WarningKind::RowPadded {
+                table,
+                columns,
+                values,
+            }
+        };
+        self.warn(self.line, kind);
+        row.resize_with(columns, V::default);
+        Ok(())
+    }
+
+    /// Return line to be yielded by next call to `next`
+    fn push_back(&mut self, number: usize, line: String) {
+        self.buf = line.into_bytes();
+        self.line = number;
+        self.held = true;
+    }
+}
@@ -0,0 +1,1472 @@
+//! Import and export of Primavera P6 XML (PMXML)
+//!
+//! [`XerFile::from_pmxml`] reads the business objects of a P6 XML export
+//! into the tables and columns the same data has in a `.xer` export, all
+//! typed records and analyses then work on it unchanged:
+//!
+//! | Element              | Table          |
+//! |----------------------|----------------|
+//! | `Currency`           | `CURRTYPE`     |
+//! | `Calendar`           | `CALENDAR`     |
+//! | `Resource`           | `RSRC`         |
+//! | `ResourceRate`       | `RSRCRATE`     |
+//! | `Project`            | `PROJECT`      |
+//! | `WBS`                | `PROJWBS`      |
+//! | `Activity`           | `TASK`         |
+//! | `Relationship`       | `TASKPRED`     |
+//! | `ResourceAssignment` | `TASKRSRC`     |
+//! | `ActivityExpense`    | `PROJCOST`     |
+//! | `ScheduleOptions`    | `SCHEDOPTIONS` |
+//!
+//! Every project gets a project node in `PROJWBS`, WBS elements and
+//! activities without parent are placed under it. Other elements, baseline
+//! projects among them, are skipped. Dates lose their seconds, fractions
+//! become percentages and names like `Finish to Start` become codes like
+//! `PR_FS`. Work times of calendars finish a minute early in PMXML,
+//! `16:59` is read as `17:00`.
+//!
+//! The header takes the P6 version from the namespace, the currency with
+//! exchange rate 1 as base currency and the latest data date as export
+//! date, today without data date.
+//!
+//! [`XerFile::to_pmxml`] writes the same tables back as P6 XML for files to
+//! be imported into P6, other tables are left out. Elements are written in
+//! the alphabetical order of the P6 schema.
+//!
+//! ## Example
+//! ```
+//! use schedule_parser::model::LinkType;
+//! use schedule_parser::XerFile;
+//!
+//! let data = r#"<?xml version="1.0" encoding="UTF-8"?>
+//! <APIBusinessObjects xmlns="http://xmlns.oracle.com/Primavera/P6/V19.12/API/BusinessObjects">
+//!   <Project>
+//!     <ObjectId>1</ObjectId><Id>P1</Id><Name>Plant</Name><WBSObjectId>10</WBSObjectId>
+//!     <DataDate>2024-03-11T08:00:00</DataDate>
+//!     <Activity>
+//!       <ObjectId>100</ObjectId><Id>A1000</Id><Name>Design &amp; approve</Name>
+//!       <Type>Task Dependent</Type><Status>In Progress</Status>
+//!       <PhysicalPercentComplete>0.25</PhysicalPercentComplete>
+//!     </Activity>
+//!     <Activity>
+//!       <ObjectId>101</ObjectId><Id>M1000</Id><Name>Handover</Name>
+//!       <Type>Finish Milestone</Type><Status>Not Started</Status>
+//!     </Activity>
+//!     <Relationship>
+//!       <ObjectId>1000</ObjectId><Type>Finish to Start</Type><Lag>8</Lag>
+//!       <PredecessorActivityObjectId>100</PredecessorActivityObjectId>
+//!       <SuccessorActivityObjectId>101</SuccessorActivityObjectId>
+//!     </Relationship>
+//!   </Project>
+//! </APIBusinessObjects>"#;
+//! let xer = XerFile::from_pmxml(data.as_bytes()).unwrap();
+//! assert_eq!(xer.header.version, "19.12");
+//!
+//! let tasks = xer.tasks().unwrap();
+//! assert_eq!(tasks[0].task_name, "Design & approve");
+//! assert_eq!(tasks[0].physical_percent_complete, 25.0);
+//! assert_eq!(tasks[1].wbs_id, 10);
+//! let relationships = xer.relationships().unwrap();
+//! assert_eq!(relationships[0].link_type, LinkType::FinishToStart);
+//! assert_eq!(relationships[0].lag, 8.0);
+//!
+//! let xml = xer.to_pmxml().unwrap();
+//! assert!(xml.contains("<Name>Design &amp; approve</Name>"));
+//! assert!(xml.contains("<PhysicalPercentComplete>0.25</PhysicalPercentComplete>"));
+//! ```
+
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::fmt::{self, Write as _};
+use std::fs::File;
+use std::io::{self, BufRead};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta, Weekday};
+use indexmap::IndexMap;
+use quick_xml::escape::{resolve_predefined_entity, EscapeError};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+
+use crate::constraints::{constraint_code, constraint_name};
+use crate::encoding::DecodeReader;
+use crate::error::ParseError;
+use crate::header::Header;
+use crate::model::{Calendar, WorkPeriod};
+use crate::parser::{Row, Table};
+use crate::xer::XerFile;
+use crate::xml::XmlWriter;
+
+/// Failure of [`XerFile::from_pmxml`]
+#[derive(Debug)]
+pub enum PmxmlError {
+    /// File could not be opened
+    Io(io::Error),
+    /// Document is not well-formed XML
+    Xml(quick_xml::Error),
+    /// Root element is not `APIBusinessObjects`
+    NotPmxml { root: String },
+    /// Element text can not be converted, e.g. an unknown activity type
+    InvalidValue {
+        object: String,
+        element: String,
+        value: String,
+    },
+}
+
+impl fmt::Display for PmxmlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => err.fmt(f),
+            Self::Xml(err) => err.fmt(f),
+            Self::NotPmxml { root } => {
+                write!(f, "root element {root:?} is not APIBusinessObjects")
+            }
+            Self::InvalidValue {
+                object,
+                element,
+                value,
+            } => write!(f, "{object}.{element}: invalid value {value:?}"),
+        }
+    }
+}
+
+impl Error for PmxmlError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            Self::Xml(err) => Some(err),
+            Self::NotPmxml { .. } | Self::InvalidValue { .. } => None,
+        }
+    }
+}
+
+impl From<quick_xml::Error> for PmxmlError {
+    fn from(err: quick_xml::Error) -> Self {
+        Self::Xml(err)
+    }
+}
+
+/// How the text of an element becomes a field value
+#[derive(Clone, Copy)]
+enum Value {
+    Text,
+    /// `2024-03-11T08:00:00` to `2024-03-11 08:00`
+    Date,
+    /// `true` or `1` to `Y`, `false` or `0` to `N`
+    Flag,
+    /// Fraction to percentage
+    Percent,
+    /// Name to code
+    Code(&'static [(&'static str, &'static str)]),
+    /// Constraint name to `cstr_type`, `None` to empty
+    Constraint,
+}
+
+/// Column, element and conversion
+type Columns = &'static [(&'static str, &'static str, Value)];
+
+const TASK_TYPES: &[(&str, &str)] = &[
+    ("Task Dependent", "TT_Task"),
+    ("Resource Dependent", "TT_Rsrc"),
+    ("Level of Effort", "TT_LOE"),
+    ("Start Milestone", "TT_Mile"),
+    ("Finish Milestone", "TT_FinMile"),
+    ("WBS Summary", "TT_WBS"),
+];
+
+const TASK_STATUSES: &[(&str, &str)] = &[
+    ("Not Started", "TK_NotStart"),
+    ("In Progress", "TK_Active"),
+    ("Completed", "TK_Complete"),
+];
+
+const PERCENT_COMPLETE_TYPES: &[(&str, &str)] = &[
+    ("Physical", "CP_Phys"),
+    ("Duration", "CP_Drtn"),
+    ("Units", "CP_Units"),
+];
+
+const RELATIONSHIP_TYPES: &[(&str, &str)] = &[
+    ("Finish to Start", "PR_FS"),
+    ("Start to Start", "PR_SS"),
+    ("Finish to Finish", "PR_FF"),
+    ("Start to Finish", "PR_SF"),
+];
+
+const RESOURCE_TYPES: &[(&str, &str)] = &[
+    ("Labor", "RT_Labor"),
+    ("Nonlabor", "RT_Equip"),
+    ("Material", "RT_Mat"),
+];
+
+const CALENDAR_TYPES: &[(&str, &str)] = &[
+    ("Global", "CA_Base"),
+    ("Project", "CA_Project"),
+    ("Resource", "CA_Rsrc"),
+];
+
+const WBS_STATUSES: &[(&str, &str)] = &[
+    ("Active", "WS_Open"),
+    ("Inactive", "WS_Closed"),
+    ("Planned", "WS_Planned"),
+    ("What-If", "WS_Whatif"),
+];
+
+const CRITICAL_PATH_TYPES: &[(&str, &str)] = &[
+    ("Critical Float", "CT_TotFloat"),
+    ("Longest Path", "CT_DrivPath"),
+];
+
+const ACCRUAL_TYPES: &[(&str, &str)] = &[
+    ("Start of Activity", "CL_Start"),
+    ("End of Activity", "CL_End"),
+    ("Uniform Over Activity", "CL_Uniform"),
+];
+
+const LAG_CALENDARS: &[(&str, &str)] = &[
+    ("Predecessor Activity Calendar", "rcal_Predecessor"),
+    ("Successor Activity Calendar", "rcal_Successor"),
+    ("24 Hour Calendar", "rcal_24Hour"),
+    ("Project Default Calendar", "rcal_ProjDefault"),
+];
+
+const FLOAT_TYPES: &[(&str, &str)] = &[
+    ("Start Float", "FT_SF"),
+    ("Finish Float", "FT_FF"),
+    (
+        "Smallest of Start Float and Finish Float",
+        "FT_SmallestFloat",
+    ),
+];
+
+/// `sched_lag_early_start_flag`
+const LAG_STARTS: &[(&str, &str)] = &[("Early Start", "Y"), ("Actual Start", "N")];
+
+const SYMBOLS: &[(&str, &str)] = &[("Period", "."), ("Comma", ","), ("Space", " ")];
+
+const CURRTYPE: Columns = &[
+    ("curr_id", "ObjectId", Value::Text),
+    ("curr_short_name", "Id", Value::Text),
+    ("curr_type", "Name", Value::Text),
+    ("curr_symbol", "Symbol", Value::Text),
+    ("decimal_digit_cnt", "DecimalPlaces", Value::Text),
+    ("decimal_symbol", "DecimalSymbol", Value::Code(SYMBOLS)),
+    (
+        "digit_group_symbol",
+        "DigitGroupingSymbol",
+        Value::Code(SYMBOLS),
+    ),
+    ("base_exch_rate", "ExchangeRate", Value::Text),
+];
+
+const CALENDAR: Columns = &[
+    ("clndr_id", "ObjectId", Value::Text),
+    ("clndr_name", "Name", Value::Text),
+    ("base_clndr_id", "BaseCalendarObjectId", Value::Text),
+    ("default_flag", "IsDefault", Value::Flag),
+    ("clndr_type", "Type", Value::Code(CALENDAR_TYPES)),
+    ("day_hr_cnt", "HoursPerDay", Value::Text),
+    ("week_hr_cnt", "HoursPerWeek", Value::Text),
+];
+
+const RSRC: Columns = &[
+    ("rsrc_id", "ObjectId", Value::Text),
+    ("parent_rsrc_id", "ParentObjectId", Value::Text),
+    ("clndr_id", "CalendarObjectId", Value::Text),
+    ("role_id", "PrimaryRoleObjectId", Value::Text),
+    ("curr_id", "CurrencyObjectId", Value::Text),
+    ("rsrc_short_name", "Id", Value::Text),
+    ("rsrc_name", "Name", Value::Text),
+    ("rsrc_type", "ResourceType", Value::Code(RESOURCE_TYPES)),
+    ("active_flag", "IsActive", Value::Flag),
+    ("def_qty_per_hr", "DefaultUnitsPerTime", Value::Text),
+];
+
+const RSRCRATE: Columns = &[
+    ("rsrc_rate_id", "ObjectId", Value::Text),
+    ("rsrc_id", "ResourceObjectId", Value::Text),
+    ("start_date", "EffectiveDate", Value::Date),
+    ("max_qty_per_hr", "MaxUnitsPerTime", Value::Text),
+    ("cost_per_qty", "PricePerUnit", Value::Text),
+    ("cost_per_qty2", "PricePerUnit2", Value::Text),
+    ("cost_per_qty3", "PricePerUnit3", Value::Text),
+    ("cost_per_qty4", "PricePerUnit4", Value::Text),
+    ("cost_per_qty5", "PricePerUnit5", Value::Text),
+];
+
+const PROJECT: Columns = &[
+    ("proj_id", "ObjectId", Value::Text),
+    ("proj_short_name", "Id", Value::Text),
+    ("clndr_id", "ActivityDefaultCalendarObjectId", Value::Text),
+    ("plan_start_date", "PlannedStartDate", Value::Date),
+    ("plan_end_date", "MustFinishByDate", Value::Date),
+    ("scd_end_date", "ScheduledFinishDate", Value::Date),
+    ("last_recalc_date", "DataDate", Value::Date),
+    (
+        "def_complete_pct_type",
+        "ActivityDefaultPercentCompleteType",
+        Value::Code(PERCENT_COMPLETE_TYPES),
+    ),
+    (
+        "critical_path_type",
+        "CriticalActivityPathType",
+        Value::Code(CRITICAL_PATH_TYPES),
+    ),
+    (
+        "critical_drtn_hr_cnt",
+        "CriticalActivityFloatLimit",
+        Value::Text,
+    ),
+];
+
+const SCHEDOPTIONS: Columns = &[
+    (
+        "sched_calendar_on_relationship_lag",
+        "RelationshipLagCalendar",
+        Value::Code(LAG_CALENDARS),
+    ),
+    (
+        "sched_float_type",
+        "ComputeTotalFloatType",
+        Value::Code(FLOAT_TYPES),
+    ),
+    (
+        "sched_open_critical_flag",
+        "MakeOpenEndedActivitiesCritical",
+        Value::Flag,
+    ),
+    (
+        "sched_lag_early_start_flag",
+        "StartToStartLagCalculationType",
+        Value::Code(LAG_STARTS),
+    ),
+    (
+        "sched_use_expect_end_flag",
+        "UseExpectedFinishDates",
+        Value::Flag,
+    ),
+];
+
+const PROJWBS: Columns = &[
+    ("wbs_id", "ObjectId", Value::Text),
+    ("obs_id", "OBSObjectId", Value::Text),
+    ("seq_num", "SequenceNumber", Value::Text),
+    ("wbs_short_name", "Code", Value::Text),
+    ("wbs_name", "Name", Value::Text),
+    ("status_code", "Status", Value::Code(WBS_STATUSES)),
+];
+
+/// Columns of `PROJWBS` set from the project, shared with the project nodes
+const PROJWBS_COMPUTED: [&str; 3] = ["proj_id", "parent_wbs_id", "proj_node_flag"];
+
+const TASK: Columns = &[
+    ("task_id", "ObjectId", Value::Text),
+    ("clndr_id", "CalendarObjectId", Value::Text),
+    ("task_code", "Id", Value::Text),
+    ("task_name", "Name", Value::Text),
+    ("task_type", "Type", Value::Code(TASK_TYPES)),
+    ("status_code", "Status", Value::Code(TASK_STATUSES)),
+    (
+        "complete_pct_type",
+        "PercentCompleteType",
+        Value::Code(PERCENT_COMPLETE_TYPES),
+    ),
+    (
+        "phys_complete_pct",
+        "PhysicalPercentComplete",
+        Value::Percent,
+    ),
+    ("target_drtn_hr_cnt", "PlannedDuration", Value::Text),
+    ("remain_drtn_hr_cnt", "RemainingDuration", Value::Text),
+    ("total_float_hr_cnt", "TotalFloat", Value::Text),
+    ("free_float_hr_cnt", "FreeFloat", Value::Text),
+    ("driving_path_flag", "IsLongestPath", Value::Flag),
+    ("cstr_type", "PrimaryConstraintType", Value::Constraint),
+    ("cstr_date", "PrimaryConstraintDate", Value::Date),
+    ("cstr_type2", "SecondaryConstraintType", Value::Constraint),
+    ("cstr_date2", "SecondaryConstraintDate", Value::Date),
+    ("target_start_date", "PlannedStartDate", Value::Date),
+    ("target_end_date", "PlannedFinishDate", Value::Date),
+    ("act_start_date", "ActualStartDate", Value::Date),
+    ("act_end_date", "ActualFinishDate", Value::Date),
+    ("restart_date", "RemainingEarlyStartDate", Value::Date),
+    ("reend_date", "RemainingEarlyFinishDate", Value::Date),
+    ("early_start_date", "EarlyStartDate", Value::Date),
+    ("early_end_date", "EarlyFinishDate", Value::Date),
+    ("late_start_date", "LateStartDate", Value::Date),
+    ("late_end_date", "LateFinishDate", Value::Date),
+];
+
+const TASKPRED: Columns = &[
+    ("task_pred_id", "ObjectId", Value::Text),
+    ("task_id", "SuccessorActivityObjectId", Value::Text),
+    ("pred_task_id", "PredecessorActivityObjectId", Value::Text),
+    ("pred_proj_id", "PredecessorProjectObjectId", Value::Text),
+    ("pred_type", "Type", Value::Code(RELATIONSHIP_TYPES)),
+    ("lag_hr_cnt", "Lag", Value::Text),
+];
+
+const TASKRSRC: Columns = &[
+    ("taskrsrc_id", "ObjectId", Value::Text),
+    ("task_id", "ActivityObjectId", Value::Text),
+    ("rsrc_id", "ResourceObjectId", Value::Text),
+    ("role_id", "RoleObjectId", Value::Text),
+    ("acct_id", "CostAccountObjectId", Value::Text),
+    ("cost_per_qty", "PricePerUnit", Value::Text),
+    ("target_qty", "PlannedUnits", Value::Text),
+    ("act_reg_qty", "ActualRegularUnits", Value::Text),
+    ("act_ot_qty", "ActualOvertimeUnits", Value::Text),
+    ("remain_qty", "RemainingUnits", Value::Text),
+    ("target_cost", "PlannedCost", Value::Text),
+    ("act_reg_cost", "ActualRegularCost", Value::Text),
+    ("act_ot_cost", "ActualOvertimeCost", Value::Text),
+    ("remain_cost", "RemainingCost", Value::Text),
+    ("target_start_date", "PlannedStartDate", Value::Date),
+    ("target_end_date", "PlannedFinishDate", Value::Date),
+    ("act_start_date", "ActualStartDate", Value::Date),
+    ("act_end_date", "ActualFinishDate", Value::Date),
+    ("restart_date", "RemainingStartDate", Value::Date),
+    ("reend_date", "RemainingFinishDate", Value::Date),
+];
+
+const PROJCOST: Columns = &[
+    ("cost_item_id", "ObjectId", Value::Text),
+    ("task_id", "ActivityObjectId", Value::Text),
+    ("acct_id", "CostAccountObjectId", Value::Text),
+    ("cost_type_id", "ExpenseCategoryObjectId", Value::Text),
+    ("cost_name", "ExpenseItem", Value::Text),
+    ("cost_load_type", "AccrualType", Value::Code(ACCRUAL_TYPES)),
+    ("target_qty", "PlannedUnits", Value::Text),
+    ("cost_per_qty", "PricePerUnit", Value::Text),
+    ("target_cost", "PlannedCost", Value::Text),
+    ("act_cost", "ActualCost", Value::Text),
+    ("remain_cost", "RemainingCost", Value::Text),
+    ("vendor_name", "Vendor", Value::Text),
+    ("po_number", "DocumentNumber", Value::Text),
+];
+
+impl XerFile {
+    /// Read and parse P6 XML file at `path`, the encoding is detected like
+    /// in [`XerFile::from_path`]
+    pub fn from_pmxml_path<P: AsRef<Path>>(path: P) -> Result<Self, PmxmlError> {
+        let file = File::open(path).map_err(PmxmlError::Io)?;
+        Self::from_pmxml(DecodeReader::detect(file).map_err(PmxmlError::Io)?)
+    }
+
+    /// Read the business objects of a P6 XML export into `.xer` tables, see
+    /// [`pmxml`](crate::pmxml)
+    pub fn from_pmxml<R: BufRead>(reader: R) -> Result<Self, PmxmlError> {
+        let (root, version) = Element::read(reader)?.ok_or_else(|| PmxmlError::NotPmxml {
+            root: String::new(),
+        })?;
+        if root.name != "APIBusinessObjects" {
+            return Err(PmxmlError::NotPmxml { root: root.name });
+        }
+        let mut tables = Tables::default();
+        tables.objects(&root, None)?;
+
+        let currency = root
+            .children("Currency")
+            .find(|currency| {
+                currency
+                    .text("ExchangeRate")
+                    .and_then(|rate| rate.parse::<f64>().ok())
+                    == Some(1.0)
+            })
+            .or_else(|| root.children("Currency").next())
+            .and_then(|currency| currency.text("Id"))
+            .unwrap_or_default();
+        let data_date = root
+            .children("Project")
+            .filter_map(|project| project.text("DataDate"))
+            .filter_map(|date| NaiveDateTime::parse_from_str(date, "%Y-%m-%dT%H:%M:%S").ok())
+            .max();
+        let header = Header {
+            version: version.unwrap_or_default(),
+            export_date: data_date.map_or_else(today, |date| date.date()),
+            export_type: "Project".into(),
+            user: String::new(),
+            user_name: String::new(),
+            database: String::new(),
+            module: "Project Management".into(),
+            currency: currency.into(),
+            codepage: None,
+        };
+        Ok(Self {
+            header,
+            tables: tables.0,
+        })
+    }
+
+    /// P6 XML of the file for import into P6, see [`pmxml`](crate::pmxml)
+    pub fn to_pmxml(&self) -> Result<String, ParseError> {
+        let version = match self.header.version.as_str() {
+            "" => "19.12",
+            version => version,
+        };
+        let namespace =
+            format!("http://xmlns.oracle.com/Primavera/P6/V{version}/API/BusinessObjects");
+        let export = Export::new(self)?;
+        let mut xml = XmlWriter::new();
+        xml.open("APIBusinessObjects", &[("xmlns", &namespace)]);
+        for row in export.rows("CURRTYPE") {
+            xml.open("Currency", &[]);
+            fields(&mut xml, "CURRTYPE", CURRTYPE, &row, vec![])?;
+            xml.close();
+        }
+        export.calendars(&mut xml, None)?;
+        for row in export.rows("RSRC") {
+            xml.open("Resource", &[]);
+            fields(&mut xml, "RSRC", RSRC, &row, vec![])?;
+            xml.close();
+        }
+        for row in export.rows("RSRCRATE") {
+            xml.open("ResourceRate", &[]);
+            fields(&mut xml, "RSRCRATE", RSRCRATE, &row, vec![])?;
+            xml.close();
+        }
+        for row in export.rows("PROJECT") {
+            let Some(proj_id) = row.get_opt("proj_id") else {
+                continue;
+            };
+            let node = export.nodes.get(proj_id);
+            let node_value = |column| node.and_then(|node| node.get_opt(column));
+            let status = node_value("status_code")
+                .and_then(|code| WBS_STATUSES.iter().find(|(_, c)| *c == code))
+                .map(|(name, _)| *name);
+            let extra = vec![
+                ("Name", node_value("wbs_name")),
+                ("OBSObjectId", node_value("obs_id")),
+                ("Status", status),
+                ("WBSObjectId", node_value("wbs_id")),
+            ];
+            xml.open("Project", &[]);
+            fields(&mut xml, "PROJECT", PROJECT, &row, extra)?;
+            export.schedule_options(&mut xml, proj_id)?;
+            export.calendars(&mut xml, Some(proj_id))?;
+            export.objects(&mut xml, Some(proj_id))?;
+            xml.close();
+        }
+        // objects of projects not in the file
+        export.objects(&mut xml, None)?;
+        xml.close();
+        Ok(xml.finish())
+    }
+}
+
+fn today() -> NaiveDate {
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs() as i64);
+    DateTime::from_timestamp(seconds, 0)
+        .unwrap_or_default()
+        .date_naive()
+}
+
+/// Project the objects being read belong to
+struct Context<'a> {
+    proj_id: &'a str,
+    /// `wbs_id` of the project node
+    node: &'a str,
+    wbs_ids: HashSet<&'a str>,
+}
+
+#[derive(Default)]
+struct Tables(IndexMap<String, Table>);
+
+impl Tables {
+    /// Business objects among the children of `parent`, projects included
+    fn objects<'a>(
+        &mut self,
+        parent: &'a Element,
+        project: Option<&Context<'a>>,
+    ) -> Result<(), PmxmlError> {
+        let proj_id = |element: &'a Element| {
+            element
+                .text("ProjectObjectId")
+                .or(project.map(|project| project.proj_id))
+                .unwrap_or_default()
+        };
+        for element in &parent.children {
+            match element.name.as_str() {
+                "Currency" => self.push("CURRTYPE", CURRTYPE, element, vec![])?,
+                "Calendar" => {
+                    let data = clndr_data(element)?;
+                    let computed = vec![("proj_id", proj_id(element)), ("clndr_data", &*data)];
+                    self.push("CALENDAR", CALENDAR, element, computed)?;
+                }
+                "Resource" => self.push("RSRC", RSRC, element, vec![])?,
+                "ResourceRate" => self.push("RSRCRATE", RSRCRATE, element, vec![])?,
+                "Project" => self.project(element)?,
+                "WBS" => {
+                    let parent = element.text("ParentObjectId");
+                    let parent = match project {
+                        Some(project) => parent
+                            .filter(|id| project.wbs_ids.contains(id))
+                            .unwrap_or(project.node),
+                        None => parent.unwrap_or_default(),
+                    };
+                    let values = [proj_id(element), parent, "N"];
+                    let computed = PROJWBS_COMPUTED.into_iter().zip(values).collect();
+                    self.push("PROJWBS", PROJWBS, element, computed)?;
+                }
+                "Activity" => {
+                    let wbs_id = element
+                        .text("WBSObjectId")
+                        .or(project.map(|project| project.node))
+                        .unwrap_or_default();
+                    let computed = vec![("proj_id", proj_id(element)), ("wbs_id", wbs_id)];
+                    self.push("TASK", TASK, element, computed)?;
+                }
+                "Relationship" => {
+                    let successor = element
+                        .text("SuccessorProjectObjectId")
+                        .unwrap_or(proj_id(element));
+                    self.push("TASKPRED", TASKPRED, element, vec![("proj_id", successor)])?;
+                }
+                "ResourceAssignment" => {
+                    let computed = vec![("proj_id", proj_id(element))];
+                    self.push("TASKRSRC", TASKRSRC, element, computed)?;
+                }
+                "ActivityExpense" => {
+                    let computed = vec![("proj_id", proj_id(element))];
+                    self.push("PROJCOST", PROJCOST, element, computed)?;
+                }
+                "ScheduleOptions" => {
+                    let proj_id = proj_id(element);
+                    let (retained, progress) = match element.text("OutOfSequenceScheduleType") {
+                        Some("Retained Logic") | None => ("Y", "N"),
+                        Some("Progress Override") => ("N", "Y"),
+                        Some("Actual Dates") => ("N", "N"),
+                        Some(value) => {
+                            return Err(PmxmlError::InvalidValue {
+                                object: element.name.clone(),
+                                element: "OutOfSequenceScheduleType".into(),
+                                value: value.into(),
+                            })
+                        }
+                    };
+                    // one set of options per project, numbered like it
+                    let computed = vec![
+                        ("schedoptions_id", proj_id),
+                        ("proj_id", proj_id),
+                        ("sched_retained_logic", retained),
+                        ("sched_progress_override", progress),
+                    ];
+                    self.push("SCHEDOPTIONS", SCHEDOPTIONS, element, computed)?;
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// `PROJECT` row, project node and the objects of the project
+    fn project(&mut self, project: &Element) -> Result<(), PmxmlError> {
+        let proj_id = project.text("ObjectId").unwrap_or_default();
+        let node = project.text("WBSObjectId").unwrap_or(proj_id);
+        self.push("PROJECT", PROJECT, project, vec![])?;
+
+        let status = convert(project, "Status", Value::Code(WBS_STATUSES))?;
+        let table = self.table("PROJWBS", PROJWBS, &PROJWBS_COMPUTED);
+        let mut row = vec![String::new(); table.header.len()];
+        let values = [
+            ("wbs_id", node),
+            ("proj_id", proj_id),
+            ("obs_id", project.text("OBSObjectId").unwrap_or_default()),
+            ("wbs_short_name", project.text("Id").unwrap_or_default()),
+            ("wbs_name", project.text("Name").unwrap_or_default()),
+            ("status_code", &status),
+            ("proj_node_flag", "Y"),
+        ];
+        for (column, value) in values {
+            if let Some(i) = table.column_index(column) {
+                row[i] = value.into();
+            }
+        }
+        table.rows.push(row);
+
+        let context = Context {
+            proj_id,
+            node,
+            wbs_ids: project
+                .children("WBS")
+                .filter_map(|wbs| wbs.text("ObjectId"))
+                .collect(),
+        };
+        self.objects(project, Some(&context))
+    }
+
+    /// Row of `element` with the mapped `columns` followed by the `computed` ones
+    fn push(
+        &mut self,
+        name: &str,
+        columns: Columns,
+        element: &Element,
+        computed: Vec<(&str, &str)>,
+    ) -> Result<(), PmxmlError> {
+        let mut row = Vec::with_capacity(columns.len() + computed.len());
+        for &(_, child, value) in columns {
+            row.push(convert(element, child, value)?);
+        }
+        let names: Vec<&str> = computed.iter().map(|(column, _)| *column).collect();
+        let table = self.table(name, columns, &names);
+        row.extend(computed.into_iter().map(|(_, value)| value.to_string()));
+        table.rows.push(row);
+        Ok(())
+    }
+
+    fn table(&mut self, name: &str, columns: Columns, computed: &[&str]) -> &mut Table {
+        self.0.entry(name.into()).or_insert_with(|| {
+            let header = columns
+                .iter()
+                .map(|(column, _, _)| *column)
+                .chain(computed.iter().copied())
+                .map(String::from)
+                .collect();
+            Table::new(name.into(), header, Vec::new())
+        })
+    }
+}
+
+fn convert(element: &Element, child: &str, value: Value) -> Result<String, PmxmlError> {
+    let Some(text) = element.text(child) else {
+        return Ok(String::new());
+    };
+    let invalid = || PmxmlError::InvalidValue {
+        object: element.name.clone(),
+        element: child.into(),
+        value: text.into(),
+    };
+    let code = |codes: &[(&str, &'static str)]| {
+        codes
+            .iter()
+            .find(|(name, _)| *name == text)
+            .map(|(_, code)| code.to_string())
+            .ok_or_else(invalid)
+    };
+    match value {
+        Value::Text => Ok(text.into()),
+        Value::Date => NaiveDateTime::parse_from_str(text, "%Y-%m-%dT%H:%M:%S")
+            .map(|date| date.format("%Y-%m-%d %H:%M").to_string())
+            .map_err(|_| invalid()),
+        Value::Flag => match text {
+            "true" | "1" => Ok("Y".into()),
+            "false" | "0" => Ok("N".into()),
+            _ => Err(invalid()),
+        },
+        Value::Percent => text
+            .parse::<f64>()
+            .map(|fraction| ((fraction * 10000.0).round() / 100.0).to_string())
+            .map_err(|_| invalid()),
+        Value::Code(codes) => code(codes),
+        Value::Constraint => match text {
+            "None" => Ok(String::new()),
+            _ => constraint_code(text).map(String::from).ok_or_else(invalid),
+        },
+    }
+}
+
+/// `clndr_data` of a calendar from its standard work week and exceptions,
+/// see [`Calendar`](crate::model::Calendar)
+fn clndr_data(calendar: &Element) -> Result<String, PmxmlError> {
+    const DAYS: [&str; 7] = [
+        "Sunday",
+        "Monday",
+        "Tuesday",
+        "Wednesday",
+        "Thursday",
+        "Friday",
+        "Saturday",
+    ];
+    let invalid = |element: &str, value: &str| PmxmlError::InvalidValue {
+        object: "Calendar".into(),
+        element: element.into(),
+        value: value.into(),
+    };
+
+    let mut week: [String; 7] = Default::default();
+    let hours = calendar
+        .child("StandardWorkWeek")
+        .into_iter()
+        .flat_map(|standard| standard.children("StandardWorkHours"));
+    for day in hours {
+        let name = day.text("DayOfWeek").unwrap_or_default();
+        let index = DAYS
+            .iter()
+            .position(|day| *day == name)
+            .ok_or_else(|| invalid("DayOfWeek", name))?;
+        week[index] = work_times(day)?;
+    }
+    let mut data = String::from("(0||CalendarData()((0||DaysOfWeek()(");
+    for (i, periods) in week.iter().enumerate() {
+        let _ = write!(data, "(0||{}()({periods}))", i + 1);
+    }
+    data.push_str("))(0||Exceptions()(");
+    let exceptions = calendar
+        .child("HolidayOrExceptions")
+        .into_iter()
+        .flat_map(|exceptions| exceptions.children("HolidayOrException"));
+    let epoch = NaiveDate::from_ymd_opt(1899, 12, 30).unwrap();
+    for (i, exception) in exceptions.enumerate() {
+        let text = exception.text("Date").unwrap_or_default();
+        let date = NaiveDateTime::parse_from_str(text, "%Y-%m-%dT%H:%M:%S")
+            .map_err(|_| invalid("Date", text))?;
+        let serial = (date.date() - epoch).num_days();
+        let _ = write!(data, "(0||{i}(d|{serial})({}))", work_times(exception)?);
+    }
+    data.push_str("))))");
+    Ok(data)
+}
+
+/// `clndr_data` work periods of the `WorkTime` children of `element`
+fn work_times(element: &Element) -> Result<String, PmxmlError> {
+    let mut periods = String::new();
+    // nil work times of days off are empty
+    let works = element
+        .children("WorkTime")
+        .filter(|work| !work.children.is_empty());
+    for (i, work) in works.enumerate() {
+        let time = |name: &str| {
+            let text = work.text(name).unwrap_or_default();
+            NaiveTime::parse_from_str(text, "%H:%M:%S").map_err(|_| PmxmlError::InvalidValue {
+                object: "WorkTime".into(),
+                element: name.into(),
+                value: text.into(),
+            })
+        };
+        let start = time("Start")?;
+        // finish is the last minute worked, midnight wraps to `00:00`
+        let finish = time("Finish")? + TimeDelta::minutes(1);
+        let _ = write!(
+            periods,
+            "(0||{i}(s|{}|f|{})())",
+            start.format("%H:%M"),
+            finish.format("%H:%M")
+        );
+    }
+    Ok(periods)
+}
+
+/// Element of the document with its text and child elements
+#[derive(Debug, Default)]
+struct Element {
+    /// Local name without namespace prefix
+    name: String,
+    text: String,
+    children: Vec<Element>,
+}
+
+impl Element {
+    /// Root element of the document in `reader` and the P6 version from its
+    /// namespace, `None` for an empty document
+    fn read<R: BufRead>(reader: R) -> Result<Option<(Self, Option<String>)>, quick_xml::Error> {
+        let mut reader = Reader::from_reader(reader);
+        let mut buf = Vec::new();
+        let mut version = None;
+        // document at the bottom, open elements above
+        let mut stack = vec![Element::default()];
+        loop {
+            match reader.read_event_into(&mut buf)? {
+                Event::Start(start) => {
+                    if stack.len() == 1 {
+                        version = namespace_version(&start)?;
+                    }
+                    stack.push(Element::start(&reader, &start)?);
+                }
+                Event::Empty(start) => {
+                    let element = Element::start(&reader, &start)?;
+                    stack.last_mut().unwrap().children.push(element);
+                }
+                Event::End(_) => {
+                    let element = stack.pop().unwrap();
+                    stack.last_mut().unwrap().children.push(element);
+                }
+                Event::Text(text) => stack.last_mut().unwrap().text += &text.decode()?,
+                Event::CData(text) => stack.last_mut().unwrap().text += &text.decode()?,
+                Event::GeneralRef(reference) => {
+                    let text = &mut stack.last_mut().unwrap().text;
+                    match reference.resolve_char_ref()? {
+                        Some(c) => text.push(c),
+                        None => {
+                            let name = reference.decode()?;
+                            let Some(value) = resolve_predefined_entity(&name) else {
+                                let position = reader.buffer_position() as usize;
+                                return Err(EscapeError::UnrecognizedEntity(
+                                    position..position,
+                                    name.into_owned(),
+                                )
+                                .into());
+                            };
+                            text.push_str(value);
+                        }
+                    }
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+            buf.clear();
+        }
+        let document = stack.pop().unwrap();
+        Ok(document
+            .children
+            .into_iter()
+            .next()
+            .map(|root| (root, version)))
+    }
+
+    fn start<R>(reader: &Reader<R>, start: &BytesStart) -> Result<Self, quick_xml::Error> {
+        let name = start.local_name();
+        let name = reader.decoder().decode(name.as_ref())?;
+        Ok(Self {
+            name: name.into_owned(),
+            ..Self::default()
+        })
+    }
+
+    fn child(&self, name: &str) -> Option<&Element> {
+        self.children.iter().find(|child| child.name == name)
+    }
+
+    fn children<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Element> {
+        self.children.iter().filter(move |child| child.name == name)
+    }
+
+    /// Trimmed text of child `name`, `None` if missing or empty
+    fn text(&self, name: &str) -> Option<&str> {
+        Some(self.child(name)?.text.trim()).filter(|text| !text.is_empty())
+    }
+}
+
+/// `19.12` of `http://xmlns.oracle.com/Primavera/P6/V19.12/API/BusinessObjects`
+fn namespace_version(start: &BytesStart) -> Result<Option<String>, quick_xml::Error> {
+    let Some(namespace) = start.try_get_attribute("xmlns")? else {
+        return Ok(None);
+    };
+    Ok(namespace
+        .unescape_value()?
+        .split('/')
+        .filter_map(|part| part.strip_prefix('V'))
+        .find(|version| version.starts_with(|c: char| c.is_ascii_digit()))
+        .map(String::from))
+}
+
+/// Rows of a file grouped by project for [`XerFile::to_pmxml`]
+struct Export<'a> {
+    xer: &'a XerFile,
+    projects: HashSet<&'a str>,
+    /// `proj_id` of every activity
+    task_projects: HashMap<&'a str, &'a str>,
+    /// Project nodes by `proj_id`
+    nodes: HashMap<&'a str, Row<'a>>,
+    /// `CALENDAR` rows with their parsed calendar
+    calendars: Vec<(Row<'a>, Calendar)>,
+}
+
+impl<'a> Export<'a> {
+    fn new(xer: &'a XerFile) -> Result<Self, ParseError> {
+        let mut export = Self {
+            xer,
+            projects: HashSet::new(),
+            task_projects: HashMap::new(),
+            nodes: HashMap::new(),
+            calendars: Vec::new(),
+        };
+        export.projects = export
+            .rows("PROJECT")
+            .filter_map(|row| row.get_opt("proj_id"))
+            .collect();
+        export.task_projects = export
+            .rows("TASK")
+            .filter_map(|row| Some((row.get_opt("task_id")?, row.get_opt("proj_id")?)))
+            .collect();
+        export.nodes = export
+            .rows("PROJWBS")
+            .filter(|row| row.get_opt("proj_node_flag") == Some("Y"))
+            .filter_map(|row| Some((row.get_opt("proj_id")?, row)))
+            .collect();
+        export.calendars = export.rows("CALENDAR").zip(xer.calendars()?).collect();
+        Ok(export)
+    }
+
+    fn rows(&self, table: &str) -> impl Iterator<Item = Row<'a>> {
+        self.xer
+            .table(table)
+            .into_iter()
+            .flat_map(|table| table.iter())
+    }
+
+    /// Project in the file `row` belongs to, by its own `proj_id` or that of
+    /// its activity
+    fn project(&self, row: &Row<'a>) -> Option<&'a str> {
+        row.get_opt("proj_id")
+            .or_else(|| self.task_projects.get(row.get_opt("task_id")?).copied())
+            .filter(|proj_id| self.projects.contains(proj_id))
+    }
+
+    fn is_node(&self, wbs_id: &str) -> bool {
+        self.nodes
+            .values()
+            .any(|node| node.get_opt("wbs_id") == Some(wbs_id))
+    }
+
+    fn schedule_options(&self, xml: &mut XmlWriter, proj_id: &str) -> Result<(), ParseError> {
+        for row in self.rows("SCHEDOPTIONS") {
+            if row.get_opt("proj_id") != Some(proj_id) {
+                continue;
+            }
+            let out_of_sequence = match (
+                row.get_opt("sched_retained_logic"),
+                row.get_opt("sched_progress_override"),
+            ) {
+                (Some("Y"), _) => "Retained Logic",
+                (_, Some("Y")) => "Progress Override",
+                _ => "Actual Dates",
+            };
+            let extra = vec![
+                ("OutOfSequenceScheduleType", Some(out_of_sequence)),
+                ("ProjectObjectId", Some(proj_id)),
+            ];
+            xml.open("ScheduleOptions", &[]);
+            fields(xml, "SCHEDOPTIONS", SCHEDOPTIONS, &row, extra)?;
+            xml.close();
+        }
+        Ok(())
+    }
+
+    /// Calendars of `project`, global and resource calendars for `None`
+    fn calendars(&self, xml: &mut XmlWriter, project: Option<&str>) -> Result<(), ParseError> {
+        const DAYS: [(&str, Weekday); 7] = [
+            ("Sunday", Weekday::Sun),
+            ("Monday", Weekday::Mon),
+            ("Tuesday", Weekday::Tue),
+            ("Wednesday", Weekday::Wed),
+            ("Thursday", Weekday::Thu),
+            ("Friday", Weekday::Fri),
+            ("Saturday", Weekday::Sat),
+        ];
+        for (row, calendar) in &self.calendars {
+            let owner = self.project(row);
+            if owner != project {
+                continue;
+            }
+            xml.open("Calendar", &[]);
+            fields(
+                xml,
+                "CALENDAR",
+                CALENDAR,
+                row,
+                vec![("ProjectObjectId", owner)],
+            )?;
+            xml.open("StandardWorkWeek", &[]);
+            for (name, weekday) in DAYS {
+                xml.open("StandardWorkHours", &[]);
+                xml.element("DayOfWeek", name);
+                work_time(xml, calendar.weekday(weekday));
+                xml.close();
+            }
+            xml.close();
+            if !calendar.exceptions.is_empty() {
+                xml.open("HolidayOrExceptions", &[]);
+                for exception in &calendar.exceptions {
+                    xml.open("HolidayOrException", &[]);
+                    xml.element("Date", exception.date.format("%Y-%m-%dT00:00:00"));
+                    work_time(xml, &exception.periods);
+                    xml.close();
+                }
+                xml.close();
+            }
+            xml.close();
+        }
+        Ok(())
+    }
+
+    /// WBS elements, activities, expenses, relationships and assignments of
+    /// `project`, those of no project in the file for `None`
+    fn objects(&self, xml: &mut XmlWriter, project: Option<&str>) -> Result<(), ParseError> {
+        for row in self.rows("PROJWBS") {
+            if row.get_opt("proj_node_flag") == Some("Y") || self.project(&row) != project {
+                continue;
+            }
+            // top level elements have the project as parent
+            let parent = row
+                .get_opt("parent_wbs_id")
+                .filter(|parent| !self.is_node(parent))
+                .or(project);
+            let extra = vec![("ProjectObjectId", project), ("ParentObjectId", parent)];
+            xml.open("WBS", &[]);
+            fields(xml, "PROJWBS", PROJWBS, &row, extra)?;
+            xml.close();
+        }
+        for row in self.rows("TASK") {
+            if self.project(&row) != project {
+                continue;
+            }
+            let wbs_id = row.get_opt("wbs_id").filter(|wbs_id| !self.is_node(wbs_id));
+            let extra = vec![("ProjectObjectId", project), ("WBSObjectId", wbs_id)];
+            xml.open("Activity", &[]);
+            fields(xml, "TASK", TASK, &row, extra)?;
+            xml.close();
+        }
+        let objects = [
+            ("ActivityExpense", "PROJCOST", PROJCOST, "ProjectObjectId"),
+            (
+                "Relationship",
+                "TASKPRED",
+                TASKPRED,
+                "SuccessorProjectObjectId",
+            ),
+            (
+                "ResourceAssignment",
+                "TASKRSRC",
+                TASKRSRC,
+                "ProjectObjectId",
+            ),
+        ];
+        for (tag, table, columns, project_element) in objects {
+            for row in self.rows(table) {
+                if self.project(&row) != project {
+                    continue;
+                }
+                xml.open(tag, &[]);
+                fields(xml, table, columns, &row, vec![(project_element, project)])?;
+                xml.close();
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Elements of the mapped `columns` of `row` and the `extra` ones in the
+/// alphabetical order of the P6 schema, empty values are left out
+fn fields(
+    xml: &mut XmlWriter,
+    table: &str,
+    columns: Columns,
+    row: &Row,
+    extra: Vec<(&str, Option<&str>)>,
+) -> Result<(), ParseError> {
+    let mut elements: Vec<(&str, String)> = Vec::new();
+    for &(column, element, value) in columns {
+        let Some(text) = row.get_opt(column) else {
+            continue;
+        };
+        let invalid = || ParseError::InvalidValue {
+            table: table.into(),
+            column: column.into(),
+            value: text.into(),
+        };
+        let text = match value {
+            Value::Text => text.to_string(),
+            Value::Date => NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M")
+                .map_err(|_| invalid())?
+                .format("%Y-%m-%dT%H:%M:%S")
+                .to_string(),
+            Value::Flag => match text {
+                "Y" => "1".into(),
+                "N" => "0".into(),
+                _ => return Err(invalid()),
+            },
+            Value::Percent => (text.parse::<f64>().map_err(|_| invalid())? / 100.0).to_string(),
+            Value::Code(codes) => codes
+                .iter()
+                .find(|(_, code)| *code == text)
+                .map(|(name, _)| name.to_string())
+                .ok_or_else(invalid)?,
+            Value::Constraint => constraint_name(text).ok_or_else(invalid)?.to_string(),
+        };
+        elements.push((element, text));
+    }
+    for (element, value) in extra {
+        if let Some(value) = value {
+            elements.push((element, value.into()));
+        }
+    }
+    elements.sort_by_key(|(element, _)| *element);
+    for (element, value) in elements {
+        xml.element(element, value);
+    }
+    Ok(())
+}
+
+/// `WorkTime` elements of `periods`, finishing on the last minute worked
+fn work_time(xml: &mut XmlWriter, periods: &[WorkPeriod]) {
+    for period in periods {
+        let finish = period.finish - TimeDelta::minutes(1);
+        xml.open("WorkTime", &[]);
+        xml.element("Start", period.start.format("%H:%M:%S"));
+        xml.element("Finish", finish.format("%H:%M:%S"));
+        xml.close();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDate, Weekday};
+
+    use super::*;
+    use crate::model::LinkType;
+    use crate::testing::column;
+
+    /// Cut down from a P6 23.12 export, elements in P6 order
+    const EXPORT: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<APIBusinessObjects xmlns="http://xmlns.oracle.com/Primavera/P6/V23.12/API/BusinessObjects" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xsi:schemaLocation="http://xmlns.oracle.com/Primavera/P6/V23.12/API/BusinessObjects http://xmlns.oracle.com/Primavera/P6/V23.12/API/p6apibo.xsd">
+  <Currency>
+    <DecimalPlaces>2</DecimalPlaces>
+    <DecimalSymbol>Period</DecimalSymbol>
+    <DigitGroupingSymbol>Comma</DigitGroupingSymbol>
+    <ExchangeRate>1</ExchangeRate>
+    <Id>USD</Id>
+    <Name>US Dollar</Name>
+    <ObjectId>1</ObjectId>
+    <Symbol>$</Symbol>
+  </Currency>
+  <Calendar>
+    <BaseCalendarObjectId xsi:nil="true"/>
+    <HoursPerDay>8</HoursPerDay>
+    <HoursPerWeek>40</HoursPerWeek>
+    <IsDefault>1</IsDefault>
+    <Name>Standard 5 Day</Name>
+    <ObjectId>597</ObjectId>
+    <Type>Global</Type>
+    <StandardWorkWeek>
+      <StandardWorkHours>
+        <DayOfWeek>Sunday</DayOfWeek>
+        <WorkTime xsi:nil="true"/>
+      </StandardWorkHours>
+      <StandardWorkHours>
+        <DayOfWeek>Monday</DayOfWeek>
+        <WorkTime>
+          <Start>08:00:00</Start>
+          <Finish>11:59:00</Finish>
+        </WorkTime>
+        <WorkTime>
+          <Start>13:00:00</Start>
+          <Finish>16:59:00</Finish>
+        </WorkTime>
+      </StandardWorkHours>
+      <StandardWorkHours>
+        <DayOfWeek>Saturday</DayOfWeek>
+        <WorkTime xsi:nil="true"/>
+      </StandardWorkHours>
+    </StandardWorkWeek>
+    <HolidayOrExceptions>
+      <HolidayOrException>
+        <Date>2024-04-01T00:00:00</Date>
+        <WorkTime xsi:nil="true"/>
+      </HolidayOrException>
+      <HolidayOrException>
+        <Date>2024-04-08T00:00:00</Date>
+        <WorkTime>
+          <Start>09:00:00</Start>
+          <Finish>12:59:00</Finish>
+        </WorkTime>
+      </HolidayOrException>
+    </HolidayOrExceptions>
+  </Calendar>
+  <Resource>
+    <CalendarObjectId>597</CalendarObjectId>
+    <DefaultUnitsPerTime>1</DefaultUnitsPerTime>
+    <Id>ENG</Id>
+    <IsActive>true</IsActive>
+    <Name>Engineer</Name>
+    <ObjectId>4711</ObjectId>
+    <ParentObjectId xsi:nil="true"/>
+    <ResourceType>Labor</ResourceType>
+  </Resource>
+  <Project>
+    <ActivityDefaultCalendarObjectId>597</ActivityDefaultCalendarObjectId>
+    <DataDate>2024-03-28T08:00:00</DataDate>
+    <Id>PRJ-A</Id>
+    <Name>Plant</Name>
+    <ObjectId>26</ObjectId>
+    <Status>Active</Status>
+    <WBSObjectId>100</WBSObjectId>
+    <WBS>
+      <Code>ENG</Code>
+      <Name>Engineering</Name>
+      <ObjectId>101</ObjectId>
+      <ParentObjectId>100</ParentObjectId>
+      <ProjectObjectId>26</ProjectObjectId>
+      <SequenceNumber>10</SequenceNumber>
+      <Status>Active</Status>
+    </WBS>
+    <Activity>
+      <CalendarObjectId>597</CalendarObjectId>
+      <Id>A1000</Id>
+      <IsLongestPath>true</IsLongestPath>
+      <Name>Design</Name>
+      <ObjectId>5001</ObjectId>
+      <PercentCompleteType>Duration</PercentCompleteType>
+      <PlannedDuration>16</PlannedDuration>
+      <PrimaryConstraintDate>2024-04-02T08:00:00</PrimaryConstraintDate>
+      <PrimaryConstraintType>Start On or After</PrimaryConstraintType>
+      <ProjectObjectId>26</ProjectObjectId>
+      <RemainingDuration>16</RemainingDuration>
+      <SecondaryConstraintDate xsi:nil="true"/>
+      <SecondaryConstraintType>None</SecondaryConstraintType>
+      <Status>Not Started</Status>
+      <Type>Task Dependent</Type>
+      <WBSObjectId>101</WBSObjectId>
+    </Activity>
+    <Activity>
+      <Id>M1000</Id>
+      <Name>Design approved</Name>
+      <ObjectId>5002</ObjectId>
+      <ProjectObjectId>26</ProjectObjectId>
+      <Status>Not Started</Status>
+      <Type>Finish Milestone</Type>
+      <WBSObjectId xsi:nil="true"/>
+    </Activity>
+    <ResourceAssignment>
+      <ActivityObjectId>5001</ActivityObjectId>
+      <ObjectId>9001</ObjectId>
+      <PlannedCost>1200</PlannedCost>
+      <PlannedUnits>16</PlannedUnits>
+      <ProjectObjectId>26</ProjectObjectId>
+      <RemainingCost>1200</RemainingCost>
+      <RemainingUnits>16</RemainingUnits>
+      <ResourceObjectId>4711</ResourceObjectId>
+      <RoleObjectId xsi:nil="true"/>
+    </ResourceAssignment>
+    <Relationship>
+      <Lag>-8</Lag>
+      <ObjectId>7001</ObjectId>
+      <PredecessorActivityObjectId>5001</PredecessorActivityObjectId>
+      <PredecessorProjectObjectId>26</PredecessorProjectObjectId>
+      <SuccessorActivityObjectId>5002</SuccessorActivityObjectId>
+      <SuccessorProjectObjectId>26</SuccessorProjectObjectId>
+      <Type>Finish to Finish</Type>
+    </Relationship>
+  </Project>
+  <BaselineProject>
+    <Id>PRJ-A B1</Id>
+    <ObjectId>27</ObjectId>
+    <Activity>
+      <Id>A1000</Id>
+      <ObjectId>6001</ObjectId>
+      <Status>Not Started</Status>
+      <Type>Task Dependent</Type>
+    </Activity>
+  </BaselineProject>
+</APIBusinessObjects>"#;
+
+    fn import() -> XerFile {
+        XerFile::from_pmxml(EXPORT.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn header_from_namespace_currency_and_data_date() {
+        let xer = import();
+        assert_eq!(xer.header.version, "23.12");
+        assert_eq!(xer.header.currency, "USD");
+        assert_eq!(xer.header.export_date.to_string(), "2024-03-28");
+        assert_eq!(column(&xer, "CURRTYPE", "decimal_symbol"), ["."]);
+    }
+
+    #[test]
+    fn work_times_and_exceptions_become_clndr_data() {
+        let xer = import();
+        assert_eq!(column(&xer, "CALENDAR", "default_flag"), ["Y"]);
+        assert_eq!(column(&xer, "CALENDAR", "clndr_type"), ["CA_Base"]);
+        assert_eq!(column(&xer, "CALENDAR", "base_clndr_id"), [""]);
+
+        let calendar = &xer.calendars().unwrap()[0];
+        let time = |value| NaiveTime::parse_from_str(value, "%H:%M").unwrap();
+        let period = |start, finish| WorkPeriod {
+            start: time(start),
+            finish: time(finish),
+        };
+        assert_eq!(
+            calendar.weekday(Weekday::Mon),
+            [period("08:00", "12:00"), period("13:00", "17:00")]
+        );
+        assert!(calendar.weekday(Weekday::Sun).is_empty());
+        // days missing from the work week have no work either
+        assert!(calendar.weekday(Weekday::Tue).is_empty());
+
+        let holiday = NaiveDate::from_ymd_opt(2024, 4, 1).unwrap();
+        let short_day = NaiveDate::from_ymd_opt(2024, 4, 8).unwrap();
+        assert!(calendar.exception(holiday).unwrap().is_holiday());
+        assert_eq!(calendar.work_periods(short_day), [period("09:00", "13:00")]);
+        assert!(column(&xer, "CALENDAR", "clndr_data")[0].contains("(0||0(d|45383)())"));
+    }
+
+    #[test]
+    fn calendars_survive_export_and_import() {
+        let xer = import();
+        let again = XerFile::from_pmxml(xer.to_pmxml().unwrap().as_bytes()).unwrap();
+        assert_eq!(again.calendars().unwrap(), xer.calendars().unwrap());
+    }
+
+    #[test]
+    fn activities_under_wbs_or_project_node() {
+        let xer = import();
+        assert_eq!(column(&xer, "PROJWBS", "wbs_id"), ["100", "101"]);
+        assert_eq!(column(&xer, "PROJWBS", "parent_wbs_id"), ["", "100"]);
+        assert_eq!(column(&xer, "PROJWBS", "proj_node_flag"), ["Y", "N"]);
+
+        // the baseline project is skipped
+        let tasks = xer.tasks().unwrap();
+        assert_eq!(tasks.len(), 2);
+        assert_eq!(tasks[0].wbs_id, 101);
+        assert_eq!(tasks[1].wbs_id, 100);
+        assert_eq!(tasks[0].clndr_id, Some(597));
+        assert_eq!(tasks[1].clndr_id, None);
+        assert!(tasks[0].driving_path);
+        assert_eq!(tasks[0].constraint_type.as_deref(), Some("CS_MSOA"));
+        assert_eq!(
+            tasks[0].constraint_date.unwrap().to_string(),
+            "2024-04-02 08:00:00"
+        );
+        assert_eq!(tasks[0].secondary_constraint_type, None);
+        assert_eq!(column(&xer, "TASK", "task_type"), ["TT_Task", "TT_FinMile"]);
+        assert_eq!(column(&xer, "TASK", "complete_pct_type"), ["CP_Drtn", ""]);
+    }
+
+    #[test]
+    fn relationships_with_lag_and_projects() {
+        let xer = import();
+        let relationships = xer.relationships().unwrap();
+        assert_eq!(relationships.len(), 1);
+        let link = &relationships[0];
+        assert_eq!((link.pred_task_id, link.task_id), (5001, 5002));
+        assert_eq!(link.link_type, LinkType::FinishToFinish);
+        assert_eq!(link.lag, -8.0);
+        assert_eq!((link.proj_id, link.pred_proj_id), (Some(26), Some(26)));
+    }
+
+    #[test]
+    fn resource_assignments() {
+        let xer = import();
+        assert_eq!(column(&xer, "TASKRSRC", "task_id"), ["5001"]);
+        assert_eq!(column(&xer, "TASKRSRC", "rsrc_id"), ["4711"]);
+        assert_eq!(column(&xer, "TASKRSRC", "role_id"), [""]);
+        assert_eq!(column(&xer, "TASKRSRC", "proj_id"), ["26"]);
+        let assignments = xer.assignments().unwrap();
+        let currency = xer.base_currency().unwrap();
+        let costs = assignments.task_costs(5001, &currency);
+        assert_eq!(costs.budgeted.amount, 1200.0);
+        assert_eq!(costs.remaining.amount, 1200.0);
+        assert_eq!(column(&xer, "RSRC", "active_flag"), ["Y"]);
+        assert_eq!(column(&xer, "RSRC", "rsrc_type"), ["RT_Labor"]);
+    }
+
+    #[test]
+    fn unknown_names_are_invalid_values() {
+        let data = EXPORT.replace("Finish to Finish", "Finish to Begin");
+        match XerFile::from_pmxml(data.as_bytes()) {
+            Err(PmxmlError::InvalidValue {
+                object,
+                element,
+                value,
+            }) => {
+                assert_eq!((&*object, &*element), ("Relationship", "Type"));
+                assert_eq!(value, "Finish to Begin");
+            }
+            other => panic!("{other:?}"),
+        }
+    }
+}
@@ -0,0 +1,76 @@
+//! Conversion of tables to Polars data frames, enabled by the `polars` feature
+//!
+//! Columns are typed by [`Table::column_types`], dates become datetimes in
+//! milliseconds without time zone and empty values are null.
+//!
+//! ## Example
+//! ```
+//! use polars::prelude::*;
+//! use schedule_parser::XerFile;
+//!
+//! let data = "ERMHDR\t19.12\t2024-03-15\n%T\tTASK\n%F\ttask_id\ttask_code\ttarget_drtn_hr_cnt\n%R\t1\tA1000\t16\n%R\t2\tA1010\t40\n%E\n";
+//! let xer = XerFile::parse(data.as_bytes()).unwrap();
+//! let tasks = xer.table("TASK").unwrap().to_dataframe().unwrap();
+//! let long = tasks.column("target_drtn_hr_cnt").unwrap().i64().unwrap().gt(20);
+//! let long = tasks.filter(&long).unwrap();
+//! assert_eq!(long.column("task_code").unwrap().str().unwrap().get(0), Some("A1010"));
+//! ```
+
+use ::polars::prelude::{Column, DataFrame, IntoColumn, NamedFrom, PolarsResult, Series, TimeUnit};
+use indexmap::IndexMap;
+
+use crate::model::parse_datetime;
+use crate::parser::Table;
+use crate::schema::ColumnType;
+use crate::xer::XerFile;
+
+impl Table {
+    /// All rows as data frame, columns are named by [`Table::unique_columns`]
+    pub fn to_dataframe(&self) -> PolarsResult<DataFrame> {
+        let columns = self
+            .unique_columns()
+            .iter()
+            .zip(self.column_types())
+            .enumerate()
+            .map(|(i, (name, kind))| self.series(name, i, kind).into_column())
+            .collect::<Vec<Column>>();
+        DataFrame::new(self.rows.len(), columns)
+    }
+
+    /// Values of column `i` as series of `kind`, values not fitting `kind` are null
+    fn series(&self, name: &str, i: usize, kind: ColumnType) -> Series {
+        let values = self.rows.iter().map(|row| {
+            row.get(i)
+                .map(String::as_str)
+                .filter(|value| !value.is_empty())
+        });
+        let name = name.into();
+        match kind {
+            ColumnType::Integer => {
+                let values: Vec<Option<i64>> = values.map(|value| value?.parse().ok()).collect();
+                Series::new(name, values)
+            }
+            ColumnType::Float => {
+                let values: Vec<Option<f64>> = values.map(|value| value?.parse().ok()).collect();
+                Series::new(name, values)
+            }
+            ColumnType::DateTime => {
+                let values: Vec<Option<i64>> = values
+                    .map(|value| Some(parse_datetime(value?)?.and_utc().timestamp_millis()))
+                    .collect();
+                Series::new(name, values).into_datetime(TimeUnit::Milliseconds, None)
+            }
+            ColumnType::Text => Series::new(name, values.collect::<Vec<_>>()),
+        }
+    }
+}
+
+impl XerFile {
+    /// Every table as data frame by name, in file order
+    pub fn to_dataframes(&self) -> PolarsResult<IndexMap<String, DataFrame>> {
+        self.tables
+            .iter()
+            .map(|(name, table)| Ok((name.clone(), table.to_dataframe()?)))
+            .collect()
+    }
+}
@@ -0,0 +1,158 @@
+//! Column selection and renaming, e.g. `task_code,task_name,target_end_date:finish`
+//!
+//! A [`Projection`] lists the columns to keep in output order, each optionally
+//! followed by `:` and the name to write it under. Columns not listed are
+//! dropped. [`XerFile::project_columns`] applies it to whole tables,
+//! [`BoundProjection::select`] to rows read one by one.
+//!
+//! ## Example
+//! ```
+//! use schedule_parser::projection::Projection;
+//! use schedule_parser::XerFile;
+//!
+//! let data = "ERMHDR\t19.12\t2024-03-15\n%T\tTASK\n\
+//!     %F\ttask_id\ttask_code\ttask_name\ttarget_end_date\n\
+//!     %R\t1\tA1000\tDesign\t2024-12-20 17:00\n%E\n";
+//! let mut xer = XerFile::parse(data.as_bytes()).unwrap();
+//!
+//! let projection: Projection = "task_code,task_name,target_end_date:finish".parse().unwrap();
+//! assert_eq!(xer.project_columns(&projection).unwrap(), ["TASK"]);
+//! let task = &xer.tables["TASK"];
+//! assert_eq!(task.header, ["task_code", "task_name", "finish"]);
+//! assert_eq!(task.rows[0], ["A1000", "Design", "2024-12-20 17:00"]);
+//! assert_eq!(task.column_index("finish"), Some(2));
+//! ```
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::parser::Table;
+use crate::xer::XerFile;
+
+/// Failure to parse or apply a [`Projection`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProjectionError {
+    /// Empty column or new name in the list
+    Syntax { spec: String },
+    /// Listed column not in the header
+    MissingColumn { column: String },
+    /// No table has all listed columns, see [`XerFile::project_columns`]
+    NoTable { columns: Vec<String> },
+}
+
+impl fmt::Display for ProjectionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Syntax { spec } => write!(f, "invalid column list entry {spec:?}"),
+            Self::MissingColumn { column } => write!(f, "no column {column}"),
+            Self::NoTable { columns } => {
+                write!(f, "no table has all columns {}", columns.join(", "))
+            }
+        }
+    }
+}
+
+impl std::error::Error for ProjectionError {}
+
+/// Columns to keep as `(column, output name)` in output order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Projection {
+    columns: Vec<(String, String)>,
+}
+
+/// [`Projection`] with columns resolved to positions in a header
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BoundProjection {
+    indices: Vec<usize>,
+    header: Vec<String>,
+}
+
+impl Projection {
+    /// Names of the listed columns in the input
+    pub fn columns(&self) -> impl Iterator<Item = &str> {
+        self.columns.iter().map(|(column, _)| column.as_str())
+    }
+
+    /// Resolve the columns in `header`, the first of duplicate names is used
+    pub fn bind(&self, header: &[String]) -> Result<BoundProjection, ProjectionError> {
+        let indices = self
+            .columns()
+            .map(|column| {
+                header
+                    .iter()
+                    .position(|name| name == column)
+                    .ok_or_else(|| ProjectionError::MissingColumn {
+                        column: column.to_string(),
+                    })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(BoundProjection {
+            indices,
+            header: self.columns.iter().map(|(_, name)| name.clone()).collect(),
+        })
+    }
+}
+
+impl FromStr for Projection {
+    type Err = ProjectionError;
+
+    fn from_str(text: &str) -> Result<Self, ProjectionError> {
+        let columns = text
+            .split(',')
+            .map(|spec| {
+                let (column, name) = spec.split_once(':').unwrap_or((spec, spec));
+                let (column, name) = (column.trim(), name.trim());
+                if column.is_empty() || name.is_empty() {
+                    return Err(ProjectionError::Syntax { spec: spec.into() });
+                }
+                Ok((column.to_string(), name.to_string()))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { columns })
+    }
+}
+
+impl BoundProjection {
+    /// Output column names
+    pub fn header(&self) -> &[String] {
+        &self.header
+    }
+
+    /// Values of the selected columns of a row in header order, cut off values are empty
+    pub fn select<'a>(&self, values: &'a [impl AsRef<str>]) -> Vec<&'a str> {
+        self.indices
+            .iter()
+            .map(|&i| values.get(i).map_or("", AsRef::as_ref))
+            .collect()
+    }
+}
+
+impl Table {
+    /// Keep only the columns of `projection` in its order and names
+    pub fn project(&mut self, projection: &Projection) -> Result<(), ProjectionError> {
+        let bound = projection.bind(&self.header)?;
+        for row in &mut self.rows {
+            *row = bound.select(row).into_iter().map(String::from).collect();
+        }
+        self.set_header(bound.header);
+        Ok(())
+    }
+}
+
+impl XerFile {
+    /// [Project](Table::project) every table with all listed columns, see
+    /// [`XerFile::for_tables_with`]
+    pub fn project_columns(
+        &mut self,
+        projection: &Projection,
+    ) -> Result<Vec<String>, ProjectionError> {
+        let columns: Vec<&str> = projection.columns().collect();
+        self.for_tables_with(
+            &columns,
+            |table| table.project(projection),
+            || ProjectionError::NoTable {
+                columns: columns.iter().map(|&column| column.into()).collect(),
+            },
+        )
+    }
+}
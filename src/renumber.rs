@@ -0,0 +1,156 @@
+//! Compact renumbering of internal ids
+
+use std::collections::HashMap;
+
+use crate::keys::{foreign_table, primary_key, udf_tables};
+use crate::xer::XerFile;
+
+impl XerFile {
+    /// Renumber the ids of every table from 1 in row order and rewrite all
+    /// references to them, including `UDFVALUE.fk_id`
+    ///
+    /// Exports of the same schedule from different databases then get the
+    /// same ids. References to rows not in a table of the file get ids after
+    /// those of its rows, the same for the same old id, so they still point
+    /// at no row. References into tables not in the file are kept as they are.
+    ///
+    /// ## Example
+    /// ```
+    /// use schedule_parser::XerFile;
+    ///
+    /// let data = "ERMHDR\t19.12\t2024-03-15\n\
+    ///     %T\tTASK\n%F\ttask_id\ttask_code\n%R\t5000\tA1000\n%R\t5003\tA1010\n\
+    ///     %T\tTASKPRED\n%F\ttask_pred_id\ttask_id\tpred_task_id\n\
+    ///     %R\t870\t5003\t5000\n%R\t871\t5003\t1\n%E\n";
+    /// let mut xer = XerFile::parse(data.as_bytes()).unwrap();
+    /// xer.renumber();
+    ///
+    /// assert_eq!(xer.table("TASK").unwrap().rows, [["1", "A1000"], ["2", "A1010"]]);
+    /// // task 1 is not in the file
+    /// assert_eq!(xer.table("TASKPRED").unwrap().rows, [["1", "2", "1"], ["2", "2", "3"]]);
+    /// ```
+    pub fn renumber(&mut self) {
+        // new id by old id, by table
+        let mut ids: HashMap<String, HashMap<String, String>> = HashMap::new();
+        for (name, table) in &self.tables {
+            let Some(key) = primary_key(name).and_then(|key| table.column_index(key)) else {
+                continue;
+            };
+            let mut next = 0;
+            let map = ids.entry(name.clone()).or_default();
+            for row in &table.rows {
+                match row.get(key) {
+                    Some(id) if !id.is_empty() && !map.contains_key(id) => {
+                        next += 1;
+                        map.insert(id.clone(), next.to_string());
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        // UDF values refer by `fk_id` to the table their type is for
+        let udf_tables: HashMap<String, String> = udf_tables(self.table("UDFTYPE"))
+            .into_iter()
+            .map(|(id, table)| (id.to_string(), table.to_string()))
+            .collect();
+
+        // dangling references get ids after the rows, not those of other rows
+        for (name, table) in &self.tables {
+            let columns: Vec<(usize, &str)> = table
+                .header
+                .iter()
+                .enumerate()
+                .filter_map(|(i, column)| Some((i, foreign_table(name, column)?)))
+                .collect();
+            let udf = (name == "UDFVALUE")
+                .then(|| {
+                    table
+                        .column_index("udf_type_id")
+                        .zip(table.column_index("fk_id"))
+                })
+                .flatten();
+            for row in &table.rows {
+                let udf_reference =
+                    udf.and_then(|(kind, fk)| Some((fk, udf_tables.get(row.get(kind)?)?.as_str())));
+                for (i, target) in columns.iter().copied().chain(udf_reference) {
+                    let (Some(old), Some(map)) = (row.get(i), ids.get_mut(target)) else {
+                        continue;
+                    };
+                    if !old.is_empty() && !map.contains_key(old) {
+                        let new = (map.len() + 1).to_string();
+                        map.insert(old.clone(), new);
+                    }
+                }
+            }
+        }
+
+        for (name, table) in &mut self.tables {
+            let mut references: Vec<(usize, &HashMap<String, String>)> = table
+                .header
+                .iter()
+                .enumerate()
+                .filter_map(|(i, column)| Some((i, ids.get(foreign_table(name, column)?)?)))
+                .collect();
+            if let Some(key) = primary_key(name).and_then(|key| table.column_index(key)) {
+                references.extend(ids.get(name).map(|map| (key, map)));
+            }
+            let udf = (name == "UDFVALUE")
+                .then(|| {
+                    table
+                        .column_index("udf_type_id")
+                        .zip(table.column_index("fk_id"))
+                })
+                .flatten();
+            for row in &mut table.rows {
+                if let Some((kind, fk)) = udf {
+                    let map = row
+                        .get(kind)
+                        .and_then(|kind| ids.get(udf_tables.get(kind)?));
+                    if let Some(new) = map.and_then(|map| map.get(row.get(fk)?)) {
+                        row[fk] = new.clone();
+                    }
+                }
+                for (i, map) in &references {
+                    if let Some(new) = row.get(*i).and_then(|old| map.get(old)) {
+                        row[*i] = new.clone();
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::{column, parse};
+
+    #[test]
+    fn dangling_references_point_at_no_row() {
+        let mut xer = parse(
+            "%T\tTASK\n%F\ttask_id\n%R\t10\n%R\t20\n\
+             %T\tTASKPRED\n%F\ttask_pred_id\ttask_id\tpred_task_id\n\
+             %R\t1\t20\t1\n%R\t2\t10\t1\n%R\t3\t10\t30\n%R\t4\t20\t\n",
+        );
+        xer.renumber();
+        assert_eq!(column(&xer, "TASK", "task_id"), ["1", "2"]);
+        assert_eq!(column(&xer, "TASKPRED", "task_id"), ["2", "1", "1", "2"]);
+        // the same missing task keeps one id, empty references stay empty
+        assert_eq!(
+            column(&xer, "TASKPRED", "pred_task_id"),
+            ["3", "3", "4", ""]
+        );
+    }
+
+    #[test]
+    fn udf_values_follow_their_table() {
+        let mut xer = parse(
+            "%T\tUDFTYPE\n%F\tudf_type_id\ttable_name\n%R\t50\tTASK\n\
+             %T\tTASK\n%F\ttask_id\n%R\t10\n\
+             %T\tUDFVALUE\n%F\tudf_type_id\tfk_id\tudf_text\n%R\t50\t10\tx\n%R\t50\t99\ty\n",
+        );
+        xer.renumber();
+        assert_eq!(column(&xer, "UDFVALUE", "udf_type_id"), ["1", "1"]);
+        assert_eq!(column(&xer, "UDFVALUE", "fk_id"), ["1", "2"]);
+    }
+}
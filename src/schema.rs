@@ -0,0 +1,195 @@
+//! Column types inferred from the values of a table
+//!
+//! `.xer` files carry no type information, every value is text. Exports to
+//! typed formats pick a type per column with [`Table::column_types`].
+//! Tables and columns differ between P6 versions and export tools,
+//! [`XerFile::schema_diff`] lists them.
+//!
+//! ## Example
+//! ```
+//! use schedule_parser::schema::ColumnType;
+//!
+//! assert_eq!(ColumnType::infer(["1", "", "20"]), ColumnType::Integer);
+//! assert_eq!(ColumnType::infer(["1", "2.5"]), ColumnType::Float);
+//! assert_eq!(ColumnType::infer(["2024-03-15 08:00"]), ColumnType::DateTime);
+//! // leading zeros are kept, e.g. in activity codes
+//! assert_eq!(ColumnType::infer(["0010", "0020"]), ColumnType::Text);
+//! ```
+
+use std::collections::HashSet;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::model::parse_datetime;
+use crate::parser::Table;
+use crate::xer::XerFile;
+
+/// Type all non-empty values of a column conform to, empty values are null
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ColumnType {
+    /// Fits `i64`
+    Integer,
+    /// Fits `f64`, integers mixed with decimals
+    Float,
+    /// Date in P6 format, see [`parse_datetime`]
+    DateTime,
+    Text,
+}
+
+impl ColumnType {
+    /// Narrowest type of `value`, `None` if it is empty
+    pub fn of(value: &str) -> Option<Self> {
+        if value.is_empty() {
+            return None;
+        }
+        let digits = value.strip_prefix('-').unwrap_or(value);
+        let bytes = digits.as_bytes();
+        // numbers in P6 have no leading zeros, codes like `0010` do
+        let number = bytes.first().is_some_and(u8::is_ascii_digit)
+            && !(bytes.len() > 1 && bytes[0] == b'0' && bytes[1].is_ascii_digit());
+        Some(if number && value.parse::<i64>().is_ok() {
+            Self::Integer
+        } else if number && bytes.iter().all(|b| b"0123456789.eE+-".contains(b)) {
+            match value.parse::<f64>() {
+                Ok(value) if value.is_finite() => Self::Float,
+                _ => Self::Text,
+            }
+        } else if parse_datetime(value).is_some() {
+            Self::DateTime
+        } else {
+            Self::Text
+        })
+    }
+
+    /// Type holding values of both types
+    pub fn widen(self, other: Self) -> Self {
+        match (self, other) {
+            _ if self == other => self,
+            (Self::Integer, Self::Float) | (Self::Float, Self::Integer) => Self::Float,
+            _ => Self::Text,
+        }
+    }
+
+    /// Type of a column with `values`, `Text` if all are empty
+    pub fn infer<'a>(values: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut column: Option<Self> = None;
+        for value in values {
+            let Some(kind) = Self::of(value) else {
+                continue;
+            };
+            let widened = column.map_or(kind, |column| column.widen(kind));
+            if widened == Self::Text {
+                return Self::Text;
+            }
+            column = Some(widened);
+        }
+        column.unwrap_or(Self::Text)
+    }
+}
+
+impl Table {
+    /// Inferred type of every column of the header, see [`schema`](crate::schema)
+    pub fn column_types(&self) -> Vec<ColumnType> {
+        (0..self.header.len())
+            .map(|i| {
+                let values = self
+                    .rows
+                    .iter()
+                    .map(|row| row.get(i).map_or("", String::as_str));
+                ColumnType::infer(values)
+            })
+            .collect()
+    }
+
+    /// Column names made unique ignoring case with a numeric suffix, e.g. a
+    /// second `name` becomes `name_2`
+    ///
+    /// SQL tables and most Arrow consumers reject duplicate names.
+    pub fn unique_columns(&self) -> Vec<String> {
+        let mut seen = HashSet::new();
+        self.header
+            .iter()
+            .map(|column| {
+                let mut name = column.clone();
+                let mut n = 1;
+                while !seen.insert(name.to_lowercase()) {
+                    n += 1;
+                    name = format!("{column}_{n}");
+                }
+                name
+            })
+            .collect()
+    }
+}
+
+/// Table or column only one of two files has
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub enum SchemaChange {
+    TableAdded(String),
+    TableRemoved(String),
+    ColumnAdded { table: String, column: String },
+    ColumnRemoved { table: String, column: String },
+}
+
+/// `+ TASK.location_id`, `-` for removed
+impl fmt::Display for SchemaChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TableAdded(table) => write!(f, "+ {table}"),
+            Self::TableRemoved(table) => write!(f, "- {table}"),
+            Self::ColumnAdded { table, column } => write!(f, "+ {table}.{column}"),
+            Self::ColumnRemoved { table, column } => write!(f, "- {table}.{column}"),
+        }
+    }
+}
+
+impl XerFile {
+    /// Tables and columns added in `new` or missing there, column order is ignored
+    ///
+    /// Columns of added and removed tables are not listed.
+    ///
+    /// ## Example
+    /// ```
+    /// use schedule_parser::XerFile;
+    ///
+    /// let old = "ERMHDR\t8.0\t2024-03-15\n%T\tTASK\n%F\ttask_id\ttask_code\n%T\tPOBS\n%F\tpobs_id\n%E\n";
+    /// let new = "ERMHDR\t19.12\t2024-03-15\n%T\tTASK\n%F\ttask_id\ttask_code\tlocation_id\n%E\n";
+    /// let old = XerFile::parse(old.as_bytes()).unwrap();
+    /// let new = XerFile::parse(new.as_bytes()).unwrap();
+    ///
+    /// let changes: Vec<String> = old.schema_diff(&new).iter().map(ToString::to_string).collect();
+    /// assert_eq!(changes, ["+ TASK.location_id", "- POBS"]);
+    /// ```
+    pub fn schema_diff(&self, new: &XerFile) -> Vec<SchemaChange> {
+        let mut changes = Vec::new();
+        for (name, table) in &self.tables {
+            let Some(other) = new.table(name) else {
+                changes.push(SchemaChange::TableRemoved(name.clone()));
+                continue;
+            };
+            for column in &other.header {
+                if table.column_index(column).is_none() {
+                    changes.push(SchemaChange::ColumnAdded {
+                        table: name.clone(),
+                        column: column.clone(),
+                    });
+                }
+            }
+            for column in &table.header {
+                if other.column_index(column).is_none() {
+                    changes.push(SchemaChange::ColumnRemoved {
+                        table: name.clone(),
+                        column: column.clone(),
+                    });
+                }
+            }
+        }
+        for name in new.tables.keys() {
+            if self.table(name).is_none() {
+                changes.push(SchemaChange::TableAdded(name.clone()));
+            }
+        }
+        changes
+    }
+}
@@ -0,0 +1,143 @@
+//! Anonymization of schedules before sharing them
+//!
+//! [`XerFile::scrub`] replaces names and free text with placeholders built
+//! from the record id, e.g. `Task 5000`, so the same file always scrubs to
+//! the same result. Ids, codes, dates, durations and relationships are kept,
+//! the scrubbed file schedules exactly like the original.
+//!
+//! | Data        | Replaced                                                                    |
+//! |-------------|-----------------------------------------------------------------------------|
+//! | header      | user, user name and database                                                |
+//! | names       | activities, WBS, resources, roles, OBS, accounts, codes, steps, expenses    |
+//! | users       | `create_user` and `update_user` of all tables, contact details of resources |
+//! | user fields | text values in `UDFVALUE`                                                   |
+//! | notebooks   | memos of activities, WBS and projects                                       |
+//! | costs       | only with [`ScrubOptions::costs`], amounts and rates become `0`             |
+//!
+//! ## Example
+//! ```
+//! use schedule_parser::scrub::ScrubOptions;
+//! use schedule_parser::XerFile;
+//!
+//! let data = "ERMHDR\t19.12\t2024-03-15\tProject\tjdoe\tJohn Doe\tacme\tProject Management\tEUR\n%T\tTASK\n%F\ttask_id\ttask_code\ttask_name\n%R\t5000\tA1000\tPour east wing slab\n%E\n";
+//! let mut xer = XerFile::parse(data.as_bytes()).unwrap();
+//! xer.scrub(&ScrubOptions::default());
+//! assert_eq!(xer.header.user_name, "User");
+//! assert_eq!(xer.table("TASK").unwrap().rows[0], ["5000", "A1000", "Task 5000"]);
+//! ```
+
+use crate::exchange::is_cost;
+use crate::parser::Table;
+use crate::xer::XerFile;
+
+/// What [`XerFile::scrub`] replaces besides names, texts and users
+#[derive(Debug, Clone, Default)]
+pub struct ScrubOptions {
+    /// Set cost amounts, rates and earned values to `0`
+    pub costs: bool,
+}
+
+/// Table, column, id column and placeholder prefix of names, the id is appended
+const NAMES: &[(&str, &str, &str, &str)] = &[
+    ("TASK", "task_name", "task_id", "Task "),
+    ("PROJWBS", "wbs_name", "wbs_id", "WBS "),
+    ("RSRC", "rsrc_name", "rsrc_id", "Resource "),
+    ("RSRC", "rsrc_short_name", "rsrc_id", "R"),
+    ("ROLES", "role_name", "role_id", "Role "),
+    ("ROLES", "role_short_name", "role_id", "ROLE"),
+    ("OBS", "obs_name", "obs_id", "OBS "),
+    ("ACCOUNT", "acct_name", "acct_id", "Account "),
+    ("ACTVCODE", "actv_code_name", "actv_code_id", "Code "),
+    ("PCATVAL", "proj_catg_name", "proj_catg_id", "Code "),
+    ("TASKPROC", "proc_name", "proc_id", "Step "),
+    ("PROJCOST", "cost_name", "cost_item_id", "Expense "),
+    ("TASKMEMO", "task_memo", "memo_id", "Memo "),
+    ("WBSMEMO", "wbs_memo", "wbs_memo_id", "Memo "),
+    ("PROJMEMO", "proj_memo", "proj_memo_id", "Memo "),
+];
+
+/// Table and column of free text and contact details, emptied
+const TEXTS: &[(&str, &str)] = &[
+    ("RSRC", "email_addr"),
+    ("RSRC", "employee_code"),
+    ("RSRC", "office_phone"),
+    ("RSRC", "other_phone"),
+    ("RSRC", "rsrc_title_name"),
+    ("RSRC", "rsrc_notes"),
+    ("ROLES", "role_descr"),
+    ("ACCOUNT", "acct_descr"),
+    ("TASKPROC", "proc_descr"),
+    ("PROJCOST", "po_number"),
+    ("PROJCOST", "vendor_name"),
+    ("PROJCOST", "cost_descr"),
+    ("TASKPRED", "comments"),
+];
+
+/// Columns holding user logins in any table
+const USERS: &[&str] = &["create_user", "update_user"];
+
+impl XerFile {
+    /// Replace identifying data in place, see [`scrub`](crate::scrub)
+    pub fn scrub(&mut self, options: &ScrubOptions) {
+        self.header.user = "user".into();
+        self.header.user_name = "User".into();
+        self.header.database = "database".into();
+
+        for &(table, column, id, prefix) in NAMES {
+            if let Some(table) = self.tables.get_mut(table) {
+                replace_by_id(table, column, id, prefix);
+            }
+        }
+        for &(table, column) in TEXTS {
+            if let Some(table) = self.tables.get_mut(table) {
+                replace(table, column, |_| String::new());
+            }
+        }
+        if let Some(table) = self.tables.get_mut("UDFVALUE") {
+            let id = table.column_index("udf_type_id");
+            replace(table, "udf_text", |row| match id.and_then(|i| row.get(i)) {
+                Some(id) => format!("Text {id}"),
+                None => "Text".into(),
+            });
+        }
+
+        for table in self.tables.values_mut() {
+            for column in USERS {
+                replace(table, column, |_| "user".into());
+            }
+            if options.costs {
+                let costs: Vec<String> = table
+                    .header
+                    .iter()
+                    .filter(|column| is_cost(column))
+                    .cloned()
+                    .collect();
+                for column in &costs {
+                    replace(table, column, |_| "0".into());
+                }
+            }
+        }
+    }
+}
+
+/// Set `column` to `prefix` and the value of column `id` of the row
+fn replace_by_id(table: &mut Table, column: &str, id: &str, prefix: &str) {
+    let Some(id) = table.column_index(id) else {
+        return;
+    };
+    replace(table, column, |row| {
+        format!("{prefix}{}", row.get(id).map_or("", String::as_str))
+    });
+}
+
+/// Set non-empty values of `column` to `value` of their row
+fn replace(table: &mut Table, column: &str, mut value: impl FnMut(&[String]) -> String) {
+    let Some(i) = table.column_index(column) else {
+        return;
+    };
+    for row in &mut table.rows {
+        if row.get(i).is_some_and(|old| !old.is_empty()) {
+            row[i] = value(row);
+        }
+    }
+}
@@ -0,0 +1,84 @@
+//! Moving all dates of a schedule, e.g. for training data or templates
+
+use chrono::TimeDelta;
+
+use crate::model::parse_datetime;
+use crate::schema::ColumnType;
+use crate::xer::XerFile;
+
+impl XerFile {
+    /// Move every date by `days` calendar days, negative moves back
+    ///
+    /// Date columns are those [`Table::column_types`](crate::Table::column_types)
+    /// infers as dates. Calendar exceptions in `CALENDAR.clndr_data` are
+    /// moved too, so holidays stay on the same activities. Weekdays only stay
+    /// the same for multiples of 7. The export date in the header is kept.
+    ///
+    /// ## Example
+    /// ```
+    /// use schedule_parser::XerFile;
+    ///
+    /// let data = "ERMHDR\t19.12\t2024-03-15\n%T\tTASK\n%F\ttask_id\tearly_start_date\n%R\t1\t2024-03-15 08:00\n%E\n";
+    /// let mut xer = XerFile::parse(data.as_bytes()).unwrap();
+    /// xer.shift_dates(-14);
+    /// assert_eq!(xer.table("TASK").unwrap().rows[0], ["1", "2024-03-01 08:00"]);
+    /// ```
+    pub fn shift_dates(&mut self, days: i64) {
+        let offset = TimeDelta::days(days);
+        for table in self.tables.values_mut() {
+            let dates: Vec<usize> = table
+                .column_types()
+                .into_iter()
+                .enumerate()
+                .filter(|(_, kind)| *kind == ColumnType::DateTime)
+                .map(|(i, _)| i)
+                .collect();
+            let calendar = (table.name == "CALENDAR")
+                .then(|| table.column_index("clndr_data"))
+                .flatten();
+            for row in &mut table.rows {
+                for &i in &dates {
+                    if let Some(value) = row.get_mut(i) {
+                        if let Some(shifted) = shift(value, offset) {
+                            *value = shifted;
+                        }
+                    }
+                }
+                if let Some(value) = calendar.and_then(|i| row.get_mut(i)) {
+                    *value = shift_exceptions(value, days);
+                }
+            }
+        }
+    }
+}
+
+/// `value` moved by `offset` in the format it is written in
+fn shift(value: &str, offset: TimeDelta) -> Option<String> {
+    let date = parse_datetime(value)?.checked_add_signed(offset)?;
+    let format = match value.len() {
+        10 => "%Y-%m-%d",
+        19 => "%Y-%m-%d %H:%M:%S",
+        _ => "%Y-%m-%d %H:%M",
+    };
+    Some(date.format(format).to_string())
+}
+
+/// Calendar data with the day numbers of `(d|45292)` exception dates moved by `days`
+fn shift_exceptions(data: &str, days: i64) -> String {
+    let mut shifted = String::with_capacity(data.len());
+    let mut rest = data;
+    while let Some(i) = rest.find("(d|") {
+        let (before, after) = rest.split_at(i + 3);
+        shifted.push_str(before);
+        let end = after
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(after.len());
+        match after[..end].parse::<i64>() {
+            Ok(serial) => shifted.push_str(&(serial + days).to_string()),
+            Err(_) => shifted.push_str(&after[..end]),
+        }
+        rest = &after[end..];
+    }
+    shifted.push_str(rest);
+    shifted
+}
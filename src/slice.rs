@@ -0,0 +1,331 @@
+//! Zero-copy parsing of `.xer` data already loaded in memory
+
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+use crate::error::ParseError;
+use crate::header::Header;
+use crate::parser::{split_fields, Table};
+
+/// Whole `.xer` file borrowing all names and values from the source text
+///
+/// Unlike [`XerFile`](crate::XerFile) no `String` is allocated per field,
+/// which matters on files of hundreds of megabytes. Convert single tables
+/// with [`TableSlice::to_table`] to use the typed models.
+///
+/// Being borrowed, values keep stray NUL bytes that
+/// [`XerReader`](crate::XerReader) drops. Lines of NUL bytes only are
+/// skipped by both.
+///
+/// ## Example
+/// ```
+/// use schedule_parser::XerSlice;
+///
+/// let data = "ERMHDR\t19.12\t2024-03-15\n%T\tTASK\n%F\ttask_id\ttask_code\n%R\t1\tA1000\n%E\n";
+/// let xer = XerSlice::parse(data).unwrap();
+/// let task = xer.table("TASK").unwrap();
+/// assert_eq!(task.row(0).unwrap().get("task_code").unwrap(), "A1000");
+/// ```
+#[derive(Debug)]
+pub struct XerSlice<'a> {
+    pub header: Header,
+    /// Tables in file order
+    pub tables: Vec<TableSlice<'a>>,
+}
+
+impl<'a> XerSlice<'a> {
+    /// Parse `data`, rules are the same as for [`XerReader`](crate::XerReader)
+    pub fn parse(data: &'a str) -> Result<Self, ParseError> {
+        let (header, body) = split_header(data)?;
+        let tables = parse_tables(body, 2)?;
+        Ok(Self { header, tables })
+    }
+
+    /// Parse `data` after checking it is valid UTF-8
+    pub fn parse_bytes(data: &'a [u8]) -> Result<Self, ParseError> {
+        Self::parse(to_str(data)?)
+    }
+
+    /// Table by name, e.g. `TASK`
+    pub fn table(&self, name: &str) -> Option<&TableSlice<'a>> {
+        self.tables.iter().find(|table| table.name == name)
+    }
+
+    /// Data was cut off before `%E`, the last table is marked [`truncated`](TableSlice::truncated)
+    pub fn is_truncated(&self) -> bool {
+        self.tables.iter().any(|table| table.truncated)
+    }
+}
+
+/// `ERMHDR` line and the rest of `data`, a leading BOM is skipped
+pub(crate) fn split_header(data: &str) -> Result<(Header, &str), ParseError> {
+    let data = data.strip_prefix('\u{feff}').unwrap_or(data);
+    let Some(line) = lines_inclusive(data).next() else {
+        return Err(ParseError::UnexpectedEof { line: 1 });
+    };
+    let body = &data[line.len()..];
+    let line = trim_line(line);
+    let header = Header::parse(line).ok_or_else(|| ParseError::InvalidHeader {
+        line: 1,
+        content: line.into(),
+    })?;
+    Ok((header, body))
+}
+
+/// Tables in `text` up to `%E`, `first_line` is the line number of its first line
+///
+/// If `text` ends before `%E` the last table is marked truncated.
+pub(crate) fn parse_tables(
+    text: &str,
+    first_line: usize,
+) -> Result<Vec<TableSlice<'_>>, ParseError> {
+    // line number, offset in `text` and line without its line break
+    let mut offset = 0;
+    let mut lines = lines_inclusive(text)
+        .enumerate()
+        .map(|(i, line)| {
+            let start = offset;
+            offset += line.len();
+            (first_line + i, start, trim_line(line))
+        })
+        // as `XerReader` skips lines of NUL bytes only
+        .filter(|(_, _, line)| line.is_empty() || line.bytes().any(|b| b != 0))
+        .peekable();
+
+    let mut tables = Vec::new();
+    while let Some((number, _, line)) = lines.next() {
+        if line.starts_with("%E") {
+            return Ok(tables);
+        }
+        if !line.starts_with("%T") {
+            // lines before the first table are ignored
+            if tables.is_empty() {
+                continue;
+            }
+            return Err(ParseError::UnexpectedRecord {
+                line: number,
+                content: line.into(),
+            });
+        }
+        let name = match split_fields(line).nth(1) {
+            Some(name) if !name.is_empty() => name,
+            _ => {
+                return Err(ParseError::MissingTableName {
+                    line: number,
+                    content: line.into(),
+                })
+            }
+        };
+        let header: Vec<&str> = match lines.next() {
+            Some((_, _, line)) if line.starts_with("%F") => split_fields(line).skip(1).collect(),
+            Some((number, _, line)) => {
+                return Err(ParseError::UnexpectedRecord {
+                    line: number,
+                    content: line.into(),
+                })
+            }
+            None => Vec::new(),
+        };
+        let mut rows = Vec::new();
+        while let Some((_, start, line)) = lines.next_if(|(_, _, line)| line.starts_with("%R")) {
+            // line breaks in text fields continue the row on the next lines
+            let mut end = start + line.len();
+            while let Some((_, next, line)) =
+                lines.next_if(|(_, _, line)| !line.starts_with(['%', '\0']))
+            {
+                end = next + line.len();
+            }
+            rows.push(split_fields(&text[start..end]).skip(1).collect());
+        }
+        tables.push(TableSlice::new(name, header, rows));
+    }
+    if let Some(table) = tables.last_mut() {
+        table.truncated = true;
+    }
+    Ok(tables)
+}
+
+/// Lines of `text` with their line break: `\n`, `\r\n` or a lone `\r`
+pub(crate) fn lines_inclusive(text: &str) -> impl Iterator<Item = &str> {
+    let mut rest = text;
+    std::iter::from_fn(move || {
+        if rest.is_empty() {
+            return None;
+        }
+        let bytes = rest.as_bytes();
+        let end = match memchr::memchr2(b'\n', b'\r', bytes) {
+            Some(i) if bytes[i] == b'\r' && bytes.get(i + 1) == Some(&b'\n') => i + 2,
+            Some(i) => i + 1,
+            None => rest.len(),
+        };
+        let (line, tail) = rest.split_at(end);
+        rest = tail;
+        Some(line)
+    })
+}
+
+/// `line` without its line break
+pub(crate) fn trim_line(line: &str) -> &str {
+    let line = line.strip_suffix('\n').unwrap_or(line);
+    line.strip_suffix('\r').unwrap_or(line)
+}
+
+/// `data` as text, error points at the first line with invalid UTF-8
+pub(crate) fn to_str(data: &[u8]) -> Result<&str, ParseError> {
+    std::str::from_utf8(data).map_err(|err| {
+        let valid = &data[..err.valid_up_to()];
+        let start = valid.iter().rposition(|&b| b == b'\n').map_or(0, |i| i + 1);
+        let end = data[start..]
+            .iter()
+            .position(|&b| b == b'\n')
+            .map_or(data.len(), |i| start + i);
+        ParseError::InvalidUtf8 {
+            line: valid.iter().filter(|&&b| b == b'\n').count() + 1,
+            content: String::from_utf8_lossy(&data[start..end])
+                .trim_end_matches('\r')
+                .into(),
+        }
+    })
+}
+
+/// Table with names and values borrowed from the source text
+#[derive(Debug)]
+pub struct TableSlice<'a> {
+    pub name: &'a str,
+    pub header: Vec<&'a str>,
+    pub rows: Vec<Vec<&'a str>>,
+    /// Data ended inside this table without `%E`, its last row may be cut off
+    pub truncated: bool,
+    /// Column name to position in `header`
+    index: HashMap<&'a str, usize>,
+}
+
+impl<'a> TableSlice<'a> {
+    pub fn new(name: &'a str, header: Vec<&'a str>, rows: Vec<Vec<&'a str>>) -> Self {
+        let mut index = HashMap::with_capacity(header.len());
+        for (i, column) in header.iter().enumerate() {
+            index.entry(*column).or_insert(i);
+        }
+        Self {
+            name,
+            header,
+            rows,
+            truncated: false,
+            index,
+        }
+    }
+
+    pub fn row_count(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Position of `column` in the header
+    pub fn column_index(&self, column: &str) -> Option<usize> {
+        self.index.get(column).copied()
+    }
+
+    /// Row `i` with access by column name
+    pub fn row(&self, i: usize) -> Option<RowSlice<'_, 'a>> {
+        self.rows.get(i).map(|values| RowSlice {
+            table: self,
+            values,
+        })
+    }
+
+    /// Rows with access by column name
+    pub fn iter(&self) -> impl Iterator<Item = RowSlice<'_, 'a>> {
+        self.rows.iter().map(|values| RowSlice {
+            table: self,
+            values,
+        })
+    }
+
+    /// Deserialize rows into user type `T`, see [`de`](crate::de)
+    ///
+    /// `T` may borrow `&str` fields from the source text.
+    pub fn deserialize<T: Deserialize<'a>>(
+        &self,
+    ) -> impl Iterator<Item = Result<T, ParseError>> + use<'_, 'a, T> {
+        self.iter().map(|row| row.deserialize())
+    }
+
+    /// Owned copy of the table
+    pub fn to_table(&self) -> Table {
+        let mut table = Table::new(
+            self.name.into(),
+            self.header
+                .iter()
+                .map(|column| column.to_string())
+                .collect(),
+            self.rows
+                .iter()
+                .map(|row| row.iter().map(|value| value.to_string()).collect())
+                .collect(),
+        );
+        table.truncated = self.truncated;
+        table
+    }
+}
+
+/// Row of a [`TableSlice`] with values looked up by column name
+#[derive(Debug, Clone, Copy)]
+pub struct RowSlice<'t, 'a> {
+    table: &'t TableSlice<'a>,
+    values: &'t [&'a str],
+}
+
+impl<'t, 'a> RowSlice<'t, 'a> {
+    /// Values in column order, trailing empty values may be cut off
+    pub fn values(&self) -> &'t [&'a str] {
+        self.values
+    }
+
+    /// Value of `column`, fails if the table has no such column
+    pub fn get(&self, column: &str) -> Result<&'a str, ParseError> {
+        self.raw(column).ok_or_else(|| ParseError::MissingColumn {
+            table: self.table.name.into(),
+            column: column.into(),
+        })
+    }
+
+    /// Value of `column`, `None` if the column is missing or the value empty
+    pub fn get_opt(&self, column: &str) -> Option<&'a str> {
+        self.raw(column).filter(|value| !value.is_empty())
+    }
+
+    /// `(column, value)` pairs for every column of the header
+    pub fn iter(&self) -> impl Iterator<Item = (&'a str, &'a str)> + 't {
+        let values = self.values;
+        self.table
+            .header
+            .iter()
+            .enumerate()
+            .map(move |(i, column)| (*column, values.get(i).copied().unwrap_or("")))
+    }
+
+    /// Deserialize into user type `T`, see [`de`](crate::de)
+    pub fn deserialize<T: Deserialize<'a>>(&self) -> Result<T, ParseError> {
+        crate::de::from_columns(self.table.name, self.iter())
+    }
+
+    fn raw(&self, column: &str) -> Option<&'a str> {
+        let index = *self.table.index.get(column)?;
+        Some(self.values.get(index).copied().unwrap_or(""))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lines_of_nul_bytes_are_skipped() {
+        let data = "ERMHDR\t19.12\t2024-03-15\n%T\tTASK\n%F\ttask_id\ttask_name\n\
+                    %R\t1\tDesign\n\0\0\n%R\t2\tBuild\r\nsecond line\n%E\n";
+        let xer = XerSlice::parse(data).unwrap();
+        let task = xer.table("TASK").unwrap();
+        assert_eq!(task.rows, [["1", "Design"], ["2", "Build\r\nsecond line"]]);
+        assert!(!xer.is_truncated());
+    }
+}
@@ -0,0 +1,187 @@
+//! Finish date slippage of an update against its baseline
+//!
+//! Activities are matched by activity ID like in [`compare`](crate::compare).
+//! The driving predecessor of an activity is the one whose finish, or start
+//! for start relationships, plus lag is latest, lags are counted as elapsed
+//! hours.
+//!
+//! ## Example
+//! ```
+//! use schedule_parser::XerFile;
+//!
+//! let header = "ERMHDR\t19.12\t2024-03-15\n%T\tTASK\n\
+//!     %F\ttask_id\tproj_id\twbs_id\ttask_code\ttask_name\ttask_type\tstatus_code\ttotal_float_hr_cnt\tearly_end_date\n";
+//! let baseline = format!("{header}%R\t1\t1\t1\tA1000\tPour slab\tTT_Task\tTK_NotStart\t40\t2024-03-15 17:00\n%E\n");
+//! let update = format!("{header}%R\t1\t1\t1\tA1000\tPour slab\tTT_Task\tTK_NotStart\t0\t2024-03-22 17:00\n%E\n");
+//! let baseline = XerFile::parse(baseline.as_bytes()).unwrap();
+//! let update = XerFile::parse(update.as_bytes()).unwrap();
+//!
+//! let slipped = baseline.slippage(&update, 5.0).unwrap();
+//! assert_eq!(slipped[0].slip_days, 7.0);
+//! assert_eq!(slipped[0].float_erosion, Some(40.0));
+//! assert!(baseline.slippage(&update, 7.0).unwrap().is_empty());
+//! ```
+
+use std::collections::{HashMap, HashSet};
+
+use chrono::{NaiveDateTime, TimeDelta};
+use serde::Serialize;
+
+use crate::error::ParseError;
+use crate::graph::LogicGraph;
+use crate::model::{LinkType, Task, WbsTree};
+use crate::xer::XerFile;
+
+/// Activity finishing later in the update than in the baseline
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Slippage {
+    pub task_code: String,
+    pub task_name: String,
+    /// WBS element of the activity in the update
+    pub wbs_id: u64,
+    /// Actual or early finish in the baseline
+    pub baseline_finish: NaiveDateTime,
+    /// Actual or early finish in the update
+    pub update_finish: NaiveDateTime,
+    /// Calendar days the finish moved, fractions for hours
+    pub slip_days: f64,
+    /// Total float in hours
+    pub baseline_total_float: Option<f64>,
+    pub update_total_float: Option<f64>,
+    /// Total float lost in hours, negative if gained
+    pub float_erosion: Option<f64>,
+    /// Activity IDs of the driving predecessors in the update, nearest first
+    pub driving_path: Vec<String>,
+}
+
+/// Slipped activities of a WBS element and its descendants
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WbsSlippage {
+    pub wbs_id: u64,
+    /// Short names from the project node down, joined by `.`
+    pub wbs_code: String,
+    pub wbs_name: String,
+    /// `0` for project nodes
+    pub depth: usize,
+    pub activities: usize,
+    pub max_slip_days: f64,
+}
+
+impl XerFile {
+    /// Activities of `self` as baseline finishing more than `threshold_days`
+    /// later in `update`, most slipped first
+    pub fn slippage(
+        &self,
+        update: &XerFile,
+        threshold_days: f64,
+    ) -> Result<Vec<Slippage>, ParseError> {
+        // the first of activities with the same ID wins
+        let baseline: HashMap<String, Task> = self
+            .tasks()?
+            .into_iter()
+            .rev()
+            .map(|task| (task.task_code.clone(), task))
+            .collect();
+        let tasks = update.tasks()?;
+        let by_id: HashMap<u64, &Task> = tasks.iter().map(|task| (task.task_id, task)).collect();
+        let graph = update.logic_graph()?;
+
+        let mut slipped = Vec::new();
+        for task in &tasks {
+            let Some(old) = baseline.get(&task.task_code) else {
+                continue;
+            };
+            let (Some(baseline_finish), Some(update_finish)) = (old.finish(), task.finish()) else {
+                continue;
+            };
+            let slip_days = (update_finish - baseline_finish).num_minutes() as f64 / (24.0 * 60.0);
+            if slip_days <= threshold_days {
+                continue;
+            }
+
+            // follow driving predecessors, a logic loop ends the path
+            let mut driving_path = Vec::new();
+            let mut seen = HashSet::from([task.task_id]);
+            let mut current = task.task_id;
+            while let Some(next) = driving_predecessor(&graph, &by_id, current) {
+                if !seen.insert(next.task_id) {
+                    break;
+                }
+                driving_path.push(next.task_code.clone());
+                current = next.task_id;
+            }
+
+            slipped.push(Slippage {
+                task_code: task.task_code.clone(),
+                task_name: task.task_name.clone(),
+                wbs_id: task.wbs_id,
+                baseline_finish,
+                update_finish,
+                slip_days,
+                baseline_total_float: old.total_float,
+                update_total_float: task.total_float,
+                float_erosion: old
+                    .total_float
+                    .zip(task.total_float)
+                    .map(|(old, new)| old - new),
+                driving_path,
+            });
+        }
+        slipped.sort_by(|a, b| b.slip_days.total_cmp(&a.slip_days));
+        Ok(slipped)
+    }
+}
+
+/// Predecessor of `task` with the latest relevant date plus lag
+fn driving_predecessor<'a>(
+    graph: &LogicGraph,
+    tasks: &HashMap<u64, &'a Task>,
+    task: u64,
+) -> Option<&'a Task> {
+    graph
+        .predecessors(task)
+        .filter_map(|link| {
+            let predecessor = *tasks.get(&link.pred_task_id)?;
+            let date = match link.link_type {
+                LinkType::FinishToStart | LinkType::FinishToFinish => predecessor.finish(),
+                LinkType::StartToStart | LinkType::StartToFinish => predecessor.start(),
+            }?;
+            let lag = TimeDelta::minutes((link.lag * 60.0) as i64);
+            Some((date + lag, predecessor))
+        })
+        .max_by_key(|(date, _)| *date)
+        .map(|(_, predecessor)| predecessor)
+}
+
+/// Count and worst slip of `slipped` activities per WBS element including
+/// its descendants, in WBS order
+pub fn summarize_by_wbs(slipped: &[Slippage], wbs: &WbsTree) -> Vec<WbsSlippage> {
+    // slips of every element and its ancestors
+    let mut slips: HashMap<u64, Vec<f64>> = HashMap::new();
+    for slippage in slipped {
+        for element in wbs.path_to_root(slippage.wbs_id) {
+            slips
+                .entry(element.wbs_id)
+                .or_default()
+                .push(slippage.slip_days);
+        }
+    }
+    wbs.preorder()
+        .filter_map(|(depth, element)| {
+            let slips = slips.get(&element.wbs_id)?;
+            let mut path: Vec<&str> = wbs
+                .path_to_root(element.wbs_id)
+                .map(|node| node.wbs_short_name.as_str())
+                .collect();
+            path.reverse();
+            Some(WbsSlippage {
+                wbs_id: element.wbs_id,
+                wbs_code: path.join("."),
+                wbs_name: element.wbs_name.clone(),
+                depth,
+                activities: slips.len(),
+                max_slip_days: slips.iter().copied().fold(f64::MIN, f64::max),
+            })
+        })
+        .collect()
+}
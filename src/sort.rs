@@ -0,0 +1,151 @@
+//! Row and table order for exports that compare byte for byte
+//!
+//! Values are compared as the type of their column by
+//! [`Table::column_types`], so `9` sorts before `10` and dates in time order.
+//! Empty and cut off values sort last in either direction. Sorts are stable:
+//! rows with equal keys keep their order, after [`XerFile::sort_canonical`]
+//! that is the canonical one.
+//!
+//! ## Example
+//! ```
+//! use schedule_parser::XerFile;
+//!
+//! let data = "ERMHDR\t19.12\t2024-03-15\n%T\tTASK\n\
+//!     %F\ttask_id\ttarget_end_date\n\
+//!     %R\t10\t2024-12-20 17:00\n\
+//!     %R\t9\t\n\
+//!     %R\t11\t2025-02-14 17:00\n\
+//!     %T\tCALENDAR\n%F\tclndr_id\n%R\t1\n%E\n";
+//! let mut xer = XerFile::parse(data.as_bytes()).unwrap();
+//! let ids = |xer: &XerFile| -> Vec<String> {
+//!     xer.tables["TASK"].rows.iter().map(|row| row[0].clone()).collect()
+//! };
+//!
+//! assert_eq!(xer.sort_rows("target_end_date", true).unwrap(), ["TASK"]);
+//! assert_eq!(ids(&xer), ["11", "10", "9"]);
+//!
+//! xer.sort_canonical();
+//! assert_eq!(ids(&xer), ["9", "10", "11"]);
+//! assert!(xer.tables.keys().eq(["CALENDAR", "TASK"]));
+//! assert!(xer.sort_rows("status_code", false).is_err());
+//! ```
+
+use std::cmp::Ordering;
+use std::fmt;
+
+use crate::model::parse_datetime;
+use crate::parser::Table;
+use crate::schema::ColumnType;
+use crate::xer::XerFile;
+
+/// Failure to sort by a column
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SortError {
+    /// Sort column not in the header
+    MissingColumn { column: String },
+    /// No table has the sort column, see [`XerFile::sort_rows`]
+    NoTable { column: String },
+}
+
+impl fmt::Display for SortError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingColumn { column } => write!(f, "no sort column {column}"),
+            Self::NoTable { column } => write!(f, "no table has sort column {column}"),
+        }
+    }
+}
+
+impl std::error::Error for SortError {}
+
+impl Table {
+    /// Sort rows by the values of `column`, stable, empty values last
+    pub fn sort_by_column(&mut self, column: &str, descending: bool) -> Result<(), SortError> {
+        let i = self
+            .column_index(column)
+            .ok_or_else(|| SortError::MissingColumn {
+                column: column.to_string(),
+            })?;
+        let kind = self.column_types()[i];
+        self.rows
+            .sort_by(|a, b| compare(kind, value(a, i), value(b, i), descending));
+        Ok(())
+    }
+
+    /// Sort rows by all columns from the first, values past the header last
+    pub fn sort_canonical(&mut self) {
+        let types = self.column_types();
+        let width = types.len();
+        self.rows.sort_by(|a, b| {
+            types
+                .iter()
+                .enumerate()
+                .map(|(i, &kind)| compare(kind, value(a, i), value(b, i), false))
+                .find(|order| order.is_ne())
+                .unwrap_or_else(|| {
+                    let rest = |row: &[String]| row.len().min(width);
+                    a[rest(a)..].cmp(&b[rest(b)..]).then(a.len().cmp(&b.len()))
+                })
+        });
+    }
+}
+
+impl XerFile {
+    /// [Sort](Table::sort_by_column) every table with `column`, see
+    /// [`XerFile::for_tables_with`]
+    pub fn sort_rows(&mut self, column: &str, descending: bool) -> Result<Vec<String>, SortError> {
+        self.for_tables_with(
+            &[column],
+            |table| table.sort_by_column(column, descending),
+            || SortError::NoTable {
+                column: column.to_string(),
+            },
+        )
+    }
+
+    /// Sort tables by name and the rows of each [canonically](Table::sort_canonical)
+    ///
+    /// Files with the same tables and rows in any order come out equal.
+    pub fn sort_canonical(&mut self) {
+        self.tables.sort_keys();
+        for table in self.tables.values_mut() {
+            table.sort_canonical();
+        }
+    }
+}
+
+fn value(row: &[String], i: usize) -> &str {
+    row.get(i).map_or("", String::as_str)
+}
+
+/// Order of `a` and `b` as values of a `kind` column, empty ones last
+fn compare(kind: ColumnType, a: &str, b: &str, descending: bool) -> Ordering {
+    let order = match (a.is_empty(), b.is_empty()) {
+        (true, true) => return Ordering::Equal,
+        (true, false) => return Ordering::Greater,
+        (false, true) => return Ordering::Less,
+        (false, false) => match kind {
+            ColumnType::Integer => a
+                .parse::<i64>()
+                .ok()
+                .zip(b.parse().ok())
+                .map(|(a, b)| a.cmp(&b)),
+            ColumnType::Float => a
+                .parse::<f64>()
+                .ok()
+                .zip(b.parse().ok())
+                .map(|(a, b)| a.total_cmp(&b)),
+            ColumnType::DateTime => parse_datetime(a)
+                .zip(parse_datetime(b))
+                .map(|(a, b)| a.cmp(&b)),
+            ColumnType::Text => None,
+        }
+        // text, and values not of the inferred type
+        .unwrap_or_else(|| a.cmp(b)),
+    };
+    if descending {
+        order.reverse()
+    } else {
+        order
+    }
+}
@@ -0,0 +1,120 @@
+//! Partition of multi-project files into one file per project
+
+use std::collections::{HashMap, HashSet};
+
+use indexmap::IndexMap;
+
+use crate::keys::{foreign_table, primary_key};
+use crate::parser::Table;
+use crate::xer::XerFile;
+
+impl XerFile {
+    /// One file per project by its short name, in `PROJECT` order
+    ///
+    /// Rows with the `proj_id` of another project are dropped, rows without
+    /// `proj_id` like resources and global calendars are copied to every
+    /// file. Rows of such tables referring to dropped rows are dropped too,
+    /// e.g. activity codes of another project's code types.
+    ///
+    /// Relationships to activities of other projects are dropped unless
+    /// `cross_project` is set, P6 imports them as external relationships.
+    ///
+    /// ## Example
+    /// ```
+    /// use schedule_parser::XerFile;
+    ///
+    /// let data = "ERMHDR\t19.12\t2024-03-15\n\
+    ///     %T\tPROJECT\n%F\tproj_id\tproj_short_name\n%R\t1\tA\n%R\t2\tB\n\
+    ///     %T\tTASK\n%F\ttask_id\tproj_id\n%R\t10\t1\n%R\t20\t2\n\
+    ///     %T\tTASKPRED\n%F\ttask_pred_id\ttask_id\tpred_task_id\tproj_id\tpred_proj_id\n%R\t1\t20\t10\t2\t1\n%E\n";
+    /// let xer = XerFile::parse(data.as_bytes()).unwrap();
+    ///
+    /// let files = xer.split_by_project(false);
+    /// assert_eq!(files["B"].table("TASK").unwrap().rows, [["20", "2"]]);
+    /// assert_eq!(files["B"].table("TASKPRED").unwrap().row_count(), 0);
+    /// assert_eq!(xer.split_by_project(true)["B"].table("TASKPRED").unwrap().row_count(), 1);
+    /// ```
+    pub fn split_by_project(&self, cross_project: bool) -> IndexMap<String, XerFile> {
+        let Some(projects) = self.table("PROJECT") else {
+            return IndexMap::new();
+        };
+        projects
+            .iter()
+            .filter_map(|project| {
+                let id = project.get_opt("proj_id")?;
+                let name = project.get_opt("proj_short_name").unwrap_or(id);
+                Some((name.to_string(), self.project(id, cross_project)))
+            })
+            .collect()
+    }
+
+    /// Copy of file with rows of project `id` and shared rows
+    fn project(&self, id: &str, cross_project: bool) -> XerFile {
+        // ids of dropped rows by table
+        let mut dropped: HashMap<&str, HashSet<String>> = HashMap::new();
+        let mut shared = Vec::new();
+        let mut tables = IndexMap::new();
+        for (name, table) in &self.tables {
+            let project = table.column_index("proj_id");
+            let pred_project = table.column_index("pred_proj_id");
+            let belongs = |row: &Vec<String>, column: Option<usize>| {
+                let value = column.and_then(|i| row.get(i)).map_or("", String::as_str);
+                value.is_empty() || value == id
+            };
+            let (rows, other): (Vec<_>, Vec<_>) = table.rows.iter().cloned().partition(|row| {
+                belongs(row, project) && (cross_project || belongs(row, pred_project))
+            });
+            if let Some(key) = primary_key(name).and_then(|key| table.column_index(key)) {
+                let ids = other.into_iter().filter_map(|row| row.get(key).cloned());
+                dropped.entry(name).or_default().extend(ids);
+            }
+            if project.is_none() {
+                shared.push(name.as_str());
+            }
+            let mut copy = Table::new(name.clone(), table.header.clone(), rows);
+            copy.truncated = table.truncated;
+            tables.insert(name.clone(), copy);
+        }
+
+        // drop shared rows referring to dropped rows until none is left
+        loop {
+            let mut changed = false;
+            for name in &shared {
+                let table = &mut tables[*name];
+                let references: Vec<(usize, &HashSet<String>)> = table
+                    .header
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(i, column)| {
+                        let ids = dropped.get(foreign_table(name, column)?)?;
+                        (!ids.is_empty()).then_some((i, ids))
+                    })
+                    .collect();
+                if references.is_empty() {
+                    continue;
+                }
+                let key = primary_key(name).and_then(|key| table.column_index(key));
+                let mut removed = Vec::new();
+                table.rows.retain(|row| {
+                    let orphan = references
+                        .iter()
+                        .any(|(i, ids)| row.get(*i).is_some_and(|value| ids.contains(value)));
+                    if orphan {
+                        removed.extend(key.and_then(|key| row.get(key)).cloned());
+                    }
+                    !orphan
+                });
+                changed |= !removed.is_empty();
+                dropped.entry(name).or_default().extend(removed);
+            }
+            if !changed {
+                break;
+            }
+        }
+
+        XerFile {
+            header: self.header.clone(),
+            tables,
+        }
+    }
+}
@@ -0,0 +1,20 @@
+//! Fixtures shared by the unit tests
+
+use crate::xer::XerFile;
+
+/// Parse `tables` after a full `ERMHDR` line in EUR, `%E` is added
+pub(crate) fn parse(tables: &str) -> XerFile {
+    let data = format!(
+        "ERMHDR\t19.12\t2024-03-15\tProject\tadmin\tAdministrator\tdb\tProject Management\tEUR\n{tables}%E\n"
+    );
+    XerFile::parse(data.as_bytes()).unwrap()
+}
+
+/// Values of `column` in every row of `table`
+pub(crate) fn column<'a>(xer: &'a XerFile, table: &str, column: &str) -> Vec<&'a str> {
+    xer.table(table)
+        .unwrap()
+        .iter()
+        .map(|row| row.get(column).unwrap())
+        .collect()
+}
@@ -0,0 +1,236 @@
+//! Hierarchy rebuilt from flat `id`/`parent_id` rows
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Record linked to its parent by id
+pub trait TreeNode {
+    fn id(&self) -> u64;
+    fn parent_id(&self) -> Option<u64>;
+
+    /// Ordering among siblings
+    fn seq(&self) -> i64 {
+        0
+    }
+}
+
+/// Forest of nodes, nodes with missing parent become roots
+///
+/// Parent links of broken files may form a loop, the node of each loop first
+/// in input order becomes a root then.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Tree<T> {
+    nodes: Vec<T>,
+    index: HashMap<u64, usize>,
+    parents: Vec<Option<usize>>,
+    children: Vec<Vec<usize>>,
+    roots: Vec<usize>,
+}
+
+impl<T: TreeNode> Tree<T> {
+    /// Link `nodes` by parent id, siblings are ordered by [`TreeNode::seq`]
+    pub fn new(nodes: Vec<T>) -> Self {
+        let index: HashMap<u64, usize> = nodes
+            .iter()
+            .enumerate()
+            .map(|(i, node)| (node.id(), i))
+            .collect();
+
+        let mut parents = vec![None; nodes.len()];
+        let mut children = vec![Vec::new(); nodes.len()];
+        let mut roots = Vec::new();
+        for (i, node) in nodes.iter().enumerate() {
+            match node.parent_id().and_then(|id| index.get(&id)) {
+                Some(&parent) if parent != i => {
+                    parents[i] = Some(parent);
+                    children[parent].push(i);
+                }
+                _ => roots.push(i),
+            }
+        }
+        for i in loop_breaks(&parents) {
+            let parent = parents[i].take().unwrap();
+            children[parent].retain(|&child| child != i);
+            roots.push(i);
+        }
+        roots.sort_by_key(|&i| (nodes[i].seq(), i));
+        for siblings in &mut children {
+            siblings.sort_by_key(|&i| nodes[i].seq());
+        }
+
+        Self {
+            nodes,
+            index,
+            parents,
+            children,
+            roots,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Nodes in input order
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.nodes.iter()
+    }
+
+    pub fn get(&self, id: u64) -> Option<&T> {
+        self.index.get(&id).map(|&i| &self.nodes[i])
+    }
+
+    pub fn roots(&self) -> impl Iterator<Item = &T> {
+        self.roots.iter().map(|&i| &self.nodes[i])
+    }
+
+    pub fn parent(&self, id: u64) -> Option<&T> {
+        let i = *self.index.get(&id)?;
+        self.parents[i].map(|p| &self.nodes[p])
+    }
+
+    /// Direct children of node `id`, empty for unknown id
+    pub fn children(&self, id: u64) -> impl Iterator<Item = &T> {
+        self.index
+            .get(&id)
+            .map_or(&[][..], |&i| &self.children[i][..])
+            .iter()
+            .map(|&i| &self.nodes[i])
+    }
+
+    /// Node `id` itself followed by its ancestors up to the root
+    pub fn path_to_root(&self, id: u64) -> impl Iterator<Item = &T> {
+        let mut next = self.index.get(&id).copied();
+        std::iter::from_fn(move || {
+            let i = next?;
+            next = self.parents[i];
+            Some(&self.nodes[i])
+        })
+    }
+
+    /// Depth of node `id`, roots are at `0`
+    pub fn depth(&self, id: u64) -> Option<usize> {
+        self.index.get(&id)?;
+        Some(self.path_to_root(id).count() - 1)
+    }
+
+    /// Whole forest depth first, yields `(depth, node)`
+    pub fn preorder(&self) -> Preorder<'_, T> {
+        Preorder {
+            tree: self,
+            stack: self.roots.iter().rev().map(|&i| (0, i)).collect(),
+        }
+    }
+
+    /// Node `id` and all its descendants depth first, depth is relative to `id`
+    pub fn subtree(&self, id: u64) -> Preorder<'_, T> {
+        Preorder {
+            tree: self,
+            stack: self.index.get(&id).map(|&i| (0, i)).into_iter().collect(),
+        }
+    }
+}
+
+/// Node of every parent loop first in input order
+fn loop_breaks(parents: &[Option<usize>]) -> Vec<usize> {
+    #[derive(Clone, Copy, PartialEq)]
+    enum State {
+        New,
+        OnPath,
+        Done,
+    }
+    let mut state = vec![State::New; parents.len()];
+    let mut breaks = Vec::new();
+    for start in 0..parents.len() {
+        let mut path = Vec::new();
+        let mut next = Some(start);
+        while let Some(i) = next.filter(|&i| state[i] == State::New) {
+            state[i] = State::OnPath;
+            path.push(i);
+            next = parents[i];
+        }
+        // walked back onto the path, its tail from there is a loop
+        if let Some(i) = next.filter(|&i| state[i] == State::OnPath) {
+            let at = path.iter().position(|&node| node == i).unwrap();
+            breaks.push(*path[at..].iter().min().unwrap());
+        }
+        for i in path {
+            state[i] = State::Done;
+        }
+    }
+    breaks
+}
+
+/// Depth first traversal, see [`Tree::preorder`]
+pub struct Preorder<'a, T> {
+    tree: &'a Tree<T>,
+    stack: Vec<(usize, usize)>,
+}
+
+impl<'a, T> Iterator for Preorder<'a, T> {
+    type Item = (usize, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (depth, i) = self.stack.pop()?;
+        let children = &self.tree.children[i];
+        self.stack
+            .extend(children.iter().rev().map(|&child| (depth + 1, child)));
+        Some((depth, &self.tree.nodes[i]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Node(u64, Option<u64>);
+
+    impl TreeNode for Node {
+        fn id(&self) -> u64 {
+            self.0
+        }
+
+        fn parent_id(&self) -> Option<u64> {
+            self.1
+        }
+    }
+
+    fn ids<'a>(nodes: impl Iterator<Item = (usize, &'a Node)>) -> Vec<(usize, u64)> {
+        nodes.map(|(depth, node)| (depth, node.0)).collect()
+    }
+
+    #[test]
+    fn parent_loops_are_broken_at_their_first_node() {
+        let tree = Tree::new(vec![
+            Node(1, None),
+            Node(102, Some(101)),
+            Node(101, Some(102)),
+            Node(103, Some(102)),
+            Node(7, Some(7)),
+            Node(201, Some(203)),
+            Node(202, Some(201)),
+            Node(203, Some(202)),
+        ]);
+        assert_eq!(
+            ids(tree.preorder()),
+            [
+                (0, 1),
+                (0, 102),
+                (1, 101),
+                (1, 103),
+                (0, 7),
+                (0, 201),
+                (1, 202),
+                (2, 203)
+            ]
+        );
+        assert_eq!(ids(tree.subtree(101)), [(0, 101)]);
+        assert_eq!(tree.depth(203), Some(2));
+        assert!(tree.parent(102).is_none());
+    }
+}
@@ -0,0 +1,132 @@
+//! Serialization of tables back to `.xer`
+
+use std::io::{self, Write};
+
+use crate::encoding::{Encoding, UTF_8};
+use crate::header::Header;
+use crate::parser::Table;
+
+/// Writes `.xer` data record by record, the counterpart of [`XerReader`](crate::XerReader)
+///
+/// Call [`write_header`](Self::write_header) first, then
+/// [`write_table`](Self::write_table) per table and [`finish`](Self::finish)
+/// to end the data with `%E`. Records are written as CRLF terminated UTF-8
+/// unless configured otherwise. Wrap files in a `BufWriter`.
+///
+/// ## Example
+/// ```
+/// use schedule_parser::{XerFile, XerWriter};
+///
+/// let data = "ERMHDR\t19.12\t2024-03-15\tProject\tadmin\tAdmin\tdb\tProject Management\tEUR\r\n%T\tCURRTYPE\r\n%F\tcurr_id\tcurr_short_name\r\n%R\t1\tEUR\r\n%E\r\n";
+/// let xer = XerFile::parse(data.as_bytes()).unwrap();
+///
+/// let mut writer = XerWriter::new(Vec::new());
+/// writer.write_header(&xer.header).unwrap();
+/// writer.write_table(xer.table("CURRTYPE").unwrap()).unwrap();
+/// let out = writer.finish().unwrap();
+/// assert_eq!(String::from_utf8(out).unwrap(), data);
+/// ```
+#[derive(Debug)]
+pub struct XerWriter<W> {
+    writer: W,
+    encoding: &'static Encoding,
+    line_break: &'static str,
+    /// Record being assembled, reused between records
+    line: String,
+}
+
+impl<W: Write> XerWriter<W> {
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            encoding: UTF_8,
+            line_break: "\r\n",
+            line: String::new(),
+        }
+    }
+
+    /// Encode records in `encoding`, e.g. the one named by [`Header::encoding`]
+    ///
+    /// Writing fails on characters `encoding` can not represent.
+    pub fn with_encoding(mut self, encoding: &'static Encoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    /// End records with `line_break` instead of `\r\n`, e.g. `\n`
+    pub fn with_line_break(mut self, line_break: &'static str) -> Self {
+        self.line_break = line_break;
+        self
+    }
+
+    /// Write the `ERMHDR` line
+    pub fn write_header(&mut self, header: &Header) -> io::Result<()> {
+        let export_date = header.export_date.format("%Y-%m-%d").to_string();
+        let fields = [
+            header.version.as_str(),
+            &export_date,
+            &header.export_type,
+            &header.user,
+            &header.user_name,
+            &header.database,
+            &header.module,
+            &header.currency,
+        ];
+        self.write_record(
+            "ERMHDR",
+            fields.into_iter().chain(header.codepage.as_deref()),
+        )
+    }
+
+    /// Write `%T`, `%F` and the `%R` records of `table` in column order
+    pub fn write_table(&mut self, table: &Table) -> io::Result<()> {
+        self.write_record("%T", [table.name.as_str()])?;
+        self.write_record("%F", table.header.iter().map(String::as_str))?;
+        for row in &table.rows {
+            self.write_record("%R", row.iter().map(String::as_str))?;
+        }
+        Ok(())
+    }
+
+    /// Write `%E`, flush and return the underlying writer
+    pub fn finish(mut self) -> io::Result<W> {
+        self.write_record("%E", [])?;
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+
+    /// Write `prefix` and `fields` separated by tabs
+    fn write_record<'a>(
+        &mut self,
+        prefix: &str,
+        fields: impl IntoIterator<Item = &'a str>,
+    ) -> io::Result<()> {
+        self.line.clear();
+        self.line.push_str(prefix);
+        for field in fields {
+            // a tab would shift all following values
+            if field.contains('\t') {
+                return Err(invalid_data(format!("value contains a tab: {field:?}")));
+            }
+            self.line.push('\t');
+            self.line.push_str(field);
+        }
+        self.line.push_str(self.line_break);
+        if self.encoding == UTF_8 {
+            return self.writer.write_all(self.line.as_bytes());
+        }
+        let (bytes, _, unmappable) = self.encoding.encode(&self.line);
+        if unmappable {
+            return Err(invalid_data(format!(
+                "record not representable in {}: {:?}",
+                self.encoding.name(),
+                self.line.trim_end()
+            )));
+        }
+        self.writer.write_all(&bytes)
+    }
+}
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
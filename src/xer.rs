@@ -0,0 +1,296 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufWriter, Write};
+use std::path::Path;
+
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+use crate::encoding::DecodeReader;
+use crate::error::ParseError;
+use crate::graph::LogicGraph;
+use crate::header::Header;
+use crate::model::{
+    AccountTree, ActivityCodes, Assignments, Calendar, Currency, Expenses, FinancialPeriods,
+    FromRow, Notebooks, ObsTree, Project, ProjectCodes, Relationship, Resource, ResourceRates,
+    ResourceTree, RoleRates, RoleTree, SchedOptions, Steps, Task, Udfs, WbsTree,
+};
+use crate::options::{ParseOptions, Warning};
+use crate::parser::{Table, XerReader};
+use crate::writer::XerWriter;
+
+/// Whole `.xer` file loaded in memory
+///
+/// Use [`XerReader`] directly to process tables one by one with low memory use.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct XerFile {
+    pub header: Header,
+    /// Tables by name in file order
+    pub tables: IndexMap<String, Table>,
+}
+
+impl XerFile {
+    /// Read and parse file at `path`, see [`XerReader::from_path`]
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, ParseError> {
+        let io = |source| ParseError::Io { line: 0, source };
+        let file = File::open(path).map_err(io)?;
+        Self::parse(DecodeReader::detect(file).map_err(io)?)
+    }
+
+    /// Read and parse file at `path` with `options`, see [`XerFile::parse_with_options`]
+    pub fn from_path_with_options<P: AsRef<Path>>(
+        path: P,
+        options: ParseOptions,
+    ) -> Result<(Self, Vec<Warning>), ParseError> {
+        let io = |source| ParseError::Io { line: 0, source };
+        let file = File::open(path).map_err(io)?;
+        Self::parse_with_options(DecodeReader::detect(file).map_err(io)?, options)
+    }
+
+    /// Read all tables from `reader`
+    ///
+    /// A repeated table replaces the earlier one with the same name.
+    pub fn parse<R: BufRead>(reader: R) -> Result<Self, ParseError> {
+        Self::parse_with_options(reader, ParseOptions::default()).map(|(xer, _)| xer)
+    }
+
+    /// Read all tables from `reader` with `options`, e.g. in lenient mode
+    ///
+    /// Returns the warnings collected along with the file.
+    pub fn parse_with_options<R: BufRead>(
+        reader: R,
+        options: ParseOptions,
+    ) -> Result<(Self, Vec<Warning>), ParseError> {
+        let reader = XerReader::from_reader(reader)?.with_options(options);
+        let header = reader.header().clone();
+        let mut tables = IndexMap::new();
+        let mut iter = reader.tables();
+        for table in &mut iter {
+            let table = table?;
+            tables.insert(table.name.clone(), table);
+        }
+        Ok((Self { header, tables }, iter.take_warnings()))
+    }
+
+    /// Table by name, e.g. `TASK`
+    pub fn table(&self, name: &str) -> Option<&Table> {
+        self.tables.get(name)
+    }
+
+    /// Write header and all tables in order to `writer` as UTF-8, see [`XerWriter`]
+    ///
+    /// ## Example
+    /// ```
+    /// use schedule_parser::XerFile;
+    ///
+    /// let data = "ERMHDR\t19.12\t2024-03-15\tProject\tadmin\tAdmin\tdb\tProject Management\tEUR\r\n%T\tCURRTYPE\r\n%F\tcurr_id\tcurr_short_name\r\n%R\t1\tEUR\r\n%E\r\n";
+    /// let xer = XerFile::parse(data.as_bytes()).unwrap();
+    /// let mut out = Vec::new();
+    /// xer.write(&mut out).unwrap();
+    /// assert_eq!(out, data.as_bytes());
+    /// ```
+    pub fn write<W: Write>(&self, writer: W) -> io::Result<()> {
+        self.write_to(XerWriter::new(writer))
+    }
+
+    /// Write all tables to `writer` with its settings, e.g. another encoding
+    pub fn write_to<W: Write>(&self, mut writer: XerWriter<W>) -> io::Result<()> {
+        writer.write_header(&self.header)?;
+        for table in self.tables.values() {
+            writer.write_table(table)?;
+        }
+        writer.finish().map(drop)
+    }
+
+    /// Write file to `path`, replacing it if it exists
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        self.write(BufWriter::new(File::create(path)?))
+    }
+
+    /// Run `f` on every table with all of `columns`, names of those tables in
+    /// file order
+    ///
+    /// Other tables are left as they are. Stops at the first error of `f`,
+    /// and fails with the error of `no_table` if no table has the columns.
+    pub fn for_tables_with<E>(
+        &mut self,
+        columns: &[&str],
+        mut f: impl FnMut(&mut Table) -> Result<(), E>,
+        no_table: impl FnOnce() -> E,
+    ) -> Result<Vec<String>, E> {
+        let mut names = Vec::new();
+        for table in self.tables.values_mut() {
+            if columns
+                .iter()
+                .all(|column| table.column_index(column).is_some())
+            {
+                f(table)?;
+                names.push(table.name.clone());
+            }
+        }
+        if names.is_empty() {
+            return Err(no_table());
+        }
+        Ok(names)
+    }
+
+    /// A table was cut off by the end of the file before `%E`, see [`Table::truncated`]
+    ///
+    /// ## Example
+    /// ```
+    /// use std::io::Cursor;
+    /// use schedule_parser::XerFile;
+    ///
+    /// let data = "ERMHDR\t19.12\t2024-03-15\n%T\tCURRTYPE\n%F\tcurr_id\n%R\t1\n%T\tTASK\n%F\ttask_id\ttask_code\n%R\t1\tA10";
+    /// let xer = XerFile::parse(Cursor::new(data)).unwrap();
+    /// assert!(xer.is_truncated());
+    /// assert!(!xer.table("CURRTYPE").unwrap().truncated);
+    /// assert!(xer.table("TASK").unwrap().truncated);
+    /// ```
+    pub fn is_truncated(&self) -> bool {
+        self.tables.values().any(|table| table.truncated)
+    }
+
+    /// Typed records of `T::TABLE`, empty if file has no such table
+    pub fn records<T: FromRow>(&self) -> Result<Vec<T>, ParseError> {
+        match self.table(T::TABLE) {
+            Some(table) => table.records().collect(),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    pub fn projects(&self) -> Result<Vec<Project>, ParseError> {
+        self.records()
+    }
+
+    /// Scheduling options of every project, with critical float threshold from `PROJECT`
+    pub fn schedule_options(&self) -> Result<Vec<SchedOptions>, ParseError> {
+        let projects = self.projects()?;
+        let mut options: Vec<SchedOptions> = self.records()?;
+        for option in &mut options {
+            let project = projects.iter().find(|p| p.proj_id == option.proj_id);
+            option.critical_float = option.critical_float.or(project.map(|p| p.critical_float));
+        }
+        Ok(options)
+    }
+
+    pub fn tasks(&self) -> Result<Vec<Task>, ParseError> {
+        self.records()
+    }
+
+    pub fn relationships(&self) -> Result<Vec<Relationship>, ParseError> {
+        self.records()
+    }
+
+    /// Network of all activities and relationships
+    pub fn logic_graph(&self) -> Result<LogicGraph, ParseError> {
+        let tasks = self.tasks()?.into_iter().map(|task| task.task_id);
+        Ok(LogicGraph::new(tasks, self.relationships()?))
+    }
+
+    pub fn calendars(&self) -> Result<Vec<Calendar>, ParseError> {
+        self.records()
+    }
+
+    pub fn currencies(&self) -> Result<Vec<Currency>, ParseError> {
+        self.records()
+    }
+
+    /// Currency all costs in the file are stored in, named by `ERMHDR`
+    pub fn base_currency(&self) -> Result<Currency, ParseError> {
+        let short_name = &self.header.currency;
+        Ok(self
+            .currencies()?
+            .into_iter()
+            .find(|currency| &currency.curr_short_name == short_name)
+            .unwrap_or_else(|| Currency::from_short_name(short_name)))
+    }
+
+    pub fn resources(&self) -> Result<Vec<Resource>, ParseError> {
+        self.records()
+    }
+
+    /// Resources linked into hierarchy, also serves lookup by `rsrc_id`
+    pub fn resource_tree(&self) -> Result<ResourceTree, ParseError> {
+        Ok(ResourceTree::new(self.resources()?))
+    }
+
+    pub fn resource_rates(&self) -> Result<ResourceRates, ParseError> {
+        Ok(ResourceRates::new(self.records()?))
+    }
+
+    /// Roles linked into hierarchy
+    pub fn role_tree(&self) -> Result<RoleTree, ParseError> {
+        Ok(RoleTree::new(self.records()?))
+    }
+
+    pub fn role_rates(&self) -> Result<RoleRates, ParseError> {
+        Ok(RoleRates::new(self.records()?))
+    }
+
+    /// Activity steps grouped by task
+    pub fn steps(&self) -> Result<Steps, ParseError> {
+        Ok(Steps::new(self.records()?))
+    }
+
+    /// Resource assignments indexed by task and resource
+    pub fn assignments(&self) -> Result<Assignments, ParseError> {
+        Ok(Assignments::new(self.records()?))
+    }
+
+    /// Cost account hierarchy
+    pub fn account_tree(&self) -> Result<AccountTree, ParseError> {
+        Ok(AccountTree::new(self.records()?))
+    }
+
+    /// Project expenses indexed by task
+    pub fn expenses(&self) -> Result<Expenses, ParseError> {
+        Ok(Expenses::new(self.records()?))
+    }
+
+    /// Project code dictionaries joined with their assignments
+    pub fn project_codes(&self) -> Result<ProjectCodes, ParseError> {
+        Ok(ProjectCodes::new(
+            self.records()?,
+            self.records()?,
+            self.records()?,
+        ))
+    }
+
+    /// Activity code dictionaries joined with their assignments
+    pub fn activity_codes(&self) -> Result<ActivityCodes, ParseError> {
+        Ok(ActivityCodes::new(
+            self.records()?,
+            self.records()?,
+            self.records()?,
+        ))
+    }
+
+    /// Financial periods joined with stored period actuals
+    pub fn financial_periods(&self) -> Result<FinancialPeriods, ParseError> {
+        Ok(FinancialPeriods::new(
+            self.records()?,
+            self.records()?,
+            self.records()?,
+        ))
+    }
+
+    /// Notebook topics joined with activity memos
+    pub fn notebooks(&self) -> Result<Notebooks, ParseError> {
+        Ok(Notebooks::new(self.records()?, self.records()?))
+    }
+
+    /// User defined field definitions with their values
+    pub fn udfs(&self) -> Result<Udfs, ParseError> {
+        Ok(Udfs::new(self.records()?, self.records()?))
+    }
+
+    /// OBS elements linked into responsibility hierarchy
+    pub fn obs_tree(&self) -> Result<ObsTree, ParseError> {
+        Ok(ObsTree::new(self.records()?))
+    }
+
+    /// WBS elements of all projects linked into a tree
+    pub fn wbs_tree(&self) -> Result<WbsTree, ParseError> {
+        Ok(WbsTree::new(self.records()?))
+    }
+}
@@ -0,0 +1,81 @@
+//! Indented XML built into a string for the XML exports
+
+use std::fmt::Display;
+
+/// XML document in the making, opened elements are closed in reverse
+pub(crate) struct XmlWriter {
+    out: String,
+    open: Vec<&'static str>,
+}
+
+impl XmlWriter {
+    /// Document starting with the XML declaration
+    pub(crate) fn new() -> Self {
+        Self {
+            out: String::from("<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n"),
+            open: Vec::new(),
+        }
+    }
+
+    /// Start `tag` with `attributes`, children follow until [`close`](Self::close)
+    pub(crate) fn open(&mut self, tag: &'static str, attributes: &[(&str, &str)]) {
+        self.indent();
+        self.out.push('<');
+        self.out.push_str(tag);
+        for (name, value) in attributes {
+            self.out.push_str(&format!(" {name}=\"{}\"", escape(value)));
+        }
+        self.out.push_str(">\n");
+        self.open.push(tag);
+    }
+
+    /// End the element opened last
+    pub(crate) fn close(&mut self) {
+        let tag = self.open.pop().expect("no open element");
+        self.indent();
+        self.out.push_str(&format!("</{tag}>\n"));
+    }
+
+    /// Element with text content
+    pub(crate) fn element(&mut self, tag: &str, value: impl Display) {
+        self.indent();
+        let value = value.to_string();
+        self.out
+            .push_str(&format!("<{tag}>{}</{tag}>\n", escape(&value)));
+    }
+
+    /// [`element`](Self::element) if there is a value
+    pub(crate) fn optional(&mut self, tag: &str, value: Option<impl Display>) {
+        if let Some(value) = value {
+            self.element(tag, value);
+        }
+    }
+
+    /// The document, all elements must be closed
+    pub(crate) fn finish(self) -> String {
+        debug_assert!(self.open.is_empty());
+        self.out
+    }
+
+    fn indent(&mut self) {
+        for _ in 0..self.open.len() {
+            self.out.push_str("  ");
+        }
+    }
+}
+
+/// `text` with markup characters replaced by entities
+pub(crate) fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
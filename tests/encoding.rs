@@ -0,0 +1,56 @@
+use std::path::Path;
+
+use schedule_parser::encoding::Encoding;
+use schedule_parser::{XerFile, XerReader};
+
+fn fixture(name: &str) -> std::path::PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(name)
+}
+
+fn task_names(xer: &XerFile) -> Vec<&str> {
+    xer.table("TASK")
+        .unwrap()
+        .iter()
+        .map(|row| row.get("task_name").unwrap())
+        .collect()
+}
+
+#[test]
+fn cp1251_from_header_codepage() {
+    let xer = XerFile::from_path(fixture("cp1251.xer")).unwrap();
+    assert_eq!(xer.header.codepage.as_deref(), Some("1251"));
+    assert_eq!(xer.header.user_name, "Администратор");
+    assert_eq!(
+        task_names(&xer),
+        ["Подготовка площадки", "Устройство опор", "Монтаж пролётов"]
+    );
+}
+
+#[test]
+fn shift_jis_from_header_codepage() {
+    let xer = XerFile::from_path(fixture("shift_jis.xer")).unwrap();
+    assert_eq!(xer.header.codepage.as_deref(), Some("Shift_JIS"));
+    assert_eq!(xer.header.encoding(), Encoding::for_label(b"shift_jis"));
+    assert_eq!(xer.header.user_name, "管理者");
+    assert_eq!(task_names(&xer), ["準備工", "下部工", "上部工"]);
+}
+
+#[test]
+fn explicit_encoding_overrides_header() {
+    let latin1 = Encoding::for_label(b"windows-1252").unwrap();
+    let reader = XerReader::from_path_with_encoding(fixture("cp1251.xer"), latin1).unwrap();
+    assert_eq!(reader.header().user_name, "Àäìèíèñòðàòîð");
+}
+
+#[test]
+fn prescan_of_transcoded_file() {
+    let reader = XerReader::from_path(fixture("shift_jis.xer"))
+        .and_then(XerReader::prescan)
+        .unwrap();
+    let counts = reader.row_counts().unwrap().to_vec();
+    assert_eq!(counts, [("PROJECT".into(), 1), ("TASK".into(), 3)]);
+    let tables: Vec<_> = reader.into_iter().collect::<Result<_, _>>().unwrap();
+    assert_eq!(tables[1].rows[2][3], "上部工");
+}